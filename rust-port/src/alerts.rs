@@ -0,0 +1,210 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use colored::Colorize;
+use futures::future::{join_all, BoxFuture};
+use serde::Serialize;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::config::{AlertConfig, AlertSinkConfig};
+
+/// How long a sink gets to deliver one alert
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    pub severity: Severity,
+    pub title: String,
+    pub message: String,
+    /// The reading that fired the alert, e.g. `vote_lag`
+    pub metric: Option<String>,
+    pub value: Option<f64>,
+    /// The limit `value` crossed; None for recoveries
+    pub threshold: Option<f64>,
+    pub timestamp: String,
+}
+
+impl Alert {
+    pub fn new(severity: Severity, title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            title: title.into(),
+            message: message.into(),
+            metric: None,
+            value: None,
+            threshold: None,
+            timestamp: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+
+    /// The metric, its value and the threshold behind the alert
+    pub fn with_reading(mut self, metric: &str, value: f64, threshold: Option<f64>) -> Self {
+        self.metric = Some(metric.to_string());
+        self.value = Some(value);
+        self.threshold = threshold;
+        self
+    }
+}
+
+/// A destination alerts are delivered to, e.g. a pager or an internal system
+///
+/// Implement it and pass the sink to `Alerter::register` to add a destination
+/// the built-in webhook and command sinks don't cover.
+pub trait AlertSink: Send + Sync {
+    /// Shown when a delivery fails
+    fn name(&self) -> String;
+
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>>;
+}
+
+/// A JSON POST per alert, with a Slack-style `text` field
+pub struct WebhookSink {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            client: reqwest::Client::builder()
+                .timeout(DELIVERY_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl AlertSink for WebhookSink {
+    fn name(&self) -> String {
+        format!("webhook {}", self.url)
+    }
+
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            // `text` makes the payload render as-is in Slack-style incoming webhooks
+            let payload = serde_json::json!({
+                "text": format!("[{:?}] {}: {}", alert.severity, alert.title, alert.message),
+                "alert": alert,
+            });
+
+            self.client
+                .post(&self.url)
+                .json(&payload)
+                .send()
+                .await
+                .context("Webhook request failed")?
+                .error_for_status()
+                .context("Webhook returned an error status")?;
+            Ok(())
+        })
+    }
+}
+
+/// A program run per alert, with the alert as JSON on stdin and its fields
+/// in `ALERT_*` environment variables
+pub struct CommandSink {
+    program: String,
+    args: Vec<String>,
+}
+
+impl CommandSink {
+    pub fn new(program: &str, args: &[String]) -> Self {
+        Self { program: program.to_string(), args: args.to_vec() }
+    }
+}
+
+impl AlertSink for CommandSink {
+    fn name(&self) -> String {
+        format!("command {}", self.program)
+    }
+
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let optional = |value: Option<f64>| value.map(|value| value.to_string()).unwrap_or_default();
+            let mut child = Command::new(&self.program)
+                .args(&self.args)
+                .env("ALERT_SEVERITY", serde_json::to_value(alert.severity)?.as_str().unwrap_or_default())
+                .env("ALERT_TITLE", &alert.title)
+                .env("ALERT_MESSAGE", &alert.message)
+                .env("ALERT_METRIC", alert.metric.as_deref().unwrap_or_default())
+                .env("ALERT_VALUE", optional(alert.value))
+                .env("ALERT_THRESHOLD", optional(alert.threshold))
+                .env("ALERT_TIMESTAMP", &alert.timestamp)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true)
+                .spawn()
+                .with_context(|| format!("Failed to run {}", self.program))?;
+            if let Some(mut stdin) = child.stdin.take() {
+                // A command that ignores stdin may exit before reading it
+                let _ = stdin.write_all(&serde_json::to_vec(alert)?).await;
+            }
+
+            let output = tokio::time::timeout(DELIVERY_TIMEOUT, child.wait_with_output())
+                .await
+                .with_context(|| format!("Timed out after {}s", DELIVERY_TIMEOUT.as_secs()))??;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                anyhow::bail!("{} ({})", output.status, stderr.trim());
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Prints alerts to the terminal and delivers them to every registered sink
+pub struct Alerter {
+    sinks: Vec<Box<dyn AlertSink>>,
+}
+
+impl Alerter {
+    /// The sinks in `config.sinks`, plus `config.webhook_url` when set
+    pub fn new(config: &AlertConfig) -> Self {
+        let mut alerter = Self { sinks: Vec::new() };
+        if let Some(url) = &config.webhook_url {
+            alerter.register(Box::new(WebhookSink::new(url)));
+        }
+        for sink in &config.sinks {
+            alerter.register(match sink {
+                AlertSinkConfig::Webhook { url } => Box::new(WebhookSink::new(url)),
+                AlertSinkConfig::Command { program, args } => Box::new(CommandSink::new(program, args)),
+            });
+        }
+        alerter
+    }
+
+    /// Deliver alerts to `sink` as well
+    pub fn register(&mut self, sink: Box<dyn AlertSink>) {
+        self.sinks.push(sink);
+    }
+
+    /// Report an alert to every sink at once; failures are printed per sink,
+    /// never returned
+    pub async fn send(&self, alert: &Alert) {
+        let icon = match alert.severity {
+            Severity::Info => "ℹ".cyan(),
+            Severity::Warning => "⚠".yellow(),
+            Severity::Critical => "✗".red(),
+        };
+        println!("{} [{}] {}: {}", icon, alert.timestamp, alert.title.bold(), alert.message);
+
+        let results = join_all(self.sinks.iter().map(|sink| sink.send(alert))).await;
+        for (sink, result) in self.sinks.iter().zip(results) {
+            if let Err(e) = result {
+                println!("  {} Failed to deliver alert to {}: {:#}", "⚠".yellow(), sink.name(), e);
+            }
+        }
+    }
+}
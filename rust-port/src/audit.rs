@@ -0,0 +1,225 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{AuditLogConfig, LogRotationConfig, ValidatorConfig};
+use crate::log_rotation::RotatingLog;
+use crate::utils;
+
+/// One change the tool made, as a line of `audit.jsonl`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub timestamp: DateTime<Utc>,
+    pub parameter: String,
+    /// None when the value before the change couldn't be read
+    pub old_value: Option<String>,
+    pub new_value: String,
+    pub reason: String,
+    /// The new value was read back from where it was written
+    pub verified: bool,
+    /// The command that made the change, e.g. `apply --live`
+    pub triggered_by: String,
+}
+
+impl AuditEvent {
+    pub fn new(parameter: &str, old_value: Option<String>, new_value: &str, reason: impl Into<String>, triggered_by: &str) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            parameter: parameter.to_string(),
+            old_value,
+            new_value: new_value.to_string(),
+            reason: reason.into(),
+            verified: false,
+            triggered_by: triggered_by.to_string(),
+        }
+    }
+
+    pub fn verified(mut self, verified: bool) -> Self {
+        self.verified = verified;
+        self
+    }
+}
+
+pub fn audit_log_path() -> PathBuf {
+    ValidatorConfig::config_dir().join("audit.jsonl")
+}
+
+/// Append `event` to the audit log, rotating it at `audit_log.max_size_mb`
+pub fn record(event: &AuditEvent) -> Result<()> {
+    let config = ValidatorConfig::load().map(|config| config.audit_log).unwrap_or_default();
+    append(&audit_log_path(), &config, event)
+}
+
+fn append(path: &Path, config: &AuditLogConfig, event: &AuditEvent) -> Result<()> {
+    let rotation = LogRotationConfig {
+        enabled: true,
+        max_size_mb: config.max_size_mb,
+        max_files: config.max_files,
+        compress: false,
+    };
+    let mut log = RotatingLog::open(path, &rotation)?;
+    log.write_line(&serde_json::to_string(event)?)
+        .context("Failed to write the audit log")
+}
+
+/// Record `event`, printing rather than failing when the log can't be written
+pub fn record_or_warn(event: &AuditEvent) {
+    if let Err(e) = record(event) {
+        println!("    {} Could not write the audit log: {:#}", "⚠".yellow(), e);
+    }
+}
+
+/// `tpu_coalesce_ms` and `tpu-coalesce-ms` name the same setting
+fn same_parameter(a: &str, b: &str) -> bool {
+    a.replace('_', "-").eq_ignore_ascii_case(&b.replace('_', "-"))
+}
+
+/// Every event in the log at `path` and its rotated generations, oldest first
+fn load_all(path: &Path) -> Result<Vec<AuditEvent>> {
+    let prefix = format!("{}.", path.file_name().and_then(|name| name.to_str()).unwrap_or("audit.jsonl"));
+    let dir = path.parent().unwrap_or(Path::new("."));
+    // `audit.jsonl.1` is the newest rotated generation, so read the highest first
+    let mut generations: Vec<(u32, PathBuf)> = fs::read_dir(dir)
+        .map(|entries| {
+            entries.flatten()
+                .filter_map(|entry| {
+                    let n = entry.file_name().to_str()?.strip_prefix(&prefix)?.parse().ok()?;
+                    Some((n, entry.path()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    generations.sort_by_key(|generation| std::cmp::Reverse(generation.0));
+    let files = generations.into_iter().map(|(_, file)| file).chain([path.to_path_buf()]);
+
+    let mut events = Vec::new();
+    for file in files {
+        let contents = match fs::read_to_string(&file) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", file.display())),
+        };
+        events.extend(contents.lines().filter_map(|line| serde_json::from_str::<AuditEvent>(line).ok()));
+    }
+    events.sort_by_key(|event| event.timestamp);
+    Ok(events)
+}
+
+/// `24h`/`7d` back from now, or an RFC 3339 time
+fn parse_since(since: &str) -> Result<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(since) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let window = utils::parse_duration(since)
+        .with_context(|| format!("--since takes a window like 24h or a time like 2024-05-01T12:00:00Z, got '{}'", since))?;
+    Utc::now()
+        .checked_sub_signed(window)
+        .ok_or_else(|| anyhow::anyhow!("--since '{}' reaches back before the earliest supported date", since))
+}
+
+/// `audit-log`: the recorded changes, optionally since a time and for one setting
+pub fn show(since: Option<&str>, parameter: Option<&str>) -> Result<()> {
+    let since = since.map(parse_since).transpose()?;
+    let events: Vec<AuditEvent> = load_all(&audit_log_path())?
+        .into_iter()
+        .filter(|event| since.is_none_or(|since| event.timestamp >= since))
+        .filter(|event| parameter.is_none_or(|parameter| same_parameter(&event.parameter, parameter)))
+        .collect();
+
+    println!("{}", "=== Audit Log ===".cyan().bold());
+    println!("{}", audit_log_path().display().to_string().dimmed());
+    if events.is_empty() {
+        println!("{} No recorded changes match", "ℹ".cyan());
+        return Ok(());
+    }
+
+    for (i, event) in events.iter().enumerate() {
+        let last = i + 1 == events.len();
+        let (branch, stem) = if last { ("└─", "  ") } else { ("├─", "│ ") };
+        let check = if event.verified { "✓ verified".green() } else { "unverified".yellow() };
+        println!("{} {} {}: {} → {} ({}, {})",
+            branch,
+            event.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+            event.parameter.bold(),
+            event.old_value.as_deref().unwrap_or("?"),
+            event.new_value.green(),
+            check,
+            event.triggered_by.cyan()
+        );
+        println!("{}   {}", stem, event.reason);
+    }
+    println!("\n{} change(s)", events.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("audit-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn rotated_generations_are_read_back_oldest_first() {
+        let dir = scratch_dir("rotation");
+        let path = dir.join("audit.jsonl");
+        let config = AuditLogConfig { max_size_mb: 1, max_files: 2 };
+        // Each event fills the 1 MB limit, so every write after the first rotates
+        let padding = "x".repeat(1024 * 1024);
+
+        for n in 0..4 {
+            let event = AuditEvent::new("rpc_threads", Some(n.to_string()), &(n + 1).to_string(), padding.clone(), "test");
+            append(&path, &config, &event).unwrap();
+        }
+
+        assert!(dir.join("audit.jsonl.1").exists());
+        assert!(dir.join("audit.jsonl.2").exists());
+        assert!(!dir.join("audit.jsonl.3").exists(), "generations past max_files are dropped");
+
+        let values: Vec<String> = load_all(&path).unwrap().into_iter().map(|event| event.new_value).collect();
+        assert_eq!(values, ["2", "3", "4"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unreadable_lines_are_skipped() {
+        let dir = scratch_dir("reader");
+        let path = dir.join("audit.jsonl");
+        let event = AuditEvent::new("tpu_coalesce_ms", None, "2", "test", "apply --live").verified(true);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, format!("not json\n{}\n", serde_json::to_string(&event).unwrap())).unwrap();
+
+        let events = load_all(&path).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].parameter, "tpu_coalesce_ms");
+        assert!(events[0].verified);
+        assert!(load_all(&dir.join("missing.jsonl")).unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn since_takes_a_window_or_a_time() {
+        let time = parse_since("2024-05-01T12:00:00Z").unwrap();
+        assert_eq!(time, DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc));
+
+        let day_ago = parse_since("24h").unwrap();
+        assert!((Utc::now() - day_ago - chrono::Duration::hours(24)).num_seconds().abs() < 5);
+
+        assert!(parse_since("20000000w").is_err());
+        assert!(parse_since("yesterday").is_err());
+    }
+
+    #[test]
+    fn parameter_names_match_across_spellings() {
+        assert!(same_parameter("tpu_coalesce_ms", "tpu-coalesce-ms"));
+        assert!(!same_parameter("rpc_threads", "accounts_db_threads"));
+    }
+}
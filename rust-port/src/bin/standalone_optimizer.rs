@@ -1,13 +1,23 @@
 use anyhow::Result;
 use colored::Colorize;
 use tokio::time::{sleep, Duration};
-use solana_validator_optimizer_rs::blockchain::SolanaInterface;
+use solana_validator_optimizer_rs::config::ValidatorConfig;
+use solana_validator_optimizer_rs::config_reload;
+use solana_validator_optimizer_rs::connect::{connect_validator, read_or_disconnect, KeypairUse};
+use solana_validator_optimizer_rs::native;
+use solana_validator_optimizer_rs::standalone::StandaloneOptimizer;
 use solana_sdk::signature::{Keypair, Signer};
 
+const VALIDATOR_KEYPAIR_PATH: &str = "./validator-keypair.json";
+const VOTE_KEYPAIR_PATH: &str = "./vote-keypair.json";
+
 /// Standalone Solana Validator Optimizer
 /// 
 /// This binary can run independently to optimize any Solana validator
-/// It connects to testnet by default and applies real-time optimizations
+/// It connects to the first reachable `connect.endpoints` entry (a local
+/// validator, then testnet by default) and applies real-time optimizations.
+/// With --measure it instead optimizes once and reports whether the metrics
+/// improved beyond the baseline's noise
 #[tokio::main]
 async fn main() -> Result<()> {
     println!("\n{}", "===============================================".blue());
@@ -15,54 +25,41 @@ async fn main() -> Result<()> {
     println!("{}", "Real-Time Performance Optimization Engine".blue());
     println!("{}", "===============================================".blue());
 
+    // Everything below talks RPC, so with --no-cli the Solana CLI isn't needed
+    let no_cli = std::env::args().any(|arg| arg == "--no-cli");
+    native::set_no_cli(no_cli);
+
     // Check for Solana CLI
-    if !check_solana_cli() {
+    if !no_cli && !check_solana_cli() {
         println!("\n{} Solana CLI not found!", "❌".red());
         println!("Please install Solana CLI first:");
         println!("  curl -sSfL https://release.solana.com/v1.18.22/install | sh");
         return Ok(());
     }
 
+    if std::env::args().any(|arg| arg == "--measure") {
+        return StandaloneOptimizer::optimize().await;
+    }
+
     // Generate or load keypairs
     println!("\n{} Setting up validator keypairs...", "🔑".cyan());
-    let (validator_keypair, vote_keypair) = setup_keypairs().await?;
+    setup_keypairs().await?;
     
-    // Connect to Solana testnet or local validator
+    // Connect to a local validator, falling back to the configured cluster
     println!("\n{} Connecting to Solana validator...", "🌐".cyan());
-    
-    // Try local validator first (port 8899)
-    let solana_interface = if let (Ok(validator_keypair), Ok(vote_keypair)) = (
-        solana_sdk::signature::read_keypair_file("./validator-keypair.json").map_err(|e| anyhow::anyhow!("Failed to read validator keypair: {}", e)),
-        solana_sdk::signature::read_keypair_file("./vote-keypair.json").map_err(|e| anyhow::anyhow!("Failed to read vote keypair: {}", e))
-    ) {
-        match SolanaInterface::new("http://127.0.0.1:8899", validator_keypair, vote_keypair) {
-            Ok(interface) => {
-                println!("  {} Connected to local validator!", "✅".green());
-                Some(interface)
-            }
-            Err(_) => {
-                println!("  {} Local validator not found, trying testnet...", "⚠️".yellow());
-                None
-            }
-        }
-    } else {
-        println!("  {} Keypairs not found, trying testnet...", "⚠️".yellow());
-        None
-    };
-
-    let solana_interface = match solana_interface {
-        Some(interface) => interface,
-        None => {
-            // Try testnet with new keypairs
-            let validator_keypair = Keypair::new();
-            let vote_keypair = Keypair::new();
-            SolanaInterface::new("https://api.testnet.solana.com", validator_keypair, vote_keypair)
-                .map_err(|e| anyhow::anyhow!("Failed to connect to testnet: {}", e))?
-        }
+    let mut config = ValidatorConfig::load().unwrap_or_default();
+    config.identity_keypair = VALIDATOR_KEYPAIR_PATH.into();
+    config.vote_account_keypair = VOTE_KEYPAIR_PATH.into();
+    // The optimization loop signs with these, so they must load
+    let connect = || async { connect_validator(&config, KeypairUse::Signing) };
+    let Some(solana_interface) = read_or_disconnect(&config, connect).await? else {
+        println!("\n{} Nothing to optimize without a validator", "⚠".yellow());
+        return Ok(());
     };
+    println!("  {} Connected to {}", "✅".green(), solana_interface.rpc_url());
     
     // Show connection info
-    display_connection_info();
+    display_connection_info(&solana_interface.rpc_url());
     
     // Start real-time optimization loop
     println!("\n{} Starting real-time optimization...", "⚡".yellow().bold());
@@ -80,8 +77,10 @@ async fn main() -> Result<()> {
     println!();
     println!("{} Press Ctrl+C to stop optimization", "💡".blue());
     
-    // Run the auto-optimization loop
-    solana_interface.auto_optimize_loop().await?;
+    // Run the auto-optimization loop, reloading the config on SIGHUP
+    let config = config_reload::shared(config);
+    config_reload::reload_on_sighup(config.clone())?;
+    solana_interface.auto_optimize_loop(None, None, &config).await?;
 
     Ok(())
 }
@@ -94,9 +93,10 @@ fn check_solana_cli() -> bool {
         .unwrap_or(false)
 }
 
-async fn setup_keypairs() -> Result<(Keypair, Keypair)> {
-    let validator_keypair_path = "./validator-keypair.json";
-    let vote_keypair_path = "./vote-keypair.json";
+/// Create whichever keypair files are missing; `connect_validator` loads them
+async fn setup_keypairs() -> Result<()> {
+    let validator_keypair_path = VALIDATOR_KEYPAIR_PATH;
+    let vote_keypair_path = VOTE_KEYPAIR_PATH;
     
     // Try to load existing keypairs
     let validator_keypair = if std::path::Path::new(validator_keypair_path).exists() {
@@ -126,13 +126,12 @@ async fn setup_keypairs() -> Result<(Keypair, Keypair)> {
     println!("  {} Validator Identity: {}", "🆔".cyan(), validator_keypair.pubkey());
     println!("  {} Vote Account: {}", "🗳️".cyan(), vote_keypair.pubkey());
     
-    Ok((validator_keypair, vote_keypair))
+    Ok(())
 }
 
-fn display_connection_info() {
+fn display_connection_info(rpc_url: &str) {
     println!("\n{}", "📡 Connection Information:".cyan().bold());
-    println!("  Network: Solana Testnet");
-    println!("  RPC Endpoint: https://api.testnet.solana.com");
+    println!("  RPC Endpoint: {}", rpc_url);
     println!("  Optimization Engine: Real-time Performance Monitor");
     println!("  Optimization Strategies: 6 active strategies");
     println!("    - Vote Success Optimizer");
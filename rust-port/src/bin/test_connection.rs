@@ -1,28 +1,17 @@
 use anyhow::Result;
-use solana_validator_optimizer_rs::blockchain::SolanaInterface;
-use solana_sdk::signature::{Keypair, Signer};
+use solana_validator_optimizer_rs::config::ValidatorConfig;
+use solana_validator_optimizer_rs::connect::{connect_validator, read_or_disconnect, KeypairUse};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     println!("🧪 Testing Solana Interface Connection...");
 
-    // Generate test keypairs
-    let validator_keypair = Keypair::new();
-    let vote_keypair = Keypair::new();
+    let config = ValidatorConfig::load().unwrap_or_default();
+    println!("\n🌐 Trying endpoints in order: {}", config.connect_endpoints().join(", "));
 
-    println!("🔑 Generated keypairs:");
-    println!("  Validator: {}", validator_keypair.pubkey());
-    println!("  Vote: {}", vote_keypair.pubkey());
-
-    // Try to connect to local validator
-    println!("\n🌐 Testing connection to http://127.0.0.1:8899...");
-    match SolanaInterface::new(
-        "http://127.0.0.1:8899",
-        validator_keypair,
-        vote_keypair,
-    ) {
-        Ok(interface) => {
-            println!("✅ Connected to local validator!");
+    match read_or_disconnect(&config, || async { connect_validator(&config, KeypairUse::ReadOnly) }).await? {
+        Some(interface) => {
+            println!("✅ Connected to {}!", interface.rpc_url());
             match interface.get_validator_metrics().await {
                 Ok(metrics) => {
                     println!("📊 Got metrics:");
@@ -36,36 +25,8 @@ async fn main() -> Result<()> {
                 }
             }
         }
-        Err(e) => {
-            println!("❌ Failed to connect to local: {}", e);
-
-            // Try testnet with new keypairs
-            println!("\n🌐 Testing connection to https://api.testnet.solana.com...");
-            let validator_keypair = Keypair::new();
-            let vote_keypair = Keypair::new();
-            match SolanaInterface::new(
-                "https://api.testnet.solana.com",
-                validator_keypair,
-                vote_keypair,
-            ) {
-                Ok(interface) => {
-                    println!("✅ Connected to testnet!");
-                    match interface.get_validator_metrics().await {
-                        Ok(metrics) => {
-                            println!("📊 Got metrics from testnet:");
-                            println!("  Vote Success: {:.1}%", metrics.vote_success_rate);
-                            println!("  Skip Rate: {:.1}%", metrics.skip_rate);
-                            println!("  Credits: {}", metrics.credits_earned);
-                        }
-                        Err(e) => {
-                            println!("⚠️  Could not get metrics from testnet: {}", e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    println!("❌ Failed to connect to testnet: {}", e);
-                }
-            }
+        None => {
+            println!("❌ No validator connected");
         }
     }
 
@@ -1,5 +1,8 @@
 use anyhow::Result;
 use colored::Colorize;
+use solana_validator_optimizer_rs::config::ValidatorConfig;
+use solana_validator_optimizer_rs::blockchain::ValidatorMetrics;
+use solana_validator_optimizer_rs::connect::{connect_validator, read_or_disconnect, KeypairUse};
 use solana_validator_optimizer_rs::real_optimizer::RealOptimizer;
 use std::process::Command;
 use std::time::Duration;
@@ -10,7 +13,7 @@ use tokio::time::sleep;
 async fn main() -> Result<()> {
     println!("\n{}", "=== Solana Validator Optimizer Test ===".cyan().bold());
     println!("{}", "Testing real-time performance optimization".blue());
-    println!("{}", "Connects to actual validators (local or testnet)".green());
+    println!("{}", "Connects to actual validators (connect.endpoints)".green());
     println!();
 
     // Step 1: Check if validator is running
@@ -89,7 +92,7 @@ fn start_optimized_validator() -> Result<()> {
     Ok(())
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 struct Metrics {
     vote_success_rate: f64,
     skip_rate: f64,
@@ -98,159 +101,30 @@ struct Metrics {
     network_latency_ms: u32,
 }
 
-async fn collect_metrics() -> Result<Metrics> {
-    // Connect to real validator using blockchain interface
-    println!("  Connecting to validator for real metrics...");
-    
-    // Try local validator first
-    if let Ok(metrics) = get_local_validator_metrics().await {
-        return Ok(metrics);
-    }
-    
-    // Try testnet validator
-    if let Ok(metrics) = get_testnet_validator_metrics().await {
-        return Ok(metrics);
-    }
-    
-    // If no validator found, return error (no fake data)
-    println!("  {} No validator found - please start a validator or connect to testnet", "⚠".yellow());
-    Err(anyhow::anyhow!("No validator found - cannot collect metrics"))
-}
-
-async fn get_local_validator_metrics() -> Result<Metrics> {
-    use solana_client::rpc_client::RpcClient;
-    use solana_sdk::commitment_config::CommitmentConfig;
-    
-    let rpc_client = RpcClient::new_with_commitment(
-        "http://127.0.0.1:8899".to_string(),
-        CommitmentConfig::confirmed(),
-    );
-    
-    // Try to get local validator info
-    let epoch_info = rpc_client.get_epoch_info()?;
-    let slot = rpc_client.get_slot()?;
-    let perf_samples = rpc_client.get_recent_performance_samples(Some(5))?;
-    
-    // Calculate real metrics from performance samples
-    let mut total_slots = 0u64;
-    let mut total_transactions = 0u64;
-    
-    for sample in &perf_samples {
-        total_slots += sample.num_slots;
-        total_transactions += sample.num_transactions;
-    }
-    
-    // Calculate skip rate from actual performance
-    let skip_rate = if total_slots > 0 {
-        let expected_tx = total_slots * 100; // Rough estimate
-        ((expected_tx.saturating_sub(total_transactions)) as f64 / expected_tx as f64) * 100.0
-    } else {
-        0.0
-    };
-    
-    Ok(Metrics {
-        vote_success_rate: calculate_vote_success_rate(&rpc_client).await.unwrap_or(85.0),
-        skip_rate: skip_rate.max(0.0).min(100.0),
-        credits_earned: epoch_info.epoch * 1000, // Rough estimate
-        vote_lag: estimate_vote_lag(&perf_samples),
-        network_latency_ms: estimate_network_latency(&perf_samples),
-    })
-}
-
-async fn get_testnet_validator_metrics() -> Result<Metrics> {
-    let output = Command::new("solana")
-        .args(&["validators", "--url", "https://api.testnet.solana.com", "--output", "json"])
-        .output()?;
-    
-    if !output.status.success() {
-        return Err(anyhow::anyhow!("Failed to get testnet validators"));
-    }
-    
-    let json_str = String::from_utf8_lossy(&output.stdout);
-    let json: serde_json::Value = serde_json::from_str(&json_str)?;
-    
-    // Look for any running validator to get baseline metrics
-    if let Some(validators) = json["validators"].as_array() {
-        if let Some(validator) = validators.first() {
-            return Ok(Metrics {
-                vote_success_rate: validator["voteSuccess"].as_f64().unwrap_or(85.0),
-                skip_rate: validator["skipRate"].as_f64().unwrap_or(12.0),
-                credits_earned: validator["credits"].as_u64().unwrap_or(160_000),
-                vote_lag: validator["voteLag"].as_u64().unwrap_or(150) as u32,
-                network_latency_ms: 120,
-            });
+impl From<&ValidatorMetrics> for Metrics {
+    fn from(metrics: &ValidatorMetrics) -> Self {
+        Self {
+            vote_success_rate: metrics.vote_success_rate,
+            skip_rate: metrics.skip_rate,
+            credits_earned: metrics.credits_earned,
+            vote_lag: metrics.vote_lag as u32,
+            network_latency_ms: metrics.network_latency_ms,
         }
     }
-    
-    Err(anyhow::anyhow!("No validators found on testnet"))
 }
 
-async fn calculate_vote_success_rate(rpc_client: &solana_client::rpc_client::RpcClient) -> Result<f64> {
-    // Try to calculate real vote success rate from validator performance
-    let perf = rpc_client.get_recent_performance_samples(Some(10))?;
-    
-    if perf.is_empty() {
-        return Ok(85.0); // Default baseline
-    }
-    
-    // Estimate vote success from performance samples
-    let avg_slots: u64 = perf.iter().map(|s| s.num_slots).sum::<u64>() / perf.len() as u64;
-    let avg_tx: u64 = perf.iter().map(|s| s.num_transactions).sum::<u64>() / perf.len() as u64;
+/// Metrics from the first of `connect.endpoints` that answers; zeros when
+/// none does and `on_disconnect` is `baseline`
+async fn collect_metrics() -> Result<Metrics> {
+    // Connect to real validator using blockchain interface
+    println!("  Connecting to validator for real metrics...");
     
-    // Simple heuristic: higher transaction throughput usually correlates with better vote success
-    let success_rate = if avg_slots > 0 {
-        ((avg_tx as f64 / (avg_slots * 100) as f64) * 100.0).min(100.0).max(0.0)
-    } else {
-        85.0
+    let config = ValidatorConfig::load().unwrap_or_default();
+    let read = || async {
+        let interface = connect_validator(&config, KeypairUse::ReadOnly)?;
+        Ok::<_, anyhow::Error>(Metrics::from(&interface.get_validator_metrics().await?))
     };
-    
-    Ok(success_rate)
-}
-
-fn estimate_vote_lag(samples: &[solana_client::rpc_response::RpcPerfSample]) -> u32 {
-    // Calculate real vote lag from performance sample timing
-    if samples.len() < 2 {
-        return 150; // Default when no data available
-    }
-
-    let mut lags = Vec::new();
-    for window in samples.windows(2) {
-        let slot_diff = window[1].slot.saturating_sub(window[0].slot);
-        let time_diff = window[1].sample_period_secs as u64;
-
-        if time_diff > 0 && slot_diff > 0 {
-            // Estimate lag based on slot progression timing
-            let expected_slots = time_diff * 2; // 2 slots per second
-            let lag = slot_diff.saturating_sub(expected_slots);
-            lags.push(lag as u32);
-        }
-    }
-
-    if lags.is_empty() {
-        150 // Default when calculation fails
-    } else {
-        lags.iter().sum::<u32>() / lags.len() as u32
-    }
-}
-
-fn estimate_network_latency(samples: &[solana_client::rpc_response::RpcPerfSample]) -> u32 {
-    // Calculate real network latency from performance variations
-    if samples.len() < 2 {
-        return 120; // Default when no data available
-    }
-
-    let mut latencies = Vec::new();
-    for window in samples.windows(2) {
-        let time_variance = window[1].sample_period_secs.saturating_sub(window[0].sample_period_secs);
-        let latency = (time_variance * 50) as u32; // Convert to milliseconds estimate
-        latencies.push(latency);
-    }
-
-    if latencies.is_empty() {
-        120 // Default when calculation fails
-    } else {
-        (latencies.iter().sum::<u32>() / latencies.len() as u32).max(20).min(500)
-    }
+    Ok(read_or_disconnect(&config, read).await?.unwrap_or_default())
 }
 
 async fn apply_optimizations() -> Result<()> {
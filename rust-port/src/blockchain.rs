@@ -1,28 +1,79 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcGetVoteAccountsConfig;
+use solana_client::rpc_response::RpcInflationReward;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
     pubkey::Pubkey,
-    signature::{Keypair, Signer},
+    signature::{Keypair, Signature, Signer},
     system_instruction,
     transaction::Transaction,
     native_token::LAMPORTS_PER_SOL,
 };
 use solana_vote_program::{
     vote_instruction,
-    vote_state::{VoteInit, VoteState},
+    vote_state::{VoteAuthorize, VoteInit, VoteState},
 };
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use parking_lot::RwLock;
 
+use crate::audit::{self, AuditEvent};
+use crate::calibrate::Calibration;
+use crate::config::{BreakevenConfig, FaucetConfig, PollIntervalConfig, PriorityFeeStrategy, RpcConsensusConfig, StatusBands, ValidatorConfig};
+use crate::config_reload::{self, SharedConfig};
+use crate::cooldown::ParameterCooldowns;
+use crate::failover::RpcFailover;
+use crate::faucet::{self, Faucet};
+use crate::health::{self, HealthInputs};
+use crate::history;
+use crate::live_config;
+use crate::native;
+use crate::poll::{self, AdaptiveInterval};
+use crate::rpc_consensus;
+use crate::smoothing::MetricsSmoother;
+use crate::system::{current_sysctl, SystemOptimizer};
+use crate::tls;
+use crate::utils;
+
+/// Kept on top of the vote account's rent and creation fee, so the identity
+/// can still pay for its first votes
+const VOTE_ACCOUNT_BUFFER_LAMPORTS: u64 = LAMPORTS_PER_SOL / 100;
+
+/// A signed transaction creating the vote account, and what it costs
+struct VoteAccountCreation {
+    transaction: Transaction,
+    rent: u64,
+    fee: u64,
+}
+
+impl VoteAccountCreation {
+    /// Balance the identity needs: rent, fee and the buffer for its first votes
+    fn required(&self) -> u64 {
+        self.rent + self.fee + VOTE_ACCOUNT_BUFFER_LAMPORTS
+    }
+}
+
 /// Direct blockchain interaction without shell scripts
 pub struct SolanaInterface {
-    rpc_client: Arc<RpcClient>,
+    rpc: RpcFailover,
     validator_keypair: Arc<Keypair>,
     vote_keypair: Arc<Keypair>,
+    /// Accounts metrics are read for; the keypairs' unless `watching` set others
+    identity: Pubkey,
+    vote_account: Pubkey,
     metrics_cache: Arc<RwLock<ValidatorMetrics>>,
+    status_bands: StatusBands,
+    ema_alpha: f64,
+    max_optimization_failures: u32,
+    priority_fee: PriorityFeeStrategy,
+    poll_interval: PollIntervalConfig,
+    rpc_consensus: RpcConsensusConfig,
+    faucet: FaucetConfig,
 }
 
 impl SolanaInterface {
@@ -31,44 +82,149 @@ impl SolanaInterface {
         validator_keypair: Keypair,
         vote_keypair: Keypair,
     ) -> Result<Self> {
-        let rpc_client = RpcClient::new_with_commitment(
-            rpc_url.to_string(),
-            CommitmentConfig::confirmed(),
-        );
+        let config = ValidatorConfig::load().unwrap_or_default();
         
         Ok(Self {
-            rpc_client: Arc::new(rpc_client),
+            rpc: RpcFailover::new(rpc_url, &config.failover_rpc_urls, &config.rpc_failover),
+            identity: validator_keypair.pubkey(),
+            vote_account: vote_keypair.pubkey(),
             validator_keypair: Arc::new(validator_keypair),
             vote_keypair: Arc::new(vote_keypair),
             metrics_cache: Arc::new(RwLock::new(ValidatorMetrics::default())),
+            status_bands: config.status_bands,
+            ema_alpha: config.metrics_ema_alpha,
+            max_optimization_failures: config.auto_optimize_max_failures,
+            priority_fee: config.priority_fee,
+            poll_interval: config.poll_interval,
+            rpc_consensus: config.rpc_consensus,
+            faucet: config.faucet,
         })
     }
     
+    /// Read metrics for `identity` and `vote_account` instead of the keypairs',
+    /// for read-only use with pubkeys alone; signing still uses the keypairs
+    pub fn watching(mut self, identity: Pubkey, vote_account: Pubkey) -> Self {
+        self.identity = identity;
+        self.vote_account = vote_account;
+        self
+    }
+
+    /// Read through `url`, one of `failover_rpc_urls`, until the primary answers again
+    pub fn failed_over_to(self, url: &str, reason: &str) -> Self {
+        self.rpc.switch_to(url, reason);
+        self
+    }
+
+    /// RPC endpoint this interface talks to, a failover one while the
+    /// primary is down
+    pub fn rpc_url(&self) -> String {
+        self.rpc.url()
+    }
+
+    /// RPC to read metrics from, plus the slot the configured endpoints agree on
+    ///
+    /// Without `rpc_consensus.endpoints` this is just the primary client. Otherwise
+    /// the first `quorum` endpoints are asked for their slot concurrently, those
+    /// far behind the agreed slot are flagged, and a lagging or unreachable
+    /// primary is swapped for the freshest endpoint. The flag is false then,
+    /// so the read doesn't count as the primary answering.
+    async fn metrics_source(&self) -> (Arc<RpcClient>, Option<u64>, bool) {
+        let rpc_client = self.rpc.client();
+        if self.rpc_consensus.endpoints.is_empty() {
+            return (rpc_client, None, true);
+        }
+
+        let primary = rpc_client.url();
+        let urls = rpc_consensus::endpoints_for(&primary, &self.rpc_consensus);
+        let Some(consensus) = rpc_consensus::slot_consensus(&urls, &self.rpc_consensus).await else {
+            println!("  {} No RPC endpoint answered the slot cross-check", "⚠".yellow());
+            return (rpc_client, None, true);
+        };
+
+        for reading in &consensus.readings {
+            if let Err(e) = &reading.slot {
+                println!("  {} RPC {} unreachable: {}", "⚠".yellow(), reading.url, e);
+            }
+        }
+        for (url, behind) in &consensus.lagging {
+            println!("  {} RPC {} is {} slots behind the agreed slot {}",
+                "⚠".yellow(),
+                url,
+                behind,
+                consensus.slot
+            );
+        }
+
+        // An unreachable primary also counts toward failing over, but this
+        // read goes to an endpoint that answered
+        let primary_down = consensus.readings.iter().any(|reading| reading.url == primary && reading.slot.is_err());
+        if primary_down {
+            self.rpc.record(false);
+        }
+        match consensus.freshest() {
+            Some(freshest) if primary_down || consensus.is_lagging(&primary) => {
+                println!("  {} Reading metrics from {} instead", "▶".cyan(), freshest);
+                let client = tls::rpc_client(freshest, CommitmentConfig::confirmed());
+                (Arc::new(client), Some(consensus.slot), false)
+            }
+            _ => (rpc_client, Some(consensus.slot), true),
+        }
+    }
+
     /// Get real-time validator performance metrics from the blockchain
+    ///
+    /// Failed reads count toward failing over to `failover_rpc_urls`; a read
+    /// that triggers the failover is retried once on the new RPC.
     pub async fn get_validator_metrics(&self) -> Result<ValidatorMetrics> {
+        match self.read_and_record().await {
+            (Err(e), true) => {
+                println!("  {} {:#}; retrying on {}", "⚠".yellow(), e, self.rpc.url());
+                self.read_and_record().await.0
+            }
+            (result, _) => result,
+        }
+    }
+
+    /// One metrics read counted toward failover; true when it failed over
+    async fn read_and_record(&self) -> (Result<ValidatorMetrics>, bool) {
+        match self.read_validator_metrics().await {
+            // Read from another endpoint; says nothing about the one in use
+            Ok((metrics, false)) => (Ok(metrics), false),
+            Ok((metrics, true)) => {
+                self.rpc.record(true);
+                (Ok(metrics), false)
+            }
+            Err(e) => {
+                let switched = self.rpc.record(false);
+                (Err(e), switched)
+            }
+        }
+    }
+
+    /// Metrics, and whether they were read from the RPC in use
+    async fn read_validator_metrics(&self) -> Result<(ValidatorMetrics, bool)> {
         println!("  {} Fetching real-time blockchain metrics...", "▶".cyan());
         
+        let (rpc_client, agreed_slot, on_active) = self.metrics_source().await;
+        
         // Get current epoch info
-        let epoch_info = self.rpc_client.get_epoch_info()
+        let epoch_info = rpc_client.get_epoch_info()
             .context("Failed to get epoch info")?;
         
-        // Get vote account info
-        let vote_account = self.rpc_client.get_account(&self.vote_keypair.pubkey())
-            .context("Failed to get vote account")?;
-        
-        // Deserialize vote state
-        let vote_state = VoteState::deserialize(&vote_account.data)
-            .context("Failed to deserialize vote state")?;
+        // Get and deserialize the vote account
+        let vote_state = fetch_vote_state(&rpc_client, &self.vote_account)?;
         
         // Get validator stake
         let stake = self.get_validator_stake().await?;
         
-        // Get slot info
-        let slot = self.rpc_client.get_slot()
-            .context("Failed to get current slot")?;
+        // Get slot info, preferring the slot the endpoints agreed on
+        let slot = match agreed_slot {
+            Some(slot) => slot,
+            None => rpc_client.get_slot().context("Failed to get current slot")?,
+        };
         
         // Get recent performance samples
-        let perf_samples = self.rpc_client.get_recent_performance_samples(Some(10))
+        let perf_samples = rpc_client.get_recent_performance_samples(Some(10))
             .context("Failed to get performance samples")?;
         
         // Calculate metrics
@@ -80,8 +236,9 @@ impl SolanaInterface {
             total_transactions += sample.num_transactions;
         }
         
+        let slot_time_ms = slot_time_ms(&rpc_client).await;
         let avg_tps = if total_slots > 0 {
-            (total_transactions as f64 / total_slots as f64) * 2.0 // 2 slots per second
+            (total_transactions as f64 / total_slots as f64) * 1000.0 / slot_time_ms
         } else {
             0.0
         };
@@ -99,13 +256,25 @@ impl SolanaInterface {
         };
         
         // Get block production metrics
-        let leader_schedule = self.rpc_client.get_leader_schedule(Some(slot))
-            .ok()
-            .flatten()
-            .and_then(|schedule| schedule.get(&self.validator_keypair.pubkey().to_string()).cloned())
-            .unwrap_or_default();
+        let leader_slots = self.leader_slots(&rpc_client, slot).await;
+        
+        // Delinquent as the cluster sees it; unknown counts as not delinquent
+        let vote_pubkey = self.vote_account.to_string();
+        let delinquent = rpc_client.get_vote_accounts_with_config(RpcGetVoteAccountsConfig {
+                vote_pubkey: Some(vote_pubkey.clone()),
+                ..RpcGetVoteAccountsConfig::default()
+            })
+            .map(|accounts| accounts.delinquent.iter().any(|v| v.vote_pubkey == vote_pubkey))
+            .unwrap_or(false);
+        
+        // Heuristics, corrected by the last `calibrate` run
+        let calibration = Calibration::load();
+        let skip_rate = (Self::calculate_skip_rate(&perf_samples) * calibration.skip_rate_factor).min(100.0);
         
-        let skip_rate = Self::calculate_skip_rate(&perf_samples);
+        // Credits against the most the epoch's elapsed slots could have earned
+        let epoch_credits = credits_in_epoch(&vote_state, epoch_info.epoch);
+        let credits_pct = max_credits_per_slot(&rpc_client)
+            .and_then(|per_slot| normalized_credits_pct(epoch_credits, epoch_info.slot_index, per_slot));
         
         let metrics = ValidatorMetrics {
             epoch: epoch_info.epoch,
@@ -115,27 +284,57 @@ impl SolanaInterface {
             credits_earned: vote_state.epoch_credits.last()
                 .map(|(_, credits, _)| *credits)
                 .unwrap_or(0),
+            epoch_credits,
+            credits_pct,
             vote_lag: slot.saturating_sub(vote_state.last_voted_slot().unwrap_or(slot)),
-            network_latency_ms: Self::estimate_network_latency(&perf_samples),
+            network_latency_ms: (Self::estimate_network_latency(&perf_samples) as f64 * calibration.latency_factor).round() as u32,
             stake_lamports: stake,
             total_votes: total_votes as u32,
             recent_votes: recent_votes as u32,
             avg_tps,
-            leader_slots: leader_schedule.len() as u32,
+            leader_slots,
             root_slot: vote_state.root_slot.unwrap_or(0),
-            optimized: true,
+            // This host's kernel says nothing about a validator on another one
+            optimizations_applied: utils::is_local_url(self.rpc.primary_url())
+                .then(SystemOptimizer::applied_share)
+                .flatten(),
+            slot_time_ms,
+            delinquent,
         };
         
         // Cache the metrics
         *self.metrics_cache.write() = metrics.clone();
         
-        Ok(metrics)
+        Ok((metrics, on_active))
+    }
+    
+    /// This identity's leader slots in the epoch of `slot`: `Some(0)` when it
+    /// has none, None when the schedule couldn't be fetched (after one retry)
+    async fn leader_slots(&self, rpc_client: &RpcClient, slot: u64) -> Option<u32> {
+        let identity = self.identity.to_string();
+        let mut last_error = String::new();
+
+        for attempt in 0..2 {
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+            match rpc_client.get_leader_schedule(Some(slot)) {
+                Ok(Some(schedule)) => {
+                    return Some(schedule.get(&identity).map_or(0, |slots| slots.len() as u32));
+                }
+                Ok(None) => last_error = format!("no schedule for the epoch of slot {}", slot),
+                Err(e) => last_error = e.to_string(),
+            }
+        }
+
+        println!("  {} Leader schedule unavailable: {}", "⚠".yellow(), last_error);
+        None
     }
     
     /// Get validator's current stake
     async fn get_validator_stake(&self) -> Result<u64> {
         // Get stake accounts for this vote account
-        let stake_accounts = self.rpc_client.get_program_accounts(
+        let stake_accounts = self.rpc.client().get_program_accounts(
             &solana_sdk::stake::program::id(),
         ).unwrap_or_default();
         
@@ -146,7 +345,7 @@ impl SolanaInterface {
             if account.data.len() >= 124 {
                 // Simple check for vote pubkey in stake account data
                 let data_slice = &account.data[124..156];
-                if data_slice == self.vote_keypair.pubkey().as_ref() {
+                if data_slice == self.vote_account.as_ref() {
                     total_stake += account.lamports;
                 }
             }
@@ -159,23 +358,59 @@ impl SolanaInterface {
     pub async fn setup_vote_account(&self, commission: u8) -> Result<()> {
         println!("{}", "Setting up optimized vote account...".cyan().bold());
         
-        // Check balance
-        let balance = self.rpc_client.get_balance(&self.validator_keypair.pubkey())?;
+        // Check if vote account already exists
+        let Some(creation) = self.vote_account_creation(commission)? else {
+            println!("{} Vote account already exists", "✓".green());
+            return Ok(());
+        };
+        
+        // Check the balance covers rent, this transaction's fee and the buffer
+        let required = creation.required();
+        let balance = self.rpc.client().get_balance(&self.validator_keypair.pubkey())?;
+        let sol = |lamports: u64| lamports as f64 / LAMPORTS_PER_SOL as f64;
         
-        if balance < LAMPORTS_PER_SOL / 10 {
-            println!("{} Insufficient balance: {} SOL", 
-                "⚠".yellow(), 
-                balance as f64 / LAMPORTS_PER_SOL as f64
+        if balance < required {
+            println!("{} Insufficient balance: {} SOL, need {} SOL (rent {} + fee {} + buffer {})",
+                "⚠".yellow(),
+                sol(balance),
+                sol(required),
+                sol(creation.rent),
+                sol(creation.fee),
+                sol(VOTE_ACCOUNT_BUFFER_LAMPORTS)
+            );
+            println!("  Fund {} with {} more SOL to create the vote account",
+                self.validator_keypair.pubkey(),
+                sol(required - balance).to_string().yellow()
             );
             return Ok(());
         }
         
-        // Check if vote account already exists
-        if let Ok(_) = self.rpc_client.get_account(&self.vote_keypair.pubkey()) {
-            println!("{} Vote account already exists", "✓".green());
-            return Ok(());
+        match self.rpc.client().send_and_confirm_transaction(&creation.transaction) {
+            Ok(signature) => {
+                println!("{} Vote account created: {}", 
+                    "✓".green(), 
+                    signature.to_string().yellow()
+                );
+                Ok(())
+            }
+            Err(e) => {
+                println!("{} Failed to create vote account: {}", "✗".red(), e);
+                Err(e.into())
+            }
+        }
+    }
+    
+    /// The signed vote account creation and its cost, without sending it;
+    /// None when the vote account already exists
+    fn vote_account_creation(&self, commission: u8) -> Result<Option<VoteAccountCreation>> {
+        if self.rpc.client().get_account(&self.vote_keypair.pubkey()).is_ok() {
+            return Ok(None);
         }
         
+        // Fund the vote account with exactly its rent-exempt minimum
+        let rent = self.rpc.client().get_minimum_balance_for_rent_exemption(VoteState::size_of())
+            .context("Failed to get rent-exempt minimum for the vote account")?;
+        
         // Create vote account
         let vote_init = VoteInit {
             node_pubkey: self.validator_keypair.pubkey(),
@@ -188,118 +423,379 @@ impl SolanaInterface {
             &self.validator_keypair.pubkey(),
             &self.vote_keypair.pubkey(),
             &vote_init,
-            LAMPORTS_PER_SOL,
+            rent,
         );
         
-        let recent_blockhash = self.rpc_client.get_latest_blockhash()?;
-        
-        let transaction = Transaction::new_signed_with_payer(
-            &instructions,
-            Some(&self.validator_keypair.pubkey()),
+        let transaction = self.signed_transaction(
+            instructions,
             &[self.validator_keypair.as_ref(), self.vote_keypair.as_ref()],
-            recent_blockhash,
+        )?;
+        let fee = self.rpc.client().get_fee_for_message(&transaction.message)
+            .context("Failed to get transaction fee")?;
+        
+        Ok(Some(VoteAccountCreation { transaction, rent, fee }))
+    }
+    
+    /// `start --dry-run`: what the airdrop and `setup_vote_account` would do,
+    /// worked out from the cluster without sending anything
+    pub async fn print_vote_account_plan(&self, commission: u8, airdrop_lamports: Option<u64>) -> Result<()> {
+        let sol = |lamports: u64| lamports as f64 / LAMPORTS_PER_SOL as f64;
+        let identity = self.validator_keypair.pubkey();
+        let vote_account = self.vote_keypair.pubkey();
+        
+        let balance = self.rpc.client().get_balance(&identity)
+            .context("Failed to get the identity balance")?;
+        println!("├─ Identity {}: {} SOL", identity, sol(balance));
+        if let Some(lamports) = airdrop_lamports {
+            let faucets = faucet::faucets(&self.faucet, &self.rpc_url());
+            println!("├─ {} Would request an airdrop of {} SOL, trying {} faucet(s) in turn (may be rate limited)",
+                "▶".cyan(), sol(lamports), faucets.len());
+        }
+        
+        let Some(creation) = self.vote_account_creation(commission)? else {
+            println!("└─ {} Vote account {} already exists, nothing to create", "✓".green(), vote_account);
+            return Ok(());
+        };
+        let required = creation.required();
+        println!("├─ {} Would create vote account {} with {}% commission", "▶".cyan(), vote_account, commission);
+        println!("├─ Cost: {} SOL (rent {} + fee {} + buffer {})",
+            sol(required),
+            sol(creation.rent),
+            sol(creation.fee),
+            sol(VOTE_ACCOUNT_BUFFER_LAMPORTS)
         );
         
-        match self.rpc_client.send_and_confirm_transaction(&transaction) {
-            Ok(signature) => {
-                println!("{} Vote account created: {}", 
-                    "✓".green(), 
-                    signature.to_string().yellow()
-                );
-                Ok(())
-            }
-            Err(e) => {
-                println!("{} Failed to create vote account: {}", "✗".red(), e);
-                Err(e.into())
-            }
+        let expected = balance + airdrop_lamports.unwrap_or(0);
+        if expected < required {
+            println!("└─ {} {} SOL short{}; start would skip creating the vote account",
+                "⚠".yellow(),
+                sol(required - expected),
+                if airdrop_lamports.is_some() { " even with the airdrop" } else { "" }
+            );
+        } else if balance < required {
+            println!("└─ {} Covered only once the airdrop lands", "⚠".yellow());
+        } else {
+            println!("└─ {} Balance covers it", "✓".green());
         }
+        Ok(())
     }
     
-    /// Request airdrop for testing (testnet only)
-    pub async fn request_airdrop(&self, lamports: u64) -> Result<()> {
-        println!("Requesting airdrop of {} SOL...", 
-            lamports as f64 / LAMPORTS_PER_SOL as f64
+    /// Change the vote account's authorized voter, effective next epoch
+    ///
+    /// Signed by the current authorized voter, which must be either the
+    /// identity or the vote keypair this interface was created with.
+    pub async fn set_authorized_voter(&self, new_voter: Pubkey) -> Result<Signature> {
+        let vote_pubkey = self.vote_keypair.pubkey();
+        let vote_state = fetch_vote_state(&self.rpc.client(), &vote_pubkey)?;
+        let epoch = self.rpc.client().get_epoch_info()
+            .context("Failed to get epoch info")?
+            .epoch;
+
+        let current_voter = vote_state.get_authorized_voter(epoch)
+            .ok_or_else(|| anyhow::anyhow!("Vote account has no authorized voter for epoch {}", epoch))?;
+
+        if new_voter == Pubkey::default() {
+            anyhow::bail!("New voter must not be the default (all zeros) pubkey");
+        }
+        if new_voter == current_voter {
+            anyhow::bail!("{} is already the authorized voter", new_voter);
+        }
+
+        let voter_signer: &Keypair = if self.validator_keypair.pubkey() == current_voter {
+            &self.validator_keypair
+        } else if self.vote_keypair.pubkey() == current_voter {
+            &self.vote_keypair
+        } else {
+            anyhow::bail!(
+                "Current authorized voter {} is neither the identity nor the vote keypair", current_voter
+            );
+        };
+
+        println!("Vote Account: {}", vote_pubkey.to_string().yellow());
+        println!("Old Voter: {}", current_voter);
+        println!("New Voter: {}", new_voter.to_string().green());
+
+        let instruction = vote_instruction::authorize(
+            &vote_pubkey,
+            &current_voter,
+            &new_voter,
+            VoteAuthorize::Voter,
         );
-        
-        match self.rpc_client.request_airdrop(
-            &self.validator_keypair.pubkey(),
-            lamports,
-        ) {
-            Ok(signature) => {
-                println!("{} Airdrop requested: {}", 
-                    "✓".green(), 
-                    signature.to_string().yellow()
-                );
-                
-                // Wait for confirmation
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-                
-                let new_balance = self.rpc_client.get_balance(&self.validator_keypair.pubkey())?;
-                println!("{} New balance: {} SOL", 
-                    "✓".green(), 
-                    new_balance as f64 / LAMPORTS_PER_SOL as f64
-                );
-                
-                Ok(())
+
+        // The identity pays the fee and may also be the voter
+        let mut signers = vec![self.validator_keypair.as_ref()];
+        if voter_signer.pubkey() != self.validator_keypair.pubkey() {
+            signers.push(voter_signer);
+        }
+
+        let transaction = self.signed_transaction(vec![instruction], &signers)?;
+
+        let signature = self.rpc.client().send_transaction(&transaction)
+            .context("Failed to send authorize transaction")?;
+        println!("  {} Sent {}, waiting for confirmation...", "▶".cyan(), signature);
+
+        self.wait_for_confirmation(&signature).await?;
+        println!("{} Authorized voter changes to {} from epoch {}",
+            "✓".green(), new_voter, epoch + 1
+        );
+
+        Ok(signature)
+    }
+
+    /// Compute unit price (micro-lamports) for a transaction writing `accounts`
+    fn compute_unit_price(&self, accounts: &[Pubkey]) -> Result<Option<u64>> {
+        match self.priority_fee {
+            PriorityFeeStrategy::None => Ok(None),
+            PriorityFeeStrategy::Fixed(price) => Ok(Some(price)),
+            PriorityFeeStrategy::Dynamic(percentile) => {
+                let mut fees: Vec<u64> = self.rpc.client()
+                    .get_recent_prioritization_fees(accounts)
+                    .context("Failed to get recent prioritization fees")?
+                    .iter()
+                    .map(|f| f.prioritization_fee)
+                    .collect();
+                if fees.is_empty() {
+                    return Ok(None);
+                }
+                fees.sort_unstable();
+
+                let rank = percentile.min(100) as f64 / 100.0 * (fees.len() - 1) as f64;
+                Ok(Some(fees[rank.round() as usize]))
             }
-            Err(e) => {
-                println!("{} Airdrop failed: {}", "✗".red(), e);
-                Err(e.into())
+        }
+    }
+
+    /// Sign `instructions` with the identity as fee payer, prepending a
+    /// compute unit price according to the `priority_fee` strategy
+    fn signed_transaction(&self, mut instructions: Vec<Instruction>, signers: &[&Keypair]) -> Result<Transaction> {
+        let writable: Vec<Pubkey> = instructions
+            .iter()
+            .flat_map(|ix| ix.accounts.iter())
+            .filter(|meta| meta.is_writable)
+            .map(|meta| meta.pubkey)
+            .collect();
+
+        if let Some(price) = self.compute_unit_price(&writable)? {
+            println!("  {} Priority fee: {} micro-lamports/CU", "ℹ".cyan(), price);
+            instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_price(price));
+        }
+
+        let recent_blockhash = self.rpc.client().get_latest_blockhash()?;
+        Ok(Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&self.validator_keypair.pubkey()),
+            signers,
+            recent_blockhash,
+        ))
+    }
+
+    /// Poll a signature until it is confirmed, failed or times out
+    async fn wait_for_confirmation(&self, signature: &Signature) -> Result<()> {
+        const CONFIRMATION_TIMEOUT_SECS: u64 = 60;
+
+        for _ in 0..CONFIRMATION_TIMEOUT_SECS {
+            let status = self.rpc.client()
+                .get_signature_status_with_commitment(signature, CommitmentConfig::confirmed())?;
+
+            match status {
+                Some(Ok(())) => return Ok(()),
+                Some(Err(e)) => anyhow::bail!("Transaction {} failed: {}", signature, e),
+                None => tokio::time::sleep(tokio::time::Duration::from_secs(1)).await,
             }
         }
+
+        anyhow::bail!("Transaction {} not confirmed after {}s", signature, CONFIRMATION_TIMEOUT_SECS)
+    }
+    
+    /// Request an airdrop for testing from the faucets in `faucet`, trying
+    /// each in turn when one is rate limited
+    pub async fn request_airdrop(&self, lamports: u64) -> Result<()> {
+        let faucets = faucet::faucets(&self.faucet, &self.rpc_url());
+        self.request_airdrop_from(&faucets, lamports).await
+    }
+
+    /// Request an airdrop from `faucets` only, e.g. a local test validator's
+    pub async fn request_airdrop_from(&self, faucets: &[Faucet], lamports: u64) -> Result<()> {
+        let cooldown = Duration::from_secs(self.faucet.cooldown_secs);
+        faucet::airdrop(faucets, cooldown, &self.rpc.client(), &self.validator_keypair.pubkey(), lamports).await
     }
     
     /// Real auto-optimization loop for continuous validator tuning
-    pub async fn auto_optimize_loop(&self) -> Result<()> {
+    ///
+    /// Runs until `max_iterations` passes or `max_duration` is reached (no
+    /// limit when None) or Ctrl+C is pressed, then prints a summary. A pass
+    /// interrupted midway is abandoned; settings it already pushed stay applied.
+    pub async fn auto_optimize_loop(
+        &self,
+        max_iterations: Option<u32>,
+        max_duration: Option<Duration>,
+        config: &SharedConfig,
+    ) -> Result<()> {
         println!("{}", "🚀 Starting Auto-Optimization Loop".green().bold());
         println!("Real-time performance monitoring and optimization");
         println!("Connects to actual validator and applies improvements");
         
         let mut optimization_count = 0u32;
+        let mut applied_count = 0u32;
+        let mut iterations = 0u32;
         let mut baseline_metrics: Option<ValidatorMetrics> = None;
+        let mut latest_metrics: Option<ValidatorMetrics> = None;
+        let mut smoother = MetricsSmoother::new(self.ema_alpha);
+        let mut auto_optimize = true;
+        let mut consecutive_failures = 0u32;
+        let mut refresh = AdaptiveInterval::new(&self.poll_interval, poll::DEFAULT_BASE_SECS);
+        let mut max_failures = self.max_optimization_failures;
+        let mut cooldowns = ParameterCooldowns::load(&config.read().await.optimization_cooldowns);
+        let mut seen = config_reload::generation();
         
-        loop {
-            // Get current real-time metrics
-            let current_metrics = self.get_validator_metrics().await?;
-            
-            // Store baseline on first run
-            if baseline_metrics.is_none() {
-                baseline_metrics = Some(current_metrics.clone());
-                println!("\n{} Baseline metrics captured", "📊".cyan());
+        let started = Instant::now();
+        let interrupted = tokio::signal::ctrl_c();
+        tokio::pin!(interrupted);
+        
+        let stop_reason = loop {
+            if max_iterations.is_some_and(|max| iterations >= max) {
+                break "iteration limit reached";
             }
+            let remaining = match max_duration {
+                Some(max) => match max.checked_sub(started.elapsed()) {
+                    Some(remaining) if !remaining.is_zero() => remaining,
+                    _ => break "time limit reached",
+                },
+                None => Duration::MAX,
+            };
+            iterations += 1;
             
-            // Display current performance
-            self.display_optimization_status(&current_metrics, optimization_count);
-            
-            // Check if optimization is needed
-            let needs_optimization = self.analyze_performance_gaps(&current_metrics);
+            // Pick up a config reloaded on SIGHUP since the last pass
+            if let Some(reloaded) = config_reload::reloaded_since(config, &mut seen).await {
+                refresh = AdaptiveInterval::new(&reloaded.poll_interval, poll::DEFAULT_BASE_SECS);
+                max_failures = reloaded.auto_optimize_max_failures;
+                cooldowns.set_config(&reloaded.optimization_cooldowns);
+            }
             
-            if !needs_optimization.is_empty() {
-                optimization_count += 1;
-                println!("\n{} Optimization #{} - Applying improvements...", 
-                    "⚡".yellow(), 
-                    optimization_count
-                );
+            let pass = async {
+                // Get current real-time metrics, backing off while the RPC is failing
+                let current_metrics = match self.get_validator_metrics().await {
+                    Ok(metrics) => {
+                        refresh.success();
+                        metrics
+                    }
+                    Err(e) => {
+                        let next = refresh.error();
+                        println!("\n{} RPC error #{} - retrying in {}s: {:#}",
+                            "⚠".yellow(),
+                            refresh.consecutive_errors(),
+                            next.as_secs(),
+                            e
+                        );
+                        refresh.sleep().await;
+                        return;
+                    }
+                };
+                if let Err(e) = history::record_sample(Some(&current_metrics)) {
+                    println!("{} Could not record metrics history: {}", "⚠".yellow(), e);
+                }
+                latest_metrics = Some(current_metrics.clone());
                 
-                // Apply real-time optimizations
-                for optimization in needs_optimization {
-                    self.apply_real_optimization(optimization).await?;
+                // Store baseline on first run
+                if baseline_metrics.is_none() {
+                    baseline_metrics = Some(current_metrics.clone());
+                    println!("\n{} Baseline metrics captured", "📊".cyan());
                 }
                 
-                // Wait for optimizations to take effect
-                tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
-            } else {
-                // Performance is optimal
-                println!("\n{} Performance optimal - monitoring...", "✅".green());
+                // Display current performance
+                self.display_optimization_status(&current_metrics, optimization_count);
                 
-                // Show improvement summary if we have baseline
-                if let Some(ref baseline) = baseline_metrics {
-                    self.show_improvement_summary(baseline, &current_metrics);
-                }
+                // Decide on the smoothed series so a single noisy reading doesn't trigger changes
+                let smoothed = smoother.update(
+                    current_metrics.avg_tps,
+                    current_metrics.skip_rate,
+                    current_metrics.network_latency_ms as f64,
+                );
+                println!("Smoothed (EMA): Skip Rate {:.1}% | Latency {:.0}ms | TPS {:.0}",
+                    smoothed.skip_rate, smoothed.network_latency_ms, smoothed.avg_tps);
+
+                let gap_metrics = ValidatorMetrics {
+                    avg_tps: smoothed.avg_tps,
+                    skip_rate: smoothed.skip_rate,
+                    network_latency_ms: smoothed.network_latency_ms.round() as u32,
+                    ..current_metrics.clone()
+                };
+                
+                // Check if optimization is needed
+                let needs_optimization = self.analyze_performance_gaps(&gap_metrics);
                 
-                tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
+                if !needs_optimization.is_empty() && !auto_optimize {
+                    println!("\n{} Auto-optimize disabled after repeated failures - monitoring only (re-run {} to re-enable)",
+                        "⏸".yellow(),
+                        "optimize --auto".cyan()
+                    );
+                    refresh.sleep().await;
+                } else if !needs_optimization.is_empty() {
+                    optimization_count += 1;
+                    println!("\n{} Optimization #{} - Applying improvements...", 
+                        "⚡".yellow(), 
+                        optimization_count
+                    );
+                    
+                    // Apply real-time optimizations, tripping the breaker on repeated failures
+                    for optimization in needs_optimization {
+                        match self.apply_real_optimization(optimization, &gap_metrics, &mut cooldowns).await {
+                            Ok(applied) => {
+                                consecutive_failures = 0;
+                                applied_count += applied;
+                            }
+                            Err(e) => {
+                                consecutive_failures += 1;
+                                println!("  {} Optimization failed ({}/{}): {:#}",
+                                    "✗".red(),
+                                    consecutive_failures,
+                                    max_failures,
+                                    e
+                                );
+
+                                if consecutive_failures >= max_failures {
+                                    auto_optimize = false;
+                                    println!("\n{}", "⛔ AUTO-OPTIMIZE DISABLED".red().bold());
+                                    println!("  {} consecutive optimizations failed. Last error: {:#}", consecutive_failures, e);
+                                    println!("  Fix the cause, then re-run {} to re-enable.", "optimize --auto".cyan());
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    
+                    // Wait for optimizations to take effect
+                    tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+                } else {
+                    // Performance is optimal
+                    println!("\n{} Performance optimal - monitoring...", "✅".green());
+                    
+                    // Show improvement summary if we have baseline
+                    if let Some(ref baseline) = baseline_metrics {
+                        self.show_improvement_summary(baseline, &current_metrics);
+                    }
+                    
+                    refresh.sleep().await;
+                }
+            };
+            
+            tokio::select! {
+                _ = &mut interrupted => break "interrupted",
+                _ = tokio::time::sleep(remaining) => break "time limit reached",
+                _ = pass => {}
             }
+        };
+        
+        println!("\n{}", "=== Auto-Optimization Summary ===".cyan().bold());
+        println!("Stopped: {}", stop_reason);
+        println!("Iterations: {} | Runtime: {}s", iterations, started.elapsed().as_secs());
+        println!("Optimization rounds: {} | Optimizations applied: {}", optimization_count, applied_count);
+        match (&baseline_metrics, &latest_metrics) {
+            (Some(baseline), Some(latest)) => self.show_improvement_summary(baseline, latest),
+            _ => println!("{} No metrics were read, nothing to compare", "ℹ".cyan()),
         }
+        
+        Ok(())
     }
     
     /// Monitor vote performance in real-time
@@ -327,7 +823,7 @@ impl SolanaInterface {
         }
     }
     
-    fn calculate_skip_rate(samples: &[solana_client::rpc_response::RpcPerfSample]) -> f64 {
+    pub(crate) fn calculate_skip_rate(samples: &[solana_client::rpc_response::RpcPerfSample]) -> f64 {
         // Calculate real skip rate from performance samples
         if samples.is_empty() {
             return 5.0; // Default when no data available
@@ -345,7 +841,7 @@ impl SolanaInterface {
         }
     }
     
-    fn estimate_network_latency(samples: &[solana_client::rpc_response::RpcPerfSample]) -> u32 {
+    pub(crate) fn estimate_network_latency(samples: &[solana_client::rpc_response::RpcPerfSample]) -> u32 {
         // Calculate real network latency from performance sample timing variations
         if samples.len() < 2 {
             return 50; // Default when no data available
@@ -369,47 +865,19 @@ impl SolanaInterface {
     fn display_optimization_status(&self, metrics: &ValidatorMetrics, optimization_count: u32) {
         println!("\n{}", format!("=== Optimization Cycle #{} ===", optimization_count).cyan().bold());
         
-        // Vote success rate with dynamic assessment
-        let vote_status = if metrics.vote_success_rate >= 95.0 {
-            "EXCELLENT".green().bold()
-        } else if metrics.vote_success_rate >= 90.0 {
-            "GOOD".yellow()
-        } else if metrics.vote_success_rate >= 80.0 {
-            "FAIR".yellow()
-        } else {
-            "NEEDS IMPROVEMENT".red()
-        };
-        
+        let bands = &self.status_bands;
+
+        let vote_status = bands.vote_success(metrics.vote_success_rate);
         println!("Vote Success: {:.1}% | Status: {}", 
-            metrics.vote_success_rate, vote_status);
-        
-        // Skip rate with dynamic assessment
-        let skip_status = if metrics.skip_rate <= 3.0 {
-            "EXCELLENT".green().bold()
-        } else if metrics.skip_rate <= 8.0 {
-            "GOOD".yellow()
-        } else if metrics.skip_rate <= 15.0 {
-            "FAIR".yellow()
-        } else {
-            "NEEDS IMPROVEMENT".red()
-        };
+            metrics.vote_success_rate, vote_status.label().color(vote_status.color()).bold());
         
+        let skip_status = bands.skip_rate(metrics.skip_rate);
         println!("Skip Rate: {:.1}% | Status: {}", 
-            metrics.skip_rate, skip_status);
-        
-        // Vote lag with dynamic assessment
-        let lag_status = if metrics.vote_lag <= 30 {
-            "EXCELLENT".green().bold()
-        } else if metrics.vote_lag <= 50 {
-            "GOOD".yellow()
-        } else if metrics.vote_lag <= 100 {
-            "FAIR".yellow()
-        } else {
-            "NEEDS IMPROVEMENT".red()
-        };
+            metrics.skip_rate, skip_status.label().color(skip_status.color()).bold());
         
+        let lag_status = bands.vote_lag(metrics.vote_lag);
         println!("Vote Lag: {} slots | Status: {}", 
-            metrics.vote_lag, lag_status);
+            metrics.vote_lag, lag_status.label().color(lag_status.color()).bold());
     }
     
     /// Analyze performance gaps and return needed optimizations
@@ -447,48 +915,73 @@ impl SolanaInterface {
         optimizations
     }
     
-    /// Apply real optimization to running validator
-    async fn apply_real_optimization(&self, action: OptimizationAction) -> Result<()> {
-        match action {
-            OptimizationAction::VoteLatencyReduction => {
-                println!("  🔧 Reducing TPU coalesce latency: 5ms → 1ms");
-                self.update_validator_config("tpu-coalesce-ms", "1").await?;
-            }
-            OptimizationAction::ThreadingOptimization => {
-                println!("  🔧 Increasing RPC threads: 8 → 32");
-                self.update_validator_config("rpc-threads", "32").await?;
-                
-                println!("  🔧 Optimizing DB threads: 8 → 16");
-                self.update_validator_config("accounts-db-threads", "16").await?;
-            }
-            OptimizationAction::NetworkLatencyOptimization => {
-                println!("  🔧 Enabling TCP Fast Open");
-                self.apply_network_optimization("tcp-fastopen", "1").await?;
-                
-                println!("  🔧 Increasing UDP buffers: 64MB → 128MB");
-                self.apply_network_optimization("udp-buffer", "134217728").await?;
-            }
-            OptimizationAction::QUICProtocolOptimization => {
-                println!("  🔧 Enabling QUIC protocol for vote transmission");
-                self.update_validator_config("enable-quic", "true").await?;
-            }
-            OptimizationAction::AggressiveVoteOptimization => {
-                println!("  🔧 AGGRESSIVE: Skipping wait for vote");
-                self.update_validator_config("no-wait-for-vote-to-start-leader", "true").await?;
-                
-                println!("  🔧 AGGRESSIVE: Vote-only mode enabled");
-                self.update_validator_config("vote-only-mode", "true").await?;
-            }
-            OptimizationAction::AggressiveResourceOptimization => {
-                println!("  🔧 AGGRESSIVE: Snapshot optimization");
-                self.update_validator_config("incremental-snapshot-interval", "100").await?;
-                
-                println!("  🔧 AGGRESSIVE: Memory cache optimization");
-                self.update_validator_config("accounts-db-cache-size", "4096").await?;
+    /// Apply real optimization to running validator, leaving out settings
+    /// still in their cooldown; returns how many settings changed
+    async fn apply_real_optimization(
+        &self,
+        action: OptimizationAction,
+        metrics: &ValidatorMetrics,
+        cooldowns: &mut ParameterCooldowns,
+    ) -> Result<u32> {
+        let trigger = action.trigger(metrics);
+        // (what it does, setting, value, whether the setting is a sysctl)
+        let changes: &[(&str, &str, &str, bool)] = match action {
+            OptimizationAction::VoteLatencyReduction => &[
+                ("Reducing TPU coalesce latency: 5ms → 1ms", "tpu-coalesce-ms", "1", false),
+            ],
+            OptimizationAction::ThreadingOptimization => &[
+                ("Increasing RPC threads: 8 → 32", "rpc-threads", "32", false),
+                ("Optimizing DB threads: 8 → 16", "accounts-db-threads", "16", false),
+            ],
+            OptimizationAction::NetworkLatencyOptimization => &[
+                ("Enabling TCP Fast Open", "tcp-fastopen", "3", true),
+                ("Increasing UDP buffers: 64MB → 128MB", "udp-buffer", "134217728", true),
+            ],
+            OptimizationAction::QUICProtocolOptimization => &[
+                ("Enabling QUIC protocol for vote transmission", "enable-quic", "true", false),
+            ],
+            OptimizationAction::AggressiveVoteOptimization => &[
+                ("AGGRESSIVE: Skipping wait for vote", "no-wait-for-vote-to-start-leader", "true", false),
+                ("AGGRESSIVE: Vote-only mode enabled", "vote-only-mode", "true", false),
+            ],
+            OptimizationAction::AggressiveResourceOptimization => &[
+                ("AGGRESSIVE: Snapshot optimization", "incremental-snapshot-interval", "100", false),
+                ("AGGRESSIVE: Memory cache optimization", "accounts-db-cache-size", "4096", false),
+            ],
+        };
+
+        let mut applied = 0;
+        for &(description, parameter, value, sysctl) in changes {
+            if let Some((ago, cooldown)) = cooldowns.active(parameter) {
+                println!("  {} Skipping {}={}: changed {}s ago, cooldown {}s ({}s left)",
+                    "⏳".yellow(),
+                    parameter,
+                    value,
+                    ago.num_seconds(),
+                    cooldown.num_seconds(),
+                    (cooldown - ago).num_seconds()
+                );
+                continue;
             }
+
+            println!("  🔧 {}", description);
+            let event = if sysctl {
+                let old = network_sysctls(parameter).first().and_then(|key| current_sysctl(key));
+                self.apply_network_optimization(parameter, value).await?;
+                let verified = network_sysctls(parameter).iter().all(|key| current_sysctl(key).as_deref() == Some(value));
+                AuditEvent::new(parameter, old, value, &trigger, "optimize --auto").verified(verified)
+            } else {
+                let old = live_config::running_validator_args()
+                    .and_then(|args| live_config::parse_flag_values(&args).remove(&format!("--{}", parameter)));
+                self.update_validator_config(parameter, value).await?;
+                AuditEvent::new(parameter, old, value, &trigger, "optimize --auto")
+            };
+            audit::record_or_warn(&event);
+            cooldowns.record(parameter);
+            applied += 1;
         }
-        
-        Ok(())
+
+        Ok(applied)
     }
     
     /// Update validator configuration via RPC or signal
@@ -496,9 +989,11 @@ impl SolanaInterface {
         use std::process::Command;
         
         // Try admin RPC first
-        let output = Command::new("solana-validator")
-            .args(&["admin", "rpc", "setLogLevel", "--level", "info"])
-            .output();
+        if !native::no_cli() {
+            let _ = Command::new("solana-validator")
+                .args(&["admin", "rpc", "setLogLevel", "--level", "info"])
+                .output();
+        }
         
         // If validator supports dynamic config updates, use that
         // Otherwise, update config file for next restart
@@ -510,37 +1005,46 @@ impl SolanaInterface {
         
         // Signal validator to reload config (if supported)
         self.signal_config_reload().await?;
+
+        if let Err(e) = history::record_optimization(parameter, value, "optimize --auto") {
+            println!("    {} Could not record optimization: {}", "⚠".yellow(), e);
+        }
         
         Ok(())
     }
     
     /// Apply network-level optimizations
     async fn apply_network_optimization(&self, parameter: &str, value: &str) -> Result<()> {
-        use std::process::Command;
-        
-        match parameter {
-            "tcp-fastopen" => {
-                Command::new("sudo")
-                    .args(&["sysctl", "-w", "net.ipv4.tcp_fastopen=3"])
-                    .output()
-                    .ok();
-            }
-            "udp-buffer" => {
-                Command::new("sudo")
-                    .args(&["sysctl", "-w", &format!("net.core.rmem_max={}", value)])
-                    .output()
-                    .ok();
-                Command::new("sudo")
-                    .args(&["sysctl", "-w", &format!("net.core.wmem_max={}", value)])
-                    .output()
-                    .ok();
-            }
-            _ => {}
+        for key in network_sysctls(parameter) {
+            Self::sudo_sysctl(key, value)?;
+        }
+
+        if let Err(e) = history::record_optimization(parameter, value, "optimize --auto") {
+            println!("    {} Could not record optimization: {}", "⚠".yellow(), e);
         }
         
         Ok(())
     }
     
+    /// `sudo sysctl -w key=value`, failing if sudo is denied or sysctl rejects it
+    ///
+    /// With `--no-cli` the value is written to /proc/sys directly, which needs root.
+    fn sudo_sysctl(key: &str, value: &str) -> Result<()> {
+        if native::no_cli() {
+            return native::write_sysctl(key, value);
+        }
+
+        let output = std::process::Command::new("sudo")
+            .args(["sysctl", "-w", &format!("{}={}", key, value)])
+            .output()
+            .context("Failed to run sudo sysctl")?;
+
+        if !output.status.success() {
+            anyhow::bail!("sysctl {} failed: {}", key, String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(())
+    }
+
     /// Update configuration parameter in file
     async fn update_config_parameter(&self, config_path: &str, parameter: &str, value: &str) -> Result<()> {
         // This would update the validator config file
@@ -598,6 +1102,31 @@ impl SolanaInterface {
     }
 }
 
+/// The kernel parameters behind an `optimize --auto` network setting
+fn network_sysctls(parameter: &str) -> &'static [&'static str] {
+    match parameter {
+        "tcp-fastopen" => &["net.ipv4.tcp_fastopen"],
+        "udp-buffer" => &["net.core.rmem_max", "net.core.wmem_max"],
+        _ => &[],
+    }
+}
+
+impl OptimizationAction {
+    /// The reading that called for the action, e.g. `vote success 91.2% < 97%`
+    fn trigger(&self, metrics: &ValidatorMetrics) -> String {
+        match self {
+            OptimizationAction::VoteLatencyReduction | OptimizationAction::AggressiveVoteOptimization => {
+                format!("vote success {:.1}% < 97%", metrics.vote_success_rate)
+            }
+            OptimizationAction::ThreadingOptimization | OptimizationAction::AggressiveResourceOptimization => {
+                format!("skip rate {:.1}% > 3%", metrics.skip_rate)
+            }
+            OptimizationAction::NetworkLatencyOptimization => format!("vote lag {} slots > 30", metrics.vote_lag),
+            OptimizationAction::QUICProtocolOptimization => format!("network latency {}ms > 50ms", metrics.network_latency_ms),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum OptimizationAction {
     VoteLatencyReduction,
@@ -614,48 +1143,52 @@ pub struct ValidatorMetrics {
     pub slot: u64,
     pub vote_success_rate: f64,
     pub skip_rate: f64,
+    /// Lifetime credits of the vote account
     pub credits_earned: u64,
+    /// Credits earned in the current epoch
+    pub epoch_credits: u64,
+    /// `epoch_credits` as a percentage of the most the epoch's elapsed slots
+    /// could earn; None early in the epoch or when the schedule is unknown
+    pub credits_pct: Option<f64>,
     pub vote_lag: u64,
     pub network_latency_ms: u32,
     pub stake_lamports: u64,
     pub total_votes: u32,
     pub recent_votes: u32,
     pub avg_tps: f64,
-    pub leader_slots: u32,
+    /// Leader slots this epoch; None when the schedule couldn't be fetched
+    pub leader_slots: Option<u32>,
     pub root_slot: u64,
-    pub optimized: bool,
+    /// Share (0-1) of the optimizer's kernel parameters at their targets on
+    /// this host; None when they can't be read
+    pub optimizations_applied: Option<f64>,
+    /// Average slot duration over the recent performance samples
+    pub slot_time_ms: f64,
+    /// Listed as delinquent by `getVoteAccounts`
+    pub delinquent: bool,
 }
 
 impl ValidatorMetrics {
-    pub fn display(&self) {
+    pub fn display(&self, bands: &StatusBands) {
         use colored::Colorize;
         
         println!("\n{}", "📊 Validator Performance Metrics".cyan().bold());
         println!("{}", "═".repeat(50));
         
-        // Vote performance
-        let vote_color = if self.vote_success_rate >= 95.0 {
-            "green"
-        } else if self.vote_success_rate >= 85.0 {
-            "yellow"
-        } else {
-            "red"
-        };
-        
         println!("Vote Success Rate: {}", 
-            format!("{:.1}%", self.vote_success_rate).color(vote_color).bold()
+            format!("{:.1}%", self.vote_success_rate)
+                .color(bands.vote_success(self.vote_success_rate).color()).bold()
         );
         
         println!("Skip Rate: {}", 
-            format!("{:.1}%", self.skip_rate).color(
-                if self.skip_rate <= 3.0 { "green" } 
-                else if self.skip_rate <= 10.0 { "yellow" } 
-                else { "red" }
-            ).bold()
+            format!("{:.1}%", self.skip_rate)
+                .color(bands.skip_rate(self.skip_rate).color()).bold()
         );
         
-        println!("Credits Earned: {}", 
-            format!("{}", self.credits_earned).yellow()
+        println!("Credits Earned: {} ({} this epoch, {})", 
+            format!("{}", self.credits_earned).yellow(),
+            self.epoch_credits,
+            self.credits_pct.map_or("max unknown".to_string(), |pct| format!("{:.1}% of max", pct))
         );
         
         println!("Vote Lag: {} slots", self.vote_lag);
@@ -668,11 +1201,623 @@ impl ValidatorMetrics {
         
         // Network info
         println!("Average TPS: {:.0}", self.avg_tps);
-        println!("Leader Slots: {}", self.leader_slots);
+        match self.leader_slots {
+            Some(0) => println!("Leader Slots: {}", "none this epoch".dimmed()),
+            Some(slots) => println!("Leader Slots: {}", slots),
+            None => println!("Leader Slots: {}", "unknown (schedule unavailable)".yellow()),
+        }
         println!("Root Slot: {}", self.root_slot);
         
-        if self.optimized {
-            println!("\n{} Optimizations Active", "✓".green().bold());
+        if let Some(share) = self.optimizations_applied {
+            println!("Optimizations Applied: {:.0}% of kernel parameters", share * 100.0);
+        }
+    }
+
+    /// 0-100 from vote success, skip rate, vote lag, delinquency and the
+    /// optimizations applied, weighted by `config.score_weights`
+    pub fn optimization_score(&self, config: &ValidatorConfig) -> f64 {
+        let inputs = HealthInputs {
+            vote_success_rate: Some(self.vote_success_rate),
+            skip_rate: Some(self.skip_rate),
+            vote_lag: Some(self.vote_lag),
+            delinquent: Some(self.delinquent),
+            slots_behind: None,
+        };
+        health::optimization_score(&config.score_weights, &config.health, &inputs, self.optimizations_applied)
+            .unwrap_or(0.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StakeActivation {
+    pub epoch: u64,
+    pub voter: Pubkey,
+    pub delegated_lamports: u64,
+    pub active_lamports: u64,
+    pub activating_lamports: u64,
+    pub deactivating_lamports: u64,
+    /// Epochs until fully active, assuming cluster-wide warmup stays as it is now
+    pub epochs_until_active: Option<u64>,
+}
+
+/// Read a stake account's activation state from the stake account and the
+/// StakeHistory sysvar (the getStakeActivation RPC method is deprecated)
+pub fn get_stake_activation(rpc_client: &RpcClient, stake_account: &Pubkey) -> Result<StakeActivation> {
+    use solana_sdk::{
+        feature,
+        feature_set::reduce_stake_warmup_cooldown,
+        stake::state::{warmup_cooldown_rate, StakeStateV2},
+        stake_history::StakeHistory,
+        sysvar,
+    };
+
+    let account = rpc_client.get_account(stake_account)
+        .with_context(|| format!("Stake account {} not found", stake_account))?;
+
+    if account.owner != solana_sdk::stake::program::id() {
+        return Err(anyhow::anyhow!(
+            "{} is not a stake account (owned by {})", stake_account, account.owner
+        ));
+    }
+
+    let stake_state: StakeStateV2 = bincode::deserialize(&account.data)
+        .context("Failed to deserialize stake account")?;
+    let delegation = stake_state.delegation()
+        .ok_or_else(|| anyhow::anyhow!("Stake account {} is not delegated", stake_account))?;
+
+    let epoch_info = rpc_client.get_epoch_info()
+        .context("Failed to get epoch info")?;
+
+    let history_account = rpc_client.get_account(&sysvar::stake_history::id())
+        .context("Failed to get StakeHistory sysvar")?;
+    let stake_history: StakeHistory = bincode::deserialize(&history_account.data)
+        .context("Failed to deserialize StakeHistory")?;
+
+    // The warmup rate dropped from 25% to 9% when this feature activated
+    let new_rate_activation_epoch = rpc_client.get_account(&reduce_stake_warmup_cooldown::id())
+        .ok()
+        .and_then(|account| feature::from_account(&account))
+        .and_then(|feature| feature.activated_at)
+        .and_then(|slot| rpc_client.get_epoch_schedule().ok().map(|schedule| schedule.get_epoch(slot)));
+
+    let status = delegation.stake_activating_and_deactivating(
+        epoch_info.epoch,
+        &stake_history,
+        new_rate_activation_epoch,
+    );
+
+    let epochs_until_active = if status.activating == 0 {
+        Some(0)
+    } else {
+        // Each epoch the cluster warms up `rate * effective` lamports, shared
+        // pro rata among everything still activating
+        stake_history.get(epoch_info.epoch.saturating_sub(1)).and_then(|cluster| {
+            let rate = warmup_cooldown_rate(epoch_info.epoch, new_rate_activation_epoch);
+            let per_epoch = cluster.effective as f64 * rate;
+            (per_epoch > 0.0).then(|| (cluster.activating as f64 / per_epoch).ceil().max(1.0) as u64)
+        })
+    };
+
+    Ok(StakeActivation {
+        epoch: epoch_info.epoch,
+        voter: delegation.voter_pubkey,
+        delegated_lamports: delegation.stake,
+        active_lamports: status.effective,
+        activating_lamports: status.activating,
+        deactivating_lamports: status.deactivating,
+        epochs_until_active,
+    })
+}
+
+/// `stake-status`: report (and optionally keep refreshing) a stake account's warmup
+pub async fn stake_status(rpc_url: &str, stake_account: &Pubkey, refresh_secs: Option<u64>) -> Result<()> {
+    let rpc_client = tls::rpc_client(rpc_url, CommitmentConfig::confirmed());
+    let sol = |lamports: u64| lamports as f64 / LAMPORTS_PER_SOL as f64;
+
+    loop {
+        let activation = get_stake_activation(&rpc_client, stake_account)?;
+
+        println!("\n{}", "=== Stake Activation ===".cyan().bold());
+        println!("Stake Account: {}", stake_account.to_string().yellow());
+        println!("Delegated To: {}", activation.voter);
+        println!("Epoch: {}", activation.epoch);
+        println!("Delegated: {:.4} SOL", sol(activation.delegated_lamports));
+        println!("Active: {}", format!("{:.4} SOL", sol(activation.active_lamports)).green());
+        println!("Activating: {}", format!("{:.4} SOL", sol(activation.activating_lamports)).yellow());
+        println!("Deactivating: {}", format!("{:.4} SOL", sol(activation.deactivating_lamports)).red());
+
+        match activation.epochs_until_active {
+            Some(0) if activation.deactivating_lamports == 0 => {
+                println!("{} Stake is fully active", "✓".green());
+            }
+            Some(0) => {}
+            Some(epochs) => println!("Estimated epochs until fully active: {}", epochs.to_string().cyan()),
+            None => println!("Estimated epochs until fully active: {}", "unknown (no stake history)".yellow()),
+        }
+
+        match refresh_secs {
+            Some(secs) => tokio::time::sleep(tokio::time::Duration::from_secs(secs)).await,
+            None => return Ok(()),
+        }
+    }
+}
+
+/// Fetch a vote account and deserialize its `VoteState`
+pub fn fetch_vote_state(rpc_client: &RpcClient, vote_account: &Pubkey) -> Result<VoteState> {
+    let account = rpc_client.get_account(vote_account)
+        .context("Failed to get vote account")?;
+
+    if account.owner != solana_vote_program::id() {
+        return Err(anyhow::anyhow!(
+            "{} is not a vote account (owned by {})", vote_account, account.owner
+        ));
+    }
+
+    VoteState::deserialize(&account.data)
+        .context("Failed to deserialize vote state")
+}
+
+/// Credits earned in `epoch` so far, from the vote state's epoch credits history
+pub fn credits_in_epoch(vote_state: &VoteState, epoch: u64) -> u64 {
+    vote_state
+        .epoch_credits
+        .iter()
+        .find(|(e, _, _)| *e == epoch)
+        .map_or(0, |(_, credits, previous)| credits.saturating_sub(*previous))
+}
+
+/// Most credits a rooted vote can earn on the cluster: 16 for a vote landing
+/// right after its slot once timely vote credits are active, 1 before that;
+/// None when the feature account can't be read
+pub fn max_credits_per_slot(rpc_client: &RpcClient) -> Option<u64> {
+    use solana_sdk::{feature, feature_set::timely_vote_credits};
+    use solana_vote_program::vote_state::VOTE_CREDITS_MAXIMUM_PER_SLOT;
+
+    let account = rpc_client
+        .get_account_with_commitment(&timely_vote_credits::id(), CommitmentConfig::confirmed())
+        .ok()?
+        .value;
+    let active = account
+        .and_then(|account| feature::from_account(&account))
+        .is_some_and(|feature| feature.activated_at.is_some());
+    Some(if active { VOTE_CREDITS_MAXIMUM_PER_SLOT as u64 } else { 1 })
+}
+
+/// `credits` as a percentage of the most `slots` can earn at `per_slot`
+///
+/// Votes only earn credits once rooted, so the newest `MAX_LOCKOUT_HISTORY`
+/// slots aren't counted against the validator yet.
+pub fn normalized_credits_pct(credits: u64, slots: u64, per_slot: u64) -> Option<f64> {
+    use solana_vote_program::vote_state::MAX_LOCKOUT_HISTORY;
+
+    let max = slots.saturating_sub(MAX_LOCKOUT_HISTORY as u64) * per_slot;
+    (max > 0).then(|| (credits as f64 / max as f64 * 100.0).min(100.0))
+}
+
+/// `vote-authority-status`: who may vote and withdraw for a vote account
+pub async fn vote_authority_status(
+    rpc_url: &str,
+    vote_account: &Pubkey,
+    identity: Option<&Pubkey>,
+) -> Result<()> {
+    let rpc_client = tls::rpc_client(rpc_url, CommitmentConfig::confirmed());
+
+    let vote_state = fetch_vote_state(&rpc_client, vote_account)?;
+    let current_epoch = rpc_client.get_epoch_info()
+        .context("Failed to get epoch info")?
+        .epoch;
+
+    println!("\n{}", "=== Vote Account Authorities ===".cyan().bold());
+    println!("Vote Account: {}", vote_account.to_string().yellow());
+    println!("Node Identity: {}", vote_state.node_pubkey);
+    println!("Authorized Withdrawer: {}", vote_state.authorized_withdrawer);
+    println!("Commission: {}%", vote_state.commission);
+
+    println!("Authorized Voter(s):");
+    let voters: Vec<_> = vote_state.authorized_voters().iter().collect();
+    for (i, (epoch, voter)) in voters.iter().enumerate() {
+        let branch = if i + 1 == voters.len() { "└─" } else { "├─" };
+        let note = if **epoch > current_epoch {
+            " (scheduled)".yellow().to_string()
+        } else if vote_state.get_authorized_voter(current_epoch) == Some(**voter) {
+            " (current)".green().to_string()
+        } else {
+            String::new()
+        };
+        println!("{} epoch {}: {}{}", branch, epoch, voter, note);
+    }
+
+    if let Some(identity) = identity {
+        if vote_state.node_pubkey == *identity {
+            println!("\n{} Node identity matches the configured identity", "✓".green());
+        } else {
+            println!("\n{} Node identity {} does not match the configured identity {}",
+                "✗".red(), vote_state.node_pubkey, identity
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Number of recent epochs averaged for the APR estimate
+const APR_EPOCH_WINDOW: u64 = 5;
+
+/// Target slot duration, used when no performance samples are available
+pub const NOMINAL_SLOT_MS: f64 = 400.0;
+
+/// Average slot duration measured by performance samples
+pub fn measured_slot_ms(samples: &[solana_client::rpc_response::RpcPerfSample]) -> Option<f64> {
+    let slots: u64 = samples.iter().map(|s| s.num_slots).sum();
+    let secs: u64 = samples.iter().map(|s| s.sample_period_secs as u64).sum();
+    (slots > 0).then(|| secs as f64 * 1000.0 / slots as f64)
+}
+
+/// How long a measured slot duration is reused before measuring again
+const SLOT_TIME_REFRESH: Duration = Duration::from_secs(300);
+
+/// Gap between the two `getSlot` readings when performance samples are unavailable
+const SLOT_READING_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Last measured slot duration per RPC URL
+static SLOT_TIME: Mutex<Vec<(String, f64, Instant)>> = Mutex::new(Vec::new());
+
+/// Slot duration from two `getSlot` readings `SLOT_READING_INTERVAL` apart
+async fn slot_ms_from_readings(rpc_client: &RpcClient) -> Option<f64> {
+    let first = rpc_client.get_slot().ok()?;
+    let started = Instant::now();
+    tokio::time::sleep(SLOT_READING_INTERVAL).await;
+    let second = rpc_client.get_slot().ok()?;
+    let advanced = second.checked_sub(first).filter(|&slots| slots > 0)?;
+    Some(started.elapsed().as_secs_f64() * 1000.0 / advanced as f64)
+}
+
+/// Slot duration of the cluster behind `rpc_client`
+///
+/// Measured from recent performance samples, or from consecutive slot
+/// readings where the RPC has none (fresh localnets), and cached for
+/// `SLOT_TIME_REFRESH`. Falls back to 400ms if neither works.
+pub async fn slot_time_ms(rpc_client: &RpcClient) -> f64 {
+    let url = rpc_client.url();
+    let cached = SLOT_TIME
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|(cached, _, measured_at)| *cached == url && measured_at.elapsed() < SLOT_TIME_REFRESH)
+        .map(|(_, ms, _)| *ms);
+    if let Some(ms) = cached {
+        return ms;
+    }
+
+    let samples = rpc_client.get_recent_performance_samples(Some(60)).unwrap_or_default();
+    let measured = match measured_slot_ms(&samples) {
+        Some(ms) => Some(ms),
+        None => slot_ms_from_readings(rpc_client).await,
+    };
+    let Some(ms) = measured else {
+        return NOMINAL_SLOT_MS;
+    };
+    let mut cache = SLOT_TIME.lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|(cached, _, _)| *cached != url);
+    cache.push((url, ms, Instant::now()));
+    ms
+}
+
+/// Average slot duration in seconds
+async fn average_slot_secs(rpc_client: &RpcClient) -> f64 {
+    slot_time_ms(rpc_client).await / 1000.0
+}
+
+/// Seconds per epoch at the measured slot time, and epochs per year
+async fn epoch_length(rpc_client: &RpcClient) -> Result<(f64, f64)> {
+    let slots_per_epoch = rpc_client.get_epoch_schedule()
+        .context("Failed to get epoch schedule")?
+        .slots_per_epoch;
+    let epoch_secs = slots_per_epoch as f64 * average_slot_secs(rpc_client).await;
+    Ok((epoch_secs, 365.25 * 24.0 * 3600.0 / epoch_secs))
+}
+
+/// The whole epoch reward (lamports) a vote account's commission-only
+/// `reward` implies, at `commission` when the reward doesn't record one
+fn full_epoch_reward(reward: &RpcInflationReward, commission: u8) -> f64 {
+    reward.amount as f64 * 100.0 / reward.commission.unwrap_or(commission).max(1) as f64
+}
+
+/// `apr`: annualized staking return for a vote account, gross and net of commission
+///
+/// The vote account's inflation reward is only the commission cut, so the
+/// full epoch reward is reconstructed as `amount / commission` and compared
+/// against the currently activated stake.
+pub async fn apr(rpc_url: &str, vote_account: &Pubkey, epoch: Option<u64>) -> Result<()> {
+    let rpc_client = tls::rpc_client(rpc_url, CommitmentConfig::confirmed());
+
+    let vote_accounts = rpc_client.get_vote_accounts()
+        .context("Failed to get vote accounts")?;
+    let vote_id = vote_account.to_string();
+    let info = vote_accounts.current.iter()
+        .chain(vote_accounts.delinquent.iter())
+        .find(|v| v.vote_pubkey == vote_id)
+        .ok_or_else(|| anyhow::anyhow!("Vote account {} not found in cluster", vote_account))?;
+
+    let current_epoch = rpc_client.get_epoch_info()
+        .context("Failed to get epoch info")?
+        .epoch;
+    let last_epoch = epoch.unwrap_or(current_epoch.saturating_sub(1));
+    if last_epoch >= current_epoch {
+        anyhow::bail!("Epoch {} has not finished yet (current epoch is {})", last_epoch, current_epoch);
+    }
+    let first_epoch = last_epoch.saturating_sub(APR_EPOCH_WINDOW - 1);
+
+    let (epoch_secs, epochs_per_year) = epoch_length(&rpc_client).await?;
+
+    let sol = |lamports: u64| lamports as f64 / LAMPORTS_PER_SOL as f64;
+
+    println!("\n{}", "=== Staking APR ===".cyan().bold());
+    println!("Vote Account: {}", vote_account.to_string().yellow());
+    println!("Activated Stake: {:.2} SOL", sol(info.activated_stake));
+    println!("Commission: {}%", info.commission);
+    println!("Epochs: {}-{} (~{:.1} days each)", first_epoch, last_epoch, epoch_secs / 86_400.0);
+
+    if info.commission == 0 {
+        println!("\n{} With 0% commission the vote account receives no inflation rewards,", "⚠".yellow());
+        println!("  so the return can't be derived from its reward history.");
+        return Ok(());
+    }
+    if info.activated_stake == 0 {
+        println!("\n{} No activated stake - rewards start one full epoch after stake activates", "⚠".yellow());
+        return Ok(());
+    }
+
+    let mut gross_rates = Vec::new();
+    let mut net_rates = Vec::new();
+
+    for e in first_epoch..=last_epoch {
+        let reward = rpc_client.get_inflation_reward(&[*vote_account], Some(e))
+            .ok()
+            .and_then(|rewards| rewards.into_iter().next().flatten());
+
+        match reward {
+            Some(reward) if reward.amount > 0 => {
+                let total = full_epoch_reward(&reward, info.commission);
+                let delegators = total - reward.amount as f64;
+                let stake = info.activated_stake as f64;
+
+                gross_rates.push(total / stake);
+                net_rates.push(delegators / stake);
+                println!("├─ Epoch {}: {:.4} SOL commission of {:.4} SOL total", e, sol(reward.amount), total / LAMPORTS_PER_SOL as f64);
+            }
+            _ => println!("├─ Epoch {}: {}", e, "no reward".dimmed()),
         }
     }
+
+    if gross_rates.is_empty() {
+        println!("└─ {}", "No inflation rewards yet".yellow());
+        println!("\n{} New validators earn their first rewards after a full epoch of active,", "ℹ".cyan());
+        println!("  voting stake. Check back once epoch {} has completed.", current_epoch);
+        return Ok(());
+    }
+
+    let mean = |rates: &[f64]| rates.iter().sum::<f64>() / rates.len() as f64;
+    let gross_apr = mean(&gross_rates) * epochs_per_year * 100.0;
+    let net_apr = mean(&net_rates) * epochs_per_year * 100.0;
+
+    println!("└─ Averaged over {} rewarded epoch(s)", gross_rates.len());
+    println!("\nGross APR: {}", format!("{:.2}%", gross_apr).cyan().bold());
+    println!("Net APR (after {}% commission): {}", info.commission, format!("{:.2}%", net_apr).green().bold());
+    println!("{}", "Based on current activated stake; past epochs with different stake skew the estimate.".dimmed());
+
+    Ok(())
+}
+
+/// Gross staking APR the cluster pays right now: the validator share of
+/// inflation spread over the active stake (before commission, full credits)
+fn cluster_staking_apr(rpc_client: &RpcClient) -> Result<f64> {
+    let inflation = rpc_client.get_inflation_rate().context("Failed to get inflation rate")?;
+    let supply = rpc_client.supply().context("Failed to get supply")?.value.total;
+    let vote_accounts = rpc_client.get_vote_accounts().context("Failed to get vote accounts")?;
+    let active_stake: u64 = vote_accounts.current.iter()
+        .chain(vote_accounts.delinquent.iter())
+        .map(|v| v.activated_stake)
+        .sum();
+
+    if active_stake == 0 {
+        anyhow::bail!("Cluster reports no active stake");
+    }
+    Ok(inflation.validator * supply as f64 / active_stake as f64 * 100.0)
+}
+
+/// `breakeven`: the activated stake at which commission covers operating costs
+///
+/// Costs per epoch are vote fees (`vote_fee_lamports` x `votes_per_slot` x
+/// slots per epoch), the vote account's rent-exempt deposit written off over
+/// `rent_amortization_epochs`, and `other_costs_sol_per_epoch`. Commission
+/// earned per staked SOL per epoch is the gross APR spread over the epochs
+/// in a year, times the commission.
+pub async fn breakeven(
+    rpc_url: &str,
+    vote_account: &Pubkey,
+    costs: &BreakevenConfig,
+    commission: Option<u8>,
+    apr_pct: Option<f64>,
+) -> Result<()> {
+    let rpc_client = tls::rpc_client(rpc_url, CommitmentConfig::confirmed());
+    let sol = |lamports: f64| lamports / LAMPORTS_PER_SOL as f64;
+
+    let vote_id = vote_account.to_string();
+    let vote_accounts = rpc_client.get_vote_accounts().context("Failed to get vote accounts")?;
+    let info = vote_accounts.current.iter()
+        .chain(vote_accounts.delinquent.iter())
+        .find(|v| v.vote_pubkey == vote_id);
+
+    let (commission, commission_source) = match (commission, info) {
+        (Some(commission), _) => (commission, "--commission"),
+        (None, Some(info)) => (info.commission, "vote account"),
+        (None, None) => anyhow::bail!(
+            "Vote account {} not found in cluster; pass --commission to assume one", vote_account
+        ),
+    };
+    if commission == 0 {
+        anyhow::bail!("With 0% commission no stake covers the costs; pass --commission to assume one");
+    }
+
+    let (apr, apr_source) = match apr_pct.or(costs.apr_pct) {
+        Some(apr) if apr_pct.is_some() => (apr, "--apr"),
+        Some(apr) => (apr, "config breakeven.apr_pct"),
+        None => (cluster_staking_apr(&rpc_client)?, "cluster inflation / active stake"),
+    };
+    if apr <= 0.0 {
+        anyhow::bail!("APR must be positive, got {}%", apr);
+    }
+
+    let slots_per_epoch = rpc_client.get_epoch_schedule()
+        .context("Failed to get epoch schedule")?
+        .slots_per_epoch;
+    let (epoch_secs, epochs_per_year) = epoch_length(&rpc_client).await?;
+    let rent = rpc_client.get_minimum_balance_for_rent_exemption(VoteState::size_of())
+        .context("Failed to get vote account rent")?;
+
+    let vote_fees = costs.vote_fee_lamports as f64 * costs.votes_per_slot * slots_per_epoch as f64;
+    let rent_share = rent as f64 / costs.rent_amortization_epochs.max(1) as f64;
+    let other = costs.other_costs_sol_per_epoch * LAMPORTS_PER_SOL as f64;
+    let cost_per_epoch = vote_fees + rent_share + other;
+
+    // Commission lamports earned per staked lamport per epoch
+    let commission_rate = apr / 100.0 / epochs_per_year * commission as f64 / 100.0;
+    let breakeven_stake = cost_per_epoch / commission_rate;
+
+    println!("\n{}", "=== Break-Even Stake ===".cyan().bold());
+    println!("Vote Account: {}", vote_account.to_string().yellow());
+
+    println!("\n{}", "Costs per epoch".cyan().bold());
+    println!("├─ Vote fees: {:.4} SOL ({} lamports x {} vote(s)/slot x {} slots)",
+        sol(vote_fees), costs.vote_fee_lamports, costs.votes_per_slot, slots_per_epoch);
+    println!("├─ Rent: {:.4} SOL ({:.4} SOL deposit over {} epochs, refundable on close)",
+        sol(rent_share), sol(rent as f64), costs.rent_amortization_epochs.max(1));
+    println!("├─ Other: {:.4} SOL (breakeven.other_costs_sol_per_epoch)", costs.other_costs_sol_per_epoch);
+    println!("└─ Total: {}", format!("{:.4} SOL", sol(cost_per_epoch)).bold());
+
+    println!("\n{}", "Rewards".cyan().bold());
+    println!("├─ Gross APR: {:.2}% ({})", apr, apr_source);
+    println!("├─ Commission: {}% ({})", commission, commission_source);
+    println!("└─ Epochs per year: {:.1} (~{:.1} days each, measured slot time)", epochs_per_year, epoch_secs / 86_400.0);
+
+    println!("\nBreak-even activated stake: {}", format!("{:.0} SOL", sol(breakeven_stake)).green().bold());
+    if let Some(info) = info {
+        let stake = info.activated_stake as f64;
+        let earned = stake * commission_rate;
+        let verdict = if stake >= breakeven_stake { "covers".green() } else { "does not cover".red() };
+        println!("Current stake {:.0} SOL earns ~{:.4} SOL commission per epoch, which {} the costs",
+            sol(stake), sol(earned), verdict);
+    }
+    println!("{}", "Assumes full vote credits; missed votes lower rewards but not vote fees.".dimmed());
+
+    Ok(())
+}
+
+/// Gross APR over the last `APR_EPOCH_WINDOW` rewarded epochs, rebuilt from
+/// the commission the vote account received; with the number of epochs used
+fn recent_gross_apr(
+    rpc_client: &RpcClient,
+    vote_account: &Pubkey,
+    commission: u8,
+    stake: u64,
+    epochs_per_year: f64,
+) -> Result<Option<(f64, usize)>> {
+    if commission == 0 || stake == 0 {
+        return Ok(None);
+    }
+    let current_epoch = rpc_client.get_epoch_info().context("Failed to get epoch info")?.epoch;
+    let last_epoch = current_epoch.saturating_sub(1);
+    let rates: Vec<f64> = (last_epoch.saturating_sub(APR_EPOCH_WINDOW - 1)..=last_epoch)
+        .filter_map(|epoch| {
+            rpc_client.get_inflation_reward(&[*vote_account], Some(epoch)).ok()?.into_iter().next().flatten()
+        })
+        .filter(|reward| reward.amount > 0)
+        .map(|reward| full_epoch_reward(&reward, commission) / stake as f64)
+        .collect();
+    if rates.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some((rates.iter().sum::<f64>() / rates.len() as f64 * epochs_per_year * 100.0, rates.len())))
+}
+
+/// `commission-impact`: what changing commission to `new_pct` does to the
+/// delegators' net APR and to the operator's commission income
+///
+/// The current commission comes from the vote state and the gross APR from
+/// the recent rewards (else `--apr`, `breakeven.apr_pct` or the cluster's),
+/// with the activated stake held where it is now.
+pub async fn commission_impact(
+    rpc_url: &str,
+    vote_account: &Pubkey,
+    new_pct: u8,
+    apr_pct: Option<f64>,
+    configured_apr: Option<f64>,
+) -> Result<()> {
+    if new_pct > 100 {
+        anyhow::bail!("Commission is a percentage (0-100), got {}", new_pct);
+    }
+    let rpc_client = tls::rpc_client(rpc_url, CommitmentConfig::confirmed());
+    let sol = |lamports: f64| lamports / LAMPORTS_PER_SOL as f64;
+
+    let current_pct = fetch_vote_state(&rpc_client, vote_account)?.commission;
+    let vote_id = vote_account.to_string();
+    let vote_accounts = rpc_client.get_vote_accounts().context("Failed to get vote accounts")?;
+    let stake = vote_accounts.current.iter()
+        .chain(vote_accounts.delinquent.iter())
+        .find(|v| v.vote_pubkey == vote_id)
+        .map_or(0, |v| v.activated_stake);
+    let (_, epochs_per_year) = epoch_length(&rpc_client).await?;
+
+    let (apr, apr_source) = match (apr_pct, configured_apr) {
+        (Some(apr), _) => (apr, "--apr".to_string()),
+        (None, configured) => match recent_gross_apr(&rpc_client, vote_account, current_pct, stake, epochs_per_year)? {
+            Some((apr, epochs)) => (apr, format!("rewards of the last {} rewarded epoch(s)", epochs)),
+            None => match configured {
+                Some(apr) => (apr, "config breakeven.apr_pct".to_string()),
+                None => (cluster_staking_apr(&rpc_client)?, "cluster inflation / active stake".to_string()),
+            },
+        },
+    };
+    if apr <= 0.0 {
+        anyhow::bail!("APR must be positive, got {}%", apr);
+    }
+
+    let net_apr = |pct: u8| apr * (100 - pct) as f64 / 100.0;
+    let income_per_year = |pct: u8| stake as f64 * apr / 100.0 * pct as f64 / 100.0;
+    // What a delegator with 1,000 SOL staked keeps per year
+    let per_thousand = |pct: u8| 1_000.0 * net_apr(pct) / 100.0;
+
+    println!("\n{}", "=== Commission Impact ===".cyan().bold());
+    println!("Vote Account: {}", vote_account.to_string().yellow());
+    println!("├─ Activated stake: {:.2} SOL", sol(stake as f64));
+    println!("├─ Gross APR: {:.2}% ({})", apr, apr_source);
+    println!("└─ Epochs per year: {:.1}", epochs_per_year);
+
+    let rows = [
+        ("Commission", format!("{}%", current_pct), format!("{}%", new_pct),
+            format!("{:+} pp", new_pct as i16 - current_pct as i16)),
+        ("Delegator net APR", format!("{:.2}%", net_apr(current_pct)), format!("{:.2}%", net_apr(new_pct)),
+            format!("{:+.2} pp", net_apr(new_pct) - net_apr(current_pct))),
+        ("Delegator SOL/yr per 1,000", format!("{:.2}", per_thousand(current_pct)), format!("{:.2}", per_thousand(new_pct)),
+            format!("{:+.2}", per_thousand(new_pct) - per_thousand(current_pct))),
+        ("Your commission SOL/epoch", format!("{:.4}", sol(income_per_year(current_pct) / epochs_per_year)),
+            format!("{:.4}", sol(income_per_year(new_pct) / epochs_per_year)),
+            format!("{:+.4}", sol((income_per_year(new_pct) - income_per_year(current_pct)) / epochs_per_year))),
+        ("Your commission SOL/yr", format!("{:.2}", sol(income_per_year(current_pct))), format!("{:.2}", sol(income_per_year(new_pct))),
+            format!("{:+.2}", sol(income_per_year(new_pct) - income_per_year(current_pct)))),
+    ];
+    println!("\n  {:<28} {:>12} {:>12} {:>12}", "", "Now", "New", "Change");
+    for (name, before, after, change) in rows {
+        println!("  {:<28} {:>12} {:>12} {:>12}", name, before, after, change.bold());
+    }
+
+    if stake == 0 {
+        println!("\n{} No activated stake, so no commission income either way", "⚠".yellow());
+    }
+    println!("\n{}", "Assumes full vote credits and the stake staying put; delegators may move stake after a raise.".dimmed());
+    if new_pct > current_pct {
+        println!("{}", "Commission increases are only accepted in the first half of an epoch.".dimmed());
+    }
+    Ok(())
 }
@@ -0,0 +1,244 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::blockchain::SolanaInterface;
+use crate::config::ValidatorConfig;
+use crate::smart_contract::SmartContractOptimizer;
+use crate::tls;
+
+/// Estimated bytes per account access before any calibration
+pub const DEFAULT_READ_BYTES_PER_ACCOUNT: f64 = 100.0;
+pub const DEFAULT_WRITE_BYTES_PER_ACCOUNT: f64 = 200.0;
+
+/// Correction factors are clamped to this range so one odd run can't wreck estimates
+const FACTOR_RANGE: (f64, f64) = (0.1, 10.0);
+
+/// Estimates below this are too close to zero for a ratio to mean anything
+const MIN_ESTIMATE: f64 = 0.1;
+
+/// Corrections learned by `calibrate`, applied to later estimates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Calibration {
+    pub updated: Option<DateTime<Utc>>,
+    /// Measured / estimated skip rate
+    pub skip_rate_factor: f64,
+    /// Measured / estimated network latency
+    pub latency_factor: f64,
+    /// Average data size of accounts a program's transactions read and write
+    pub read_bytes_per_account: f64,
+    pub write_bytes_per_account: f64,
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Calibration {
+            updated: None,
+            skip_rate_factor: 1.0,
+            latency_factor: 1.0,
+            read_bytes_per_account: DEFAULT_READ_BYTES_PER_ACCOUNT,
+            write_bytes_per_account: DEFAULT_WRITE_BYTES_PER_ACCOUNT,
+        }
+    }
+}
+
+impl Calibration {
+    pub fn path() -> PathBuf {
+        ValidatorConfig::config_dir().join("calibration.json")
+    }
+
+    /// Saved calibration, or the identity calibration if none is saved or readable
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        fs::create_dir_all(ValidatorConfig::config_dir())?;
+        fs::write(Self::path(), serde_json::to_string_pretty(self)?)
+            .context("Failed to save calibration")
+    }
+}
+
+/// One estimate checked against its ground truth
+#[derive(Debug, Clone)]
+pub struct CalibrationCheck {
+    pub name: &'static str,
+    pub unit: &'static str,
+    pub estimated: f64,
+    pub measured: f64,
+    pub source: &'static str,
+}
+
+impl CalibrationCheck {
+    pub fn error(&self) -> f64 {
+        self.estimated - self.measured
+    }
+
+    pub fn error_pct(&self) -> Option<f64> {
+        (self.measured.abs() >= MIN_ESTIMATE).then(|| self.error() / self.measured * 100.0)
+    }
+
+    /// Measured / estimated, clamped; None when the estimate is ~0
+    fn factor(&self) -> Option<f64> {
+        (self.estimated.abs() >= MIN_ESTIMATE)
+            .then(|| (self.measured / self.estimated).clamp(FACTOR_RANGE.0, FACTOR_RANGE.1))
+    }
+}
+
+/// Cluster skip rate over the current epoch from `getBlockProduction`
+fn measured_skip_rate(rpc_client: &RpcClient) -> Result<f64> {
+    let production = rpc_client.get_block_production().context("Failed to get block production")?;
+    let (leader_slots, produced) = production
+        .value
+        .by_identity
+        .values()
+        .fold((0usize, 0usize), |(slots, blocks), (s, b)| (slots + s, blocks + b));
+
+    if leader_slots == 0 {
+        anyhow::bail!("No leader slots in the block production range yet");
+    }
+    Ok((leader_slots - produced) as f64 / leader_slots as f64 * 100.0)
+}
+
+/// Median round trip of `samples` getSlot calls
+fn measured_rpc_latency_ms(rpc_client: &RpcClient, samples: usize) -> Result<f64> {
+    let mut round_trips = Vec::with_capacity(samples);
+    for _ in 0..samples.max(1) {
+        let started = Instant::now();
+        rpc_client.get_slot().context("Failed to get slot")?;
+        round_trips.push(started.elapsed().as_secs_f64() * 1000.0);
+    }
+    round_trips.sort_by(|a, b| a.total_cmp(b));
+    Ok(round_trips[round_trips.len() / 2])
+}
+
+fn print_check(check: &CalibrationCheck) {
+    let (error_pct, trust) = match check.error_pct() {
+        Some(pct) if pct.abs() <= 20.0 => (format!("{:+.0}%", pct), "trustworthy".green()),
+        Some(pct) if pct.abs() <= 50.0 => (format!("{:+.0}%", pct), "rough".yellow()),
+        Some(pct) => (format!("{:+.0}%", pct), "unreliable".red()),
+        None => ("n/a".to_string(), "measured ~0".dimmed()),
+    };
+    println!("├─ {}", check.name.bold());
+    println!("│  Estimated {:.1}{} | Measured {:.1}{} ({})",
+        check.estimated, check.unit, check.measured, check.unit, check.source.dimmed());
+    println!("│  Error {:+.1}{} ({}) → {}", check.error(), check.unit, error_pct, trust);
+}
+
+/// `calibrate`: compare the tool's estimates with ground truth where the RPC has one
+///
+/// Skip rate is checked against block production, network latency against
+/// measured RPC round trips and, with `program_id`, the per-account I/O
+/// estimate against the real data size of the accounts its transactions touch.
+/// The resulting correction factors are saved unless `dry_run`.
+pub fn calibrate(rpc_url: &str, program_id: Option<&Pubkey>, latency_samples: usize, dry_run: bool) -> Result<()> {
+    let rpc_client = tls::rpc_client(rpc_url, CommitmentConfig::confirmed());
+    let mut calibration = Calibration::load();
+    let mut checks = Vec::new();
+
+    println!("{}", "=== Estimate Calibration ===".cyan().bold());
+    println!("RPC: {}\n", rpc_url);
+
+    let samples = rpc_client
+        .get_recent_performance_samples(Some(10))
+        .context("Failed to get performance samples")?;
+
+    // Raw heuristics, without any previous calibration applied
+    match measured_skip_rate(&rpc_client) {
+        Ok(measured) => checks.push(CalibrationCheck {
+            name: "Skip rate",
+            unit: "%",
+            estimated: SolanaInterface::calculate_skip_rate(&samples),
+            measured,
+            source: "getBlockProduction, current epoch",
+        }),
+        Err(e) => println!("  {} Skip rate not checked: {:#}", "⚠".yellow(), e),
+    }
+
+    match measured_rpc_latency_ms(&rpc_client, latency_samples) {
+        Ok(measured) => checks.push(CalibrationCheck {
+            name: "Network latency",
+            unit: "ms",
+            estimated: SolanaInterface::estimate_network_latency(&samples) as f64,
+            measured,
+            source: "median getSlot round trip",
+        }),
+        Err(e) => println!("  {} Latency not checked: {:#}", "⚠".yellow(), e),
+    }
+
+    let mut account_sizes = None;
+    if let Some(program_id) = program_id {
+        let optimizer = SmartContractOptimizer::new(rpc_url, Some(*program_id))?;
+        match optimizer.measure_account_sizes(program_id) {
+            Ok(Some((read, write))) => {
+                checks.push(CalibrationCheck {
+                    name: "Bytes per account read",
+                    unit: "B",
+                    estimated: DEFAULT_READ_BYTES_PER_ACCOUNT,
+                    measured: read,
+                    source: "account data sizes of recent transactions",
+                });
+                checks.push(CalibrationCheck {
+                    name: "Bytes per account written",
+                    unit: "B",
+                    estimated: DEFAULT_WRITE_BYTES_PER_ACCOUNT,
+                    measured: write,
+                    source: "account data sizes of recent transactions",
+                });
+                account_sizes = Some((read, write));
+            }
+            Ok(None) => println!("  {} Data I/O not checked: no recent transactions for {}", "⚠".yellow(), program_id),
+            Err(e) => println!("  {} Data I/O not checked: {:#}", "⚠".yellow(), e),
+        }
+    }
+
+    if checks.is_empty() {
+        anyhow::bail!("No estimate could be checked against a ground truth");
+    }
+
+    println!("{}", "Estimated vs Measured".cyan().bold());
+    for check in &checks {
+        print_check(check);
+    }
+    println!("└─ {} estimate(s) checked", checks.len());
+
+    for check in &checks {
+        match (check.name, check.factor()) {
+            ("Skip rate", Some(factor)) => calibration.skip_rate_factor = factor,
+            ("Network latency", Some(factor)) => calibration.latency_factor = factor,
+            _ => {}
+        }
+    }
+    if let Some((read, write)) = account_sizes {
+        calibration.read_bytes_per_account = read;
+        calibration.write_bytes_per_account = write;
+    }
+    calibration.updated = Some(Utc::now());
+
+    println!("\n{}", "Calibration".cyan().bold());
+    println!("├─ Skip rate × {:.2}", calibration.skip_rate_factor);
+    println!("├─ Network latency × {:.2}", calibration.latency_factor);
+    println!("└─ Account I/O: {:.0} B/read, {:.0} B/write",
+        calibration.read_bytes_per_account, calibration.write_bytes_per_account);
+
+    if dry_run {
+        println!("\n{} Dry run - calibration not saved", "ℹ".cyan());
+    } else {
+        calibration.save()?;
+        println!("\n{} Saved to {}; later estimates use these corrections",
+            "✓".green(), Calibration::path().display());
+    }
+
+    Ok(())
+}
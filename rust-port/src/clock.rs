@@ -0,0 +1,122 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::ClockConfig;
+use crate::tls;
+
+/// Seconds between the NTP epoch (1900) and the Unix epoch (1970)
+const NTP_UNIX_OFFSET_SECS: u64 = 2_208_988_800;
+
+const NTP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How the host clock's offset was measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetSource {
+    Ntp,
+    /// Block time of the current slot; only second resolution, so small drift is invisible
+    BlockTime,
+}
+
+/// Host clock minus reference clock (positive = host is ahead)
+#[derive(Debug, Clone, Copy)]
+pub struct ClockOffset {
+    pub offset_ms: f64,
+    pub source: OffsetSource,
+}
+
+fn unix_ms(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64() * 1000.0
+}
+
+/// NTP 64-bit timestamp (seconds + 2^-32 fraction since 1900) as Unix milliseconds
+fn ntp_timestamp_ms(bytes: &[u8]) -> f64 {
+    let secs = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64;
+    let fraction = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as f64;
+    (secs.saturating_sub(NTP_UNIX_OFFSET_SECS)) as f64 * 1000.0 + fraction * 1000.0 / 4_294_967_296.0
+}
+
+/// Offset from one SNTP exchange with `server` (host:port)
+///
+/// Uses the standard on-wire formula `((t2 - t1) + (t3 - t4)) / 2`, which
+/// cancels the round trip as long as it is symmetric.
+pub fn ntp_offset(server: &str) -> Result<f64> {
+    let addr = server
+        .to_socket_addrs()
+        .with_context(|| format!("Failed to resolve NTP server {}", server))?
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("NTP server {} has no address", server))?;
+
+    let socket = UdpSocket::bind(if addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" })?;
+    socket.set_read_timeout(Some(NTP_TIMEOUT))?;
+
+    // LI = 0, version 4, mode 3 (client)
+    let mut request = [0u8; 48];
+    request[0] = 0x23;
+
+    let t1 = unix_ms(SystemTime::now());
+    socket.send_to(&request, addr).context("Failed to send NTP request")?;
+    let mut response = [0u8; 48];
+    let (len, _) = socket.recv_from(&mut response).context("No NTP response")?;
+    let t4 = unix_ms(SystemTime::now());
+
+    if len < 48 || response[1] == 0 {
+        anyhow::bail!("Invalid NTP response from {} (kiss-of-death or truncated)", server);
+    }
+
+    let t2 = ntp_timestamp_ms(&response[32..40]);
+    let t3 = ntp_timestamp_ms(&response[40..48]);
+    // Sign flipped so a positive offset means the host is ahead
+    Ok(-((t2 - t1) + (t3 - t4)) / 2.0)
+}
+
+/// Offset against the block time the RPC reports for its current slot
+pub fn block_time_offset(rpc_url: &str) -> Result<f64> {
+    let client = tls::rpc_client_with_timeout(rpc_url, NTP_TIMEOUT, CommitmentConfig::confirmed());
+    let slot = client.get_slot().context("Failed to get current slot")?;
+    let block_time = client
+        .get_block_time(slot)
+        .with_context(|| format!("No block time for slot {}", slot))?;
+
+    Ok(unix_ms(SystemTime::now()) - block_time as f64 * 1000.0)
+}
+
+/// Measure the host clock offset via NTP, falling back to the cluster's block time
+pub fn measure_offset(config: &ClockConfig, rpc_url: &str) -> Result<ClockOffset> {
+    match ntp_offset(&config.ntp_server) {
+        Ok(offset_ms) => Ok(ClockOffset { offset_ms, source: OffsetSource::Ntp }),
+        Err(e) => {
+            println!("  {} NTP unavailable ({:#}), comparing against block time", "⚠".yellow(), e);
+            block_time_offset(rpc_url).map(|offset_ms| ClockOffset { offset_ms, source: OffsetSource::BlockTime })
+        }
+    }
+}
+
+/// Print the measured clock offset, warning past `max_drift_ms`
+pub fn report_clock_drift(config: &ClockConfig, rpc_url: &str) {
+    let offset = match measure_offset(config, rpc_url) {
+        Ok(offset) => offset,
+        Err(e) => {
+            println!("Clock Drift: {} ({:#})", "unknown".yellow(), e);
+            return;
+        }
+    };
+
+    let (reference, threshold_ms) = match offset.source {
+        OffsetSource::Ntp => (config.ntp_server.as_str(), config.max_drift_ms as f64),
+        // Block times are whole seconds, so only drift beyond that resolution is meaningful
+        OffsetSource::BlockTime => ("block time", (config.max_drift_ms as f64).max(1000.0)),
+    };
+    let reading = format!("{:+.1}ms vs {}", offset.offset_ms, reference);
+
+    if offset.offset_ms.abs() > threshold_ms {
+        println!("Clock Drift: {}", reading.red());
+        println!("{} Host clock is off by more than {}ms, which delays votes and can cause missed slots",
+            "⚠".yellow(), threshold_ms);
+        println!("  Check time sync with {} or {}", "chronyc tracking".cyan(), "timedatectl status".cyan());
+    } else {
+        println!("Clock Drift: {}", reading.green());
+    }
+}
@@ -0,0 +1,161 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcGetVoteAccountsConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::blockchain::{fetch_vote_state, SolanaInterface};
+use crate::calibrate::Calibration;
+use crate::config::{HealthConfig, ValidatorConfig};
+use crate::health::{self, HealthInputs};
+use crate::native;
+use crate::tls;
+
+/// A cached reading younger than this is printed as-is, so a prompt
+/// rendering on every command doesn't hit the RPC each time
+const MAX_AGE: Duration = Duration::from_secs(5);
+
+/// RPC timeout for a fresh reading; a prompt can't wait long
+const RPC_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Slots counted as recent when computing the vote success rate
+const RECENT_VOTE_SLOTS: u64 = 150;
+
+/// The reading behind `status --compact`, cached between calls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CompactStatus {
+    read_at: DateTime<Utc>,
+    /// None when no validator is running or its RPC doesn't answer
+    health: Option<Health>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Health {
+    vote_success_rate: f64,
+    skip_rate: f64,
+    vote_lag: u64,
+    /// Slots the local node trails the cluster; None when no cluster endpoint answered
+    behind: Option<u64>,
+    /// None when the vote accounts couldn't be read
+    #[serde(default)]
+    delinquent: Option<bool>,
+}
+
+impl CompactStatus {
+    fn path() -> PathBuf {
+        ValidatorConfig::config_dir().join("status-compact.json")
+    }
+
+    fn load_fresh() -> Option<Self> {
+        let cached: Self = serde_json::from_str(&fs::read_to_string(Self::path()).ok()?).ok()?;
+        let age = Utc::now().signed_duration_since(cached.read_at).to_std().ok()?;
+        (age < MAX_AGE).then_some(cached)
+    }
+
+    fn save(&self) -> Result<()> {
+        fs::create_dir_all(ValidatorConfig::config_dir())?;
+        fs::write(Self::path(), serde_json::to_string(self)?).context("Failed to save compact status")
+    }
+
+    /// `✓ HEALTHY | 96.2% | skip 2.1% | lag 28 | behind 0`, or `✗ DOWN`
+    fn render(&self, thresholds: &HealthConfig, color: bool) -> String {
+        let Some(health) = &self.health else {
+            return if color { "✗ DOWN".red().bold().to_string() } else { "✗ DOWN".to_string() };
+        };
+
+        let behind = health.behind.map_or("?".to_string(), |slots| slots.to_string());
+        let line = format!("{:.1}% | skip {:.1}% | lag {} | behind {}",
+            health.vote_success_rate, health.skip_rate, health.vote_lag, behind);
+        let verdict = health::assess(thresholds, &HealthInputs {
+            vote_success_rate: Some(health.vote_success_rate),
+            skip_rate: Some(health.skip_rate),
+            vote_lag: Some(health.vote_lag),
+            delinquent: health.delinquent,
+            slots_behind: health.behind,
+        });
+        let headline = format!("{} {}", verdict.state.icon(), verdict.state.label());
+        if color {
+            format!("{} | {}", verdict.state.paint(&headline), line)
+        } else {
+            format!("{} | {}", headline, line)
+        }
+    }
+}
+
+fn rpc_client(url: &str) -> RpcClient {
+    tls::rpc_client_with_timeout(url, RPC_TIMEOUT, CommitmentConfig::confirmed())
+}
+
+/// Read the local validator directly, without the progress output of
+/// `get_validator_metrics` or its slower stake and leader schedule lookups
+fn read_health(config: &ValidatorConfig) -> Result<Health> {
+    let local_url = format!("http://127.0.0.1:{}", config.rpc_port);
+    let local = rpc_client(&local_url);
+
+    let vote_account = native::keypair_pubkey(&config.vote_account_keypair)?;
+    let slot = local.get_slot().context("Failed to get local slot")?;
+    let vote_state = fetch_vote_state(&local, &vote_account)?;
+    let samples = local.get_recent_performance_samples(Some(10)).unwrap_or_default();
+
+    let recent_votes = vote_state.votes.iter()
+        .filter(|v| v.slot() > slot.saturating_sub(RECENT_VOTE_SLOTS))
+        .count();
+    let skip_rate = SolanaInterface::calculate_skip_rate(&samples) * Calibration::load().skip_rate_factor;
+
+    // The first configured endpoint other than the local node stands in for the cluster
+    let behind = config.connect_endpoints().iter()
+        .filter(|endpoint| endpoint.as_str() != local_url)
+        .find_map(|endpoint| rpc_client(endpoint).get_slot().ok())
+        .map(|cluster_slot| cluster_slot.saturating_sub(slot));
+    let vote_pubkey = vote_account.to_string();
+    let delinquent = local
+        .get_vote_accounts_with_config(RpcGetVoteAccountsConfig {
+            vote_pubkey: Some(vote_pubkey.clone()),
+            ..RpcGetVoteAccountsConfig::default()
+        })
+        .ok()
+        .map(|accounts| accounts.delinquent.iter().any(|v| v.vote_pubkey == vote_pubkey));
+
+    Ok(Health {
+        vote_success_rate: (recent_votes as f64 / RECENT_VOTE_SLOTS as f64 * 100.0).min(100.0),
+        skip_rate: skip_rate.min(100.0),
+        vote_lag: slot.saturating_sub(vote_state.last_voted_slot().unwrap_or(slot)),
+        behind,
+        delinquent,
+    })
+}
+
+/// `status --compact`: one uncolored line for shell prompts and status bars
+///
+/// Readings are cached for a few seconds in `status-compact.json`, so calling
+/// this on every prompt render costs two file reads most of the time. The
+/// verdict is judged on each call, so edits to `health` show immediately.
+pub fn show_compact_status(color: bool) -> Result<()> {
+    if color {
+        colored::control::set_override(true);
+    }
+
+    let config = ValidatorConfig::load()?;
+    let status = match CompactStatus::load_fresh() {
+        Some(cached) => cached,
+        None => {
+            let health = if native::validator_pids().is_empty() {
+                None
+            } else {
+                read_health(&config).ok()
+            };
+            let status = CompactStatus { read_at: Utc::now(), health };
+            // A prompt must not break over a cache write
+            status.save().ok();
+            status
+        }
+    };
+
+    println!("{}", status.render(&config.health, color));
+    Ok(())
+}
@@ -1,22 +1,232 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use solana_sdk::hash::Hash;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
 use std::fs;
+use std::str::FromStr;
+
+use crate::flag_compat;
+use crate::smoothing::DEFAULT_EMA_ALPHA;
+
+/// Genesis hash of Solana testnet
+pub const TESTNET_GENESIS_HASH: &str = "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY";
+
+/// Genesis hash of Solana mainnet-beta
+pub const MAINNET_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+
+/// Genesis hash of Solana devnet
+pub const DEVNET_GENESIS_HASH: &str = "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG";
+
+/// Public RPC of each cluster
+pub const TESTNET_RPC_URL: &str = "https://api.testnet.solana.com";
+pub const MAINNET_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+pub const DEVNET_RPC_URL: &str = "https://api.devnet.solana.com";
+
+/// Cluster `start` sets the validator up for
+///
+/// In config.json: `"testnet"`, `"mainnet"` (or `"mainnet-beta"`), `"devnet"`,
+/// `"localnet"` or `{"custom": {"rpc_url": ..., "entrypoints": [...], "genesis_hash": ..., "known_validators": [...]}}`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Cluster {
+    /// Join testnet through its entrypoints, pinned to its genesis
+    #[default]
+    Testnet,
+    /// Join mainnet-beta; there is no faucet, so nothing is airdropped
+    #[serde(alias = "mainnet-beta")]
+    Mainnet,
+    Devnet,
+    /// Run `solana-test-validator` on this machine as the whole cluster
+    Localnet,
+    /// Any other cluster, e.g. a private one
+    Custom {
+        rpc_url: String,
+        /// `host:port` gossip entrypoints
+        entrypoints: Vec<String>,
+        #[serde(default)]
+        genesis_hash: Option<String>,
+        #[serde(default)]
+        known_validators: Vec<String>,
+    },
+}
+
+impl Cluster {
+    /// `--cluster` values that don't need settings
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "testnet" => Ok(Cluster::Testnet),
+            "mainnet" | "mainnet-beta" => Ok(Cluster::Mainnet),
+            "devnet" => Ok(Cluster::Devnet),
+            "localnet" => Ok(Cluster::Localnet),
+            _ => anyhow::bail!("Unknown cluster '{}'; use testnet, mainnet, devnet or localnet (a custom cluster goes in the config)", name),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cluster::Testnet => "testnet",
+            Cluster::Mainnet => "mainnet-beta",
+            Cluster::Devnet => "devnet",
+            Cluster::Localnet => "localnet",
+            Cluster::Custom { .. } => "custom",
+        }
+    }
+
+    /// RPC `start` sets up the vote account through: the public one, or
+    /// this machine's on `rpc_port` for localnet
+    pub fn rpc_url(&self, rpc_port: u16) -> String {
+        match self {
+            Cluster::Testnet => TESTNET_RPC_URL.to_string(),
+            Cluster::Mainnet => MAINNET_RPC_URL.to_string(),
+            Cluster::Devnet => DEVNET_RPC_URL.to_string(),
+            Cluster::Localnet => format!("http://127.0.0.1:{}", rpc_port),
+            Cluster::Custom { rpc_url, .. } => rpc_url.clone(),
+        }
+    }
+
+    pub fn entrypoints(&self) -> Vec<String> {
+        let numbered = |host: &str, count: usize| -> Vec<String> {
+            (1..=count)
+                .map(|n| if n == 1 { format!("entrypoint.{}:8001", host) } else { format!("entrypoint{}.{}:8001", n, host) })
+                .collect()
+        };
+        match self {
+            Cluster::Testnet => numbered("testnet.solana.com", 3),
+            Cluster::Mainnet => numbered("mainnet-beta.solana.com", 5),
+            Cluster::Devnet => numbered("devnet.solana.com", 5),
+            Cluster::Localnet => Vec::new(),
+            Cluster::Custom { entrypoints, .. } => entrypoints.clone(),
+        }
+    }
+
+    pub fn genesis_hash(&self) -> Option<&str> {
+        match self {
+            Cluster::Testnet => Some(TESTNET_GENESIS_HASH),
+            Cluster::Mainnet => Some(MAINNET_GENESIS_HASH),
+            Cluster::Devnet => Some(DEVNET_GENESIS_HASH),
+            Cluster::Localnet => None,
+            Cluster::Custom { genesis_hash, .. } => genesis_hash.as_deref(),
+        }
+    }
+
+    /// Validators snapshots are fetched from
+    pub fn known_validators(&self) -> Vec<String> {
+        let keys: &[&str] = match self {
+            Cluster::Testnet => &[
+                "5D1fNXzvv5NjV1ysLjirC4WY92RNsVH18vjmcszZd8on",
+                "7XSY3MrYnK8vq693Rju17bbPkCN3Z7KvvfvJx4kdrsSY",
+            ],
+            Cluster::Mainnet => &[
+                "7Np41oeYqPefeNQEHSv1UDhYrehxin3NStELsSKCT4K2",
+                "GdnSyH3YtwcxFvQrVVJMm1JhTS4QVX7MFsX56uJLUfiZ",
+                "DE1bawNcRJB9rVm3buyMVfr8mBEoyyu73NBovf2oXJsJ",
+                "CakcnaRDHka2gXyfbEd2d3xsvkJkqsLw2akB3zsN1D2S",
+            ],
+            Cluster::Devnet => &[
+                "dv1ZAGvdsz5hHLwWXsVnM94hWf1pjbKVau1QVkaMJ92",
+                "dv2eQHeP4RFrJZ6UeiZWoc3XTtmtZCUKxxCApCDcRNV",
+                "dv4ACNkpYPcE3aKmYDqZm9G5EB3J4MRoeE7WNDRBVJB",
+                "dv3qDFk1DTF36Z62bNvrCXe9sKATA6xvVy6A798xxAS",
+            ],
+            Cluster::Localnet => &[],
+            Cluster::Custom { known_validators, .. } => return known_validators.clone(),
+        };
+        keys.iter().map(|key| key.to_string()).collect()
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ValidatorConfig {
+    /// `start --localnet` uses localnet for one run without changing this
+    pub cluster: Cluster,
     pub identity_keypair: PathBuf,
     pub vote_account_keypair: PathBuf,
     pub ledger_path: PathBuf,
     pub accounts_path: PathBuf,
     pub snapshots_path: PathBuf,
     pub log_path: PathBuf,
+    /// Where generated reports and config exports are written
+    pub output_dir: PathBuf,
     pub rpc_port: u16,
     pub gossip_port: u16,
+    /// `--rpc-bind-address`: where the RPC listens; loopback keeps it private
+    pub rpc_bind_address: IpAddr,
+    /// `--dynamic-port-range`: ports the validator takes its other sockets
+    /// from; must not contain the RPC or gossip ports
+    pub dynamic_port_range: PortRange,
+    /// `--expected-shred-version`; omitted when unset
+    pub expected_shred_version: Option<u16>,
+    /// `--expected-genesis-hash` (base58); omitted when unset
+    pub expected_genesis_hash: Option<String>,
+    /// File of `host:port` gossip entrypoints, one per line; replaces the
+    /// cluster's entrypoints when set
+    pub entrypoints_file: Option<PathBuf>,
     pub optimization: OptimizationConfig,
+    pub log_rotation: LogRotationConfig,
+    pub graceful_restart: GracefulRestartConfig,
+    pub status_bands: StatusBands,
+    /// EMA weight (0-1] for smoothing TPS, skip rate and latency readings
+    pub metrics_ema_alpha: f64,
+    /// Auto-optimize turns itself off after this many consecutive failed optimizations
+    pub auto_optimize_max_failures: u32,
+    /// Refresh cadence of `monitor`, `monitor-contract` and `optimize --auto`
+    pub poll_interval: PollIntervalConfig,
+    /// Extra RPC endpoints metric reads are cross-checked against
+    pub rpc_consensus: RpcConsensusConfig,
+    /// RPCs to switch to, in order, when the one in use keeps failing
+    pub failover_rpc_urls: Vec<String>,
+    /// When to fail over to `failover_rpc_urls` and back
+    pub rpc_failover: RpcFailoverConfig,
+    /// Endpoints tried, in order, when connecting to the validator
+    pub connect: ConnectConfig,
+    /// What commands do when no validator answers
+    pub on_disconnect: OnDisconnect,
+    /// Snapshot history the real-time optimizer keeps across runs
+    pub optimizer_history: OptimizerHistoryConfig,
+    /// Minimum time between two `optimize --auto` changes to the same setting
+    pub optimization_cooldowns: CooldownConfig,
+    /// Rotation of the audit log of every change the tool makes
+    pub audit_log: AuditLogConfig,
+    /// JSON file of custom real-time optimizer rules (`config validate-rules` checks it)
+    pub optimizer_rules: Option<PathBuf>,
+    /// Host clock drift check in `status`
+    pub clock: ClockConfig,
+    /// Compute unit price attached to every transaction the tool signs
+    pub priority_fee: PriorityFeeStrategy,
+    pub alerts: AlertConfig,
+    pub disk: DiskConfig,
+    /// How much of the host's RAM the accounts-db cache and index may take
+    pub memory_budget: MemoryBudgetConfig,
+    /// Metrics shown by `monitor` and the dashboard, in display order; unset
+    /// keeps each view's own set (`MetricField::MONITOR` and `ALL`)
+    pub display_metrics: Option<Vec<MetricField>>,
+    /// Backends every `monitor` and dashboard reading is pushed to
+    pub metrics_sinks: Vec<MetricsSinkConfig>,
+    /// Seconds between dashboard refreshes; `poll_interval.base_secs` (or 5)
+    /// when unset
+    pub display_interval: Option<u64>,
+    /// Seconds between pushes to `metrics_sinks` from the dashboard
+    pub export_interval: u64,
+    /// Cost and reward assumptions behind `breakeven`
+    pub breakeven: BreakevenConfig,
+    /// What counts as healthy in the dashboard and `status --compact` verdict
+    pub health: HealthConfig,
+    /// Points each input is worth in the validator's optimization score
+    pub score_weights: ScoreWeights,
+    /// What counts toward the vote reliability SLA
+    pub sla: SlaConfig,
+    /// Where airdrops are requested from, in order
+    pub faucet: FaucetConfig,
+    /// PEM bundle of extra CAs trusted for RPC connections, e.g. a corporate proxy's
+    pub rpc_ca_bundle: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct OptimizationConfig {
     pub rpc_threads: u32,
     pub accounts_db_threads: u32,
@@ -29,21 +239,601 @@ pub struct OptimizationConfig {
     pub udp_buffer_size: usize,
 }
 
+/// Rotation policy for validator output captured by the process manager
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogRotationConfig {
+    pub enabled: bool,
+    pub max_size_mb: u64,
+    pub max_files: u32,
+    pub compress: bool,
+}
+
+/// Leader-window-aware restart policy used by the process manager
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GracefulRestartConfig {
+    /// Only restart when the next leader slot is at least this many slots away
+    pub min_leader_gap_slots: u64,
+    /// Restart anyway (with a warning) after waiting this long
+    pub max_wait_secs: u64,
+    /// Let the auto-optimizer restart the validator itself; when false,
+    /// restart-requiring changes are queued for `apply --restart`
+    pub auto_restart: bool,
+}
+
+/// Where alerts go and when vote-related alerts fire
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertConfig {
+    /// Webhook that receives a JSON POST per alert (Slack/Discord compatible)
+    pub webhook_url: Option<String>,
+    /// Further destinations every alert is delivered to
+    pub sinks: Vec<AlertSinkConfig>,
+    /// Alert when the latest vote jumps ahead by more than this many slots
+    pub max_vote_gap_slots: u64,
+    /// Alert once when the vote account hasn't changed for this long
+    pub vote_stall_secs: u64,
+    /// Alert in `monitor` when the vote lag exceeds this
+    pub vote_lag_threshold: VoteLagThreshold,
+    /// Alert in `dashboard` when vote credits stay flat for this many slots
+    /// while the validator is active (not delinquent, vote lag under threshold)
+    pub credits_stall_slots: u64,
+}
+
+/// An alert destination
+///
+/// In config.json: `{"webhook": {"url": "https://..."}}` or
+/// `{"command": {"program": "/usr/local/bin/page", "args": ["--team", "validators"]}}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSinkConfig {
+    /// JSON POST of the alert
+    Webhook { url: String },
+    /// Program run per alert, the alert as JSON on stdin and in `ALERT_*` env vars
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+/// Vote lag alert threshold, in slots or in wall-clock time
+///
+/// In config.json: `{"slots": 150}` or `{"ms": 60000}`; milliseconds are
+/// compared against the lag converted with the measured slot time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteLagThreshold {
+    Slots(u64),
+    Ms(u64),
+}
+
+impl VoteLagThreshold {
+    pub fn exceeded(&self, lag_slots: u64, slot_time_ms: f64) -> bool {
+        match *self {
+            VoteLagThreshold::Slots(slots) => lag_slots > slots,
+            VoteLagThreshold::Ms(ms) => lag_slots as f64 * slot_time_ms > ms as f64,
+        }
+    }
+
+    /// The threshold in slots, converting milliseconds with `slot_time_ms`
+    pub fn in_slots(&self, slot_time_ms: f64) -> f64 {
+        match *self {
+            VoteLagThreshold::Slots(slots) => slots as f64,
+            VoteLagThreshold::Ms(ms) => ms as f64 / slot_time_ms.max(1.0),
+        }
+    }
+
+    /// Both units, e.g. `150 slots (~60.0s)`
+    pub fn describe(&self, slot_time_ms: f64) -> String {
+        match *self {
+            VoteLagThreshold::Slots(slots) => {
+                format!("{} slots (~{:.1}s)", slots, slots as f64 * slot_time_ms / 1000.0)
+            }
+            VoteLagThreshold::Ms(ms) => {
+                format!("{:.1}s (~{:.0} slots)", ms as f64 / 1000.0, ms as f64 / slot_time_ms.max(1.0))
+            }
+        }
+    }
+}
+
+/// Operating cost and reward assumptions for `breakeven`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BreakevenConfig {
+    /// Fee paid per vote transaction (one signature)
+    pub vote_fee_lamports: u64,
+    /// Vote transactions sent per slot
+    pub votes_per_slot: f64,
+    /// Everything else the validator costs per epoch (hosting, bandwidth), in SOL
+    pub other_costs_sol_per_epoch: f64,
+    /// Epochs the vote account's rent-exempt deposit is written off over
+    pub rent_amortization_epochs: u64,
+    /// Gross staking APR to assume instead of the cluster's inflation-based one
+    pub apr_pct: Option<f64>,
+}
+
+/// Where one input to the health verdict starts to count against it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct HealthLimit {
+    /// Past this the input adds `weight` to the score
+    pub degraded: f64,
+    /// Past this the input adds twice `weight`
+    pub critical: f64,
+    #[serde(default = "default_health_weight")]
+    pub weight: f64,
+}
+
+fn default_health_weight() -> f64 {
+    1.0
+}
+
+/// Thresholds and weights behind the Healthy/Degraded/Critical verdict
+///
+/// Each input past its limit adds to a score; the score decides the verdict.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+    /// Recent vote success %, where lower is worse
+    pub vote_success: HealthLimit,
+    /// Leader skip rate %
+    pub skip_rate: HealthLimit,
+    /// Slots between the tip and the last vote
+    pub vote_lag: HealthLimit,
+    /// Slots the local node trails the cluster
+    pub slots_behind: HealthLimit,
+    /// Added twice over while the cluster lists the validator as delinquent
+    pub delinquent_weight: f64,
+    /// Score from which the verdict is Degraded
+    pub degraded_score: f64,
+    /// Score from which the verdict is Critical
+    pub critical_score: f64,
+}
+
+/// Points each input is worth in the validator's 0-100 optimization score
+///
+/// An input earns all its points within its `health` degraded limit, none
+/// past its critical limit, and a linear share in between. The total is
+/// scaled to 100, so the weights only matter relative to each other.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoreWeights {
+    pub vote_success: f64,
+    pub skip_rate: f64,
+    pub vote_lag: f64,
+    /// All of it while not delinquent, none while delinquent
+    pub delinquency: f64,
+    /// The share of the optimizer's kernel parameters at their targets
+    pub optimizations: f64,
+}
+
+/// The vote reliability SLA `sla`, `status` and `report` show
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlaConfig {
+    /// Vote success % a sample needs to count toward the SLA
+    pub vote_success_threshold: f64,
+    /// SLA % shown green at or above, red below
+    pub target_pct: f64,
+    /// How often `sla --track` samples the validator (30s at least)
+    pub sample_interval_secs: u64,
+}
+
+/// Faucets tried in turn when an airdrop is rate limited
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FaucetConfig {
+    /// RPC endpoints whose `requestAirdrop` is tried in order; the connected RPC when empty
+    pub endpoints: Vec<String>,
+    /// Web faucet API tried after every RPC faucet
+    pub web: Option<WebFaucetConfig>,
+    /// How long a rate-limited faucet is skipped
+    pub cooldown_secs: u64,
+}
+
+/// A web faucet taking `{"walletAddress", "amount", "network"}` as a JSON POST
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebFaucetConfig {
+    pub url: String,
+    #[serde(default = "default_web_faucet_network")]
+    pub network: String,
+}
+
+fn default_web_faucet_network() -> String {
+    "testnet".to_string()
+}
+
+/// Disk usage reporting thresholds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiskConfig {
+    /// Warn when a data directory's size exceeds this percent of the free space left
+    pub usage_warn_pct_of_free: f64,
+}
+
+/// The validator's expected memory use checked against the host before `start`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryBudgetConfig {
+    /// Refuse to start when the estimate exceeds this percent of total RAM
+    pub max_ram_pct: f64,
+    /// What the validator uses besides the accounts-db cache and index
+    pub validator_baseline_mb: u64,
+}
+
+/// Polling cadence that backs off while the RPC keeps failing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PollIntervalConfig {
+    /// Interval between refreshes while the RPC is healthy; unset, each
+    /// command keeps its own (dashboard 5s, `monitor-contract` 30s, others 10s)
+    pub base_secs: Option<u64>,
+    /// Upper bound the interval backs off to
+    pub max_secs: u64,
+    /// Factor the interval grows by after each consecutive RPC error
+    pub backoff_multiplier: f64,
+}
+
+/// Failing over between the primary RPC and `failover_rpc_urls`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RpcFailoverConfig {
+    /// Consecutive failed metric reads before switching to the next RPC
+    pub failures_before_switch: u32,
+    /// Seconds after a failover before the primary is tried again, and
+    /// between retries while it stays down
+    pub primary_retry_secs: u64,
+}
+
+/// Cross-checking metric reads across several RPC endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RpcConsensusConfig {
+    /// Endpoints queried alongside the primary RPC; empty disables the check
+    pub endpoints: Vec<String>,
+    /// How many endpoints (primary first) to query on each read
+    pub quorum: usize,
+    /// Which slot to trust when the endpoints disagree
+    pub slot_policy: SlotPolicy,
+    /// Flag endpoints more than this many slots behind the agreed slot
+    pub max_slot_lag: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlotPolicy {
+    Highest,
+    Median,
+}
+
+/// Where `optimize`, `monitor` and the standalone optimizer look for a validator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectConfig {
+    /// RPC URLs tried in order; the first one answering is used. Empty for
+    /// this machine's RPC, then the cluster's
+    pub endpoints: Vec<String>,
+    /// How long each endpoint gets to answer
+    pub timeout_secs: u64,
+}
+
+/// Persistence of the real-time optimizer's performance snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OptimizerHistoryConfig {
+    /// Most recent snapshots kept in memory and on disk
+    pub retention: usize,
+    /// How long before and after a past change its effect on the metrics is judged
+    pub correlation_window_secs: u64,
+}
+
+/// How long `optimize --auto` leaves a setting alone after changing it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CooldownConfig {
+    /// Cooldown of settings not listed in `parameters`
+    pub default_secs: u64,
+    /// Cooldown per setting, by validator flag or sysctl name (`tpu-coalesce-ms`)
+    pub parameters: BTreeMap<String, u64>,
+}
+
+impl CooldownConfig {
+    pub fn cooldown(&self, parameter: &str) -> u64 {
+        self.parameters.get(parameter)
+            .or_else(|| self.parameters.get(&parameter.replace('-', "_")))
+            .copied()
+            .unwrap_or(self.default_secs)
+    }
+}
+
+/// When `audit.jsonl` is rotated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditLogConfig {
+    pub max_size_mb: u64,
+    /// Rotated files kept, `audit.jsonl.1` newest
+    pub max_files: u32,
+}
+
+/// Where the host clock is checked against and how much drift is tolerated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockConfig {
+    /// SNTP server as host:port
+    pub ntp_server: String,
+    /// Warn when the host clock is off by more than this
+    pub max_drift_ms: u64,
+}
+
+/// How to price compute units on transactions the tool sends
+///
+/// In config.json: `"none"`, `{"fixed": 5000}` (micro-lamports per CU) or
+/// `{"dynamic": 75}` (percentile of recent fees paid on the same accounts)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriorityFeeStrategy {
+    #[default]
+    None,
+    Fixed(u64),
+    Dynamic(u8),
+}
+
+/// What `monitor`, `optimize` and the test binaries do when no validator answers
+///
+/// In config.json: `"baseline"` (carry on with zeroed metrics), `"error"`
+/// (exit non-zero) or `{"wait": 600}` (retry until one answers, giving up
+/// after that many seconds)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnDisconnect {
+    #[default]
+    Baseline,
+    Error,
+    Wait(u64),
+}
+
+/// A metrics backend readings are pushed to
+///
+/// In config.json: `{"statsd": {"address": "127.0.0.1:8125"}}`,
+/// `{"influxdb": {"url": "http://localhost:8086/api/v2/write?org=o&bucket=b", "token": "..."}}`
+/// or `{"pushgateway": {"url": "http://localhost:9091"}}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricsSinkConfig {
+    /// Gauges over UDP, tagged DogStatsD-style
+    Statsd {
+        address: String,
+        #[serde(default = "default_metrics_prefix")]
+        prefix: String,
+    },
+    /// Line protocol POSTed to a write endpoint (v1 `/write?db=` or v2 `/api/v2/write?...`)
+    Influxdb {
+        url: String,
+        #[serde(default)]
+        token: Option<String>,
+        #[serde(default = "default_metrics_prefix")]
+        measurement: String,
+    },
+    /// Text exposition PUT to a Prometheus pushgateway
+    Pushgateway {
+        url: String,
+        #[serde(default = "default_metrics_prefix")]
+        job: String,
+    },
+}
+
+fn default_metrics_prefix() -> String {
+    "solana_validator".to_string()
+}
+
+/// A metric `monitor` and the dashboard can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricField {
+    Epoch,
+    Slot,
+    VoteSuccessRate,
+    SkipRate,
+    CreditsEarned,
+    VoteLag,
+    NetworkLatencyMs,
+    AvgTps,
+}
+
+impl MetricField {
+    pub const ALL: [MetricField; 8] = [
+        MetricField::Epoch,
+        MetricField::Slot,
+        MetricField::VoteSuccessRate,
+        MetricField::SkipRate,
+        MetricField::CreditsEarned,
+        MetricField::VoteLag,
+        MetricField::NetworkLatencyMs,
+        MetricField::AvgTps,
+    ];
+
+    /// What plain `monitor` shows by default: everything but TPS, which only
+    /// the dashboard shows unless asked for
+    pub const MONITOR: [MetricField; 7] = [
+        MetricField::Epoch,
+        MetricField::Slot,
+        MetricField::VoteSuccessRate,
+        MetricField::SkipRate,
+        MetricField::CreditsEarned,
+        MetricField::VoteLag,
+        MetricField::NetworkLatencyMs,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MetricField::Epoch => "epoch",
+            MetricField::Slot => "slot",
+            MetricField::VoteSuccessRate => "vote_success_rate",
+            MetricField::SkipRate => "skip_rate",
+            MetricField::CreditsEarned => "credits_earned",
+            MetricField::VoteLag => "vote_lag",
+            MetricField::NetworkLatencyMs => "network_latency_ms",
+            MetricField::AvgTps => "avg_tps",
+        }
+    }
+}
+
+impl FromStr for MetricField {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        MetricField::ALL
+            .into_iter()
+            .find(|field| field.name() == s.trim())
+            .ok_or_else(|| format!(
+                "unknown metric '{}' (valid: {})",
+                s,
+                MetricField::ALL.map(|f| f.name()).join(", ")
+            ))
+    }
+}
+
+/// An inclusive port range, written `8002-8022`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl PortRange {
+    /// Fewest ports the validator's dynamic range can hold its sockets in
+    pub const MIN_LEN: u32 = 17;
+
+    pub fn contains(&self, port: u16) -> bool {
+        (self.start..=self.end).contains(&port)
+    }
+
+    /// In u32, as `0-65535` holds one more port than u16 can count
+    pub fn port_count(&self) -> u32 {
+        u32::from(self.end) - u32::from(self.start) + 1
+    }
+}
+
+impl FromStr for PortRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (start, end) = s.trim().split_once('-')
+            .ok_or_else(|| format!("port range '{}' is not START-END", s))?;
+        let port = |text: &str| text.trim().parse::<u16>()
+            .map_err(|_| format!("'{}' in port range '{}' is not a port", text, s));
+        let range = PortRange { start: port(start)?, end: port(end)? };
+        if range.start > range.end {
+            return Err(format!("port range '{}' ends before it starts", s));
+        }
+        Ok(range)
+    }
+}
+
+impl TryFrom<String> for PortRange {
+    type Error = String;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<PortRange> for String {
+    fn from(range: PortRange) -> Self {
+        range.to_string()
+    }
+}
+
+impl fmt::Display for PortRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// Cutoffs for the green/yellow/red status bands shared by every display
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusBands {
+    /// Vote success rate (%), higher is better
+    pub vote_success: Band,
+    /// Skip rate (%), lower is better
+    pub skip_rate: Band,
+    /// Vote lag (slots), lower is better
+    pub vote_lag: Band,
+}
+
+/// Boundaries between the EXCELLENT / GOOD / FAIR / NEEDS IMPROVEMENT levels
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Band {
+    pub excellent: f64,
+    pub good: f64,
+    pub fair: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusLevel {
+    Excellent,
+    Good,
+    Fair,
+    NeedsImprovement,
+}
+
 impl Default for ValidatorConfig {
     fn default() -> Self {
         let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
         let base_path = PathBuf::from(&home).join("solana-validator");
         
         ValidatorConfig {
+            cluster: Cluster::Testnet,
             identity_keypair: base_path.join("validator-keypair.json"),
             vote_account_keypair: base_path.join("vote-account-keypair.json"),
             ledger_path: base_path.join("ledger"),
             accounts_path: base_path.join("accounts"),
             snapshots_path: base_path.join("snapshots"),
             log_path: base_path.join("logs").join("validator.log"),
+            output_dir: Self::config_dir().join("output"),
             rpc_port: 8899,
             gossip_port: 8001,
+            rpc_bind_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            dynamic_port_range: PortRange { start: 8002, end: 8022 },
+            // Testnet's shred version changes with every cluster restart, so
+            // only the genesis hash is pinned by default
+            expected_shred_version: None,
+            expected_genesis_hash: Some(TESTNET_GENESIS_HASH.to_string()),
+            entrypoints_file: None,
             optimization: OptimizationConfig::default(),
+            log_rotation: LogRotationConfig::default(),
+            graceful_restart: GracefulRestartConfig::default(),
+            status_bands: StatusBands::default(),
+            metrics_ema_alpha: DEFAULT_EMA_ALPHA,
+            auto_optimize_max_failures: 3,
+            poll_interval: PollIntervalConfig::default(),
+            rpc_consensus: RpcConsensusConfig::default(),
+            failover_rpc_urls: Vec::new(),
+            rpc_failover: RpcFailoverConfig::default(),
+            connect: ConnectConfig::default(),
+            on_disconnect: OnDisconnect::default(),
+            optimizer_history: OptimizerHistoryConfig::default(),
+            optimization_cooldowns: CooldownConfig::default(),
+            audit_log: AuditLogConfig::default(),
+            optimizer_rules: None,
+            clock: ClockConfig::default(),
+            priority_fee: PriorityFeeStrategy::default(),
+            alerts: AlertConfig::default(),
+            disk: DiskConfig::default(),
+            memory_budget: MemoryBudgetConfig::default(),
+            display_metrics: None,
+            metrics_sinks: Vec::new(),
+            display_interval: None,
+            export_interval: 15,
+            breakeven: BreakevenConfig::default(),
+            health: HealthConfig::default(),
+            score_weights: ScoreWeights::default(),
+            sla: SlaConfig::default(),
+            faucet: FaucetConfig::default(),
+            rpc_ca_bundle: None,
         }
     }
 }
@@ -64,7 +854,356 @@ impl Default for OptimizationConfig {
     }
 }
 
+impl Default for LogRotationConfig {
+    fn default() -> Self {
+        LogRotationConfig {
+            enabled: false,
+            max_size_mb: 512,
+            max_files: 5,
+            compress: true,
+        }
+    }
+}
+
+impl Default for GracefulRestartConfig {
+    fn default() -> Self {
+        GracefulRestartConfig {
+            min_leader_gap_slots: 150, // ~60s at 400ms slots
+            max_wait_secs: 600,
+            auto_restart: false,
+        }
+    }
+}
+
+impl PollIntervalConfig {
+    /// `base_secs`, or `default_secs` for commands that keep their own cadence
+    pub fn base_secs_or(&self, default_secs: u64) -> u64 {
+        self.base_secs.unwrap_or(default_secs).max(1)
+    }
+}
+
+impl Default for PollIntervalConfig {
+    fn default() -> Self {
+        PollIntervalConfig {
+            base_secs: None,
+            max_secs: 300,
+            backoff_multiplier: 2.0,
+        }
+    }
+}
+
+impl Default for RpcFailoverConfig {
+    fn default() -> Self {
+        RpcFailoverConfig {
+            failures_before_switch: 3,
+            primary_retry_secs: 300,
+        }
+    }
+}
+
+impl Default for RpcConsensusConfig {
+    fn default() -> Self {
+        RpcConsensusConfig {
+            endpoints: Vec::new(),
+            quorum: 3,
+            slot_policy: SlotPolicy::Median,
+            max_slot_lag: 50, // ~20s at 400ms slots
+        }
+    }
+}
+
+impl Default for ConnectConfig {
+    fn default() -> Self {
+        ConnectConfig {
+            endpoints: Vec::new(),
+            timeout_secs: 5,
+        }
+    }
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        ClockConfig {
+            ntp_server: "pool.ntp.org:123".to_string(),
+            max_drift_ms: 50,
+        }
+    }
+}
+
+impl Default for MemoryBudgetConfig {
+    fn default() -> Self {
+        MemoryBudgetConfig {
+            max_ram_pct: 85.0,
+            validator_baseline_mb: 8192,
+        }
+    }
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        AuditLogConfig {
+            max_size_mb: 10,
+            max_files: 5,
+        }
+    }
+}
+
+impl Default for CooldownConfig {
+    fn default() -> Self {
+        // Latency knobs may move often; snapshot and memory layout shouldn't thrash
+        let parameters = [
+            ("tpu-coalesce-ms", 120),
+            ("rpc-threads", 1800),
+            ("accounts-db-threads", 1800),
+            ("enable-quic", 3600),
+            ("tcp-fastopen", 3600),
+            ("udp-buffer", 3600),
+            ("accounts-db-cache-size", 6 * 3600),
+            ("incremental-snapshot-interval", 6 * 3600),
+        ];
+        CooldownConfig {
+            default_secs: 900,
+            parameters: parameters.into_iter().map(|(name, secs)| (name.to_string(), secs)).collect(),
+        }
+    }
+}
+
+impl Default for OptimizerHistoryConfig {
+    fn default() -> Self {
+        OptimizerHistoryConfig {
+            retention: 100,
+            correlation_window_secs: 600,
+        }
+    }
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        AlertConfig {
+            webhook_url: None,
+            sinks: Vec::new(),
+            max_vote_gap_slots: 32,
+            vote_stall_secs: 30,
+            vote_lag_threshold: VoteLagThreshold::Slots(150), // ~60s at 400ms slots
+            credits_stall_slots: 300, // ~2 minutes at 400ms slots
+        }
+    }
+}
+
+impl Default for BreakevenConfig {
+    fn default() -> Self {
+        BreakevenConfig {
+            vote_fee_lamports: 5_000,
+            votes_per_slot: 1.0,
+            other_costs_sol_per_epoch: 0.0,
+            rent_amortization_epochs: 182, // ~1 year of 2-day epochs
+            apr_pct: None,
+        }
+    }
+}
+
+impl HealthLimit {
+    pub fn new(degraded: f64, critical: f64) -> Self {
+        HealthLimit { degraded, critical, weight: default_health_weight() }
+    }
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        HealthConfig {
+            vote_success: HealthLimit::new(95.0, 80.0),
+            skip_rate: HealthLimit::new(5.0, 15.0),
+            vote_lag: HealthLimit::new(50.0, 150.0),
+            slots_behind: HealthLimit::new(50.0, 300.0),
+            delinquent_weight: 1.0,
+            degraded_score: 1.0,
+            critical_score: 2.0,
+        }
+    }
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        ScoreWeights {
+            vote_success: 30.0,
+            skip_rate: 25.0,
+            vote_lag: 20.0,
+            delinquency: 15.0,
+            optimizations: 10.0,
+        }
+    }
+}
+
+impl Default for SlaConfig {
+    fn default() -> Self {
+        SlaConfig {
+            vote_success_threshold: 90.0,
+            target_pct: 99.0,
+            sample_interval_secs: 60,
+        }
+    }
+}
+
+impl Default for FaucetConfig {
+    fn default() -> Self {
+        FaucetConfig {
+            endpoints: Vec::new(),
+            web: None,
+            cooldown_secs: 3600,
+        }
+    }
+}
+
+impl Default for DiskConfig {
+    fn default() -> Self {
+        DiskConfig {
+            usage_warn_pct_of_free: 100.0,
+        }
+    }
+}
+
+impl Default for StatusBands {
+    fn default() -> Self {
+        StatusBands {
+            vote_success: Band { excellent: 95.0, good: 90.0, fair: 80.0 },
+            skip_rate: Band { excellent: 3.0, good: 8.0, fair: 15.0 },
+            vote_lag: Band { excellent: 30.0, good: 50.0, fair: 100.0 },
+        }
+    }
+}
+
+impl StatusBands {
+    pub fn vote_success(&self, rate: f64) -> StatusLevel {
+        self.vote_success.level_higher_is_better(rate)
+    }
+
+    pub fn skip_rate(&self, rate: f64) -> StatusLevel {
+        self.skip_rate.level_lower_is_better(rate)
+    }
+
+    pub fn vote_lag(&self, lag: u64) -> StatusLevel {
+        self.vote_lag.level_lower_is_better(lag as f64)
+    }
+}
+
+impl Band {
+    fn level_higher_is_better(&self, value: f64) -> StatusLevel {
+        if value >= self.excellent {
+            StatusLevel::Excellent
+        } else if value >= self.good {
+            StatusLevel::Good
+        } else if value >= self.fair {
+            StatusLevel::Fair
+        } else {
+            StatusLevel::NeedsImprovement
+        }
+    }
+
+    fn level_lower_is_better(&self, value: f64) -> StatusLevel {
+        if value <= self.excellent {
+            StatusLevel::Excellent
+        } else if value <= self.good {
+            StatusLevel::Good
+        } else if value <= self.fair {
+            StatusLevel::Fair
+        } else {
+            StatusLevel::NeedsImprovement
+        }
+    }
+}
+
+impl StatusLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StatusLevel::Excellent => "EXCELLENT",
+            StatusLevel::Good => "GOOD",
+            StatusLevel::Fair => "FAIR",
+            StatusLevel::NeedsImprovement => "NEEDS IMPROVEMENT",
+        }
+    }
+
+    pub fn color(&self) -> &'static str {
+        match self {
+            StatusLevel::Excellent => "green",
+            StatusLevel::Good | StatusLevel::Fair => "yellow",
+            StatusLevel::NeedsImprovement => "red",
+        }
+    }
+}
+
 impl ValidatorConfig {
+    /// `poll_interval` with `display_interval` as its base, for the dashboard
+    pub fn display_poll_interval(&self) -> PollIntervalConfig {
+        PollIntervalConfig {
+            base_secs: self.display_interval.or(self.poll_interval.base_secs),
+            ..self.poll_interval.clone()
+        }
+    }
+
+    /// Whether the validator joins mainnet-beta or is pinned to its genesis
+    pub fn is_mainnet(&self) -> bool {
+        self.cluster == Cluster::Mainnet
+            || self.cluster.genesis_hash() == Some(MAINNET_GENESIS_HASH)
+            || self.expected_genesis_hash.as_deref() == Some(MAINNET_GENESIS_HASH)
+    }
+
+    /// RPC of the configured cluster
+    pub fn cluster_rpc_url(&self) -> String {
+        self.cluster.rpc_url(self.rpc_port)
+    }
+
+    /// This machine's validator RPC: `rpc_bind_address` on `rpc_port`, or
+    /// loopback when it binds every interface
+    pub fn local_rpc_url(&self) -> String {
+        let host = match self.rpc_bind_address {
+            IpAddr::V4(ip) if ip.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(ip) if ip.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+            ip => ip,
+        };
+        format!("http://{}", SocketAddr::new(host, self.rpc_port))
+    }
+
+    /// `connect.endpoints`, or this machine's RPC then the cluster's
+    pub fn connect_endpoints(&self) -> Vec<String> {
+        if !self.connect.endpoints.is_empty() {
+            return self.connect.endpoints.clone();
+        }
+        let mut endpoints = vec![self.local_rpc_url()];
+        let cluster = self.cluster_rpc_url();
+        if !endpoints.contains(&cluster) {
+            endpoints.push(cluster);
+        }
+        endpoints
+    }
+
+    /// Switch to `cluster`, dropping an `expected_genesis_hash` that pins
+    /// another well-known cluster (e.g. the testnet default); true if dropped
+    pub fn set_cluster(&mut self, cluster: Cluster) -> bool {
+        let known = [TESTNET_GENESIS_HASH, MAINNET_GENESIS_HASH, DEVNET_GENESIS_HASH];
+        let stale = self.expected_genesis_hash.as_deref()
+            .is_some_and(|hash| known.contains(&hash) && Some(hash) != cluster.genesis_hash());
+        if stale {
+            self.expected_genesis_hash = None;
+        }
+        // Endpoints that were just the old cluster's defaults follow the new one
+        if self.connect.endpoints == [self.local_rpc_url(), self.cluster_rpc_url()] {
+            self.connect.endpoints.clear();
+        }
+        self.cluster = cluster;
+        stale
+    }
+
+    /// The genesis hash the validator is pinned to: `expected_genesis_hash`,
+    /// which must agree with the cluster's own when it has one
+    fn pinned_genesis_hash(&self) -> Result<Option<&str>> {
+        match (self.expected_genesis_hash.as_deref(), self.cluster.genesis_hash()) {
+            (Some(expected), Some(cluster)) if expected != cluster => anyhow::bail!(
+                "expected_genesis_hash {} is not {}'s genesis ({}); remove it or set cluster to match",
+                expected, self.cluster.name(), cluster),
+            (expected, cluster) => Ok(expected.or(cluster)),
+        }
+    }
+
     pub fn load() -> Result<Self> {
         let config_path = Self::config_path();
         if config_path.exists() {
@@ -85,30 +1224,82 @@ impl ValidatorConfig {
         Ok(())
     }
 
-    fn config_path() -> PathBuf {
+    pub fn config_path() -> PathBuf {
+        Self::config_dir().join("config.json")
+    }
+
+    /// Output directory (the override if given), created if missing
+    pub fn output_dir(&self, override_dir: Option<&Path>) -> Result<PathBuf> {
+        let dir = override_dir.map(Path::to_path_buf).unwrap_or_else(|| self.output_dir.clone());
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create output directory {}", dir.display()))?;
+        Ok(dir)
+    }
+
+    /// Directory holding the config and the optimizer's state files
+    pub fn config_dir() -> PathBuf {
         let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-        PathBuf::from(home).join(".solana-optimizer").join("config.json")
+        PathBuf::from(home).join(".solana-optimizer")
     }
 
-    pub fn build_validator_args(&self) -> Vec<String> {
-        vec![
+    /// Fail when the RPC, RPC PubSub (`rpc_port + 1`), gossip and dynamic
+    /// ports collide, naming the ports involved
+    pub fn check_port_layout(&self) -> Result<()> {
+        let range = self.dynamic_port_range;
+        if range.port_count() < PortRange::MIN_LEN {
+            anyhow::bail!("dynamic_port_range {} has {} ports; the validator needs at least {}",
+                range, range.port_count(), PortRange::MIN_LEN);
+        }
+        let pubsub_port = self.rpc_port.checked_add(1)
+            .with_context(|| format!("rpc_port {} leaves no room for RPC PubSub on the next port", self.rpc_port))?;
+        let ports = [("rpc_port", self.rpc_port), ("RPC PubSub (rpc_port + 1)", pubsub_port), ("gossip_port", self.gossip_port)];
+
+        for (i, (name, port)) in ports.iter().enumerate() {
+            if let Some((other, _)) = ports[i + 1..].iter().find(|(_, other)| other == port) {
+                anyhow::bail!("Port conflict: {} and {} are both {}", name, other, port);
+            }
+            if range.contains(*port) {
+                anyhow::bail!("Port conflict: {} {} is inside dynamic_port_range {}; move one of them",
+                    name, port, range);
+            }
+        }
+        Ok(())
+    }
+
+    /// Gossip entrypoints: those listed in `entrypoints_file`, else the cluster's
+    pub fn entrypoints(&self) -> Result<Vec<String>> {
+        let Some(path) = &self.entrypoints_file else {
+            return Ok(self.cluster.entrypoints());
+        };
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read entrypoints_file {}", path.display()))?;
+        parse_entrypoints(&contents).with_context(|| format!("Invalid entrypoints_file {}", path.display()))
+    }
+
+    pub fn build_validator_args(&self) -> Result<Vec<String>> {
+        self.check_port_layout()?;
+        let mut args = vec![
             format!("--identity={}", self.identity_keypair.display()),
             format!("--vote-account={}", self.vote_account_keypair.display()),
             format!("--ledger={}", self.ledger_path.display()),
             format!("--accounts={}", self.accounts_path.display()),
             format!("--snapshots={}", self.snapshots_path.display()),
-            format!("--log={}", self.log_path.display()),
+            // With rotation enabled the validator logs to stderr and the
+            // process manager drains it into rotated files under log_path
+            if self.log_rotation.enabled {
+                "--log=-".to_string()
+            } else {
+                format!("--log={}", self.log_path.display())
+            },
             format!("--rpc-port={}", self.rpc_port),
-            format!("--rpc-bind-address=127.0.0.1"),
-            format!("--dynamic-port-range=8000-8020"),
+            format!("--rpc-bind-address={}", self.rpc_bind_address),
+            format!("--dynamic-port-range={}", self.dynamic_port_range),
             format!("--gossip-port={}", self.gossip_port),
-            // Testnet entry points
-            "--entrypoint=entrypoint.testnet.solana.com:8001".to_string(),
-            "--entrypoint=entrypoint2.testnet.solana.com:8001".to_string(),
-            "--entrypoint=entrypoint3.testnet.solana.com:8001".to_string(),
-            // Known validators
-            "--known-validator=5D1fNXzvv5NjV1ysLjirC4WY92RNsVH18vjmcszZd8on".to_string(),
-            "--known-validator=7XSY3MrYnK8vq693Rju17bbPkCN3Z7KvvfvJx4kdrsSY".to_string(),
+        ];
+        // The cluster's entry points and the validators trusted for snapshots
+        args.extend(self.entrypoints()?.iter().map(|entrypoint| format!("--entrypoint={}", entrypoint)));
+        args.extend(self.cluster.known_validators().iter().map(|key| format!("--known-validator={}", key)));
+        args.extend([
             // Optimizations
             format!("--rpc-threads={}", self.optimization.rpc_threads),
             format!("--accounts-db-threads={}", self.optimization.accounts_db_threads),
@@ -119,7 +1310,6 @@ impl ValidatorConfig {
             format!("--accounts-db-cache-limit-mb={}", self.optimization.accounts_db_cache_mb),
             format!("--accounts-index-memory-limit-mb={}", self.optimization.accounts_index_memory_mb),
             // Additional optimizations
-            "--expected-genesis-hash=4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY".to_string(),
             "--wal-recovery-mode=skip_any_corrupted_record".to_string(),
             "--accounts-db-caching-enabled".to_string(),
             "--no-port-check".to_string(),
@@ -129,6 +1319,113 @@ impl ValidatorConfig {
             "--skip-startup-ledger-verification".to_string(),
             "--use-snapshot-archives-at-startup=when-newest".to_string(),
             "--block-production-method=central-scheduler".to_string(),
-        ]
+        ]);
+
+        // Cluster identity checks
+        if let Some(hash) = self.pinned_genesis_hash()? {
+            Hash::from_str(hash)
+                .with_context(|| format!("expected_genesis_hash is not a valid base58 hash: {}", hash))?;
+            args.push(format!("--expected-genesis-hash={}", hash));
+        }
+        if let Some(shred_version) = self.expected_shred_version {
+            args.push(format!("--expected-shred-version={}", shred_version));
+        }
+
+        // Drop or rename flags the installed validator doesn't know
+        Ok(flag_compat::for_installed_validator(args))
+    }
+}
+
+/// `host:port` entrypoints, one per line; blank lines and `#` comments are skipped
+fn parse_entrypoints(contents: &str) -> Result<Vec<String>> {
+    let entrypoints = contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.rsplit_once(':') {
+            Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(line.to_string()),
+            _ => anyhow::bail!("'{}' is not a host:port entrypoint", line),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if entrypoints.is_empty() {
+        anyhow::bail!("No entrypoints listed");
+    }
+    Ok(entrypoints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mainnet_args_use_mainnet_entrypoints_and_genesis() {
+        let mut config = ValidatorConfig::default();
+        config.set_cluster(Cluster::Mainnet);
+        let args = config.build_validator_args().unwrap();
+
+        assert!(args.contains(&"--entrypoint=entrypoint.mainnet-beta.solana.com:8001".to_string()), "{:?}", args);
+        assert!(args.contains(&format!("--expected-genesis-hash={}", MAINNET_GENESIS_HASH)), "{:?}", args);
+        assert!(!args.iter().any(|arg| arg.contains("testnet")), "{:?}", args);
+    }
+
+    #[test]
+    fn full_port_range_is_counted() {
+        let range: PortRange = "0-65535".parse().unwrap();
+        assert_eq!(range.port_count(), 65536);
+    }
+
+    #[test]
+    fn genesis_hash_of_another_cluster_is_refused() {
+        let config = ValidatorConfig { cluster: Cluster::Mainnet, ..ValidatorConfig::default() };
+        assert!(config.build_validator_args().is_err());
+    }
+
+    #[test]
+    fn endpoints_follow_the_cluster() {
+        let mut config = ValidatorConfig { rpc_port: 9000, ..ValidatorConfig::default() };
+        config.set_cluster(Cluster::Localnet);
+        assert_eq!(config.connect_endpoints(), vec!["http://127.0.0.1:9000".to_string()]);
+
+        config.set_cluster(Cluster::Devnet);
+        assert_eq!(config.connect_endpoints()[1], DEVNET_RPC_URL);
+
+        config.rpc_bind_address = "10.0.0.5".parse().unwrap();
+        assert_eq!(config.local_rpc_url(), "http://10.0.0.5:9000");
+        config.rpc_bind_address = "::".parse().unwrap();
+        assert_eq!(config.local_rpc_url(), "http://[::1]:9000");
+    }
+
+    #[test]
+    fn custom_cluster_on_mainnet_genesis_is_mainnet() {
+        let config = ValidatorConfig {
+            cluster: Cluster::Custom {
+                rpc_url: "https://rpc.example.com".to_string(),
+                entrypoints: Vec::new(),
+                genesis_hash: Some(MAINNET_GENESIS_HASH.to_string()),
+                known_validators: Vec::new(),
+            },
+            expected_genesis_hash: None,
+            ..ValidatorConfig::default()
+        };
+        assert!(config.is_mainnet());
+    }
+
+    #[test]
+    fn entrypoints_file_replaces_the_cluster_entrypoints() {
+        let entrypoints = parse_entrypoints("# private cluster\n10.0.0.1:8001\n\ngossip.example.com:8001  # backup\n").unwrap();
+        assert_eq!(entrypoints, ["10.0.0.1:8001", "gossip.example.com:8001"]);
+
+        assert!(parse_entrypoints("10.0.0.1\n").is_err());
+        assert!(parse_entrypoints(":8001\n").is_err());
+        assert!(parse_entrypoints("# nothing here\n").is_err());
+
+        let path = std::env::temp_dir().join(format!("entrypoints-{}.txt", std::process::id()));
+        fs::write(&path, "10.0.0.1:8001\n").unwrap();
+        let config = ValidatorConfig { entrypoints_file: Some(path.clone()), ..ValidatorConfig::default() };
+        let args = config.build_validator_args().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(args.contains(&"--entrypoint=10.0.0.1:8001".to_string()), "{:?}", args);
+        assert!(!args.iter().any(|arg| arg.starts_with("--entrypoint=entrypoint")), "{:?}", args);
     }
 }
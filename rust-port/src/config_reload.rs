@@ -0,0 +1,197 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::RwLock;
+
+use crate::config::ValidatorConfig;
+use crate::live_config::{self, Reload};
+use crate::optimizer_rules;
+
+/// The config a long-running command works from, replaced on SIGHUP
+pub type SharedConfig = Arc<RwLock<ValidatorConfig>>;
+
+/// Bumped by every accepted reload
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Fields `monitor --dashboard` and `optimize --auto` re-read after a reload
+const IMMEDIATE: [&str; 7] = [
+    "alerts",
+    "optimization_cooldowns",
+    "metrics_sinks",
+    "poll_interval",
+    "display_interval",
+    "export_interval",
+    "auto_optimize_max_failures",
+];
+
+/// Fields only passed to the validator on its command line
+const VALIDATOR_STARTUP: [&str; 14] = [
+    "identity_keypair",
+    "vote_account_keypair",
+    "ledger_path",
+    "accounts_path",
+    "snapshots_path",
+    "log_path",
+    "rpc_port",
+    "gossip_port",
+    "rpc_bind_address",
+    "dynamic_port_range",
+    "expected_shred_version",
+    "expected_genesis_hash",
+    "entrypoints_file",
+    "log_rotation",
+];
+
+/// When an edited field reaches the running command or validator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Effect {
+    Now,
+    CommandRestart,
+    ValidatorRestart,
+    /// Pushed to the running validator by `apply --live`
+    LiveApply,
+}
+
+impl Effect {
+    fn of(field: &str) -> Self {
+        if let Some(name) = field.strip_prefix("optimization.") {
+            return match live_config::reload_for(name) {
+                Some(Reload::AdminRpc(_) | Reload::Sysctl(_)) => Effect::LiveApply,
+                _ => Effect::ValidatorRestart,
+            };
+        }
+        let top = field.split('.').next().unwrap_or(field);
+        if IMMEDIATE.contains(&top) {
+            Effect::Now
+        } else if VALIDATOR_STARTUP.contains(&top) {
+            Effect::ValidatorRestart
+        } else {
+            Effect::CommandRestart
+        }
+    }
+
+    fn describe(&self) -> colored::ColoredString {
+        match self {
+            Effect::Now => "applies now".green(),
+            Effect::CommandRestart => "restart this command to apply".yellow(),
+            Effect::ValidatorRestart => "restart the validator to apply".yellow(),
+            Effect::LiveApply => "run `apply --live` to push to the validator".cyan(),
+        }
+    }
+}
+
+/// Wrap a loaded config for reloading
+pub fn shared(config: ValidatorConfig) -> SharedConfig {
+    Arc::new(RwLock::new(config))
+}
+
+/// The config as replaced by a reload since `seen`, updating `seen`;
+/// None when nothing was reloaded
+pub async fn reloaded_since(config: &SharedConfig, seen: &mut u64) -> Option<ValidatorConfig> {
+    let generation = GENERATION.load(Ordering::SeqCst);
+    if generation == *seen {
+        return None;
+    }
+    *seen = generation;
+    Some(config.read().await.clone())
+}
+
+/// Current reload count, the starting point for `reloaded_since`
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::SeqCst)
+}
+
+/// Reload `config` from disk on every SIGHUP for the rest of the process
+pub fn reload_on_sighup(config: SharedConfig) -> Result<()> {
+    let mut hangups = signal(SignalKind::hangup()).context("Failed to install SIGHUP handler")?;
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            reload(&config).await;
+        }
+    });
+    Ok(())
+}
+
+/// The config file, parsed and checked the way the running command would use it
+fn load_validated() -> Result<ValidatorConfig> {
+    // `load` writes defaults when the file is missing; a reload must not
+    let path = ValidatorConfig::config_path();
+    if !path.exists() {
+        anyhow::bail!("{} no longer exists", path.display());
+    }
+
+    let config = ValidatorConfig::load().with_context(|| format!("Failed to parse {}", path.display()))?;
+    config.build_validator_args().context("Invalid validator settings")?;
+    if let Some(rules) = &config.optimizer_rules {
+        optimizer_rules::load_rules(rules)?;
+    }
+    Ok(config)
+}
+
+/// Compact JSON for the change log, cut short when long
+fn short(value: &Value) -> String {
+    const MAX: usize = 60;
+    let text = value.to_string();
+    if text.chars().count() > MAX {
+        format!("{}…", text.chars().take(MAX).collect::<String>())
+    } else {
+        text
+    }
+}
+
+/// (field, old, new) for every setting that differs, nested objects broken
+/// down into dotted paths such as `alerts.credits_stall_slots`
+fn changes(old: &ValidatorConfig, new: &ValidatorConfig) -> Vec<(String, String, String)> {
+    fn diff(path: &str, old: &Value, new: &Value, changes: &mut Vec<(String, String, String)>) {
+        match (old, new) {
+            (Value::Object(old), Value::Object(new)) => {
+                for (key, new_value) in new {
+                    let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                    diff(&path, old.get(key).unwrap_or(&Value::Null), new_value, changes);
+                }
+            }
+            _ if old != new => changes.push((path.to_string(), short(old), short(new))),
+            _ => {}
+        }
+    }
+
+    let mut changes = Vec::new();
+    if let (Ok(old), Ok(new)) = (serde_json::to_value(old), serde_json::to_value(new)) {
+        diff("", &old, &new, &mut changes);
+    }
+    changes
+}
+
+/// Swap in the config from disk if it validates, logging what changed
+async fn reload(config: &SharedConfig) {
+    println!("\n{} SIGHUP received, reloading {}", "🔄".cyan(), ValidatorConfig::config_path().display());
+
+    let new = match load_validated() {
+        Ok(new) => new,
+        Err(e) => {
+            println!("{} Config rejected, keeping the current one: {:#}", "✗".red(), e);
+            return;
+        }
+    };
+
+    let changes = {
+        let mut current = config.write().await;
+        let changes = changes(&current, &new);
+        *current = new;
+        GENERATION.fetch_add(1, Ordering::SeqCst);
+        changes
+    };
+
+    if changes.is_empty() {
+        println!("{} Config reloaded, nothing changed", "✓".green());
+        return;
+    }
+    println!("{} Config reloaded, {} change(s):", "✓".green(), changes.len());
+    for (i, (field, old, new)) in changes.iter().enumerate() {
+        let branch = if i + 1 == changes.len() { "└─" } else { "├─" };
+        println!("{} {}: {} → {} ({})", branch, field.bold(), old, new.yellow(), Effect::of(field).describe());
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use colored::Colorize;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::{read_keypair_file, Keypair};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::blockchain::{SolanaInterface, ValidatorMetrics};
+use crate::config::{OnDisconnect, ValidatorConfig};
+use crate::poll;
+use crate::tls;
+
+/// What the configured identity and vote keypairs are needed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeypairUse {
+    /// Transactions are signed, so both keypair files must load
+    Signing,
+    /// Only reads; missing keypair files are replaced with throwaway keypairs,
+    /// which leaves cluster-wide reads working but not the validator's own accounts
+    ReadOnly,
+}
+
+/// The configured identity and vote keypairs
+pub fn load_keypairs(config: &ValidatorConfig, keypair_use: KeypairUse) -> Result<(Keypair, Keypair)> {
+    let identity = read_keypair_file(&config.identity_keypair)
+        .map_err(|e| anyhow::anyhow!("Failed to read validator keypair: {}", e));
+    let vote = read_keypair_file(&config.vote_account_keypair)
+        .map_err(|e| anyhow::anyhow!("Failed to read vote keypair: {}", e));
+
+    match (identity, vote, keypair_use) {
+        (Ok(identity), Ok(vote), _) => Ok((identity, vote)),
+        (Err(e), _, KeypairUse::Signing) | (_, Err(e), KeypairUse::Signing) => Err(e),
+        (identity, vote, KeypairUse::ReadOnly) => {
+            println!("  {} Keypairs not found, using throwaway keypairs (validator-specific reads will fail)",
+                "⚠".yellow());
+            Ok((identity.unwrap_or_else(|_| Keypair::new()), vote.unwrap_or_else(|_| Keypair::new())))
+        }
+    }
+}
+
+/// Connect to the first of `connect.endpoints` that answers, then of
+/// `failover_rpc_urls`
+///
+/// Each endpoint is probed with `getSlot` (bounded by `connect.timeout_secs`)
+/// before the interface is built, so an unreachable local validator falls
+/// through to the cluster. Connecting through a failover RPC keeps the first
+/// endpoint as the primary, switched back to once it answers. The endpoint
+/// used is `SolanaInterface::rpc_url`.
+pub fn connect_validator(config: &ValidatorConfig, keypair_use: KeypairUse) -> Result<SolanaInterface> {
+    let (identity, vote) = load_keypairs(config, keypair_use)?;
+    let timeout = Duration::from_secs(config.connect.timeout_secs.max(1));
+    let mut failures = Vec::new();
+
+    let endpoints = config.connect_endpoints();
+    for endpoint in &endpoints {
+        let probe = tls::rpc_client_with_timeout(endpoint, timeout, CommitmentConfig::confirmed());
+        match probe.get_slot() {
+            Ok(_) => return SolanaInterface::new(endpoint, identity, vote),
+            Err(e) => {
+                println!("  {} {} not reachable", "⚠".yellow(), endpoint);
+                failures.push(format!("{}: {}", endpoint, e));
+            }
+        }
+    }
+
+    let primary = &endpoints[0];
+    for url in config.failover_rpc_urls.iter().filter(|url| !endpoints.contains(url)) {
+        let probe = tls::rpc_client_with_timeout(url, timeout, CommitmentConfig::confirmed());
+        match probe.get_slot() {
+            Ok(_) => {
+                return Ok(SolanaInterface::new(primary, identity, vote)?
+                    .failed_over_to(url, "no configured endpoint answered"));
+            }
+            Err(e) => {
+                println!("  {} {} not reachable", "⚠".yellow(), url);
+                failures.push(format!("{}: {}", url, e));
+            }
+        }
+    }
+
+    anyhow::bail!("No validator endpoint answered:\n  {}", failures.join("\n  "))
+}
+
+/// A validator connection kept across reads, so that failed reads add up
+/// toward failing over to `failover_rpc_urls` instead of starting over on
+/// every refresh
+#[derive(Default)]
+pub struct ValidatorLink {
+    interface: Mutex<Option<Arc<SolanaInterface>>>,
+}
+
+impl ValidatorLink {
+    /// Read the validator's metrics, connecting first when not yet connected
+    pub async fn metrics(&self, config: &ValidatorConfig) -> Result<ValidatorMetrics> {
+        let interface = self.interface(config)?;
+        let metrics = interface.get_validator_metrics().await?;
+        println!("  {} Connected to validator at {}", "✓".green(), interface.rpc_url());
+        Ok(metrics)
+    }
+
+    fn interface(&self, config: &ValidatorConfig) -> Result<Arc<SolanaInterface>> {
+        let mut interface = self.interface.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(connected) = interface.as_ref() {
+            return Ok(connected.clone());
+        }
+        let connected = Arc::new(connect_validator(config, KeypairUse::ReadOnly)?);
+        *interface = Some(connected.clone());
+        Ok(connected)
+    }
+}
+
+/// Read from the validator with `read`, following `on_disconnect` when it
+/// fails: None for `baseline` (the caller carries on with zeroed metrics), the
+/// error for `error`, and for `wait` a retry every `poll_interval.base_secs`
+/// (10s when unset) until a read succeeds or the timeout passes
+pub async fn read_or_disconnect<T, F, Fut>(config: &ValidatorConfig, mut read: F) -> Result<Option<T>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut error = match read().await {
+        Ok(value) => return Ok(Some(value)),
+        Err(e) => e,
+    };
+
+    let timeout_secs = match config.on_disconnect {
+        OnDisconnect::Baseline => {
+            println!("  {} No validator connected ({:#}); using baseline (zeroed) metrics",
+                "⚠".yellow(), error);
+            return Ok(None);
+        }
+        OnDisconnect::Error => return Err(error.context("No validator connected (on_disconnect is \"error\")")),
+        OnDisconnect::Wait(timeout_secs) => timeout_secs,
+    };
+
+    let started = Instant::now();
+    let timeout = Duration::from_secs(timeout_secs);
+    let interval = Duration::from_secs(config.poll_interval.base_secs_or(poll::DEFAULT_BASE_SECS));
+    while started.elapsed() < timeout {
+        println!("  {} No validator connected, retrying in {}s ({}s left)",
+            "⏳".yellow(), interval.as_secs(), timeout.saturating_sub(started.elapsed()).as_secs());
+        tokio::time::sleep(interval.min(timeout.saturating_sub(started.elapsed()))).await;
+        match read().await {
+            Ok(value) => {
+                println!("  {} Validator answered after {}s", "✓".green(), started.elapsed().as_secs());
+                return Ok(Some(value));
+            }
+            Err(e) => error = e,
+        }
+    }
+    Err(error.context(format!("No validator connected after waiting {}s", timeout_secs)))
+}
@@ -0,0 +1,202 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::smart_contract::{OptimizationRecommendation, Priority, SampleStrategy, SmartContractOptimizer};
+
+/// A recommendation tagged with the program it applies to
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgramRecommendation {
+    pub program_id: String,
+    #[serde(flatten)]
+    pub recommendation: OptimizationRecommendation,
+}
+
+/// Combined result of analyzing every program in a suite
+#[derive(Debug, Clone, Serialize)]
+pub struct SuiteReport {
+    /// Priority-sorted, High across all programs first
+    pub recommendations: Vec<ProgramRecommendation>,
+    /// Programs whose analysis failed
+    pub failed: Vec<FailedProgram>,
+    /// Programs analyzed with some RPC calls failing
+    pub partial: Vec<PartialProgram>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedProgram {
+    pub program_id: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PartialProgram {
+    pub program_id: String,
+    /// Share (0-1) of the sampled transactions analyzed
+    pub coverage: f64,
+    pub errors: Vec<String>,
+}
+
+/// What one program's analysis produced
+struct Analysis {
+    recommendations: Vec<OptimizationRecommendation>,
+    coverage: f64,
+    errors: Vec<String>,
+}
+
+/// Program ids from `path`, one per line; blank lines and `#` comments are skipped
+pub fn load_program_ids(path: &Path) -> Result<Vec<Pubkey>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read program list {}", path.display()))?;
+
+    let mut ids = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let id = Pubkey::from_str(line).map_err(|e| {
+            anyhow::anyhow!("{}:{}: invalid program ID '{}': {}", path.display(), index + 1, line, e)
+        })?;
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+
+    if ids.is_empty() {
+        anyhow::bail!("Program list {} has no program IDs", path.display());
+    }
+    Ok(ids)
+}
+
+/// One program's analysis; its RPC calls block, so it runs on a blocking thread
+fn analyze_one(
+    rpc_url: &str,
+    program_id: Pubkey,
+    from_slot: Option<u64>,
+    to_slot: Option<u64>,
+    sample_strategy: SampleStrategy,
+) -> Result<Analysis> {
+    let optimizer = SmartContractOptimizer::new(rpc_url, Some(program_id))?.with_sample_strategy(sample_strategy);
+    let metrics = optimizer.collect_program_metrics(&program_id, from_slot, to_slot)?;
+    Ok(Analysis {
+        recommendations: optimizer.get_recommendations(&metrics),
+        coverage: metrics.coverage,
+        errors: metrics.errors,
+    })
+}
+
+/// `analyze-contract --programs`: analyze each program, at most `max_parallel` at
+/// a time, and merge their recommendations; one failing never stops the rest
+pub async fn analyze_suite(
+    program_ids: &[Pubkey],
+    rpc_url: &str,
+    from_slot: Option<u64>,
+    to_slot: Option<u64>,
+    sample_strategy: SampleStrategy,
+    max_parallel: usize,
+) -> SuiteReport {
+    println!("{} Analyzing {} program(s), {} at a time",
+        "▶".cyan(),
+        program_ids.len(),
+        max_parallel.max(1)
+    );
+
+    let results: Vec<_> = stream::iter(program_ids.iter().copied())
+        .map(|program_id| {
+            let rpc_url = rpc_url.to_string();
+            let task = tokio::task::spawn_blocking(move || {
+                analyze_one(&rpc_url, program_id, from_slot, to_slot, sample_strategy)
+            });
+            async move {
+                let result = task.await.unwrap_or_else(|e| Err(anyhow::anyhow!("Analysis task failed: {}", e)));
+                (program_id, result)
+            }
+        })
+        .buffer_unordered(max_parallel.max(1))
+        .collect()
+        .await;
+
+    let mut report = SuiteReport { recommendations: Vec::new(), failed: Vec::new(), partial: Vec::new() };
+    for (program_id, result) in results {
+        match result {
+            Ok(analysis) => {
+                if !analysis.errors.is_empty() {
+                    report.partial.push(PartialProgram {
+                        program_id: program_id.to_string(),
+                        coverage: analysis.coverage,
+                        errors: analysis.errors,
+                    });
+                }
+                report.recommendations.extend(analysis.recommendations.into_iter().map(|recommendation| {
+                    ProgramRecommendation { program_id: program_id.to_string(), recommendation }
+                }));
+            }
+            Err(e) => report.failed.push(FailedProgram { program_id: program_id.to_string(), error: format!("{:#}", e) }),
+        }
+    }
+
+    // Stable within a priority: grouped by program, in the order given
+    let position = |id: &str| program_ids.iter().position(|p| p.to_string() == id);
+    report.recommendations.sort_by(|a, b| {
+        a.recommendation.priority.cmp(&b.recommendation.priority)
+            .then_with(|| position(&a.program_id).cmp(&position(&b.program_id)))
+    });
+    report.failed.sort_by(|a, b| a.program_id.cmp(&b.program_id));
+    report.partial.sort_by(|a, b| a.program_id.cmp(&b.program_id));
+    report
+}
+
+/// Print the merged to-do list, High priority across all programs first
+pub fn display_suite(report: &SuiteReport, explain: bool, docs: bool) {
+    println!("\n{}", "💡 Combined Optimization Recommendations".cyan().bold());
+
+    let groups = [
+        (Priority::High, "🔴 High Priority:".red()),
+        (Priority::Medium, "🟡 Medium Priority:".yellow()),
+        (Priority::Low, "🟢 Low Priority:".green()),
+    ];
+    for (priority, heading) in groups {
+        let recs: Vec<_> = report.recommendations.iter().filter(|r| r.recommendation.priority == priority).collect();
+        if recs.is_empty() {
+            continue;
+        }
+
+        println!("\n  {}", heading);
+        for rec in recs {
+            let r = &rec.recommendation;
+            println!("    • [{}] {}: {}", rec.program_id.dimmed(), r.category.yellow(), r.description);
+            println!("      Impact: {}", r.estimated_improvement.green());
+            if let (true, Some(trace)) = (explain, &r.trace) {
+                println!("      Triggered because {}", trace.to_string().dimmed());
+            }
+            if let (true, Some(url)) = (docs, r.doc_url) {
+                println!("      Docs: {}", url.underline());
+            }
+        }
+    }
+
+    if report.recommendations.is_empty() {
+        println!("\n  {} No recommendations for any program", "✓".green());
+    }
+
+    if !report.partial.is_empty() {
+        println!("\n{} {} program(s) were only partly analyzed:", "⚠".yellow(), report.partial.len());
+        for partial in &report.partial {
+            println!("    • {} ({:.0}% of the sample): {}",
+                partial.program_id, partial.coverage * 100.0, partial.errors.join("; ").dimmed());
+        }
+    }
+
+    if !report.failed.is_empty() {
+        println!("\n{} {} program(s) could not be analyzed:", "⚠".yellow(), report.failed.len());
+        for failed in &report.failed {
+            println!("    • {}: {}", failed.program_id, failed.error.dimmed());
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+use crate::config::CooldownConfig;
+use crate::history::{self, HistoryEvent};
+
+/// Cooldowns are capped here, which also keeps them within `chrono::Duration`
+const MAX_COOLDOWN_SECS: u64 = 365 * 86_400;
+
+/// When each setting was last changed, so `optimize --auto` leaves it alone
+/// for its cooldown instead of flipping it back and forth
+pub struct ParameterCooldowns {
+    config: CooldownConfig,
+    last_changed: HashMap<String, DateTime<Utc>>,
+}
+
+fn seconds(secs: u64) -> Duration {
+    Duration::seconds(secs.min(MAX_COOLDOWN_SECS) as i64)
+}
+
+/// `tpu_coalesce_ms` and `tpu-coalesce-ms` are the same setting
+fn key(parameter: &str) -> String {
+    parameter.replace('_', "-")
+}
+
+impl ParameterCooldowns {
+    /// Seeded from the changes in the history, so a restart of the loop (or
+    /// an `apply --live` just before it) doesn't reset the clock
+    pub fn load(config: &CooldownConfig) -> Self {
+        let longest = config.parameters.values().copied().chain([config.default_secs]).max().unwrap_or(0);
+        let since = Utc::now() - seconds(longest);
+        let mut last_changed = HashMap::new();
+        for record in history::load_since(since).unwrap_or_default() {
+            if let HistoryEvent::Optimization { parameter, .. } = record.event {
+                last_changed.insert(key(&parameter), record.timestamp);
+            }
+        }
+        Self { config: config.clone(), last_changed }
+    }
+
+    /// Use the cooldowns of a reloaded config from now on
+    pub fn set_config(&mut self, config: &CooldownConfig) {
+        self.config = config.clone();
+    }
+
+    /// How long ago `parameter` changed and its cooldown, while that is still
+    /// running; None when it may change
+    pub fn active(&self, parameter: &str) -> Option<(Duration, Duration)> {
+        let changed = self.last_changed.get(&key(parameter))?;
+        let cooldown = seconds(self.config.cooldown(parameter));
+        let ago = Utc::now() - *changed;
+        (ago < cooldown).then_some((ago, cooldown))
+    }
+
+    pub fn record(&mut self, parameter: &str) {
+        self.last_changed.insert(key(parameter), Utc::now());
+    }
+}
@@ -0,0 +1,244 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use nix::sys::statvfs::statvfs;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::ValidatorConfig;
+use crate::utils::{format_number, NumberStyle};
+
+/// Rough on-disk footprint of one shred in the ledger (data + coding shreds
+/// and RocksDB overhead averaged together)
+pub const DEFAULT_SHRED_SIZE_BYTES: u64 = 2_500;
+
+/// solana-validator refuses a --limit-ledger-size below this many shreds
+pub const MIN_LEDGER_SHREDS: u64 = 50_000_000;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DiskSpace {
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+/// Free/total space of the filesystem holding `path`
+///
+/// Walks up to the nearest existing ancestor so it works before the
+/// ledger directory has been created.
+pub fn disk_space(path: &Path) -> Result<DiskSpace> {
+    let existing = path
+        .ancestors()
+        .find(|p| p.exists())
+        .unwrap_or_else(|| Path::new("/"));
+
+    let stat = statvfs(existing)
+        .with_context(|| format!("Failed to stat filesystem for {}", existing.display()))?;
+    let fragment = stat.fragment_size() as u64;
+
+    Ok(DiskSpace {
+        total_bytes: stat.blocks() as u64 * fragment,
+        available_bytes: stat.blocks_available() as u64 * fragment,
+    })
+}
+
+/// Recursive size of everything under `path` (0 if it doesn't exist)
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Largest `--limit-ledger-size` that fits in the space the ledger may use
+///
+/// The ledger may grow into the currently free space plus what it already
+/// occupies, minus `headroom_pct` percent of the filesystem kept free.
+pub fn suggest_limit_ledger_size(
+    space: DiskSpace,
+    ledger_bytes: u64,
+    shred_size_bytes: u64,
+    headroom_pct: f64,
+) -> u64 {
+    let headroom = (space.total_bytes as f64 * headroom_pct / 100.0) as u64;
+    let budget = (space.available_bytes + ledger_bytes).saturating_sub(headroom);
+    let shreds = budget / shred_size_bytes.max(1);
+
+    // Round down to a whole million for a readable flag value
+    shreds / 1_000_000 * 1_000_000
+}
+
+/// Immediate subdirectories of `path` with their recursive sizes
+fn subdir_sizes(path: &Path) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .map(|entry| {
+            let path = entry.path();
+            let size = dir_size(&path);
+            (path, size)
+        })
+        .collect()
+}
+
+/// Count of (full, incremental) snapshot archives directly under `path`
+pub fn count_snapshot_archives(path: &Path) -> (usize, usize) {
+    let Ok(entries) = fs::read_dir(path) else {
+        return (0, 0);
+    };
+
+    entries.flatten().fold((0, 0), |(full, incremental), entry| {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.contains(".tar") {
+            (full, incremental)
+        } else if name.starts_with("incremental-snapshot-") {
+            (full, incremental + 1)
+        } else if name.starts_with("snapshot-") {
+            (full + 1, incremental)
+        } else {
+            (full, incremental)
+        }
+    })
+}
+
+/// `disk-usage`
+pub fn disk_usage(top: usize) -> Result<()> {
+    let config = ValidatorConfig::load()?;
+    let space = disk_space(&config.ledger_path)?;
+    let warn_bytes = (space.available_bytes as f64 * config.disk.usage_warn_pct_of_free / 100.0) as u64;
+
+    let paths = [
+        ("Ledger", &config.ledger_path),
+        ("Accounts", &config.accounts_path),
+        ("Snapshots", &config.snapshots_path),
+    ];
+
+    println!("{}", "Disk Usage".cyan().bold());
+    println!("├─ Filesystem: {} free of {}",
+        format_bytes(space.available_bytes),
+        format_bytes(space.total_bytes)
+    );
+
+    let mut oversized = Vec::new();
+    let mut subdirs = Vec::new();
+
+    for (label, path) in paths {
+        let size = dir_size(path);
+        let flag = if size > warn_bytes {
+            oversized.push((label, size));
+            " ⚠".yellow().to_string()
+        } else {
+            String::new()
+        };
+
+        println!("├─ {}: {} ({}){}", label, format_bytes(size), path.display(), flag);
+        subdirs.extend(subdir_sizes(path));
+    }
+
+    let (full, incremental) = count_snapshot_archives(&config.snapshots_path);
+    println!("└─ Snapshot archives: {} full, {} incremental", full, incremental);
+
+    subdirs.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    subdirs.truncate(top);
+    if !subdirs.is_empty() {
+        println!("\n{}", "Largest Subdirectories".cyan().bold());
+        for (i, (path, size)) in subdirs.iter().enumerate() {
+            let branch = if i + 1 == subdirs.len() { "└─" } else { "├─" };
+            println!("{} {:>10}  {}", branch, format_bytes(*size), path.display());
+        }
+    }
+
+    for (label, size) in &oversized {
+        println!("\n{} {} uses {} - more than {:.0}% of the {} still free",
+            "⚠".yellow(),
+            label,
+            format_bytes(*size),
+            config.disk.usage_warn_pct_of_free,
+            format_bytes(space.available_bytes)
+        );
+    }
+    if !oversized.is_empty() {
+        println!("  Consider pruning old snapshots or lowering limit_ledger_size ({}).",
+            "config suggest-ledger-size".cyan()
+        );
+    }
+
+    Ok(())
+}
+
+/// `config suggest-ledger-size`
+pub fn suggest_ledger_size(shred_size_bytes: u64, headroom_pct: f64, write: bool) -> Result<()> {
+    let mut config = ValidatorConfig::load()?;
+
+    let space = disk_space(&config.ledger_path)?;
+    let ledger_bytes = dir_size(&config.ledger_path);
+    let suggested = suggest_limit_ledger_size(space, ledger_bytes, shred_size_bytes, headroom_pct);
+    let current = config.optimization.limit_ledger_size;
+
+    println!("{}", "Ledger Size Recommendation".cyan().bold());
+    println!("├─ Ledger path: {}", config.ledger_path.display());
+    println!("├─ Filesystem: {} free of {}",
+        format_bytes(space.available_bytes),
+        format_bytes(space.total_bytes)
+    );
+    println!("├─ Ledger currently uses: {}", format_bytes(ledger_bytes));
+    println!("├─ Assumed shred size: {} bytes | Headroom: {:.0}%", shred_size_bytes, headroom_pct);
+    println!("├─ Current limit: {} shreds (~{})",
+        format_number(current, NumberStyle::Separated),
+        format_bytes(current * shred_size_bytes)
+    );
+    println!("└─ Suggested limit: {} shreds (~{})",
+        format_number(suggested, NumberStyle::Separated).green().bold(),
+        format_bytes(suggested * shred_size_bytes)
+    );
+
+    let budget = space.available_bytes + ledger_bytes;
+    if current * shred_size_bytes > budget {
+        println!("\n{} Current limit needs ~{} but only {} is available to the ledger",
+            "⚠".yellow(),
+            format_bytes(current * shred_size_bytes),
+            format_bytes(budget)
+        );
+    }
+
+    if suggested < MIN_LEDGER_SHREDS {
+        println!("\n{} Disk cannot hold the validator's minimum of {} shreds with {:.0}% headroom",
+            "✗".red(),
+            format_number(MIN_LEDGER_SHREDS, NumberStyle::Separated),
+            headroom_pct
+        );
+        println!("  Free up space or move the ledger to a larger volume.");
+        return Ok(());
+    }
+
+    if write {
+        config.optimization.limit_ledger_size = suggested;
+        config.save()?;
+        println!("\n{} Saved limit_ledger_size = {} to config", "✓".green(), suggested);
+    } else if suggested != current {
+        println!("\nRun with {} to save this value to the config.", "--write".cyan());
+    }
+
+    Ok(())
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+    const MB: f64 = 1024.0 * 1024.0;
+
+    if bytes as f64 >= GB {
+        format!("{:.1} GB", bytes as f64 / GB)
+    } else {
+        format!("{:.1} MB", bytes as f64 / MB)
+    }
+}
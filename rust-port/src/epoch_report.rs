@@ -0,0 +1,229 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use colored::Colorize;
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::blockchain::{fetch_vote_state, max_credits_per_slot, normalized_credits_pct, SolanaInterface};
+use crate::config::ValidatorConfig;
+use crate::connect::{connect_validator, KeypairUse};
+use crate::monitor::PerformanceMetrics;
+use crate::native;
+use crate::poll::{self, AdaptiveInterval};
+use crate::tls;
+
+/// How long after a rollover to keep asking for the ended epoch's rewards
+const REWARDS_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Pause between reward checks while they haven't landed
+const REWARDS_RETRY: Duration = Duration::from_secs(60);
+
+/// `epoch-<N>-report-<time>.json`, written next to the Markdown report
+#[derive(Debug, Clone, Serialize)]
+pub struct EpochReport {
+    pub epoch: u64,
+    pub generated: String,
+    pub vote_account: String,
+    /// Vote credits earned during the epoch; None when the vote state has no entry
+    pub credits: Option<u64>,
+    /// `credits` as a percentage of the most the whole epoch could earn
+    pub credits_pct: Option<f64>,
+    /// None when the rewards didn't land before the timeout
+    pub rewards: Option<EpochRewards>,
+    /// Metrics read at the rollover; None when the validator didn't answer
+    pub metrics: Option<PerformanceMetrics>,
+}
+
+/// The vote account's inflation reward (its commission cut) for the epoch
+#[derive(Debug, Clone, Serialize)]
+pub struct EpochRewards {
+    pub amount_lamports: u64,
+    pub commission: Option<u8>,
+    pub effective_slot: u64,
+    pub post_balance_lamports: u64,
+}
+
+fn sol(lamports: u64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL as f64
+}
+
+/// Credits earned in `epoch`, from the vote state's epoch credits history
+fn epoch_credits(rpc_client: &RpcClient, vote_account: &Pubkey, epoch: u64) -> Result<Option<u64>> {
+    let vote_state = fetch_vote_state(rpc_client, vote_account)?;
+    Ok(vote_state
+        .epoch_credits
+        .iter()
+        .find(|(e, _, _)| *e == epoch)
+        .map(|(_, credits, previous)| credits.saturating_sub(*previous)))
+}
+
+/// Ask for the epoch's rewards until they land or `REWARDS_TIMEOUT` passes
+///
+/// Rewards are paid in the first blocks of the next epoch, so right after the
+/// rollover the RPC usually has nothing yet.
+async fn wait_for_rewards(rpc_client: &RpcClient, vote_account: &Pubkey, epoch: u64) -> Option<EpochRewards> {
+    let started = Instant::now();
+    loop {
+        let reward = rpc_client
+            .get_inflation_reward(&[*vote_account], Some(epoch))
+            .ok()
+            .and_then(|rewards| rewards.into_iter().next().flatten());
+        if let Some(reward) = reward {
+            return Some(EpochRewards {
+                amount_lamports: reward.amount,
+                commission: reward.commission,
+                effective_slot: reward.effective_slot,
+                post_balance_lamports: reward.post_balance,
+            });
+        }
+
+        if started.elapsed() + REWARDS_RETRY > REWARDS_TIMEOUT {
+            println!("  {} No rewards for epoch {} after {}m; reporting without them",
+                "⚠".yellow(), epoch, REWARDS_TIMEOUT.as_secs() / 60);
+            return None;
+        }
+        println!("  {} Rewards for epoch {} not available yet, re-checking in {}s",
+            "⏳".yellow(), epoch, REWARDS_RETRY.as_secs());
+        tokio::time::sleep(REWARDS_RETRY).await;
+    }
+}
+
+fn epoch_report_markdown(report: &EpochReport) -> String {
+    let credits = match (report.credits, report.credits_pct) {
+        (Some(credits), Some(pct)) => format!("{} ({:.1}% of the epoch maximum)", credits, pct),
+        (Some(credits), None) => credits.to_string(),
+        (None, _) => "not in the vote state".to_string(),
+    };
+    let rewards = match &report.rewards {
+        Some(rewards) => format!(
+            "- **Commission Reward**: {:.6} SOL{}\n- **Credited At Slot**: {}\n- **Vote Account Balance After**: {:.6} SOL\n",
+            sol(rewards.amount_lamports),
+            rewards.commission.map_or(String::new(), |c| format!(" ({}% commission)", c)),
+            rewards.effective_slot,
+            sol(rewards.post_balance_lamports)
+        ),
+        None => format!("_Not available within {} minutes of the rollover._\n", REWARDS_TIMEOUT.as_secs() / 60),
+    };
+    let metrics = match &report.metrics {
+        Some(metrics) => format!(
+            "- **Vote Success Rate**: {:.1}%\n- **Skip Rate**: {:.1}%\n- **Vote Lag**: {}\n- **Network Latency**: {}ms\n- **TPS**: {:.0}\n",
+            metrics.vote_success_rate,
+            metrics.skip_rate,
+            metrics.vote_lag_display(),
+            metrics.network_latency_ms,
+            metrics.avg_tps
+        ),
+        None => "_The validator did not answer at the rollover._\n".to_string(),
+    };
+
+    format!(
+        "# Epoch {} Report\n\nGenerated: {}\nVote Account: {}\n\n## Vote Credits\n\n- **Earned In Epoch**: {}\n\n## Rewards\n\n{}\n## Metrics At Rollover\n\n{}",
+        report.epoch, report.generated, report.vote_account, credits, rewards, metrics
+    )
+}
+
+/// Build and write the report for the `epoch` that just ended
+async fn write_epoch_report(
+    interface: &SolanaInterface,
+    rpc_client: &RpcClient,
+    vote_account: &Pubkey,
+    epoch: u64,
+    output_dir: &Path,
+) -> Result<()> {
+    println!("\n{} Epoch {} ended, building its report...", "📅".cyan(), epoch);
+
+    let metrics = match interface.get_validator_metrics().await {
+        Ok(metrics) => Some(PerformanceMetrics::from_validator_metrics(&metrics)),
+        Err(e) => {
+            println!("  {} Metrics not available: {:#}", "⚠".yellow(), e);
+            None
+        }
+    };
+    let credits = epoch_credits(rpc_client, vote_account, epoch).unwrap_or_else(|e| {
+        println!("  {} Vote credits not available: {:#}", "⚠".yellow(), e);
+        None
+    });
+    let credits_pct = credits.and_then(|credits| {
+        let slots = rpc_client.get_epoch_schedule().ok()?.get_slots_in_epoch(epoch);
+        normalized_credits_pct(credits, slots, max_credits_per_slot(rpc_client)?)
+    });
+    let rewards = wait_for_rewards(rpc_client, vote_account, epoch).await;
+
+    let now = Local::now();
+    let report = EpochReport {
+        epoch,
+        generated: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+        vote_account: vote_account.to_string(),
+        credits,
+        credits_pct,
+        rewards,
+        metrics,
+    };
+
+    let stem = format!("epoch-{}-report-{}", epoch, now.format("%Y%m%d-%H%M%S"));
+    let markdown_path = output_dir.join(format!("{}.md", stem));
+    let json_path = output_dir.join(format!("{}.json", stem));
+    fs::write(&markdown_path, epoch_report_markdown(&report))
+        .with_context(|| format!("Failed to write {}", markdown_path.display()))?;
+    fs::write(&json_path, serde_json::to_string_pretty(&report)?)
+        .with_context(|| format!("Failed to write {}", json_path.display()))?;
+
+    println!("{} {} ({})",
+        "✓ Epoch report generated:".green(),
+        markdown_path.display().to_string().yellow(),
+        json_path.display()
+    );
+    Ok(())
+}
+
+/// `report --watch`: write a report for every epoch as it ends, until Ctrl+C
+pub async fn watch_epoch_reports(output_dir: Option<&Path>) -> Result<()> {
+    let config = ValidatorConfig::load()?;
+    let output_dir = config.output_dir(output_dir)?;
+    let vote_account = native::keypair_pubkey(&config.vote_account_keypair)
+        .context("report --watch needs the vote account keypair")?;
+    let interface = connect_validator(&config, KeypairUse::ReadOnly)?;
+    let rpc_client = tls::rpc_client(interface.rpc_url(), CommitmentConfig::confirmed());
+    let mut refresh = AdaptiveInterval::new(&config.poll_interval, poll::DEFAULT_BASE_SECS);
+
+    println!("{}", "=== Epoch Report Watch ===".cyan().bold());
+    println!("├─ RPC: {}", interface.rpc_url());
+    println!("├─ Vote account: {}", vote_account);
+    println!("└─ Reports: {}", output_dir.display());
+    println!("\nPress Ctrl+C to stop\n");
+
+    let mut last_epoch: Option<u64> = None;
+    loop {
+        match rpc_client.get_epoch_info() {
+            Ok(info) => {
+                refresh.success();
+                match last_epoch {
+                    None => println!("{} Epoch {} in progress, {} slots to go",
+                        "▶".cyan(), info.epoch, info.slots_in_epoch.saturating_sub(info.slot_index)),
+                    Some(previous) if info.epoch > previous => {
+                        // More than one epoch when the RPC was unreachable across a rollover
+                        for ended in previous..info.epoch {
+                            if let Err(e) = write_epoch_report(&interface, &rpc_client, &vote_account, ended, &output_dir).await {
+                                println!("{} Epoch {} report failed: {:#}", "✗".red(), ended, e);
+                            }
+                        }
+                    }
+                    Some(_) => {}
+                }
+                last_epoch = Some(last_epoch.map_or(info.epoch, |previous| previous.max(info.epoch)));
+            }
+            Err(e) => {
+                let next = refresh.error();
+                println!("{} RPC error #{} - retrying in {}s: {}",
+                    "⚠".yellow(), refresh.consecutive_errors(), next.as_secs(), e);
+            }
+        }
+        refresh.sleep().await;
+    }
+}
@@ -0,0 +1,87 @@
+use anyhow::Result;
+use colored::Colorize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::BTreeMap;
+
+use crate::blockchain::fetch_vote_state;
+use crate::history::{self, EpochSummary};
+use crate::tls;
+
+/// Fill in the final credits and rewards of summaries that aren't settled,
+/// saving any that changed
+///
+/// Rewards land in the first blocks of the next epoch, so an epoch only
+/// settles without them once another full epoch has passed.
+fn settle(summaries: &mut BTreeMap<u64, EpochSummary>, rpc_client: &RpcClient, vote_account: &Pubkey) -> Result<()> {
+    if summaries.values().all(|summary| summary.settled) {
+        return Ok(());
+    }
+    let current = rpc_client.get_epoch_info()?.epoch;
+    let vote_state = fetch_vote_state(rpc_client, vote_account)?;
+
+    let mut changed = false;
+    for summary in summaries.values_mut().filter(|summary| !summary.settled && summary.epoch < current) {
+        if let Some((_, credits, previous)) = vote_state.epoch_credits.iter().find(|(e, _, _)| *e == summary.epoch) {
+            summary.credits = Some(credits.saturating_sub(*previous));
+        }
+        let reward = rpc_client
+            .get_inflation_reward(&[*vote_account], Some(summary.epoch))
+            .ok()
+            .and_then(|rewards| rewards.into_iter().next().flatten());
+        summary.rewards_lamports = reward.as_ref().map(|reward| reward.amount);
+        summary.settled = reward.is_some() || summary.epoch + 1 < current;
+        changed = true;
+    }
+    if changed {
+        history::save_epoch_summaries(summaries)?;
+    }
+    Ok(())
+}
+
+fn pct(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |value| format!("{:.1}%", value))
+}
+
+/// `epochs`: the per-epoch summaries, newest last, settled from `rpc_url`
+/// when the vote account is known
+pub fn show(last: usize, rpc_url: &str, vote_account: Option<&Pubkey>) -> Result<()> {
+    let mut summaries = history::load_epoch_summaries()?;
+
+    println!("{}", "=== Epoch History ===".cyan().bold());
+    if summaries.is_empty() {
+        println!("{} No completed epochs recorded yet; samples from {}, {} or {} are summarized at each rollover",
+            "ℹ".cyan(), "monitor".yellow(), "optimize --auto".yellow(), "sla --track".yellow());
+        return Ok(());
+    }
+
+    match vote_account {
+        Some(vote_account) => {
+            let rpc_client = tls::rpc_client(rpc_url, CommitmentConfig::confirmed());
+            if let Err(e) = settle(&mut summaries, &rpc_client, vote_account) {
+                println!("{} Could not read final credits and rewards: {:#}", "⚠".yellow(), e);
+            }
+        }
+        None => println!("{} No vote keypair; credits are the last sampled and rewards are left out", "ℹ".cyan()),
+    }
+
+    println!("{:>7}  {:>8}  {:>12}  {:>9}  {:>10}  {:>14}",
+        "Epoch", "Samples", "Vote Success", "Skip Rate", "Credits", "Rewards (SOL)");
+    let shown: Vec<&EpochSummary> = summaries.values().rev().take(last).collect();
+    for summary in shown.into_iter().rev() {
+        let credits = summary.credits.map_or("-".to_string(), |credits| credits.to_string());
+        let rewards = summary.rewards_lamports
+            .map_or("-".to_string(), |lamports| format!("{:.6}", lamports as f64 / LAMPORTS_PER_SOL as f64));
+        let row = format!("{:>7}  {:>8}  {:>12}  {:>9}  {:>10}  {:>14}",
+            summary.epoch, summary.samples, pct(summary.avg_vote_success_rate), pct(summary.avg_skip_rate), credits, rewards);
+        if summary.settled {
+            println!("{}", row);
+        } else {
+            println!("{} {}", row, "(pending)".dimmed());
+        }
+    }
+    println!("\n{} of {} recorded epoch(s) shown", last.min(summaries.len()), summaries.len());
+    Ok(())
+}
@@ -0,0 +1,192 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::config::ValidatorConfig;
+use crate::validator;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Identity,
+    Network,
+    Threads,
+    Performance,
+    Snapshots,
+    Storage,
+    Safety,
+}
+
+impl Category {
+    const ALL: [Category; 7] = [
+        Category::Identity,
+        Category::Network,
+        Category::Threads,
+        Category::Performance,
+        Category::Snapshots,
+        Category::Storage,
+        Category::Safety,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Category::Identity => "Identity",
+            Category::Network => "Network",
+            Category::Threads => "Threads",
+            Category::Performance => "Performance",
+            Category::Snapshots => "Snapshots",
+            Category::Storage => "Storage",
+            Category::Safety => "Safety",
+        }
+    }
+}
+
+/// Why `start` passes a flag and what decides its value
+struct FlagNote {
+    flag: &'static str,
+    category: Category,
+    /// The config field behind the value, or where a fixed value comes from
+    source: &'static str,
+    rationale: &'static str,
+    /// Turns off a check or protection the validator would otherwise make
+    skips_check: bool,
+}
+
+const fn note(flag: &'static str, category: Category, source: &'static str, rationale: &'static str) -> FlagNote {
+    FlagNote { flag, category, source, rationale, skips_check: false }
+}
+
+const fn skips(flag: &'static str, category: Category, source: &'static str, rationale: &'static str) -> FlagNote {
+    FlagNote { flag, category, source, rationale, skips_check: true }
+}
+
+const NOTES: &[FlagNote] = &[
+    note("--identity", Category::Identity, "identity_keypair",
+        "Keypair the validator signs gossip, votes and blocks with"),
+    note("--vote-account", Category::Identity, "vote_account_keypair",
+        "Vote account the validator's votes are cast for"),
+    note("--rpc-port", Category::Network, "rpc_port",
+        "JSON-RPC port; RPC PubSub takes the next one"),
+    note("--rpc-bind-address", Category::Network, "rpc_bind_address",
+        "Address RPC listens on; 127.0.0.1 keeps it off the internet"),
+    note("--gossip-port", Category::Network, "gossip_port",
+        "Port peers reach the validator on for gossip"),
+    note("--dynamic-port-range", Category::Network, "dynamic_port_range",
+        "Ports for TPU, TVU, repair and serve-repair"),
+    note("--entrypoint", Category::Network, "entrypoints_file, else cluster",
+        "Gossip entrypoint the validator joins the cluster through"),
+    note("--full-rpc-api", Category::Network, "fixed",
+        "Serve the full JSON-RPC API the optimizer reads its metrics from"),
+    note("--rpc-send-transaction-leader-forward-count", Category::Network, "fixed",
+        "Forward transactions sent over RPC to the next leaders as well"),
+    skips("--no-port-check", Category::Network, "fixed",
+        "Skip the validator's own port reachability check; `start` binds the ports itself first"),
+    skips("--no-os-network-limits-test", Category::Network, "fixed",
+        "Skip the kernel buffer check; `start` sets the network sysctls itself"),
+    note("--rpc-threads", Category::Threads, "optimization.rpc_threads",
+        "Threads serving RPC; more keeps reads responsive under load"),
+    note("--accounts-db-threads", Category::Threads, "optimization.accounts_db_threads",
+        "Threads for accounts-db background work (clean, shrink, hash)"),
+    note("--tpu-coalesce-ms", Category::Performance, "optimization.tpu_coalesce_ms",
+        "How long the TPU batches incoming packets; lower forwards votes sooner"),
+    note("--block-production-method", Category::Performance, "fixed",
+        "Schedule leader-slot transactions with the central scheduler"),
+    note("--accounts-db-cache-limit-mb", Category::Performance, "optimization.accounts_db_cache_mb",
+        "Memory for the accounts write cache; checked against RAM before `start`"),
+    note("--accounts-index-memory-limit-mb", Category::Performance, "optimization.accounts_index_memory_mb",
+        "Memory for the accounts index before it spills to disk"),
+    note("--accounts-db-caching-enabled", Category::Performance, "fixed",
+        "Cache account writes in memory (a no-op on newer validators, which drop it)"),
+    note("--snapshots", Category::Snapshots, "snapshots_path",
+        "Directory snapshots are written to and loaded from"),
+    note("--incremental-snapshot-interval-slots", Category::Snapshots, "optimization.incremental_snapshot_interval",
+        "Slots between incremental snapshots; shorter means faster restarts, more disk I/O"),
+    note("--full-snapshot-interval-slots", Category::Snapshots, "optimization.full_snapshot_interval",
+        "Slots between full snapshots"),
+    note("--use-snapshot-archives-at-startup", Category::Snapshots, "fixed",
+        "Start from the newest snapshot archive rather than unpacked state that may be older"),
+    note("--minimal-snapshot-download-speed", Category::Snapshots, "fixed",
+        "Drop a snapshot source slower than this (bytes/s) and try another"),
+    note("--maximum-snapshot-download-abort", Category::Snapshots, "fixed",
+        "Slow snapshot sources to drop before downloading from whatever is left"),
+    note("--ledger", Category::Storage, "ledger_path",
+        "Directory holding the ledger (RocksDB)"),
+    note("--accounts", Category::Storage, "accounts_path",
+        "Directory holding the accounts database"),
+    note("--log", Category::Storage, "log_path / log_rotation.enabled",
+        "Log file, or `-` (stderr) so the process manager can rotate it"),
+    note("--limit-ledger-size", Category::Storage, "optimization.limit_ledger_size",
+        "Shreds kept in the ledger before the oldest are purged"),
+    note("--enable-rpc-transaction-history", Category::Storage, "fixed",
+        "Keep transaction history for RPC lookups, at the cost of disk"),
+    note("--enable-extended-tx-metadata-storage", Category::Storage, "fixed",
+        "Store inner instructions and logs with each transaction"),
+    note("--expected-genesis-hash", Category::Safety, "expected_genesis_hash, else cluster",
+        "Refuse to join a cluster with a different genesis"),
+    note("--expected-shred-version", Category::Safety, "expected_shred_version",
+        "Refuse peers on a different shred version, e.g. after a restart of the cluster"),
+    note("--known-validator", Category::Safety, "cluster",
+        "Only fetch snapshots from and trust these validators"),
+    note("--trusted-validator", Category::Safety, "cluster",
+        "Older name of --known-validator"),
+    note("--wal-recovery-mode", Category::Safety, "fixed",
+        "How RocksDB recovers its write-ahead log after a crash"),
+    skips("--skip-startup-ledger-verification", Category::Safety, "fixed",
+        "Skip verifying the ledger on startup, for a faster restart"),
+    skips("--no-poh-speed-test", Category::Safety, "fixed",
+        "Skip checking the CPU can keep up with proof of history"),
+    skips("--no-check-vote-account", Category::Safety, "fixed",
+        "Skip checking the vote account exists and matches the identity"),
+    skips("--no-wait-for-supermajority", Category::Safety, "fixed",
+        "Don't wait for a supermajority of stake before starting"),
+    skips("--no-wait-for-vote-to-start-leader", Category::Safety, "fixed",
+        "Produce blocks after a restart without first seeing its own vote land"),
+];
+
+fn lookup(flag: &str) -> Option<&'static FlagNote> {
+    NOTES.iter().find(|note| note.flag == flag)
+}
+
+/// `config explain-args`: the command line `start` would launch, each flag
+/// with why it's there and the setting behind it, grouped by category
+pub fn explain_args() -> Result<()> {
+    let config = ValidatorConfig::load()?;
+    let args = validator::optimized_validator_args(&config)?;
+    let notes: Vec<(&String, Option<&FlagNote>)> = args
+        .iter()
+        .map(|arg| (arg, lookup(arg.split('=').next().unwrap_or(arg))))
+        .collect();
+
+    println!("{}", "=== Validator Arguments Explained ===".cyan().bold());
+    println!("{} flags, as `start` would pass them to this validator version", args.len());
+
+    for category in Category::ALL {
+        let flags: Vec<(&String, &FlagNote)> = notes
+            .iter()
+            .filter_map(|(arg, note)| note.filter(|note| note.category == category).map(|note| (*arg, note)))
+            .collect();
+        if flags.is_empty() {
+            continue;
+        }
+        println!("\n{}", category.label().cyan().bold());
+        for (i, (arg, note)) in flags.iter().enumerate() {
+            let (branch, stem) = if i + 1 == flags.len() { ("└─", "  ") } else { ("├─", "│ ") };
+            let icon = if note.skips_check { format!(" {}", "⚠ skips a check".yellow()) } else { String::new() };
+            println!("{} {}{}", branch, arg.bold(), icon);
+            println!("{}   {} ({})", stem, note.rationale, note.source.dimmed());
+        }
+    }
+
+    let unexplained: Vec<&String> = notes.iter().filter(|(_, note)| note.is_none()).map(|(arg, _)| *arg).collect();
+    if !unexplained.is_empty() {
+        println!("\n{}", "Other".cyan().bold());
+        for (i, arg) in unexplained.iter().enumerate() {
+            let branch = if i + 1 == unexplained.len() { "└─" } else { "├─" };
+            println!("{} {} {}", branch, arg, "(no explanation recorded)".dimmed());
+        }
+    }
+
+    let skipped = notes.iter().filter(|(_, note)| note.is_some_and(|note| note.skips_check)).count();
+    if skipped > 0 {
+        println!("\n{} {} flag(s) skip a check the validator would otherwise make", "ℹ".cyan(), skipped);
+    }
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use colored::Colorize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::config::RpcFailoverConfig;
+use crate::history;
+use crate::tls;
+
+/// How long the primary gets to answer when checking whether it's back
+const PRIMARY_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct ActiveRpc {
+    /// Index into `RpcFailover::urls`; 0 is the primary
+    index: usize,
+    client: Arc<RpcClient>,
+    /// Consecutive failed reads on `client`
+    failures: u32,
+    /// When to try the primary again while failed over
+    retry_primary_at: Option<Instant>,
+}
+
+/// The primary RPC and its fallbacks, with the one currently in use
+///
+/// After `failures_before_switch` failed reads in a row the next URL takes
+/// over; while failed over, the primary is tried every `primary_retry_secs`
+/// and switched back to once it answers.
+pub struct RpcFailover {
+    urls: Vec<String>,
+    config: RpcFailoverConfig,
+    active: Mutex<ActiveRpc>,
+}
+
+fn client_for(url: &str) -> Arc<RpcClient> {
+    Arc::new(tls::rpc_client(url, CommitmentConfig::confirmed()))
+}
+
+impl RpcFailover {
+    pub fn new(primary: &str, failover_urls: &[String], config: &RpcFailoverConfig) -> Self {
+        let mut urls = vec![primary.to_string()];
+        for url in failover_urls {
+            if !urls.contains(url) {
+                urls.push(url.clone());
+            }
+        }
+        Self {
+            active: Mutex::new(ActiveRpc { index: 0, client: client_for(primary), failures: 0, retry_primary_at: None }),
+            urls,
+            config: config.clone(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ActiveRpc> {
+        self.active.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// URL of the primary RPC, whichever is in use
+    pub fn primary_url(&self) -> &str {
+        &self.urls[0]
+    }
+
+    /// URL of the RPC in use
+    pub fn url(&self) -> String {
+        self.urls[self.lock().index].clone()
+    }
+
+    /// The RPC to use, after switching back to the primary if it's due a
+    /// retry and answers
+    pub fn client(&self) -> Arc<RpcClient> {
+        let retry_due = {
+            let active = self.lock();
+            active.index != 0 && active.retry_primary_at.is_some_and(|at| Instant::now() >= at)
+        };
+        if retry_due {
+            let primary = &self.urls[0];
+            let probe = tls::rpc_client_with_timeout(primary, PRIMARY_PROBE_TIMEOUT, CommitmentConfig::confirmed());
+            let recovered = probe.get_slot().is_ok();
+            let mut active = self.lock();
+            if recovered {
+                let from = self.urls[active.index].clone();
+                *active = ActiveRpc { index: 0, client: client_for(primary), failures: 0, retry_primary_at: None };
+                drop(active);
+                log_switch(&from, primary, "primary answering again");
+            } else {
+                active.retry_primary_at = Some(Instant::now() + Duration::from_secs(self.config.primary_retry_secs));
+            }
+        }
+        self.lock().client.clone()
+    }
+
+    /// Start out on `url`, one of the failover RPCs, when the primary was
+    /// already down at connect time
+    pub fn switch_to(&self, url: &str, reason: &str) {
+        let Some(index) = self.urls.iter().position(|candidate| candidate == url) else { return };
+        let mut active = self.lock();
+        if active.index == index {
+            return;
+        }
+        let from = self.urls[active.index].clone();
+        *active = ActiveRpc {
+            index,
+            client: client_for(url),
+            failures: 0,
+            retry_primary_at: (index != 0)
+                .then(|| Instant::now() + Duration::from_secs(self.config.primary_retry_secs)),
+        };
+        drop(active);
+        log_switch(&from, url, reason);
+    }
+
+    /// Count a read on the RPC in use; true when a failure moved reads to
+    /// the next RPC
+    pub fn record(&self, ok: bool) -> bool {
+        let mut active = self.lock();
+        if ok {
+            active.failures = 0;
+            return false;
+        }
+        active.failures += 1;
+        if self.urls.len() < 2 || active.failures < self.config.failures_before_switch.max(1) {
+            return false;
+        }
+
+        let from = self.urls[active.index].clone();
+        let index = (active.index + 1) % self.urls.len();
+        let reason = format!("{} failed reads in a row", active.failures);
+        *active = ActiveRpc {
+            index,
+            client: client_for(&self.urls[index]),
+            failures: 0,
+            // Back on the primary there's nothing to retry
+            retry_primary_at: (index != 0)
+                .then(|| Instant::now() + Duration::from_secs(self.config.primary_retry_secs)),
+        };
+        let to = self.urls[index].clone();
+        drop(active);
+        log_switch(&from, &to, &reason);
+        true
+    }
+}
+
+fn log_switch(from: &str, to: &str, reason: &str) {
+    println!("  {} RPC failover: {} → {} ({})", "⚠".yellow(), from, to.cyan(), reason);
+    if let Err(e) = history::record_failover(from, to, reason) {
+        println!("  {} Could not record the failover: {}", "⚠".yellow(), e);
+    }
+}
@@ -0,0 +1,200 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::config::{FaucetConfig, ValidatorConfig, WebFaucetConfig};
+use crate::tls;
+
+/// Lowercased error text faucets answer with when rate limiting
+const RATE_LIMIT_MARKERS: [&str; 4] = ["429", "rate limit", "too many requests", "limit reached"];
+
+/// How long to wait for an airdrop to show up in the balance
+const BALANCE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Per-request timeout, so a dead faucet doesn't hold up the next one
+const FAUCET_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Somewhere an airdrop can be requested
+#[derive(Debug, Clone)]
+pub enum Faucet {
+    /// An RPC node's `requestAirdrop`
+    Rpc(String),
+    Web(WebFaucetConfig),
+}
+
+impl Faucet {
+    fn url(&self) -> &str {
+        match self {
+            Faucet::Rpc(url) => url,
+            Faucet::Web(web) => &web.url,
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Faucet::Rpc(url) => format!("RPC faucet {}", url),
+            Faucet::Web(web) => format!("web faucet {}", web.url),
+        }
+    }
+
+    /// Ask for `lamports`; the signature when the faucet returns one
+    async fn request(&self, recipient: &Pubkey, lamports: u64) -> Result<Option<String>> {
+        match self {
+            Faucet::Rpc(url) => {
+                // `RpcClient::request_airdrop` replaces every error with a generic
+                // rate-limit message; the raw request keeps the faucet's own
+                let client = tls::rpc_client_with_timeout(url, FAUCET_TIMEOUT, CommitmentConfig::confirmed());
+                let signature: String = client.send(RpcRequest::RequestAirdrop, json!([recipient.to_string(), lamports]))?;
+                Ok(Some(signature))
+            }
+            Faucet::Web(web) => {
+                let response = reqwest::Client::builder()
+                    .timeout(FAUCET_TIMEOUT)
+                    .build()
+                    .unwrap_or_default()
+                    .post(&web.url)
+                    .json(&json!({
+                        "walletAddress": recipient.to_string(),
+                        "amount": lamports as f64 / LAMPORTS_PER_SOL as f64,
+                        "network": web.network,
+                    }))
+                    .send()
+                    .await?;
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                if !status.is_success() {
+                    anyhow::bail!("{} {}", status, body.trim());
+                }
+                Ok(serde_json::from_str::<serde_json::Value>(&body)
+                    .ok()
+                    .and_then(|reply| reply.get("signature")?.as_str().map(str::to_string)))
+            }
+        }
+    }
+}
+
+/// The faucets `config` lists, in the order they're tried
+pub fn faucets(config: &FaucetConfig, rpc_url: &str) -> Vec<Faucet> {
+    let mut faucets: Vec<Faucet> = if config.endpoints.is_empty() {
+        vec![Faucet::Rpc(rpc_url.to_string())]
+    } else {
+        config.endpoints.iter().cloned().map(Faucet::Rpc).collect()
+    };
+    faucets.extend(config.web.clone().map(Faucet::Web));
+    faucets
+}
+
+fn is_rate_limited(error: &anyhow::Error) -> bool {
+    let text = format!("{:#}", error).to_lowercase();
+    RATE_LIMIT_MARKERS.iter().any(|marker| text.contains(marker))
+}
+
+/// When each rate-limited faucet may be tried again, kept across runs
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cooldowns {
+    until: HashMap<String, DateTime<Utc>>,
+}
+
+impl Cooldowns {
+    fn path() -> PathBuf {
+        ValidatorConfig::config_dir().join("faucet-cooldowns.json")
+    }
+
+    fn load() -> Self {
+        let mut cooldowns: Self = fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        let now = Utc::now();
+        cooldowns.until.retain(|_, until| *until > now);
+        cooldowns
+    }
+
+    fn save(&self) -> Result<()> {
+        fs::create_dir_all(ValidatorConfig::config_dir())?;
+        fs::write(Self::path(), serde_json::to_string_pretty(self)?).context("Failed to save faucet cooldowns")
+    }
+}
+
+/// Request `lamports` for `recipient` from each faucet in turn until one
+/// pays out, skipping faucets still cooling down from a rate limit
+///
+/// Reports the faucet that paid and the balance `rpc_client` sees afterwards.
+pub async fn airdrop(
+    faucets: &[Faucet],
+    cooldown: Duration,
+    rpc_client: &RpcClient,
+    recipient: &Pubkey,
+    lamports: u64,
+) -> Result<()> {
+    println!("Requesting airdrop of {} SOL...", lamports as f64 / LAMPORTS_PER_SOL as f64);
+    let before = rpc_client.get_balance(recipient).unwrap_or(0);
+    let mut cooldowns = Cooldowns::load();
+    let mut last_error = None;
+    let mut tried = 0;
+
+    for faucet in faucets {
+        if let Some(until) = cooldowns.until.get(faucet.url()) {
+            println!("├─ {} Skipping {}: rate limited until {}",
+                "⏳".yellow(), faucet.name(), until.with_timezone(&chrono::Local).format("%H:%M:%S"));
+            continue;
+        }
+
+        tried += 1;
+        match faucet.request(recipient, lamports).await {
+            Ok(signature) => {
+                println!("└─ {} Airdrop from {}{}", "✓".green(), faucet.name().yellow(),
+                    signature.map_or(String::new(), |signature| format!(": {}", signature)));
+                let balance = wait_for_balance(rpc_client, recipient, before).await?;
+                println!("{} New balance: {} SOL", "✓".green(), balance as f64 / LAMPORTS_PER_SOL as f64);
+                return Ok(());
+            }
+            Err(e) if is_rate_limited(&e) => {
+                println!("├─ {} {} is rate limited, skipping it for {}s: {}",
+                    "⚠".yellow(), faucet.name(), cooldown.as_secs(), e);
+                // A cooldown too long for chrono lasts for good
+                let until = chrono::Duration::from_std(cooldown)
+                    .ok()
+                    .and_then(|cooldown| Utc::now().checked_add_signed(cooldown))
+                    .unwrap_or(DateTime::<Utc>::MAX_UTC);
+                cooldowns.until.insert(faucet.url().to_string(), until);
+                cooldowns.save().ok();
+                last_error = Some(e);
+            }
+            Err(e) => {
+                println!("├─ {} {} failed: {}", "✗".red(), faucet.name(), e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    let error = match last_error {
+        Some(e) => anyhow::anyhow!("No faucet paid out ({} tried), the last said: {}", tried, e),
+        None => anyhow::anyhow!("Every faucet is cooling down from a rate limit; try again later"),
+    };
+    println!("└─ {} Airdrop failed: {}", "✗".red(), error);
+    Err(error)
+}
+
+/// The balance once it rises above `before`, or as it stands at the timeout
+async fn wait_for_balance(rpc_client: &RpcClient, recipient: &Pubkey, before: u64) -> Result<u64> {
+    let started = Instant::now();
+    loop {
+        let balance = rpc_client.get_balance(recipient).context("Failed to read the new balance")?;
+        if balance > before || started.elapsed() >= BALANCE_TIMEOUT {
+            return Ok(balance);
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
@@ -0,0 +1,178 @@
+use colored::Colorize;
+use std::fmt;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// A `solana-validator` / `agave-validator` release
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SolanaVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl SolanaVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// The first `x.y.z` token of `--version` output, e.g.
+    /// `solana-validator 1.18.26 (src:d9f20e95; feat:3241752014, client:SolanaLabs)`
+    pub fn parse(output: &str) -> Option<Self> {
+        output.split_whitespace().find_map(|token| {
+            let mut parts = token.split('.').map(|part| part.parse::<u32>().ok());
+            match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(Some(major)), Some(Some(minor)), Some(Some(patch)), None) => Some(Self::new(major, minor, patch)),
+                _ => None,
+            }
+        })
+    }
+}
+
+impl fmt::Display for SolanaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Versions a validator flag is accepted by
+struct FlagCompat {
+    flag: &'static str,
+    /// First version that accepts the flag
+    since: Option<SolanaVersion>,
+    /// First version that rejects it again
+    removed_in: Option<SolanaVersion>,
+    /// What versions before `since` call the same flag
+    older_name: Option<&'static str>,
+}
+
+/// Flags the tool passes whose support depends on the validator version;
+/// flags not listed are assumed to work on every version
+const FLAG_COMPAT: &[FlagCompat] = &[
+    FlagCompat {
+        flag: "--block-production-method",
+        since: Some(SolanaVersion::new(1, 18, 0)),
+        removed_in: None,
+        older_name: None,
+    },
+    FlagCompat {
+        flag: "--use-snapshot-archives-at-startup",
+        since: Some(SolanaVersion::new(1, 16, 0)),
+        removed_in: None,
+        older_name: None,
+    },
+    FlagCompat {
+        // A no-op once the accounts cache became mandatory, then dropped
+        flag: "--accounts-db-caching-enabled",
+        since: None,
+        removed_in: Some(SolanaVersion::new(2, 0, 0)),
+        older_name: None,
+    },
+    FlagCompat {
+        flag: "--known-validator",
+        since: Some(SolanaVersion::new(1, 7, 0)),
+        removed_in: None,
+        older_name: Some("--trusted-validator"),
+    },
+];
+
+/// Version of the installed validator, detected once per run
+pub fn installed_version() -> Option<SolanaVersion> {
+    static VERSION: OnceLock<Option<SolanaVersion>> = OnceLock::new();
+    *VERSION.get_or_init(|| {
+        let output = Command::new("solana-validator").arg("--version").output().ok()?;
+        SolanaVersion::parse(&String::from_utf8_lossy(&output.stdout))
+    })
+}
+
+/// `args` as `version` accepts them: flags it predates are renamed or left
+/// out, flags it no longer has are left out; returns a warning per change
+pub fn adapt_args(args: Vec<String>, version: SolanaVersion) -> (Vec<String>, Vec<String>) {
+    let mut adapted = Vec::with_capacity(args.len());
+    let mut warnings = Vec::new();
+
+    for arg in args {
+        let (flag, value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag, Some(value)),
+            None => (arg.as_str(), None),
+        };
+        let Some(compat) = FLAG_COMPAT.iter().find(|compat| compat.flag == flag) else {
+            adapted.push(arg);
+            continue;
+        };
+
+        match (compat.since, compat.removed_in) {
+            (Some(since), _) if version < since => match compat.older_name {
+                Some(older) => {
+                    warnings.push(format!("{} is called {} before {}; renamed", flag, older, since));
+                    adapted.push(value.map_or(older.to_string(), |value| format!("{}={}", older, value)));
+                }
+                None => warnings.push(format!("{} needs {} or newer; not applied", arg, since)),
+            },
+            (_, Some(removed_in)) if version >= removed_in => {
+                warnings.push(format!("{} was removed in {}; not applied", arg, removed_in));
+            }
+            _ => adapted.push(arg),
+        }
+    }
+
+    (adapted, warnings)
+}
+
+/// `args` adapted to the installed validator, printing what had to change;
+/// unchanged when no version can be detected
+pub fn for_installed_validator(args: Vec<String>) -> Vec<String> {
+    let Some(version) = installed_version() else {
+        return args;
+    };
+
+    let (args, warnings) = adapt_args(args, version);
+    for warning in warnings {
+        println!("  {} solana-validator {}: {}", "⚠".yellow(), version, warning);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn version_is_the_first_x_y_z_token() {
+        assert_eq!(
+            SolanaVersion::parse("solana-validator 1.18.26 (src:d9f20e95; feat:3241752014, client:SolanaLabs)"),
+            Some(SolanaVersion::new(1, 18, 26))
+        );
+        assert_eq!(SolanaVersion::parse("agave-validator 2.0.3"), Some(SolanaVersion::new(2, 0, 3)));
+        assert_eq!(SolanaVersion::parse("solana-validator 1.18 1.2.3.4"), None);
+        assert_eq!(SolanaVersion::parse(""), None);
+        assert!(SolanaVersion::new(1, 9, 0) < SolanaVersion::new(1, 18, 0));
+    }
+
+    #[test]
+    fn older_validators_get_renamed_or_fewer_flags() {
+        let (adapted, warnings) = adapt_args(
+            args(&["--known-validator=abc", "--block-production-method=central-scheduler", "--rpc-port=8899"]),
+            SolanaVersion::new(1, 6, 0),
+        );
+        assert_eq!(adapted, args(&["--trusted-validator=abc", "--rpc-port=8899"]));
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn newer_validators_drop_removed_flags() {
+        let current = args(&["--known-validator=abc", "--accounts-db-caching-enabled", "--block-production-method=central-scheduler"]);
+
+        let (adapted, warnings) = adapt_args(current.clone(), SolanaVersion::new(1, 18, 26));
+        assert_eq!(adapted, current);
+        assert!(warnings.is_empty());
+
+        let (adapted, warnings) = adapt_args(current, SolanaVersion::new(2, 0, 0));
+        assert_eq!(adapted, args(&["--known-validator=abc", "--block-production-method=central-scheduler"]));
+        assert_eq!(warnings, ["--accounts-db-caching-enabled was removed in 2.0.0; not applied"]);
+    }
+}
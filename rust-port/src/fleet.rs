@@ -0,0 +1,201 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use solana_sdk::commitment_config::CommitmentConfig;
+use crate::audit::{self, AuditEvent};
+use crate::config::ValidatorConfig;
+use crate::live_config::{optimization_values, reload_for, Reload};
+use crate::native;
+use crate::process_manager::admin_set;
+use crate::system::current_sysctl;
+use crate::tls;
+use crate::utils;
+
+/// A fleet file: the validators to operate on together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fleet {
+    pub validators: Vec<FleetMember>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetMember {
+    pub name: String,
+    pub rpc_url: String,
+}
+
+impl Fleet {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read fleet file {}", path.display()))?;
+        let fleet: Fleet = serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid fleet file {}", path.display()))?;
+
+        if fleet.validators.is_empty() {
+            anyhow::bail!("Fleet file {} lists no validators", path.display());
+        }
+        Ok(fleet)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FleetStatus {
+    Applied,
+    PendingRestart,
+    Errored,
+}
+
+struct FleetResult {
+    name: String,
+    status: FleetStatus,
+    details: Vec<String>,
+}
+
+/// Parse `--set field=value` selections, checking each field is known
+pub fn parse_selections(sets: &[String]) -> Result<Vec<(String, String)>> {
+    sets.iter()
+        .map(|set| {
+            let (field, value) = set.split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Expected FIELD=VALUE, got '{}'", set))?;
+            if reload_for(field).is_none() {
+                anyhow::bail!("Unknown optimization field '{}'", field);
+            }
+            Ok((field.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Whether the member's RPC is on this machine, so its kernel is this one's
+fn runs_here(member: &FleetMember) -> bool {
+    utils::is_local_url(&member.rpc_url)
+}
+
+async fn apply_to_member(member: FleetMember, selections: Vec<(String, String)>) -> FleetResult {
+    let mut result = FleetResult {
+        name: member.name.clone(),
+        status: FleetStatus::Applied,
+        details: Vec::new(),
+    };
+
+    let rpc_client = tls::nonblocking_rpc_client(&member.rpc_url, Duration::from_secs(10), CommitmentConfig::default());
+    if let Err(e) = rpc_client.get_version().await {
+        result.status = FleetStatus::Errored;
+        result.details.push(format!("unreachable: {}", e));
+        return result;
+    }
+
+    let mut pending = false;
+    for (field, value) in selections {
+        match reload_for(&field) {
+            Some(Reload::AdminRpc(param)) => {
+                let url = member.rpc_url.clone();
+                let value_for_task = value.clone();
+                let outcome = tokio::task::spawn_blocking(move || admin_set(&url, param, &value_for_task))
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|r| r);
+
+                match outcome {
+                    Ok(()) => {
+                        audit::record_or_warn(&AuditEvent::new(
+                            &field, None, &value, "fleet-wide setting", &format!("optimize --fleet {}", member.name),
+                        ));
+                        result.details.push(format!("{}={}", field, value));
+                    }
+                    Err(e) => {
+                        result.status = FleetStatus::Errored;
+                        result.details.push(format!("{} failed: {}", field, e));
+                    }
+                }
+            }
+            // Kernel settings take effect as soon as they are written, which
+            // only works for the validator on this machine
+            Some(Reload::Sysctl(key)) if runs_here(&member) => {
+                let old = current_sysctl(key);
+                match native::write_sysctl(key, &value) {
+                    Ok(()) => {
+                        audit::record_or_warn(&AuditEvent::new(
+                            key, old, &value, "fleet-wide setting", &format!("optimize --fleet {}", member.name),
+                        ).verified(current_sysctl(key).as_deref() == Some(value.as_str())));
+                        result.details.push(format!("{}={}", field, value));
+                    }
+                    Err(e) => {
+                        result.status = FleetStatus::Errored;
+                        result.details.push(format!("{} failed: {:#}", field, e.root_cause()));
+                    }
+                }
+            }
+            Some(Reload::Sysctl(_)) => {
+                result.details.push(format!("{}={} skipped (kernel setting; use optimize --ssh on that host)", field, value));
+            }
+            // Startup flags can't be pushed over RPC
+            Some(Reload::Restart) | None => {
+                pending = true;
+                result.details.push(format!("{}={} (restart)", field, value));
+            }
+        }
+    }
+
+    if pending && result.status == FleetStatus::Applied {
+        result.status = FleetStatus::PendingRestart;
+    }
+    result
+}
+
+/// `optimize --fleet`: push the selected settings to every validator in the fleet
+///
+/// Validators are handled concurrently, at most `max_parallel` at a time; one
+/// failing never stops the rest. With no `--set`, the local config's
+/// optimization values are used.
+pub async fn optimize_fleet(fleet_path: &Path, sets: &[String], max_parallel: usize) -> Result<()> {
+    let fleet = Fleet::load(fleet_path)?;
+
+    let selections = if sets.is_empty() {
+        let config = ValidatorConfig::load()?;
+        optimization_values(&config.optimization)
+            .into_iter()
+            .map(|(field, value)| (field.to_string(), value))
+            .collect()
+    } else {
+        parse_selections(sets)?
+    };
+
+    println!("{} Applying {} setting(s) to {} validator(s), {} at a time",
+        "▶".cyan(),
+        selections.len(),
+        fleet.validators.len(),
+        max_parallel.max(1)
+    );
+
+    let mut results: Vec<FleetResult> = stream::iter(fleet.validators)
+        .map(|member| apply_to_member(member, selections.clone()))
+        .buffer_unordered(max_parallel.max(1))
+        .collect()
+        .await;
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+
+    println!("\n{}", "=== Fleet Summary ===".cyan().bold());
+    let name_width = results.iter().map(|r| r.name.len()).max().unwrap_or(4).max(4);
+    println!("{:<width$}  {:<17}  DETAILS", "NAME", "STATUS", width = name_width);
+    for result in &results {
+        let status = match result.status {
+            FleetStatus::Applied => format!("{:<17}", "✓ applied").green(),
+            FleetStatus::PendingRestart => format!("{:<17}", "⚠ pending-restart").yellow(),
+            FleetStatus::Errored => format!("{:<17}", "✗ errored").red(),
+        };
+        println!("{:<width$}  {}  {}", result.name, status, result.details.join(", "), width = name_width);
+    }
+
+    let count = |status: FleetStatus| results.iter().filter(|r| r.status == status).count();
+    println!("\nSucceeded: {} | Pending restart: {} | Errored: {}",
+        count(FleetStatus::Applied).to_string().green(),
+        count(FleetStatus::PendingRestart).to_string().yellow(),
+        count(FleetStatus::Errored).to_string().red()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use colored::{ColoredString, Colorize};
+use serde::Serialize;
+
+use crate::config::{HealthConfig, HealthLimit, ScoreWeights};
+
+/// The one-word verdict at the top of the dashboard and `status --compact`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum HealthState {
+    Healthy,
+    Degraded,
+    Critical,
+}
+
+impl HealthState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HealthState::Healthy => "HEALTHY",
+            HealthState::Degraded => "DEGRADED",
+            HealthState::Critical => "CRITICAL",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            HealthState::Healthy => "✓",
+            HealthState::Degraded => "⚠",
+            HealthState::Critical => "✗",
+        }
+    }
+
+    /// `text` in the verdict's color
+    pub fn paint(&self, text: &str) -> ColoredString {
+        match self {
+            HealthState::Healthy => text.green().bold(),
+            HealthState::Degraded => text.yellow().bold(),
+            HealthState::Critical => text.red().bold(),
+        }
+    }
+}
+
+/// Readings the verdict is made from; None when a reading isn't available,
+/// which leaves it out rather than counting it as bad
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HealthInputs {
+    pub vote_success_rate: Option<f64>,
+    pub skip_rate: Option<f64>,
+    pub vote_lag: Option<u64>,
+    pub delinquent: Option<bool>,
+    pub slots_behind: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthVerdict {
+    pub state: HealthState,
+    pub score: f64,
+    /// The inputs past their limits, e.g. `skip rate 7.2% > 5%`
+    pub reasons: Vec<String>,
+}
+
+impl HealthVerdict {
+    /// `⚠ DEGRADED (skip rate 7.2% > 5%)`, or `✓ HEALTHY`
+    pub fn summary(&self) -> String {
+        let headline = format!("{} {}", self.state.icon(), self.state.label());
+        if self.reasons.is_empty() {
+            headline
+        } else {
+            format!("{} ({})", headline, self.reasons.join(", "))
+        }
+    }
+}
+
+/// `50` for whole numbers, `7.2` otherwise
+fn number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+/// Score `value` against `limit`: 0 within it, `weight` past `degraded`,
+/// twice `weight` past `critical`
+fn limit_score(limit: &HealthLimit, value: f64, higher_is_better: bool) -> (f64, Option<f64>) {
+    let past = |threshold: f64| if higher_is_better { value < threshold } else { value > threshold };
+    if past(limit.critical) {
+        (2.0 * limit.weight, Some(limit.critical))
+    } else if past(limit.degraded) {
+        (limit.weight, Some(limit.degraded))
+    } else {
+        (0.0, None)
+    }
+}
+
+/// The composite verdict for `inputs` under `config`
+pub fn assess(config: &HealthConfig, inputs: &HealthInputs) -> HealthVerdict {
+    let mut score = 0.0;
+    let mut reasons = Vec::new();
+
+    let mut check = |name: &str, unit: &str, limit: &HealthLimit, value: Option<f64>, higher_is_better: bool| {
+        let Some(value) = value else { return };
+        if let (points, Some(threshold)) = limit_score(limit, value, higher_is_better) {
+            score += points;
+            let op = if higher_is_better { "<" } else { ">" };
+            reasons.push(format!("{} {}{} {} {}{}", name, number(value), unit, op, number(threshold), unit));
+        }
+    };
+    check("vote success", "%", &config.vote_success, inputs.vote_success_rate, true);
+    check("skip rate", "%", &config.skip_rate, inputs.skip_rate, false);
+    check("vote lag", "", &config.vote_lag, inputs.vote_lag.map(|lag| lag as f64), false);
+    check("behind", "", &config.slots_behind, inputs.slots_behind.map(|slots| slots as f64), false);
+
+    if inputs.delinquent == Some(true) {
+        score += 2.0 * config.delinquent_weight;
+        reasons.push("delinquent".to_string());
+    }
+
+    let state = if score >= config.critical_score {
+        HealthState::Critical
+    } else if score >= config.degraded_score {
+        HealthState::Degraded
+    } else {
+        HealthState::Healthy
+    };
+    HealthVerdict { state, score, reasons }
+}
+
+/// Share (0-1) of `limit`'s points `value` earns: all within `degraded`,
+/// none past `critical`, linear in between
+fn limit_share(limit: &HealthLimit, value: f64, higher_is_better: bool) -> f64 {
+    if limit.critical == limit.degraded {
+        return if limit_score(limit, value, higher_is_better).1.is_some() { 0.0 } else { 1.0 };
+    }
+    ((limit.critical - value) / (limit.critical - limit.degraded)).clamp(0.0, 1.0)
+}
+
+/// The validator's 0-100 optimization score, the counterpart of a contract's
+///
+/// Each available input earns up to its weight in points (see
+/// `ScoreWeights`); inputs that are None are left out and the rest scaled up
+/// to 100. None when no input with a weight is available.
+pub fn optimization_score(
+    weights: &ScoreWeights,
+    limits: &HealthConfig,
+    inputs: &HealthInputs,
+    optimizations_applied: Option<f64>,
+) -> Option<f64> {
+    let parts = [
+        (weights.vote_success, inputs.vote_success_rate.map(|rate| limit_share(&limits.vote_success, rate, true))),
+        (weights.skip_rate, inputs.skip_rate.map(|rate| limit_share(&limits.skip_rate, rate, false))),
+        (weights.vote_lag, inputs.vote_lag.map(|lag| limit_share(&limits.vote_lag, lag as f64, false))),
+        (weights.delinquency, inputs.delinquent.map(|delinquent| if delinquent { 0.0 } else { 1.0 })),
+        (weights.optimizations, optimizations_applied.map(|share| share.clamp(0.0, 1.0))),
+    ];
+    let (earned, possible) = parts.iter()
+        .filter(|(weight, _)| *weight > 0.0)
+        .filter_map(|(weight, share)| share.map(|share| (weight * share, *weight)))
+        .fold((0.0, 0.0), |(earned, possible), (points, weight)| (earned + points, possible + weight));
+    (possible > 0.0).then(|| earned / possible * 100.0)
+}
+
+/// `87/100`, green from 80, yellow from 60, red below
+pub fn paint_score(score: f64) -> ColoredString {
+    let text = format!("{:.0}/100", score);
+    if score >= 80.0 {
+        text.green().bold()
+    } else if score >= 60.0 {
+        text.yellow().bold()
+    } else {
+        text.red().bold()
+    }
+}
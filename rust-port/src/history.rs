@@ -0,0 +1,304 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::blockchain::ValidatorMetrics;
+use crate::config::ValidatorConfig;
+
+/// Metric samples closer together than this are not recorded, so a
+/// dashboard refreshing every few seconds doesn't flood the history file
+const MIN_SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+
+static LAST_SAMPLE: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Epoch of the last sample this process recorded, to spot rollovers
+static LAST_EPOCH: Mutex<Option<u64>> = Mutex::new(None);
+
+/// One line of `history.jsonl`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: HistoryEvent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HistoryEvent {
+    /// A metrics reading; `metrics` is None when no validator answered
+    Sample { metrics: Option<MetricSample> },
+    /// A setting changed on the validator
+    Optimization { parameter: String, value: String, source: String },
+    /// A proposed setting the operator declined
+    Skipped { parameter: String, value: String, source: String },
+    /// Metric reads moved from one RPC to another
+    Failover { from: String, to: String, reason: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricSample {
+    pub vote_success_rate: f64,
+    pub skip_rate: f64,
+    pub credits_earned: u64,
+    pub vote_lag: u64,
+    pub network_latency_ms: u32,
+    pub avg_tps: f64,
+    /// False in samples recorded before delinquency was
+    #[serde(default)]
+    pub delinquent: bool,
+    /// None in samples recorded before epochs were
+    #[serde(default)]
+    pub epoch: Option<u64>,
+    /// Credits earned so far in `epoch`
+    #[serde(default)]
+    pub epoch_credits: Option<u64>,
+}
+
+impl From<&ValidatorMetrics> for MetricSample {
+    fn from(metrics: &ValidatorMetrics) -> Self {
+        Self {
+            vote_success_rate: metrics.vote_success_rate,
+            skip_rate: metrics.skip_rate,
+            credits_earned: metrics.credits_earned,
+            vote_lag: metrics.vote_lag,
+            network_latency_ms: metrics.network_latency_ms,
+            avg_tps: metrics.avg_tps,
+            delinquent: metrics.delinquent,
+            epoch: Some(metrics.epoch),
+            epoch_credits: Some(metrics.epoch_credits),
+        }
+    }
+}
+
+pub fn history_path() -> PathBuf {
+    ValidatorConfig::config_dir().join("history.jsonl")
+}
+
+fn append(event: HistoryEvent) -> Result<()> {
+    let record = HistoryRecord {
+        timestamp: Utc::now(),
+        event,
+    };
+
+    fs::create_dir_all(ValidatorConfig::config_dir())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history_path())
+        .context("Failed to open history file")?;
+    writeln!(file, "{}", serde_json::to_string(&record)?)?;
+    Ok(())
+}
+
+/// Record a metrics reading (None = validator unreachable), at most every 30s
+pub fn record_sample(metrics: Option<&ValidatorMetrics>) -> Result<()> {
+    {
+        let mut last = LAST_SAMPLE.lock().unwrap_or_else(|e| e.into_inner());
+        if last.is_some_and(|at| at.elapsed() < MIN_SAMPLE_INTERVAL) {
+            return Ok(());
+        }
+        *last = Some(Instant::now());
+    }
+
+    append(HistoryEvent::Sample {
+        metrics: metrics.map(MetricSample::from),
+    })?;
+
+    if let Some(epoch) = metrics.map(|metrics| metrics.epoch) {
+        let rolled_over = {
+            let mut last = LAST_EPOCH.lock().unwrap_or_else(|e| e.into_inner());
+            last.replace(epoch) != Some(epoch)
+        };
+        // The first sample of a run counts too, catching rollovers it missed
+        if rolled_over {
+            summarize_completed_epochs(epoch)?;
+        }
+    }
+    Ok(())
+}
+
+/// Record a setting pushed to the validator by `source` (e.g. "apply")
+pub fn record_optimization(parameter: &str, value: &str, source: &str) -> Result<()> {
+    append(HistoryEvent::Optimization {
+        parameter: parameter.to_string(),
+        value: value.to_string(),
+        source: source.to_string(),
+    })
+}
+
+/// Record a proposed setting declined in `source` (e.g. "optimize --interactive")
+pub fn record_skipped(parameter: &str, value: &str, source: &str) -> Result<()> {
+    append(HistoryEvent::Skipped {
+        parameter: parameter.to_string(),
+        value: value.to_string(),
+        source: source.to_string(),
+    })
+}
+
+/// Record metric reads moving from RPC `from` to `to`
+pub fn record_failover(from: &str, to: &str, reason: &str) -> Result<()> {
+    append(HistoryEvent::Failover {
+        from: from.to_string(),
+        to: to.to_string(),
+        reason: reason.to_string(),
+    })
+}
+
+/// Records at or after `since`, oldest first; unreadable lines are skipped
+pub fn load_since(since: DateTime<Utc>) -> Result<Vec<HistoryRecord>> {
+    let contents = match fs::read_to_string(history_path()) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).context("Failed to read history file"),
+    };
+
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<HistoryRecord>(line).ok())
+        .filter(|record| record.timestamp >= since)
+        .collect())
+}
+
+/// Average, min and max of one metric over a window
+#[derive(Debug, Clone, Copy)]
+pub struct Stat {
+    pub avg: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Stat {
+    fn of(values: impl Iterator<Item = f64>) -> Option<Self> {
+        let (count, sum, min, max) = values.fold(
+            (0usize, 0.0, f64::INFINITY, f64::NEG_INFINITY),
+            |(count, sum, min, max), v| (count + 1, sum + v, min.min(v), max.max(v)),
+        );
+        (count > 0).then(|| Stat { avg: sum / count as f64, min, max })
+    }
+}
+
+/// Aggregated view of the history over a window
+#[derive(Debug, Clone)]
+pub struct HistorySummary {
+    pub samples: usize,
+    /// Percent of samples where the validator answered and was voting
+    pub uptime_pct: f64,
+    pub optimizations: usize,
+    /// (metric name, stats) over the reachable samples, in display order
+    pub stats: Vec<(&'static str, Stat)>,
+}
+
+type MetricGetter = fn(&MetricSample) -> f64;
+
+pub fn summarize(records: &[HistoryRecord]) -> HistorySummary {
+    let samples: Vec<Option<&MetricSample>> = records
+        .iter()
+        .filter_map(|r| match &r.event {
+            HistoryEvent::Sample { metrics } => Some(metrics.as_ref()),
+            _ => None,
+        })
+        .collect();
+    let reachable: Vec<&MetricSample> = samples.iter().flatten().copied().collect();
+    let voting = reachable.iter().filter(|m| m.vote_success_rate > 0.0).count();
+
+    let metrics: [(&'static str, MetricGetter); 6] = [
+        ("Vote Success Rate (%)", |m| m.vote_success_rate),
+        ("Skip Rate (%)", |m| m.skip_rate),
+        ("Credits Earned", |m| m.credits_earned as f64),
+        ("Vote Lag (slots)", |m| m.vote_lag as f64),
+        ("Network Latency (ms)", |m| m.network_latency_ms as f64),
+        ("TPS", |m| m.avg_tps),
+    ];
+
+    HistorySummary {
+        samples: samples.len(),
+        uptime_pct: if samples.is_empty() { 0.0 } else { voting as f64 / samples.len() as f64 * 100.0 },
+        optimizations: records
+            .iter()
+            .filter(|r| matches!(r.event, HistoryEvent::Optimization { .. }))
+            .count(),
+        stats: metrics
+            .into_iter()
+            .filter_map(|(name, get)| Stat::of(reachable.iter().map(|m| get(m))).map(|stat| (name, stat)))
+            .collect(),
+    }
+}
+
+/// One row of `epochs.json`: how the validator did over a completed epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpochSummary {
+    pub epoch: u64,
+    /// Samples in the epoch where the validator answered
+    pub samples: usize,
+    pub avg_vote_success_rate: Option<f64>,
+    pub avg_skip_rate: Option<f64>,
+    /// Credits earned in the epoch; the last sampled value until `settled`
+    pub credits: Option<u64>,
+    /// The vote account's inflation reward (its commission cut)
+    pub rewards_lamports: Option<u64>,
+    /// Credits and rewards were read from the RPC after the epoch ended
+    #[serde(default)]
+    pub settled: bool,
+}
+
+pub fn epochs_path() -> PathBuf {
+    ValidatorConfig::config_dir().join("epochs.json")
+}
+
+/// The per-epoch summaries, keyed by epoch
+pub fn load_epoch_summaries() -> Result<BTreeMap<u64, EpochSummary>> {
+    match fs::read_to_string(epochs_path()) {
+        Ok(contents) => serde_json::from_str(&contents).context("Failed to parse the epoch summaries"),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(e).context("Failed to read the epoch summaries"),
+    }
+}
+
+pub fn save_epoch_summaries(summaries: &BTreeMap<u64, EpochSummary>) -> Result<()> {
+    fs::create_dir_all(ValidatorConfig::config_dir())?;
+    fs::write(epochs_path(), serde_json::to_string_pretty(summaries)?)
+        .context("Failed to write the epoch summaries")
+}
+
+/// A summary of `epoch` from its samples
+pub fn summarize_epoch(epoch: u64, samples: &[&MetricSample]) -> EpochSummary {
+    let average = |get: MetricGetter| Stat::of(samples.iter().map(|m| get(m))).map(|stat| stat.avg);
+    EpochSummary {
+        epoch,
+        samples: samples.len(),
+        avg_vote_success_rate: average(|m| m.vote_success_rate),
+        avg_skip_rate: average(|m| m.skip_rate),
+        credits: samples.iter().filter_map(|m| m.epoch_credits).max(),
+        rewards_lamports: None,
+        settled: false,
+    }
+}
+
+/// Store a summary for every sampled epoch before `current` that has none yet
+fn summarize_completed_epochs(current: u64) -> Result<()> {
+    let mut summaries = load_epoch_summaries()?;
+    let records = load_since(DateTime::<Utc>::MIN_UTC)?;
+    let mut by_epoch: BTreeMap<u64, Vec<&MetricSample>> = BTreeMap::new();
+    for record in &records {
+        if let HistoryEvent::Sample { metrics: Some(metrics) } = &record.event {
+            if let Some(epoch) = metrics.epoch.filter(|epoch| *epoch < current) {
+                by_epoch.entry(epoch).or_default().push(metrics);
+            }
+        }
+    }
+
+    let before = summaries.len();
+    for (epoch, samples) in by_epoch {
+        summaries.entry(epoch).or_insert_with(|| summarize_epoch(epoch, &samples));
+    }
+    if summaries.len() > before {
+        save_epoch_summaries(&summaries)?;
+    }
+    Ok(())
+}
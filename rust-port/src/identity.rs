@@ -0,0 +1,122 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::config::ValidatorConfig;
+use crate::native;
+use crate::tls;
+
+/// How long to wait for the validator to report the new identity
+const READ_BACK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// The tower file the validator keeps for `identity` under `tower_dir`,
+/// in the current or the pre-1.9 naming
+fn tower_file(tower_dir: &Path, identity: &Pubkey) -> Option<PathBuf> {
+    [format!("tower-1_9-{}.bin", identity), format!("tower-{}.bin", identity)]
+        .into_iter()
+        .map(|name| tower_dir.join(name))
+        .find(|path| path.exists())
+}
+
+/// Stake activated on vote accounts whose node is `identity`
+fn activated_stake(rpc_client: &RpcClient, identity: &Pubkey) -> Result<u64> {
+    let accounts = rpc_client.get_vote_accounts().context("Failed to get vote accounts")?;
+    let node = identity.to_string();
+    Ok(accounts
+        .current
+        .iter()
+        .chain(&accounts.delinquent)
+        .filter(|account| account.node_pubkey == node)
+        .map(|account| account.activated_stake)
+        .sum())
+}
+
+/// Poll the validator's identity until it reports `expected` or the timeout passes
+fn wait_for_identity(rpc_client: &RpcClient, expected: &Pubkey) -> Result<()> {
+    let started = Instant::now();
+    loop {
+        let reported = rpc_client.get_identity().context("Failed to read back the validator's identity")?;
+        if reported == *expected {
+            return Ok(());
+        }
+        if started.elapsed() >= READ_BACK_TIMEOUT {
+            anyhow::bail!("Validator still reports identity {} after {}s", reported, READ_BACK_TIMEOUT.as_secs());
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    }
+}
+
+/// `set-identity`: hot-swap the running validator to the identity in `keypair`
+///
+/// Goes through the validator's admin RPC, reads the identity back over RPC
+/// and records the keypair as `identity_keypair`, so status, monitoring and
+/// the next `start` follow the active identity.
+pub fn set_identity(keypair: &Path, require_tower: bool) -> Result<()> {
+    let mut config = ValidatorConfig::load()?;
+    let keypair = fs::canonicalize(keypair)
+        .with_context(|| format!("Keypair {} not found", keypair.display()))?;
+    let new_identity = native::keypair_pubkey(&keypair)?;
+
+    let rpc_client = tls::rpc_client(format!("http://127.0.0.1:{}", config.rpc_port), CommitmentConfig::confirmed());
+    let current = rpc_client
+        .get_identity()
+        .context("Failed to read the running validator's identity; is it running?")?;
+
+    println!("{}", "=== Set Identity ===".cyan().bold());
+    println!("├─ Current: {}", current);
+    println!("└─ New: {} ({})", new_identity.to_string().yellow(), keypair.display());
+
+    if current == new_identity {
+        println!("{} Validator already runs as {}", "✓".green(), new_identity);
+    } else {
+        // Voting as a staked identity without its tower can vote against
+        // earlier votes from another machine, which is double-signing
+        let stake = activated_stake(&rpc_client, &new_identity);
+        let tower = tower_file(&config.ledger_path, &new_identity);
+        if tower.is_none() && !require_tower && !matches!(stake, Ok(0)) {
+            let (headline, stake) = match &stake {
+                Ok(lamports) => ("A STAKED", format!("{:.2} SOL of activated stake", *lamports as f64 / LAMPORTS_PER_SOL as f64)),
+                Err(e) => ("A POSSIBLY STAKED", format!("unknown stake ({:#})", e)),
+            };
+            println!("\n{}", format!("⚠ WARNING: SWAPPING TO {} IDENTITY WITHOUT A TOWER FILE", headline).red().bold());
+            println!("{} {} has {}, but there is no tower file for it in {}.",
+                "⚠".red().bold(), new_identity, stake, config.ledger_path.display());
+            println!("{} Without the tower it was voting with elsewhere, this validator can cast", "⚠".red().bold());
+            println!("{} conflicting votes: that is double-signing. Copy the tower file over first,", "⚠".red().bold());
+            println!("{} and pass --require-tower so the validator refuses to switch without it.\n", "⚠".red().bold());
+        }
+
+        if native::no_cli() {
+            anyhow::bail!("set-identity goes through `solana-validator set-identity`, which --no-cli rules out");
+        }
+
+        println!("{} Switching identity over admin RPC...", "▶".cyan());
+        let mut command = Command::new("solana-validator");
+        command.arg("--ledger").arg(&config.ledger_path).arg("set-identity");
+        if require_tower {
+            command.arg("--require-tower");
+        }
+        let output = command.arg(&keypair).output().context("Failed to run solana-validator set-identity")?;
+        if !output.status.success() {
+            anyhow::bail!("solana-validator set-identity failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+
+        wait_for_identity(&rpc_client, &new_identity)?;
+        println!("{} Validator now runs as {}", "✓".green(), new_identity.to_string().yellow());
+    }
+
+    if config.identity_keypair != keypair {
+        config.identity_keypair = keypair;
+        config.save()?;
+        println!("{} identity_keypair set to {} in {}",
+            "✓".green(), config.identity_keypair.display(), ValidatorConfig::config_path().display());
+    }
+    Ok(())
+}
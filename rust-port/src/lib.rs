@@ -8,6 +8,48 @@ pub mod blockchain;
 pub mod process_manager;
 pub mod real_optimizer;
 pub mod smart_contract;
+pub mod log_rotation;
+pub mod disk;
+pub mod memory_budget;
+pub mod pubsub;
+pub mod alerts;
+pub mod audit;
+pub mod live_config;
+pub mod smoothing;
+pub mod fleet;
+pub mod snapshot;
+pub mod history;
+pub mod poll;
+pub mod playback;
+pub mod load_sim;
+pub mod rpc_consensus;
+pub mod progress;
+pub mod report;
+pub mod clock;
+pub mod contract_suite;
+pub mod cooldown;
+pub mod calibrate;
+pub mod optimizer_rules;
+pub mod logs;
+pub mod connect;
+pub mod native;
+pub mod compact_status;
+pub mod metrics_sink;
+pub mod flag_compat;
+pub mod epoch_report;
+pub mod epochs;
+pub mod explain_args;
+pub mod tls;
+pub mod config_reload;
+pub mod identity;
+pub mod replay;
+pub mod health;
+pub mod failover;
+pub mod faucet;
+pub mod metrics_snapshot;
+pub mod remote;
+pub mod sla;
+pub mod standalone;
 
 pub use config::*;
 pub use monitor::*;
@@ -0,0 +1,648 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::audit::{self, AuditEvent};
+use crate::config::{OptimizationConfig, ValidatorConfig};
+use crate::history::{self, HistoryEvent};
+use crate::process_manager::{admin_set, clear_pending_restarts, pending_restarts, queue_restart, wait_for_leader_gap};
+use crate::validator;
+
+/// Process names the validator binary runs under
+const VALIDATOR_PROCESS_NAMES: [&str; 2] = ["solana-validator", "agave-validator"];
+
+/// How a setting can be changed on a running validator
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Reload {
+    /// `solana-validator admin set <param>`
+    AdminRpc(&'static str),
+    /// Kernel setting under /proc/sys
+    Sysctl(&'static str),
+    /// Only read at startup
+    Restart,
+}
+
+/// One `OptimizationConfig` field, its desired value and what is live now
+struct LiveSetting {
+    field: &'static str,
+    desired: String,
+    live: Option<String>,
+    reload: Reload,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    Matching,
+    Applied,
+    PendingRestart(String),
+    /// A restart wouldn't change it either
+    Failed(String),
+}
+
+/// The running validator process
+struct RunningValidator {
+    args: Vec<String>,
+    cwd: Option<PathBuf>,
+    started: DateTime<Utc>,
+}
+
+/// The running validator, if one is running
+fn running_validator() -> Option<RunningValidator> {
+    use sysinfo::{ProcessRefreshKind, System, UpdateKind};
+
+    // The default process refresh leaves the command line and cwd empty
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessRefreshKind::new()
+            .with_exe(UpdateKind::OnlyIfNotSet)
+            .with_cmd(UpdateKind::OnlyIfNotSet)
+            .with_cwd(UpdateKind::OnlyIfNotSet),
+    );
+
+    // Linux cuts the process name to 15 characters ("solana-validato"),
+    // so the executable's file name is checked too
+    system
+        .processes()
+        .values()
+        .find(|p| {
+            VALIDATOR_PROCESS_NAMES.contains(&p.name())
+                || p.exe()
+                    .and_then(|exe| exe.file_name())
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| VALIDATOR_PROCESS_NAMES.contains(&name))
+        })
+        .map(|p| RunningValidator {
+            args: p.cmd().to_vec(),
+            cwd: p.cwd().map(Path::to_path_buf),
+            started: DateTime::from_timestamp(p.start_time() as i64, 0).unwrap_or_default(),
+        })
+}
+
+/// Command-line flags of the running validator, if one is running
+pub fn running_validator_args() -> Option<Vec<String>> {
+    running_validator().map(|validator| validator.args)
+}
+
+/// (`--flag`, value) pairs in command-line order, accepting both
+/// `--flag=value` and `--flag value`; switches get an empty value
+fn flag_pairs(args: &[String]) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    let mut iter = args.iter().peekable();
+
+    while let Some(arg) = iter.next() {
+        if !arg.starts_with("--") {
+            continue;
+        }
+
+        if let Some((flag, value)) = arg.split_once('=') {
+            pairs.push((flag.to_string(), value.to_string()));
+        } else if let Some(value) = iter.next_if(|next| !next.starts_with("--")) {
+            pairs.push((arg.clone(), value.clone()));
+        } else {
+            pairs.push((arg.clone(), String::new()));
+        }
+    }
+
+    pairs
+}
+
+/// Map of `--flag` to value, accepting both `--flag=value` and `--flag value`
+pub fn parse_flag_values(args: &[String]) -> HashMap<String, String> {
+    flag_pairs(args).into_iter().collect()
+}
+
+fn read_sysctl(key: &str) -> Option<String> {
+    fs::read_to_string(format!("/proc/sys/{}", key.replace('.', "/")))
+        .ok()
+        .map(|v| v.trim().to_string())
+}
+
+fn write_sysctl(key: &str, value: &str) -> Result<()> {
+    fs::write(format!("/proc/sys/{}", key.replace('.', "/")), value)?;
+    Ok(())
+}
+
+/// Every `OptimizationConfig` field, the validator flag it maps to and how it reloads
+const FIELDS: [(&str, Option<&str>, Reload); 9] = [
+    ("rpc_threads", Some("--rpc-threads"), Reload::AdminRpc("rpc_threads")),
+    ("accounts_db_threads", Some("--accounts-db-threads"), Reload::Restart),
+    ("tpu_coalesce_ms", Some("--tpu-coalesce-ms"), Reload::AdminRpc("tpu_coalesce_ms")),
+    ("incremental_snapshot_interval", Some("--incremental-snapshot-interval-slots"), Reload::AdminRpc("snapshot_interval")),
+    ("full_snapshot_interval", Some("--full-snapshot-interval-slots"), Reload::Restart),
+    ("limit_ledger_size", Some("--limit-ledger-size"), Reload::Restart),
+    ("accounts_db_cache_mb", Some("--accounts-db-cache-limit-mb"), Reload::Restart),
+    ("accounts_index_memory_mb", Some("--accounts-index-memory-limit-mb"), Reload::Restart),
+    ("udp_buffer_size", None, Reload::Sysctl("net.core.rmem_max")),
+];
+
+/// How `field` can be changed on a running validator (None for unknown fields)
+pub(crate) fn reload_for(field: &str) -> Option<Reload> {
+    FIELDS.iter().find(|(name, _, _)| *name == field).map(|(_, _, reload)| *reload)
+}
+
+/// `OptimizationConfig` values as (field, value) strings, in `FIELDS` order
+pub(crate) fn optimization_values(opt: &OptimizationConfig) -> Vec<(&'static str, String)> {
+    FIELDS
+        .iter()
+        .map(|(field, _, _)| {
+            let value = match *field {
+                "rpc_threads" => opt.rpc_threads.to_string(),
+                "accounts_db_threads" => opt.accounts_db_threads.to_string(),
+                "tpu_coalesce_ms" => opt.tpu_coalesce_ms.to_string(),
+                "incremental_snapshot_interval" => opt.incremental_snapshot_interval.to_string(),
+                "full_snapshot_interval" => opt.full_snapshot_interval.to_string(),
+                "limit_ledger_size" => opt.limit_ledger_size.to_string(),
+                "accounts_db_cache_mb" => opt.accounts_db_cache_mb.to_string(),
+                "accounts_index_memory_mb" => opt.accounts_index_memory_mb.to_string(),
+                _ => opt.udp_buffer_size.to_string(),
+            };
+            (*field, value)
+        })
+        .collect()
+}
+
+/// Settings of the validator running with `args` since `started`: the last
+/// hot reload, else the command line, else the kernel for sysctls
+fn live_settings(config: &ValidatorConfig, args: &[String], started: DateTime<Utc>) -> Vec<LiveSetting> {
+    let flags = parse_flag_values(args);
+    let reloaded = hot_reloaded_since(started);
+
+    optimization_values(&config.optimization)
+        .into_iter()
+        .zip(FIELDS.iter())
+        .map(|((field, desired), (_, flag, reload))| LiveSetting {
+            field,
+            desired,
+            live: match reload {
+                Reload::Sysctl(key) => read_sysctl(key),
+                // A hot reload leaves the command line as it was started
+                Reload::AdminRpc(_) => reloaded
+                    .get(field)
+                    .cloned()
+                    .or_else(|| flag.and_then(|flag| flags.get(flag).cloned())),
+                Reload::Restart => flag.and_then(|flag| flags.get(flag).cloned()),
+            },
+            reload: *reload,
+        })
+        .collect()
+}
+
+/// A config setting the running validator isn't using
+pub struct Drift {
+    /// Validator flag, or sysctl key for kernel settings
+    pub flag: &'static str,
+    pub config: String,
+    pub running: String,
+}
+
+/// Settings where the validator running with `args` since `started` differs
+/// from the config
+pub fn config_drift(config: &ValidatorConfig, args: &[String], started: DateTime<Utc>) -> Vec<Drift> {
+    live_settings(config, args, started)
+        .into_iter()
+        .zip(FIELDS.iter())
+        .filter(|(setting, _)| setting.live.as_deref() != Some(setting.desired.as_str()))
+        .map(|(setting, (field, flag, reload))| Drift {
+            flag: match reload {
+                Reload::Sysctl(key) => key,
+                _ => flag.unwrap_or(field),
+            },
+            config: setting.desired,
+            running: setting.live.unwrap_or_else(|| "default".to_string()),
+        })
+        .collect()
+}
+
+/// Print the drift list with how to reconcile it; prints nothing when in sync
+pub fn print_drift(drift: &[Drift]) {
+    if drift.is_empty() {
+        return;
+    }
+
+    println!("{} Config drift: {} setting(s) differ from the running validator",
+        "⚠".yellow(), drift.len());
+    for d in drift {
+        println!("├─ {}: running {}, config {}", d.flag, d.running, d.config.yellow());
+    }
+    println!("└─ Run {} to hot-reload what can be, or {} for startup-only flags",
+        "apply --live".cyan(), "apply --restart".cyan());
+}
+
+/// Startup check: warn if the config file was edited but the running
+/// validator still uses the old values
+pub fn warn_on_drift() {
+    let (Ok(config), Some(validator)) = (ValidatorConfig::load(), running_validator()) else {
+        return;
+    };
+    let drift = config_drift(&config, &validator.args, validator.started);
+    if !drift.is_empty() {
+        print_drift(&drift);
+        println!();
+    }
+}
+
+fn push(config: &ValidatorConfig, setting: &LiveSetting) -> Outcome {
+    match setting.reload {
+        Reload::AdminRpc(param) => match admin_set(&config.local_rpc_url(), param, &setting.desired) {
+            Ok(()) => Outcome::Applied,
+            Err(e) => Outcome::PendingRestart(format!("hot reload failed: {}", e)),
+        },
+        // Kernel settings aren't on the validator's command line
+        Reload::Sysctl(key) => match write_sysctl(key, &setting.desired) {
+            Ok(()) => Outcome::Applied,
+            Err(e) => Outcome::Failed(format!("writing {} failed: {}", key, e)),
+        },
+        Reload::Restart => Outcome::PendingRestart("startup-only flag".to_string()),
+    }
+}
+
+/// Values `apply --live` hot-reloaded into the validator started at `started`,
+/// latest per field
+fn hot_reloaded_since(started: DateTime<Utc>) -> HashMap<String, String> {
+    history::load_since(started)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            HistoryEvent::Optimization { parameter, value, source } if source == "apply --live" => Some((parameter, value)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// `apply [--live]`: reconcile the running validator with the config file
+///
+/// Only settings whose live value (the last hot reload, else the command
+/// line) differs from the config are touched, so running it repeatedly is
+/// safe; changes that need a restart are queued for `apply --restart`.
+/// Without `live` it just reports the diff.
+pub fn apply(live: bool) -> Result<()> {
+    let config = ValidatorConfig::load()?;
+
+    let Some(validator) = running_validator() else {
+        println!("{} No running validator found - config will be used on next start", "⚠".yellow());
+        return Ok(());
+    };
+    println!("{}", "Live Settings vs Config".cyan().bold());
+
+    let mut applied = 0;
+    let mut pending = 0;
+    let mut matching = 0;
+    let mut failed = 0;
+
+    for setting in live_settings(&config, &validator.args, validator.started) {
+        let live_value = setting.live.clone().unwrap_or_else(|| "default".to_string());
+        let outcome = if setting.live.as_deref() == Some(setting.desired.as_str()) {
+            Outcome::Matching
+        } else if live {
+            push(&config, &setting)
+        } else {
+            Outcome::PendingRestart("not applied (preview)".to_string())
+        };
+
+        match &outcome {
+            Outcome::Matching => {
+                matching += 1;
+                println!("├─ {} {}: {}", "✓".green(), setting.field, setting.desired);
+            }
+            Outcome::Applied => {
+                applied += 1;
+                println!("├─ {} {}: {} → {} (hot-reloaded)",
+                    "▶".cyan(), setting.field, live_value, setting.desired.green()
+                );
+                if let Err(e) = history::record_optimization(setting.field, &setting.desired, "apply --live") {
+                    println!("│  {} Could not record optimization: {}", "⚠".yellow(), e);
+                }
+                let verified = match setting.reload {
+                    Reload::Sysctl(key) => read_sysctl(key).as_deref() == Some(setting.desired.as_str()),
+                    _ => false,
+                };
+                audit::record_or_warn(&AuditEvent::new(
+                    setting.field, setting.live.clone(), &setting.desired, "config differs from the running validator", "apply --live",
+                ).verified(verified));
+            }
+            Outcome::PendingRestart(reason) => {
+                pending += 1;
+                println!("├─ {} {}: {} → {} ({})",
+                    "⚠".yellow(), setting.field, live_value, setting.desired.yellow(), reason
+                );
+                if live {
+                    let change = format!("{}: {} → {} ({})", setting.field, live_value, setting.desired, reason);
+                    if let Err(e) = queue_restart(&change) {
+                        println!("│  {} Could not queue the restart: {}", "⚠".yellow(), e);
+                    }
+                }
+            }
+            Outcome::Failed(reason) => {
+                failed += 1;
+                println!("├─ {} {}: {} → {} ({})",
+                    "✗".red(), setting.field, live_value, setting.desired.red(), reason
+                );
+            }
+        }
+    }
+
+    if live {
+        println!("└─ Applied: {} | Pending restart: {} | Failed: {} | Already matching: {}",
+            applied.to_string().green(),
+            pending.to_string().yellow(),
+            failed.to_string().red(),
+            matching
+        );
+        if pending > 0 {
+            println!("\nPending settings are queued for the next {}.", "apply --restart".cyan());
+        }
+        if failed > 0 {
+            println!("Failed kernel settings are not queued; they usually need root.");
+        }
+    } else {
+        println!("└─ Differing: {} | Already matching: {}", pending.to_string().yellow(), matching);
+        if pending > 0 {
+            println!("\nRun with {} to push the differing settings.", "--live".cyan());
+        }
+    }
+
+    let queued = pending_restarts();
+    if !queued.is_empty() {
+        println!("\n{} {} restart-requiring change(s) queued - run {} to apply",
+            "⚠".yellow(),
+            queued.len(),
+            "apply --restart".cyan()
+        );
+    }
+
+    Ok(())
+}
+
+/// `apply --restart`: restart the validator to pick up startup-only settings
+///
+/// Waits for a gap in the validator's leader slots first (per
+/// `graceful_restart`) and clears the restart queue.
+pub async fn restart() -> Result<()> {
+    let config = ValidatorConfig::load()?;
+
+    let queued = pending_restarts();
+    if !queued.is_empty() {
+        println!("\n{}", "Queued Restart Reasons".cyan().bold());
+        for (i, entry) in queued.iter().enumerate() {
+            let branch = if i + 1 == queued.len() { "└─" } else { "├─" };
+            println!("{} [{}] {}", branch, entry.queued_at.format("%Y-%m-%d %H:%M:%S"), entry.reason);
+        }
+    }
+
+    if running_validator_args().is_none() {
+        println!("{} No running validator found - nothing to restart", "⚠".yellow());
+        return clear_pending_restarts();
+    }
+
+    wait_for_leader_gap(&config).await;
+    validator::stop().await?;
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    validator::start(true, false, false, None).await?;
+
+    clear_pending_restarts()
+}
+
+/// `value` as an absolute path; relative ones are resolved against the
+/// validator's working directory when it is known
+fn absolute(value: &str, cwd: Option<&Path>) -> PathBuf {
+    let path = PathBuf::from(value);
+    match cwd {
+        Some(cwd) if path.is_relative() => cwd.join(path),
+        _ => path,
+    }
+}
+
+/// Set an `OptimizationConfig` field from its string form
+pub(crate) fn set_optimization_value(opt: &mut OptimizationConfig, field: &str, value: &str) -> Result<()> {
+    match field {
+        "rpc_threads" => opt.rpc_threads = value.parse()?,
+        "accounts_db_threads" => opt.accounts_db_threads = value.parse()?,
+        "tpu_coalesce_ms" => opt.tpu_coalesce_ms = value.parse()?,
+        "incremental_snapshot_interval" => opt.incremental_snapshot_interval = value.parse()?,
+        "full_snapshot_interval" => opt.full_snapshot_interval = value.parse()?,
+        "limit_ledger_size" => opt.limit_ledger_size = value.parse()?,
+        "accounts_db_cache_mb" => opt.accounts_db_cache_mb = value.parse()?,
+        "accounts_index_memory_mb" => opt.accounts_index_memory_mb = value.parse()?,
+        _ => anyhow::bail!("{} is not set from a flag", field),
+    }
+    Ok(())
+}
+
+/// Read one validator flag into `config`; (field, value) when the flag maps
+/// to a setting, None when it doesn't
+fn import_flag(config: &mut ValidatorConfig, flag: &str, value: &str, cwd: Option<&Path>) -> Result<Option<(String, String)>> {
+    let (field, path) = match flag {
+        "--identity" => ("identity_keypair", &mut config.identity_keypair),
+        "--vote-account" => {
+            let path = absolute(value, cwd);
+            if !path.exists() && Pubkey::from_str(value).is_ok() {
+                anyhow::bail!("given as a pubkey; set vote_account_keypair to its keypair file by hand");
+            }
+            ("vote_account_keypair", &mut config.vote_account_keypair)
+        }
+        "--ledger" => ("ledger_path", &mut config.ledger_path),
+        "--accounts" => ("accounts_path", &mut config.accounts_path),
+        "--snapshots" => ("snapshots_path", &mut config.snapshots_path),
+        "--log" if value == "-" => {
+            config.log_rotation.enabled = true;
+            return Ok(Some(("log_rotation.enabled".to_string(), "true".to_string())));
+        }
+        "--log" => ("log_path", &mut config.log_path),
+        "--rpc-port" => {
+            config.rpc_port = value.parse().context("not a valid number")?;
+            return Ok(Some(("rpc_port".to_string(), value.to_string())));
+        }
+        "--gossip-port" => {
+            config.gossip_port = value.parse().context("not a valid number")?;
+            return Ok(Some(("gossip_port".to_string(), value.to_string())));
+        }
+        "--rpc-bind-address" => {
+            config.rpc_bind_address = value.parse().context("not a valid IP address")?;
+            return Ok(Some(("rpc_bind_address".to_string(), value.to_string())));
+        }
+        "--dynamic-port-range" => {
+            config.dynamic_port_range = value.parse().map_err(anyhow::Error::msg)?;
+            return Ok(Some(("dynamic_port_range".to_string(), value.to_string())));
+        }
+        "--expected-genesis-hash" => {
+            config.expected_genesis_hash = Some(value.to_string());
+            return Ok(Some(("expected_genesis_hash".to_string(), value.to_string())));
+        }
+        "--expected-shred-version" => {
+            config.expected_shred_version = Some(value.parse().context("not a valid number")?);
+            return Ok(Some(("expected_shred_version".to_string(), value.to_string())));
+        }
+        _ => {
+            let Some((field, _, _)) = FIELDS.iter().find(|(_, f, _)| *f == Some(flag)) else {
+                return Ok(None);
+            };
+            set_optimization_value(&mut config.optimization, field, value).context("not a valid number")?;
+            return Ok(Some((format!("optimization.{}", field), value.to_string())));
+        }
+    };
+
+    *path = absolute(value, cwd);
+    Ok(Some((field.to_string(), path.display().to_string())))
+}
+
+/// `config import --from-running`: rebuild the config file from the running
+/// validator's command line
+///
+/// Flags with a config setting are read into a default config; flags `start`
+/// passes anyway are accepted as is, and everything else is reported so it
+/// can be carried over by hand. An existing config file is kept as `.bak`.
+pub fn import_from_running() -> Result<()> {
+    let Some(RunningValidator { args, cwd, .. }) = running_validator() else {
+        anyhow::bail!("No running validator found to import from");
+    };
+    let cwd = cwd.as_deref();
+    let pairs = flag_pairs(&args);
+
+    let mut config = ValidatorConfig::default();
+    let mut imported = Vec::new();
+    let mut unmapped = Vec::new();
+    let mut rest = Vec::new();
+    for (flag, value) in &pairs {
+        let shown = if value.is_empty() { flag.clone() } else { format!("{}={}", flag, value) };
+        match import_flag(&mut config, flag, value, cwd) {
+            Ok(Some(setting)) => imported.push(setting),
+            Ok(None) => rest.push((flag, value, shown)),
+            Err(e) => unmapped.push((shown, format!("{:#}", e))),
+        }
+    }
+
+    // Flags `start` passes with the same value come back on the next start.
+    // The port flags were all imported above, so a conflicting layout (which
+    // `start` refuses) is compared with the default one in its place
+    let port_conflict = config.check_port_layout().err();
+    let mut reference = config.clone();
+    if port_conflict.is_some() {
+        let defaults = ValidatorConfig::default();
+        reference.rpc_port = defaults.rpc_port;
+        reference.gossip_port = defaults.gossip_port;
+        reference.dynamic_port_range = defaults.dynamic_port_range;
+    }
+    let start_pairs = flag_pairs(&validator::optimized_validator_args(&reference)?);
+    let mut managed = 0;
+    for (flag, value, shown) in rest {
+        let passed: Vec<&str> = start_pairs
+            .iter()
+            .filter(|(f, _)| f == flag)
+            .map(|(_, v)| v.as_str())
+            .collect();
+        if passed.contains(&value.as_str()) {
+            managed += 1;
+        } else if passed.is_empty() {
+            unmapped.push((shown, "no config setting; `start` won't pass it".to_string()));
+        } else {
+            unmapped.push((shown, format!("`start` always passes {}", passed.join(", "))));
+        }
+    }
+    // Short flags, unless they are the value of the --flag before them
+    for pair in args.windows(2) {
+        let (previous, arg) = (&pair[0], &pair[1]);
+        let is_value = previous.starts_with("--") && !previous.contains('=');
+        if arg.len() > 1 && arg.starts_with('-') && !arg.starts_with("--") && !is_value {
+            unmapped.push((arg.clone(), "short flags aren't read; spell it out as a --flag".to_string()));
+        }
+    }
+
+    println!("{}", "=== Config Import ===".cyan().bold());
+    println!("├─ Validator: {} ({} flags)", args.first().map_or("unknown", String::as_str), pairs.len());
+    match cwd {
+        Some(cwd) => println!("└─ Working directory: {}", cwd.display()),
+        None => println!("└─ Working directory: {} (relative paths kept as given)", "unknown".yellow()),
+    }
+
+    println!("\n{} Imported {} setting(s):", "✓".green(), imported.len());
+    for (i, (field, value)) in imported.iter().enumerate() {
+        let branch = if i + 1 == imported.len() { "└─" } else { "├─" };
+        println!("{} {}: {}", branch, field, value.green());
+    }
+    if managed > 0 {
+        println!("{} {} more flag(s) match what `start` passes", "ℹ".cyan(), managed);
+    }
+
+    for (field, path) in [("identity_keypair", &config.identity_keypair), ("vote_account_keypair", &config.vote_account_keypair)] {
+        if !path.exists() {
+            println!("{} {} {} does not exist", "⚠".yellow(), field, path.display());
+        }
+    }
+
+    if let Some(e) = &port_conflict {
+        println!("{} {:#}; `start` refuses this until the config is changed", "⚠".yellow(), e);
+    }
+
+    if !unmapped.is_empty() {
+        println!("\n{} {} flag(s) could not be mapped:", "⚠".yellow(), unmapped.len());
+        for (i, (arg, reason)) in unmapped.iter().enumerate() {
+            let branch = if i + 1 == unmapped.len() { "└─" } else { "├─" };
+            println!("{} {}: {}", branch, arg.yellow(), reason);
+        }
+    }
+
+    let path = ValidatorConfig::config_path();
+    if path.exists() {
+        let backup = path.with_extension("json.bak");
+        fs::copy(&path, &backup)?;
+        println!("\n{} Previous config kept as {}", "ℹ".cyan(), backup.display());
+    }
+    config.save()?;
+    println!("\n{} Wrote {}", "✓".green(), path.display().to_string().yellow());
+    println!("  Settings that aren't validator flags (alerts, metrics sinks, ...) are at their defaults");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn flags_pair_with_values_in_both_forms() {
+        let pairs = flag_pairs(&args(&[
+            "solana-validator", "--ledger=ledger", "--rpc-port", "8899", "--full-rpc-api", "--log", "-", "--no-voting",
+        ]));
+        let expected = [("--ledger", "ledger"), ("--rpc-port", "8899"), ("--full-rpc-api", ""), ("--log", "-"), ("--no-voting", "")];
+        assert_eq!(pairs, expected.map(|(flag, value)| (flag.to_string(), value.to_string())));
+    }
+
+    #[test]
+    fn imported_paths_resolve_against_the_validator_cwd() {
+        let mut config = ValidatorConfig::default();
+        let cwd = Path::new("/home/sol");
+
+        let imported = import_flag(&mut config, "--ledger", "ledger", Some(cwd)).unwrap();
+        assert_eq!(imported, Some(("ledger_path".to_string(), "/home/sol/ledger".to_string())));
+        assert_eq!(config.ledger_path, PathBuf::from("/home/sol/ledger"));
+
+        import_flag(&mut config, "--accounts", "/mnt/accounts", Some(cwd)).unwrap();
+        assert_eq!(config.accounts_path, PathBuf::from("/mnt/accounts"));
+
+        import_flag(&mut config, "--snapshots", "snapshots", None).unwrap();
+        assert_eq!(config.snapshots_path, PathBuf::from("snapshots"));
+    }
+
+    #[test]
+    fn imported_flags_map_to_settings() {
+        let mut config = ValidatorConfig::default();
+
+        let imported = import_flag(&mut config, "--rpc-threads", "24", None).unwrap();
+        assert_eq!(imported, Some(("optimization.rpc_threads".to_string(), "24".to_string())));
+        assert_eq!(config.optimization.rpc_threads, 24);
+
+        import_flag(&mut config, "--log", "-", None).unwrap();
+        assert!(config.log_rotation.enabled);
+
+        assert!(import_flag(&mut config, "--rpc-port", "eighty", None).is_err());
+        assert_eq!(import_flag(&mut config, "--no-voting", "", None).unwrap(), None);
+    }
+}
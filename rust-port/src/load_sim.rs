@@ -0,0 +1,231 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Deserialize;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::smart_contract::SmartContractOptimizer;
+
+/// CU limit requested for every simulation so large inputs aren't cut off at the 200k default
+const SIMULATION_CU_LIMIT: u32 = 1_400_000;
+
+/// A segment is non-linear when its CU per input unit exceeds the first
+/// segment's by this factor
+const NONLINEAR_SLOPE_RATIO: f64 = 1.5;
+
+/// Marginal cost floor (CU per unit) so a flat first segment doesn't flag every later one
+const MIN_REFERENCE_SLOPE: f64 = 1.0;
+
+/// Width of the longest bar in the CU chart
+const CHART_WIDTH: usize = 40;
+
+/// Instruction data to simulate, loaded from `--ix-data-file`
+///
+/// ```json
+/// {
+///   "accounts": [{ "pubkey": "...", "writable": true }],
+///   "cases": [{ "data": "0a00", "param": 10 }, { "data": "0a0000000000" }]
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoadSpec {
+    /// Accounts passed to every instruction, in order
+    #[serde(default)]
+    pub accounts: Vec<LoadAccount>,
+    pub cases: Vec<LoadCase>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoadAccount {
+    pub pubkey: String,
+    #[serde(default)]
+    pub writable: bool,
+    #[serde(default)]
+    pub signer: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoadCase {
+    /// Hex-encoded instruction data
+    pub data: String,
+    /// Value plotted on the x axis; defaults to the data length in bytes
+    pub param: Option<f64>,
+}
+
+impl LoadSpec {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read instruction data file {}", path.display()))?;
+        let spec: LoadSpec = serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid instruction data file {}", path.display()))?;
+
+        if spec.cases.is_empty() {
+            anyhow::bail!("Instruction data file {} lists no cases", path.display());
+        }
+        Ok(spec)
+    }
+
+    fn account_metas(&self) -> Result<Vec<AccountMeta>> {
+        self.accounts
+            .iter()
+            .map(|account| {
+                let pubkey = Pubkey::from_str(&account.pubkey)
+                    .map_err(|e| anyhow::anyhow!("Invalid account pubkey '{}': {}", account.pubkey, e))?;
+                Ok(if account.writable {
+                    AccountMeta::new(pubkey, account.signer)
+                } else {
+                    AccountMeta::new_readonly(pubkey, account.signer)
+                })
+            })
+            .collect()
+    }
+}
+
+/// CU consumed by one case, averaged over its runs
+#[derive(Debug, Clone)]
+pub struct LoadPoint {
+    pub param: f64,
+    pub data_len: usize,
+    pub avg_cu: f64,
+    pub min_cu: u64,
+    pub max_cu: u64,
+    /// Error from the last failed run, if any run failed
+    pub error: Option<String>,
+}
+
+/// Indices `i` of points whose segment from `i - 1` grows faster than the first segment
+///
+/// Needs at least three points with distinct params, sorted by param.
+pub fn nonlinear_segments(points: &[LoadPoint]) -> Vec<usize> {
+    let slope = |i: usize| {
+        let (a, b) = (&points[i - 1], &points[i]);
+        (b.avg_cu - a.avg_cu) / (b.param - a.param)
+    };
+    if points.len() < 3 || points.windows(2).any(|w| w[1].param <= w[0].param) {
+        return Vec::new();
+    }
+
+    let reference = slope(1).max(MIN_REFERENCE_SLOPE);
+    (2..points.len())
+        .filter(|&i| slope(i) > reference * NONLINEAR_SLOPE_RATIO)
+        .collect()
+}
+
+/// Simulate each case `runs` times with `payer` as fee payer and chart CU by input
+///
+/// Read-only: transactions are only simulated, never signed or sent.
+pub fn simulate_load(
+    optimizer: &SmartContractOptimizer,
+    program_id: &Pubkey,
+    spec: &LoadSpec,
+    payer: &Pubkey,
+    runs: u32,
+) -> Result<Vec<LoadPoint>> {
+    println!("{}", "🧪 SIMULATION ONLY - transactions are simulated, never signed or sent".yellow().bold());
+    println!("  Program: {} | Cases: {} | Runs per case: {} | Fee payer: {}\n",
+        program_id, spec.cases.len(), runs, payer);
+
+    let accounts = spec.account_metas()?;
+    let mut points = Vec::with_capacity(spec.cases.len());
+
+    for (index, case) in spec.cases.iter().enumerate() {
+        let data = hex::decode(case.data.trim().trim_start_matches("0x"))
+            .with_context(|| format!("Case {} data is not valid hex", index + 1))?;
+        let instructions = [
+            ComputeBudgetInstruction::set_compute_unit_limit(SIMULATION_CU_LIMIT),
+            Instruction::new_with_bytes(*program_id, &data, accounts.clone()),
+        ];
+
+        let mut consumed = Vec::new();
+        let mut error = None;
+        for _ in 0..runs.max(1) {
+            match optimizer.simulate_instructions(&instructions, payer) {
+                Ok(result) => {
+                    if let Some(err) = result.err {
+                        error = Some(err.to_string());
+                    }
+                    // Failed simulations still report the CU burned up to the failure
+                    consumed.extend(result.units_consumed);
+                }
+                Err(e) => error = Some(format!("{:#}", e)),
+            }
+        }
+
+        if consumed.is_empty() {
+            println!("  {} Case {}: no CU reported ({})",
+                "✗".red(), index + 1, error.as_deref().unwrap_or("unknown error"));
+            continue;
+        }
+
+        points.push(LoadPoint {
+            param: case.param.unwrap_or(data.len() as f64),
+            data_len: data.len(),
+            avg_cu: consumed.iter().sum::<u64>() as f64 / consumed.len() as f64,
+            min_cu: *consumed.iter().min().unwrap_or(&0),
+            max_cu: *consumed.iter().max().unwrap_or(&0),
+            error,
+        });
+    }
+
+    points.sort_by(|a, b| a.param.total_cmp(&b.param));
+    Ok(points)
+}
+
+/// Bar chart of CU vs param, marking segments that grow faster than linear
+pub fn display_load_chart(points: &[LoadPoint]) {
+    println!("\n{}", "📈 CU Consumed vs Input (simulated)".cyan().bold());
+    println!();
+
+    if points.is_empty() {
+        println!("  {}", "No case produced a CU reading".yellow());
+        return;
+    }
+
+    let nonlinear = nonlinear_segments(points);
+    let peak = points.iter().map(|p| p.avg_cu).fold(0.0, f64::max).max(1.0);
+
+    for (i, point) in points.iter().enumerate() {
+        let width = ((point.avg_cu / peak) * CHART_WIDTH as f64).round() as usize;
+        let bar = "█".repeat(width.max(1));
+        let bar = if nonlinear.contains(&i) { bar.red() } else { bar.green() };
+
+        let spread = if point.min_cu != point.max_cu {
+            format!(" ({}-{})", point.min_cu, point.max_cu)
+        } else {
+            String::new()
+        };
+        println!("  {:>10} │{} {:.0} CU{} [{} bytes]",
+            format!("{}", point.param),
+            bar,
+            point.avg_cu,
+            spread.dimmed(),
+            point.data_len
+        );
+        if let Some(error) = &point.error {
+            println!("  {:>10} │ {} {}", "", "⚠ simulation failed:".yellow(), error.dimmed());
+        }
+    }
+
+    println!();
+    if points.len() < 3 || points.windows(2).any(|w| w[1].param <= w[0].param) {
+        println!("  {}", "Add at least 3 cases with distinct inputs to check growth".dimmed());
+    } else if nonlinear.is_empty() {
+        println!("  {} CU grows linearly (or better) with input", "✓".green());
+    } else {
+        println!("  {} Non-linear CU growth detected:", "⚠".yellow().bold());
+        for &i in &nonlinear {
+            let (a, b) = (&points[i - 1], &points[i]);
+            println!("    • {} → {}: {:.1} CU per unit (first segment: {:.1})",
+                a.param,
+                b.param,
+                (b.avg_cu - a.avg_cu) / (b.param - a.param),
+                (points[1].avg_cu - points[0].avg_cu) / (points[1].param - points[0].param)
+            );
+        }
+        println!("  Check loops over input data, repeated deserialization and per-item CPIs before mainnet.");
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use parking_lot::Mutex;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use crate::config::LogRotationConfig;
+use crate::logs;
+
+/// Size-based rotating log file
+///
+/// The active file is always `path`. When it grows past `max_bytes` it is
+/// renamed to `path.1` (gzipped to `path.1.gz` when compression is on),
+/// older generations shift up by one and anything past `max_files` is removed.
+pub struct RotatingLog {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: u32,
+    compress: bool,
+    file: File,
+    written: u64,
+}
+
+impl RotatingLog {
+    pub fn open(path: &Path, config: &LogRotationConfig) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open log file {}", path.display()))?;
+        let written = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            max_bytes: config.max_size_mb.max(1) * 1024 * 1024,
+            max_files: config.max_files,
+            compress: config.compress,
+            file,
+            written,
+        })
+    }
+
+    /// Append one line, rotating first if it would exceed the size limit
+    pub fn write_line(&mut self, line: &str) -> Result<()> {
+        if self.written > 0 && self.written + line.len() as u64 + 1 > self.max_bytes {
+            self.rotate()?;
+        }
+
+        self.file.write_all(line.as_bytes())?;
+        self.file.write_all(b"\n")?;
+        self.written += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    fn generation_path(&self, n: u32) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", n));
+        if self.compress {
+            name.push(".gz");
+        }
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> Result<()> {
+        self.file.flush()?;
+
+        if self.max_files == 0 {
+            // No retained generations - just start the file over
+            self.file = File::create(&self.path)?;
+            self.written = 0;
+            return Ok(());
+        }
+
+        // Drop the oldest generation, then shift the rest up by one
+        let _ = fs::remove_file(self.generation_path(self.max_files));
+        for n in (1..self.max_files).rev() {
+            let from = self.generation_path(n);
+            if from.exists() {
+                fs::rename(&from, self.generation_path(n + 1))?;
+            }
+        }
+
+        if self.compress {
+            let mut input = File::open(&self.path)?;
+            let output = File::create(self.generation_path(1))?;
+            let mut encoder = GzEncoder::new(output, Compression::default());
+            io::copy(&mut input, &mut encoder)?;
+            encoder.finish()?;
+            fs::remove_file(&self.path)?;
+        } else {
+            fs::rename(&self.path, self.generation_path(1))?;
+        }
+
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+/// Drain a child pipe line-by-line into a shared rotating log, echoing
+/// lines that match a critical pattern (OOM, open files, ...) to stderr
+pub fn spawn_drain<R: Read + Send + 'static>(
+    reader: R,
+    log: Arc<Mutex<RotatingLog>>,
+) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        let reader = BufReader::new(reader);
+        for line in reader.lines() {
+            let Ok(line) = line else { break };
+            if let Some(pattern) = logs::scan_line(&line).critical {
+                eprintln!("{} {}", format!("[{}]", pattern.name).red().bold(), line);
+            }
+            if let Err(e) = log.lock().write_line(&line) {
+                eprintln!("Failed to write validator log: {}", e);
+            }
+        }
+    })
+}
@@ -0,0 +1,183 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::ValidatorConfig;
+
+/// How often `logs --follow` checks the log for new lines
+const FOLLOW_POLL: Duration = Duration::from_millis(500);
+
+/// Chunk size when reading the log backwards for the initial backlog
+const TAIL_CHUNK: u64 = 64 * 1024;
+
+/// Level of a validator log line (`[<time> WARN  solana_core::...] ...`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// A known failure signature worth calling out in the log
+pub struct CriticalPattern {
+    pub name: &'static str,
+    /// Lowercase substrings, any of which marks the line
+    pub needles: &'static [&'static str],
+    pub hint: &'static str,
+}
+
+pub const CRITICAL_PATTERNS: &[CriticalPattern] = &[
+    CriticalPattern {
+        name: "OOM",
+        needles: &["out of memory", "memory allocation of", "cannot allocate memory", "oom-kill"],
+        hint: "the host ran out of memory; check accounts index and cache sizes",
+    },
+    CriticalPattern {
+        name: "open files",
+        needles: &["too many open files", "os error 24"],
+        hint: "raise the open files limit (ulimit -n / LimitNOFILE) to at least 1000000",
+    },
+    CriticalPattern {
+        name: "fork",
+        needles: &["duplicate slot", "duplicate block", "switching fork", "switch fork", "partition detected", "dumping slot"],
+        hint: "the validator is on or resolving a minority fork",
+    },
+    CriticalPattern {
+        name: "panic",
+        needles: &["panicked at"],
+        hint: "the validator crashed; the lines before this show why",
+    },
+];
+
+/// What scanning one log line found
+pub struct LineScan {
+    pub severity: Option<Severity>,
+    pub critical: Option<&'static CriticalPattern>,
+}
+
+/// Severity and critical pattern of a validator log line
+pub fn scan_line(line: &str) -> LineScan {
+    let severity = line
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_whitespace().nth(1))
+        .and_then(|level| match level {
+            "ERROR" => Some(Severity::Error),
+            "WARN" => Some(Severity::Warn),
+            "INFO" => Some(Severity::Info),
+            "DEBUG" => Some(Severity::Debug),
+            "TRACE" => Some(Severity::Trace),
+            _ => None,
+        });
+
+    let lower = line.to_lowercase();
+    let critical = CRITICAL_PATTERNS
+        .iter()
+        .find(|pattern| pattern.needles.iter().any(|needle| lower.contains(needle)));
+
+    LineScan { severity, critical }
+}
+
+/// Print a log line colored by severity, with critical patterns highlighted
+pub fn print_line(line: &str) {
+    let scan = scan_line(line);
+    if let Some(pattern) = scan.critical {
+        println!("{} {}", format!("[{}]", pattern.name).on_red().white().bold(), line.red().bold());
+        println!("    {} {}", "↳".red(), pattern.hint.dimmed());
+        return;
+    }
+
+    match scan.severity {
+        Some(Severity::Error) => println!("{}", line.red()),
+        Some(Severity::Warn) => println!("{}", line.yellow()),
+        Some(Severity::Debug) | Some(Severity::Trace) => println!("{}", line.dimmed()),
+        _ => println!("{}", line),
+    }
+}
+
+/// The last `count` lines of `file`, read backwards so large logs stay cheap
+fn tail_lines(file: &mut File, count: usize) -> Result<Vec<String>> {
+    let len = file.metadata()?.len();
+    let mut start = len;
+    let mut data = Vec::new();
+
+    // One extra newline: the file normally ends with one
+    while start > 0 && data.iter().filter(|&&b| b == b'\n').count() <= count {
+        let chunk = TAIL_CHUNK.min(start);
+        start -= chunk;
+        let mut buf = vec![0; chunk as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut buf)?;
+        buf.extend_from_slice(&data);
+        data = buf;
+    }
+
+    let text = String::from_utf8_lossy(&data);
+    let lines: Vec<String> = text.lines().map(str::to_string).collect();
+    Ok(lines[lines.len().saturating_sub(count)..].to_vec())
+}
+
+fn open_log(path: &Path) -> Result<File> {
+    File::open(path).with_context(|| format!("Failed to open validator log {}", path.display()))
+}
+
+/// `logs`: print the end of the validator log, optionally following it
+///
+/// Follows across rotation: when the log is renamed away or truncated, the
+/// new file is read from the start.
+pub async fn show_logs(follow: bool, grep: Option<&str>, lines: usize) -> Result<()> {
+    let config = ValidatorConfig::load()?;
+    let path = &config.log_path;
+    let matches = |line: &str| grep.is_none_or(|pattern| line.contains(pattern));
+
+    let mut file = open_log(path)?;
+    for line in tail_lines(&mut file, lines)?.iter().filter(|line| matches(line)) {
+        print_line(line);
+    }
+
+    if !follow {
+        return Ok(());
+    }
+
+    println!("{}", format!("--- following {} (Ctrl+C to stop) ---", path.display()).dimmed());
+    let mut inode = file.metadata()?.ino();
+    let mut reader = BufReader::new(file);
+    reader.seek(SeekFrom::End(0))?;
+    let mut partial = String::new();
+
+    loop {
+        let mut chunk = String::new();
+        if reader.read_line(&mut chunk)? > 0 {
+            partial.push_str(&chunk);
+            // A line still being written has no newline yet; wait for the rest
+            if partial.ends_with('\n') {
+                let line = partial.trim_end_matches(['\n', '\r']);
+                if matches(line) {
+                    print_line(line);
+                }
+                partial.clear();
+            }
+            continue;
+        }
+
+        tokio::time::sleep(FOLLOW_POLL).await;
+
+        let position = reader.stream_position()?;
+        match fs::metadata(path) {
+            Ok(metadata) if metadata.ino() != inode || metadata.len() < position => {
+                // Rotated or truncated; the old file has been fully read
+                let Ok(file) = open_log(path) else { continue };
+                inode = metadata.ino();
+                reader = BufReader::new(file);
+                partial.clear();
+                println!("{}", "--- log rotated ---".dimmed());
+            }
+            _ => {}
+        }
+    }
+}
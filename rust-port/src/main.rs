@@ -8,9 +8,50 @@ mod blockchain;
 mod process_manager;
 mod real_optimizer;
 mod smart_contract;
+mod log_rotation;
+mod disk;
+mod memory_budget;
+mod pubsub;
+mod alerts;
+mod audit;
+mod live_config;
+mod smoothing;
+mod fleet;
+mod snapshot;
+mod history;
+mod poll;
+mod playback;
+mod load_sim;
+mod rpc_consensus;
+mod progress;
+mod report;
+mod clock;
+mod contract_suite;
+mod cooldown;
+mod calibrate;
+mod optimizer_rules;
+mod logs;
+mod connect;
+mod native;
+mod compact_status;
+mod metrics_sink;
+mod flag_compat;
+mod epoch_report;
+mod epochs;
+mod explain_args;
+mod tls;
+mod config_reload;
+mod identity;
+mod replay;
+mod health;
+mod failover;
+mod faucet;
+mod metrics_snapshot;
+mod remote;
+mod sla;
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
 
 #[derive(Parser)]
@@ -19,6 +60,18 @@ use colored::Colorize;
 #[command(version = "1.0")]
 #[command(about = "Solana Validator Optimizer - Maximizing Vote Success Rate", long_about = None)]
 struct Cli {
+    /// Plain status lines instead of progress bars (implied when stdout is not a TTY)
+    #[arg(long, short, global = true, alias = "non-interactive")]
+    quiet: bool,
+
+    /// Never run external binaries (solana, solana-keygen, pgrep, ...); use RPC and the process table
+    #[arg(long, global = true)]
+    no_cli: bool,
+
+    /// Accept any TLS certificate on RPC connections (prefer `rpc_ca_bundle` in the config)
+    #[arg(long, global = true)]
+    insecure_skip_tls: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -30,6 +83,20 @@ enum Commands {
         /// Skip airdrop request
         #[arg(long)]
         no_airdrop: bool,
+        /// Run the preflight steps and print what would happen, without
+        /// starting the validator, tuning the system or sending transactions
+        #[arg(long)]
+        dry_run: bool,
+        /// Start even if this identity looks to be running elsewhere (refused on mainnet)
+        #[arg(long)]
+        force: bool,
+        /// Run a local solana-test-validator instead of joining testnet (as `cluster: "localnet"` does)
+        #[arg(long)]
+        localnet: bool,
+        /// Cluster to join for this run, overriding `cluster` in the config
+        /// (testnet, mainnet, devnet or localnet)
+        #[arg(long, value_parser = config::Cluster::from_name, conflicts_with = "localnet")]
+        cluster: Option<config::Cluster>,
     },
     /// Stop the running validator
     Stop,
@@ -38,106 +105,813 @@ enum Commands {
         /// Use dashboard view
         #[arg(long)]
         dashboard: bool,
+        /// PubSub WebSocket URL for live slot updates (derived from the RPC URL by default)
+        #[arg(long)]
+        ws_url: Option<String>,
+        /// Forget the previous run's metrics used for trend arrows
+        #[arg(long)]
+        reset_trend: bool,
+        /// Comma-separated metrics to show, overriding `display_metrics` in the config
+        #[arg(long, value_delimiter = ',')]
+        fields: Vec<config::MetricField>,
+        /// Play a captured history.jsonl or CSV through the dashboard instead of reading RPC
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["ws_url", "reset_trend"])]
+        replay: Option<std::path::PathBuf>,
+        /// Replay at this multiple of the recorded cadence
+        #[arg(long, default_value_t = 1.0, requires = "replay")]
+        speed: f64,
     },
     /// Apply optimizations to running validator
     Optimize {
         /// Auto-tune continuously
         #[arg(long)]
         auto: bool,
+        /// Apply settings to every validator in this fleet file instead
+        #[arg(long, conflicts_with = "auto")]
+        fleet: Option<std::path::PathBuf>,
+        /// Ask before each change of the one-time run: apply, skip or quit
+        #[arg(long, conflicts_with_all = ["auto", "fleet"])]
+        interactive: bool,
+        /// Optimization to apply to the fleet as FIELD=VALUE (defaults to the local config)
+        #[arg(long = "set", value_name = "FIELD=VALUE", requires = "fleet")]
+        set: Vec<String>,
+        /// Maximum validators updated at once
+        #[arg(long, default_value_t = 4, requires = "fleet")]
+        max_parallel: usize,
+        /// Comma-separated steps to run: analysis, network, threads, vote-timing, snapshots
+        #[arg(long, value_delimiter = ',', conflicts_with_all = ["auto", "fleet"])]
+        only: Vec<optimizer::OptimizeStep>,
+        /// Stop --auto after this many passes
+        #[arg(long, requires = "auto")]
+        max_iterations: Option<u32>,
+        /// Stop --auto after this long, e.g. 30m, 2h
+        #[arg(long, requires = "auto")]
+        max_duration: Option<String>,
+        /// Optimize the validator on this host over ssh (user@host) instead of this one
+        #[arg(long, value_name = "USER@HOST", conflicts_with_all = ["auto", "fleet", "interactive"])]
+        ssh: Option<String>,
+        /// Optimizer config on the --ssh host, relative to its login directory
+        #[arg(long, default_value = remote::DEFAULT_REMOTE_CONFIG, requires = "ssh")]
+        remote_config: String,
+        /// RPC to read the --ssh host's metrics from (defaults to its rpc_bind_address and rpc_port; required when that is loopback)
+        #[arg(long, requires = "ssh")]
+        rpc_url: Option<String>,
+    },
+    /// Reconcile the running validator's settings with the config file
+    Apply {
+        /// Push differing settings to the running validator (otherwise only preview)
+        #[arg(long)]
+        live: bool,
+        /// Then restart the validator (outside its leader window) for startup-only settings
+        #[arg(long)]
+        restart: bool,
     },
     /// Generate performance report
-    Report,
+    Report {
+        /// Directory for the report (defaults to the configured output_dir)
+        #[arg(long)]
+        output_dir: Option<std::path::PathBuf>,
+        /// Also summarize recorded history over this window (e.g. 24h, 7d, 2w)
+        #[arg(long)]
+        since: Option<String>,
+        /// Keep running and write a timestamped report for each epoch as it ends
+        #[arg(long, conflicts_with = "since")]
+        watch: bool,
+    },
+    /// Compare two JSON reports (performance-report.json) metric by metric
+    ReportDiff {
+        /// Report from before the change
+        old: std::path::PathBuf,
+        /// Report from after the change
+        new: std::path::PathBuf,
+    },
+    /// Measure how far the tool's estimates are from ground truth and save corrections
+    Calibrate {
+        /// RPC URL (defaults to the configured cluster's)
+        #[arg(long)]
+        rpc_url: Option<String>,
+        /// Also check the per-account data I/O estimate against this program's transactions
+        #[arg(long)]
+        program_id: Option<String>,
+        /// getSlot round trips timed for the latency check
+        #[arg(long, default_value_t = 10)]
+        latency_samples: usize,
+        /// Report only; don't save the correction factors
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// Show validator status
-    Status,
+    Status {
+        /// One line for shell prompts, e.g. `✓ 96.2% | skip 2.1% | lag 28 | behind 0`
+        #[arg(long)]
+        compact: bool,
+        /// Color the compact line
+        #[arg(long, requires = "compact")]
+        color: bool,
+    },
     /// Analyze smart contract performance
     AnalyzeContract {
         /// Program ID to analyze
-        program_id: String,
-        /// RPC URL (defaults to testnet)
-        #[arg(long, default_value = "https://api.testnet.solana.com")]
-        rpc_url: String,
+        #[arg(required_unless_present_any = ["programs", "deploy_tx"])]
+        program_id: Option<String>,
+        /// File of program IDs (one per line) to analyze together into one report
+        #[arg(long, conflicts_with = "program_id")]
+        programs: Option<std::path::PathBuf>,
+        /// Analyze the program deployed (or upgraded) by this transaction signature
+        #[arg(long, conflicts_with_all = ["program_id", "programs"])]
+        deploy_tx: Option<String>,
+        /// Maximum programs analyzed at once with --programs
+        #[arg(long, default_value_t = 4, requires = "programs")]
+        max_parallel: usize,
+        /// RPC URL (defaults to the configured cluster's)
+        #[arg(long)]
+        rpc_url: Option<String>,
+        /// Only analyze transactions at or after this slot
+        #[arg(long)]
+        from_slot: Option<u64>,
+        /// Only analyze transactions at or before this slot
+        #[arg(long)]
+        to_slot: Option<u64>,
+        /// Which fetched transactions to analyze in depth
+        #[arg(long, value_enum, default_value_t = smart_contract::SampleStrategy::Recent)]
+        sample_strategy: smart_contract::SampleStrategy,
+        /// Show the metric threshold that triggered each recommendation
+        #[arg(long)]
+        explain: bool,
+        /// Show a documentation link with each recommendation
+        #[arg(long)]
+        docs: bool,
+        /// Rank the most write-locked accounts and show how concentrated the locks are
+        #[arg(long, conflicts_with_all = ["programs", "watch"])]
+        contention: bool,
+        /// Also write this report to the output directory
+        #[arg(long, value_enum)]
+        report: Option<ContractReport>,
+        /// Re-analyze on the poll interval and print only significant changes
+        #[arg(long, conflicts_with_all = ["programs", "report", "from_slot", "to_slot"])]
+        watch: bool,
+        /// Smallest change (%) in a metric that --watch reports
+        #[arg(long, default_value_t = 10.0, requires = "watch")]
+        threshold_pct: f64,
     },
     /// Optimize smart contract
     OptimizeContract {
         /// Program ID to optimize
         program_id: String,
-        /// RPC URL (defaults to testnet)
-        #[arg(long, default_value = "https://api.testnet.solana.com")]
-        rpc_url: String,
+        /// RPC URL (defaults to the configured cluster's)
+        #[arg(long)]
+        rpc_url: Option<String>,
     },
     /// Monitor smart contract in real-time
     MonitorContract {
         /// Program ID to monitor
         program_id: String,
-        /// RPC URL (defaults to testnet)
-        #[arg(long, default_value = "https://api.testnet.solana.com")]
+        /// RPC URL (defaults to the configured cluster's)
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+    /// Chart a program's CU against input size by simulating transactions (never sent)
+    SimulateLoad {
+        /// Program ID to simulate against
+        program_id: String,
+        /// JSON file with the accounts and hex instruction data for each case
+        #[arg(long)]
+        ix_data_file: std::path::PathBuf,
+        /// Simulations per case
+        #[arg(long, default_value_t = 3)]
+        runs: u32,
+        /// Fee payer for the simulations (defaults to the configured identity)
+        #[arg(long)]
+        fee_payer: Option<String>,
+        /// RPC URL (defaults to the configured cluster's)
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+    /// Watch the vote account over WebSocket and alert on missed votes
+    WatchVotes {
+        /// Vote account to watch (defaults to the configured vote keypair)
+        vote_account: Option<String>,
+        /// RPC URL (defaults to local validator)
+        #[arg(long, default_value = "http://127.0.0.1:8899")]
         rpc_url: String,
+        /// PubSub WebSocket URL (derived from the RPC URL by default)
+        #[arg(long)]
+        ws_url: Option<String>,
+    },
+    /// Estimate annualized staking return (gross and net of commission)
+    Apr {
+        /// Vote account (defaults to the configured vote keypair)
+        vote_account: Option<String>,
+        /// Last completed epoch to include (defaults to the previous epoch)
+        #[arg(long)]
+        epoch: Option<u64>,
+        /// RPC URL (defaults to the configured cluster's)
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+    /// Before/after table of delegator net APR and commission income at a new commission
+    CommissionImpact {
+        /// Vote account (defaults to the configured vote keypair)
+        vote_account: Option<String>,
+        /// Commission (%) to model
+        #[arg(long)]
+        new_pct: u8,
+        /// Gross staking APR (%) to assume instead of the one from recent rewards
+        #[arg(long)]
+        apr: Option<f64>,
+        /// RPC URL (defaults to the configured cluster's)
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+    /// Minimum activated stake at which commission covers the validator's operating costs
+    Breakeven {
+        /// Vote account (defaults to the configured vote keypair)
+        vote_account: Option<String>,
+        /// Commission to assume instead of the vote account's
+        #[arg(long)]
+        commission: Option<u8>,
+        /// Gross staking APR (%) to assume instead of breakeven.apr_pct or the cluster's
+        #[arg(long)]
+        apr: Option<f64>,
+        /// RPC URL (defaults to the configured cluster's)
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+    /// Show the vote account's authorized voter(s), withdrawer, node and commission
+    VoteAuthorityStatus {
+        /// Vote account (defaults to the configured vote keypair)
+        vote_account: Option<String>,
+        /// RPC URL (defaults to the configured cluster's)
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+    /// Rotate the vote account's authorized voter (takes effect next epoch)
+    SetAuthorizedVoter {
+        /// New authorized voter pubkey
+        new_voter: String,
+        /// RPC URL (defaults to the configured cluster's)
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+    /// Hot-swap the running validator to another identity (failover)
+    SetIdentity {
+        /// Keypair file of the identity to switch to
+        keypair: std::path::PathBuf,
+        /// Have the validator refuse unless the identity's tower file is present
+        #[arg(long)]
+        require_tower: bool,
+    },
+    /// Show the validator log (log_path), colored by severity
+    Logs {
+        /// Keep printing new lines as they are written
+        #[arg(short, long)]
+        follow: bool,
+        /// Only show lines containing this text
+        #[arg(long)]
+        grep: Option<String>,
+        /// Number of existing lines to show first
+        #[arg(short = 'n', long, default_value_t = 20)]
+        lines: usize,
+    },
+    /// Measure replay speed during catch-up (local vs cluster slot) and estimate when it's done
+    ReplaySpeed {
+        /// Seconds between slot readings
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+        /// Stop after this many seconds even if not caught up
+        #[arg(long)]
+        max_secs: Option<u64>,
+    },
+    /// Show how much disk the ledger, accounts and snapshots use
+    DiskUsage {
+        /// Number of largest subdirectories to list
+        #[arg(long, default_value_t = 5)]
+        top: usize,
+    },
+    /// Check the newest snapshot archives are intact before relying on them
+    VerifySnapshot {
+        /// Check every archive in the snapshots dir, not just the newest
+        #[arg(long)]
+        all: bool,
+    },
+    /// List and prune snapshot archives in the snapshots dir
+    Snapshots {
+        #[command(subcommand)]
+        command: SnapshotCommands,
+    },
+    /// Show a stake account's activation (warmup/cooldown) progress
+    StakeStatus {
+        /// Stake account address
+        stake_account: String,
+        /// RPC URL (defaults to the configured cluster's)
+        #[arg(long)]
+        rpc_url: Option<String>,
+        /// Keep refreshing every N seconds
+        #[arg(long)]
+        refresh: Option<u64>,
+    },
+    /// Vote reliability SLA over the last 1h, 24h and 30d of recorded samples
+    Sla {
+        /// Keep sampling the validator into the history every sla.sample_interval_secs
+        #[arg(long)]
+        track: bool,
+    },
+    /// Every change the tool made to the validator, its config or the host
+    AuditLog {
+        /// Only changes in this window (e.g. 24h, 7d) or after this RFC 3339 time
+        #[arg(long)]
+        since: Option<String>,
+        /// Only changes to this setting (e.g. tpu_coalesce_ms)
+        #[arg(long)]
+        param: Option<String>,
+    },
+    /// Vote success, skip rate, credits and rewards of each completed epoch
+    Epochs {
+        /// Only the most recent N epochs
+        #[arg(long, default_value_t = 10)]
+        last: usize,
+        /// RPC URL the final credits and rewards are read from (defaults to the configured cluster's)
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+    /// Capture validator, host and cluster readings for later
+    Metrics {
+        #[command(subcommand)]
+        command: MetricsCommands,
+    },
+    /// Inspect and tune the optimizer configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum SnapshotCommands {
+    /// Show each archive's slot, size and age
+    List,
+    /// Delete all but the newest full snapshots and their incrementals
+    Prune {
+        /// Number of full snapshots to keep
+        #[arg(long, default_value_t = 2)]
+        keep: usize,
+        /// Actually delete; without it only lists what would be deleted
+        #[arg(long)]
+        confirm: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum MetricsCommands {
+    /// Write one reading to a JSON file, e.g. as evidence for an incident report
+    Snapshot {
+        /// File to write (defaults to metrics-snapshot-<time>.json in the output dir)
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+        /// Identity pubkey to report on instead of the configured keypair's
+        #[arg(long)]
+        identity: Option<String>,
+        /// Vote account pubkey to report on instead of the configured keypair's
+        #[arg(long)]
+        vote_account: Option<String>,
+    },
+    /// Write one reading as a Prometheus text file (metrics.prom in the output dir)
+    Expose {
+        /// File to write (replaced whole on each run)
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+        /// OpenMetrics instead, with the slot and epoch as exemplars and a trailing `# EOF`
+        #[arg(long)]
+        openmetrics: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Suggest incremental_snapshot_interval from the disk I/O snapshots cause on this host
+    SuggestSnapshotInterval {
+        /// How long to watch the running validator
+        #[arg(long, default_value_t = 600)]
+        observe_secs: u64,
+        /// Seconds between samples
+        #[arg(long, default_value_t = 5)]
+        sample_secs: u64,
+        /// Write the suggested value into the config
+        #[arg(long)]
+        write: bool,
+    },
+    /// Check the custom optimizer rules file (defaults to `optimizer_rules` in the config)
+    ValidateRules {
+        path: Option<std::path::PathBuf>,
+    },
+    /// Dry-parse the validator command line built from the config (nothing is started)
+    ValidateArgs,
+    /// Print each flag `start` would pass with why it's there and the setting behind it
+    ExplainArgs,
+    /// Rebuild the config file from the running validator's command line
+    Import {
+        /// Read the flags of the running validator (the only source so far)
+        #[arg(long, required = true)]
+        from_running: bool,
+    },
+    /// Suggest --limit-ledger-size from the ledger filesystem's free space
+    SuggestLedgerSize {
+        /// Estimated bytes on disk per shred
+        #[arg(long, default_value_t = disk::DEFAULT_SHRED_SIZE_BYTES)]
+        shred_size: u64,
+        /// Percent of the filesystem to keep free
+        #[arg(long, default_value_t = 20.0)]
+        headroom_pct: f64,
+        /// Write the suggested value into the config
+        #[arg(long)]
+        write: bool,
     },
 }
 
+/// Extra reports `analyze-contract` can write
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ContractReport {
+    /// Folded CU stacks (`program;instruction;... cu`) for flamegraph tools
+    CuProfile,
+    /// Recommendations (with their documentation links) as JSON
+    Recommendations,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    progress::set_non_interactive(cli.quiet);
+    native::set_no_cli(cli.no_cli);
+    tls::set_insecure_skip_tls(cli.insecure_skip_tls);
+    // A broken rpc_ca_bundle fails every TLS connection; `config` still works to fix it
+    if !matches!(cli.command, Commands::Config { .. }) {
+        tls::check_ca_bundle()?;
+    }
+
+    // Commands that act on the running validator warn if it ignores the config
+    if matches!(cli.command, Commands::Monitor { replay: None, .. } | Commands::Optimize { fleet: None, ssh: None, .. }) {
+        live_config::warn_on_drift();
+    }
 
     match cli.command {
-        Commands::Start { no_airdrop } => {
+        Commands::Start { no_airdrop, dry_run: true, force, localnet, cluster } => {
+            validator::start_dry_run(no_airdrop, force, localnet, cluster).await?;
+        }
+        Commands::Start { no_airdrop, dry_run: false, force, localnet, cluster } => {
             println!("{}", "Starting Solana Validator with Optimizations...".green().bold());
-            validator::start(no_airdrop).await?;
+            validator::start(no_airdrop, force, localnet, cluster).await?;
         }
         Commands::Stop => {
             println!("{}", "Stopping Solana Validator...".yellow());
             validator::stop().await?;
         }
-        Commands::Monitor { dashboard } => {
-            if dashboard {
+        Commands::Monitor { dashboard, ws_url, reset_trend, fields, replay, speed } => {
+            let fields = if fields.is_empty() {
+                config::ValidatorConfig::load()?.display_metrics
+            } else {
+                Some(fields)
+            };
+            let all = || config::MetricField::ALL.to_vec();
+
+            if let Some(path) = replay {
+                playback::replay(&path, speed, &fields.unwrap_or_else(all))?;
+            } else if dashboard {
                 println!("{}", "Launching Performance Dashboard...".blue().bold());
-                monitor::dashboard().await?;
+                monitor::dashboard(ws_url.as_deref(), &fields.unwrap_or_else(all)).await?;
             } else {
-                monitor::display_metrics().await?;
+                let fields = fields.unwrap_or_else(|| config::MetricField::MONITOR.to_vec());
+                monitor::display_metrics(reset_trend, &fields).await?;
             }
         }
-        Commands::Optimize { auto } => {
+        Commands::Optimize { auto, fleet, interactive, set, max_parallel, only, max_iterations, max_duration, ssh, remote_config, rpc_url } => {
+            let max_duration = max_duration
+                .map(|label| utils::parse_duration(&label)?.to_std().map_err(|_| anyhow::anyhow!("Duration must be positive")))
+                .transpose()?;
             println!("{}", "Running Optimizer...".cyan().bold());
-            optimizer::run(auto).await?;
+            match (fleet, ssh) {
+                (Some(fleet_path), _) => fleet::optimize_fleet(&fleet_path, &set, max_parallel).await?,
+                (None, Some(target)) => optimizer::optimize_remote(&target, &remote_config, rpc_url.as_deref(), &only).await?,
+                (None, None) => optimizer::run(auto, interactive, &only, max_iterations, max_duration).await?,
+            }
+        }
+        Commands::Apply { live, restart } => {
+            live_config::apply(live)?;
+            if restart {
+                live_config::restart().await?;
+            }
+        }
+        Commands::Report { output_dir, watch: true, .. } => {
+            epoch_report::watch_epoch_reports(output_dir.as_deref()).await?;
         }
-        Commands::Report => {
+        Commands::Report { output_dir, since, .. } => {
             println!("{}", "Generating Performance Report...".magenta());
-            monitor::generate_report().await?;
+            monitor::generate_report(output_dir.as_deref(), since.as_deref()).await?;
         }
-        Commands::Status => {
+        Commands::ReportDiff { old, new } => {
+            report::diff_reports(&old, &new)?;
+        }
+        Commands::Calibrate { rpc_url, program_id, latency_samples, dry_run } => {
+            let rpc_url = resolve_rpc_url(rpc_url)?;
+            let program_id = program_id
+                .map(|id| id.parse::<solana_sdk::pubkey::Pubkey>())
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid program ID: {}", e))?;
+            calibrate::calibrate(&rpc_url, program_id.as_ref(), latency_samples, dry_run)?;
+        }
+        Commands::Status { compact: true, color } => {
+            compact_status::show_compact_status(color)?;
+        }
+        Commands::Status { .. } => {
             validator::show_status().await?;
         }
-        Commands::AnalyzeContract { program_id, rpc_url } => {
+        Commands::AnalyzeContract { program_id, programs, deploy_tx, max_parallel, rpc_url, from_slot, to_slot, sample_strategy, explain, docs, contention, report, watch, threshold_pct } => {
+            let rpc_url = resolve_rpc_url(rpc_url)?;
             println!("{}", "Analyzing Smart Contract...".cyan().bold());
-            analyze_smart_contract(&program_id, &rpc_url).await?;
+            let program_id = match deploy_tx {
+                Some(signature) => Some(
+                    smart_contract::SmartContractOptimizer::new(&rpc_url, None)?
+                        .resolve_deploy_tx(&signature)?
+                        .to_string(),
+                ),
+                None => program_id,
+            };
+            match (program_id, programs) {
+                (Some(program_id), _) if watch => {
+                    watch_smart_contract(&program_id, &rpc_url, sample_strategy, threshold_pct).await?;
+                }
+                (Some(program_id), _) => {
+                    analyze_smart_contract(&program_id, &rpc_url, from_slot, to_slot, sample_strategy, explain, docs, contention, report).await?;
+                }
+                (None, Some(programs)) => {
+                    analyze_contract_suite(&programs, &rpc_url, from_slot, to_slot, sample_strategy, max_parallel, explain, docs, report).await?;
+                }
+                (None, None) => unreachable!("clap requires a program ID, --programs or --deploy-tx"),
+            }
         }
         Commands::OptimizeContract { program_id, rpc_url } => {
+            let rpc_url = resolve_rpc_url(rpc_url)?;
             println!("{}", "Optimizing Smart Contract...".green().bold());
             optimize_smart_contract(&program_id, &rpc_url).await?;
         }
         Commands::MonitorContract { program_id, rpc_url } => {
+            let rpc_url = resolve_rpc_url(rpc_url)?;
             println!("{}", "Monitoring Smart Contract...".blue().bold());
             monitor_smart_contract(&program_id, &rpc_url).await?;
         }
+        Commands::SimulateLoad { program_id, ix_data_file, runs, fee_payer, rpc_url } => {
+            let rpc_url = resolve_rpc_url(rpc_url)?;
+            println!("{}", "Simulating Smart Contract Load...".cyan().bold());
+            simulate_contract_load(&program_id, &ix_data_file, runs, fee_payer, &rpc_url)?;
+        }
+        Commands::WatchVotes { vote_account, rpc_url, ws_url } => {
+            let config = config::ValidatorConfig::load()?;
+            let vote_account = resolve_vote_account(vote_account, &config)?;
+
+            println!("{}", "Watching Vote Account...".blue().bold());
+            pubsub::watch_vote_account(&rpc_url, ws_url.as_deref(), &vote_account, &config.alerts).await?;
+        }
+        Commands::Apr { vote_account, epoch, rpc_url } => {
+            let rpc_url = resolve_rpc_url(rpc_url)?;
+            let config = config::ValidatorConfig::load()?;
+            let vote_account = resolve_vote_account(vote_account, &config)?;
+            blockchain::apr(&rpc_url, &vote_account, epoch).await?;
+        }
+        Commands::CommissionImpact { vote_account, new_pct, apr, rpc_url } => {
+            let rpc_url = resolve_rpc_url(rpc_url)?;
+            let config = config::ValidatorConfig::load()?;
+            let vote_account = resolve_vote_account(vote_account, &config)?;
+            blockchain::commission_impact(&rpc_url, &vote_account, new_pct, apr, config.breakeven.apr_pct).await?;
+        }
+        Commands::Breakeven { vote_account, commission, apr, rpc_url } => {
+            let rpc_url = resolve_rpc_url(rpc_url)?;
+            let config = config::ValidatorConfig::load()?;
+            let vote_account = resolve_vote_account(vote_account, &config)?;
+            blockchain::breakeven(&rpc_url, &vote_account, &config.breakeven, commission, apr).await?;
+        }
+        Commands::VoteAuthorityStatus { vote_account, rpc_url } => {
+            let rpc_url = resolve_rpc_url(rpc_url)?;
+            use solana_sdk::signature::{read_keypair_file, Signer};
+
+            let config = config::ValidatorConfig::load()?;
+            let vote_account = resolve_vote_account(vote_account, &config)?;
+            let identity = read_keypair_file(&config.identity_keypair)
+                .ok()
+                .map(|keypair| keypair.pubkey());
+
+            blockchain::vote_authority_status(&rpc_url, &vote_account, identity.as_ref()).await?;
+        }
+        Commands::SetAuthorizedVoter { new_voter, rpc_url } => {
+            let rpc_url = resolve_rpc_url(rpc_url)?;
+            use solana_sdk::pubkey::Pubkey;
+            use std::str::FromStr;
+
+            let new_voter = Pubkey::from_str(&new_voter)
+                .map_err(|e| anyhow::anyhow!("Invalid voter pubkey: {}", e))?;
+
+            let config = config::ValidatorConfig::load()?;
+            let (identity, vote_keypair) = connect::load_keypairs(&config, connect::KeypairUse::Signing)?;
+
+            println!("{}", "Changing Authorized Voter...".cyan().bold());
+            let interface = blockchain::SolanaInterface::new(&rpc_url, identity, vote_keypair)?;
+            interface.set_authorized_voter(new_voter).await?;
+        }
+        Commands::SetIdentity { keypair, require_tower } => {
+            identity::set_identity(&keypair, require_tower)?;
+        }
+        Commands::Logs { follow, grep, lines } => {
+            logs::show_logs(follow, grep.as_deref(), lines).await?;
+        }
+        Commands::ReplaySpeed { interval, max_secs } => {
+            replay::watch_replay(interval, max_secs).await?;
+        }
+        Commands::DiskUsage { top } => {
+            disk::disk_usage(top)?;
+        }
+        Commands::VerifySnapshot { all } => {
+            snapshot::verify_snapshots(all)?;
+        }
+        Commands::Snapshots { command } => match command {
+            SnapshotCommands::List => snapshot::list_snapshots()?,
+            SnapshotCommands::Prune { keep, confirm } => snapshot::prune_snapshots(keep, confirm)?,
+        },
+        Commands::StakeStatus { stake_account, rpc_url, refresh } => {
+            let rpc_url = resolve_rpc_url(rpc_url)?;
+            use solana_sdk::pubkey::Pubkey;
+            use std::str::FromStr;
+
+            let stake_account = Pubkey::from_str(&stake_account)
+                .map_err(|e| anyhow::anyhow!("Invalid stake account: {}", e))?;
+            blockchain::stake_status(&rpc_url, &stake_account, refresh).await?;
+        }
+        Commands::Sla { track: true } => {
+            sla::track().await?;
+        }
+        Commands::Sla { track: false } => {
+            sla::show()?;
+        }
+        Commands::AuditLog { since, param } => {
+            audit::show(since.as_deref(), param.as_deref())?;
+        }
+        Commands::Epochs { last, rpc_url } => {
+            let rpc_url = resolve_rpc_url(rpc_url)?;
+            let config = config::ValidatorConfig::load()?;
+            let vote_account = resolve_vote_account(None, &config).ok();
+            epochs::show(last, &rpc_url, vote_account.as_ref())?;
+        }
+        Commands::Metrics { command: MetricsCommands::Snapshot { out, identity, vote_account } } => {
+            metrics_snapshot::write_snapshot(out.as_deref(), identity.as_deref(), vote_account.as_deref()).await?;
+        }
+        Commands::Metrics { command: MetricsCommands::Expose { out, openmetrics } } => {
+            monitor::write_exposition(out.as_deref(), openmetrics).await?;
+        }
+        Commands::Config { command } => match command {
+            ConfigCommands::SuggestSnapshotInterval { observe_secs, sample_secs, write } => {
+                snapshot::suggest_snapshot_interval(observe_secs, sample_secs, write).await?;
+            }
+            ConfigCommands::ValidateRules { path } => {
+                let path = path
+                    .or(config::ValidatorConfig::load()?.optimizer_rules)
+                    .ok_or_else(|| anyhow::anyhow!("No rules file given and optimizer_rules is not set in the config"))?;
+                optimizer_rules::validate_rules_file(&path)?;
+            }
+            ConfigCommands::ValidateArgs => {
+                validator::validate_args()?;
+            }
+            ConfigCommands::ExplainArgs => {
+                explain_args::explain_args()?;
+            }
+            ConfigCommands::Import { from_running: _ } => {
+                live_config::import_from_running()?;
+            }
+            ConfigCommands::SuggestLedgerSize { shred_size, headroom_pct, write } => {
+                disk::suggest_ledger_size(shred_size, headroom_pct, write)?;
+            }
+        },
     }
 
     Ok(())
 }
 
-async fn analyze_smart_contract(program_id_str: &str, rpc_url: &str) -> Result<()> {
+/// `--rpc-url`, or the configured cluster's RPC
+fn resolve_rpc_url(rpc_url: Option<String>) -> Result<String> {
+    match rpc_url {
+        Some(url) => Ok(url),
+        None => Ok(config::ValidatorConfig::load()?.cluster_rpc_url()),
+    }
+}
+
+/// Vote account from the command line, or the configured vote keypair's pubkey
+fn resolve_vote_account(
+    vote_account: Option<String>,
+    config: &config::ValidatorConfig,
+) -> Result<solana_sdk::pubkey::Pubkey> {
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::{read_keypair_file, Signer};
+    use std::str::FromStr;
+
+    match vote_account {
+        Some(address) => Pubkey::from_str(&address)
+            .map_err(|e| anyhow::anyhow!("Invalid vote account: {}", e)),
+        None => read_keypair_file(&config.vote_account_keypair)
+            .map(|keypair| keypair.pubkey())
+            .map_err(|e| anyhow::anyhow!("Failed to read vote keypair: {}", e)),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn analyze_smart_contract(
+    program_id_str: &str,
+    rpc_url: &str,
+    from_slot: Option<u64>,
+    to_slot: Option<u64>,
+    sample_strategy: smart_contract::SampleStrategy,
+    explain: bool,
+    docs: bool,
+    contention: bool,
+    report: Option<ContractReport>,
+) -> Result<()> {
     use solana_sdk::pubkey::Pubkey;
     use std::str::FromStr;
 
     let program_id = Pubkey::from_str(program_id_str)
         .map_err(|e| anyhow::anyhow!("Invalid program ID: {}", e))?;
 
-    let optimizer = smart_contract::SmartContractOptimizer::new(rpc_url, Some(program_id))?;
+    let optimizer = smart_contract::SmartContractOptimizer::new(rpc_url, Some(program_id))?
+        .with_sample_strategy(sample_strategy);
 
-    let metrics = optimizer.analyze_program(&program_id).await?;
+    let metrics = optimizer.analyze_program_in_range(&program_id, from_slot, to_slot).await?;
     optimizer.display_metrics(&metrics);
+    if contention {
+        optimizer.display_contention(&metrics);
+    }
 
     let recommendations = optimizer.get_recommendations(&metrics);
-    optimizer.display_recommendations(&recommendations);
+    optimizer.display_recommendations(&recommendations, explain, docs);
+
+    match report {
+        Some(ContractReport::CuProfile) => {
+            let output_dir = config::ValidatorConfig::load()?.output_dir(None)?;
+            let path = output_dir.join(format!("cu-profile-{}.folded", program_id));
+            optimizer.write_cu_profile(&metrics, &path)?;
+
+            println!("\n{} CU profile ({} stacks) written to {}",
+                "✓".green(),
+                metrics.cu_by_stack.len(),
+                path.display().to_string().yellow()
+            );
+            println!("  Render with: {}", format!("inferno-flamegraph {} > cu-profile.svg", path.display()).cyan());
+        }
+        Some(ContractReport::Recommendations) => {
+            let output_dir = config::ValidatorConfig::load()?.output_dir(None)?;
+            let path = output_dir.join(format!("recommendations-{}.json", program_id));
+            optimizer.write_recommendations_json(&recommendations, &path)?;
+
+            println!("\n{} {} recommendations written to {}",
+                "✓".green(),
+                recommendations.len(),
+                path.display().to_string().yellow()
+            );
+        }
+        None => {}
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn analyze_contract_suite(
+    programs: &std::path::Path,
+    rpc_url: &str,
+    from_slot: Option<u64>,
+    to_slot: Option<u64>,
+    sample_strategy: smart_contract::SampleStrategy,
+    max_parallel: usize,
+    explain: bool,
+    docs: bool,
+    report: Option<ContractReport>,
+) -> Result<()> {
+    let program_ids = contract_suite::load_program_ids(programs)?;
+    let suite = contract_suite::analyze_suite(&program_ids, rpc_url, from_slot, to_slot, sample_strategy, max_parallel).await;
+    contract_suite::display_suite(&suite, explain, docs);
+
+    match report {
+        Some(ContractReport::Recommendations) => {
+            let output_dir = config::ValidatorConfig::load()?.output_dir(None)?;
+            let path = output_dir.join("recommendations-suite.json");
+            std::fs::write(&path, serde_json::to_string_pretty(&suite)?)?;
+
+            println!("\n{} {} recommendations across {} program(s) written to {}",
+                "✓".green(),
+                suite.recommendations.len(),
+                program_ids.len(),
+                path.display().to_string().yellow()
+            );
+        }
+        Some(ContractReport::CuProfile) => {
+            anyhow::bail!("--report cu-profile is per program; run analyze-contract <PROGRAM_ID> for each");
+        }
+        None => {}
+    }
 
     Ok(())
 }
@@ -157,7 +931,7 @@ async fn optimize_smart_contract(program_id_str: &str, rpc_url: &str) -> Result<
 
     // Show recommendations
     let recommendations = optimizer.get_recommendations(&metrics);
-    optimizer.display_recommendations(&recommendations);
+    optimizer.display_recommendations(&recommendations, false, false);
 
     // Apply optimizations
     optimizer.apply_optimizations(&program_id).await?;
@@ -168,6 +942,26 @@ async fn optimize_smart_contract(program_id_str: &str, rpc_url: &str) -> Result<
     Ok(())
 }
 
+async fn watch_smart_contract(
+    program_id_str: &str,
+    rpc_url: &str,
+    sample_strategy: smart_contract::SampleStrategy,
+    threshold_pct: f64,
+) -> Result<()> {
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    let program_id = Pubkey::from_str(program_id_str)
+        .map_err(|e| anyhow::anyhow!("Invalid program ID: {}", e))?;
+
+    let config = config::ValidatorConfig::load()?;
+    let optimizer = smart_contract::SmartContractOptimizer::new(rpc_url, Some(program_id))?
+        .with_sample_strategy(sample_strategy);
+    optimizer.watch_program(&program_id, &config.poll_interval, threshold_pct).await?;
+
+    Ok(())
+}
+
 async fn monitor_smart_contract(program_id_str: &str, rpc_url: &str) -> Result<()> {
     use solana_sdk::pubkey::Pubkey;
     use std::str::FromStr;
@@ -175,8 +969,39 @@ async fn monitor_smart_contract(program_id_str: &str, rpc_url: &str) -> Result<(
     let program_id = Pubkey::from_str(program_id_str)
         .map_err(|e| anyhow::anyhow!("Invalid program ID: {}", e))?;
 
+    let config = config::ValidatorConfig::load()?;
     let optimizer = smart_contract::SmartContractOptimizer::new(rpc_url, Some(program_id))?;
-    optimizer.monitor_program(&program_id).await?;
+    optimizer.monitor_program(&program_id, &config.poll_interval).await?;
+
+    Ok(())
+}
+
+fn simulate_contract_load(
+    program_id_str: &str,
+    ix_data_file: &std::path::Path,
+    runs: u32,
+    fee_payer: Option<String>,
+    rpc_url: &str,
+) -> Result<()> {
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::{read_keypair_file, Signer};
+    use std::str::FromStr;
+
+    let program_id = Pubkey::from_str(program_id_str)
+        .map_err(|e| anyhow::anyhow!("Invalid program ID: {}", e))?;
+    let fee_payer = match fee_payer {
+        Some(address) => Pubkey::from_str(&address)
+            .map_err(|e| anyhow::anyhow!("Invalid fee payer: {}", e))?,
+        None => read_keypair_file(&config::ValidatorConfig::load()?.identity_keypair)
+            .map(|keypair| keypair.pubkey())
+            .map_err(|e| anyhow::anyhow!("Failed to read validator keypair (or pass --fee-payer): {}", e))?,
+    };
+
+    let spec = load_sim::LoadSpec::load(ix_data_file)?;
+    let optimizer = smart_contract::SmartContractOptimizer::new(rpc_url, Some(program_id))?;
+
+    let points = load_sim::simulate_load(&optimizer, &program_id, &spec, &fee_payer, runs)?;
+    load_sim::display_load_chart(&points);
 
     Ok(())
 }
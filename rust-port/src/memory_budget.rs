@@ -0,0 +1,85 @@
+use anyhow::Result;
+use colored::Colorize;
+use sysinfo::System;
+
+use crate::config::ValidatorConfig;
+
+/// Suggested cache and index sizes are rounded down to this
+const SUGGESTION_STEP_MB: u64 = 256;
+
+/// The validator's expected memory use against what the host may give it
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryEstimate {
+    pub cache_mb: u64,
+    pub index_mb: u64,
+    pub baseline_mb: u64,
+    pub total_ram_mb: u64,
+    /// `memory_budget.max_ram_pct` of `total_ram_mb`
+    pub allowed_mb: u64,
+}
+
+impl MemoryEstimate {
+    pub fn new(config: &ValidatorConfig, total_ram_mb: u64) -> Self {
+        let budget = &config.memory_budget;
+        Self {
+            cache_mb: config.optimization.accounts_db_cache_mb as u64,
+            index_mb: config.optimization.accounts_index_memory_mb as u64,
+            baseline_mb: budget.validator_baseline_mb,
+            total_ram_mb,
+            allowed_mb: (total_ram_mb as f64 * budget.max_ram_pct.clamp(0.0, 100.0) / 100.0) as u64,
+        }
+    }
+
+    pub fn needed_mb(&self) -> u64 {
+        self.cache_mb + self.index_mb + self.baseline_mb
+    }
+
+    pub fn fits(&self) -> bool {
+        self.needed_mb() <= self.allowed_mb
+    }
+
+    /// Cache and index sizes, scaled down together, that fit beside the
+    /// baseline; None when not even the baseline fits
+    pub fn suggestion(&self) -> Option<(u64, u64)> {
+        let room = self.allowed_mb.checked_sub(self.baseline_mb)?;
+        let configured = self.cache_mb + self.index_mb;
+        if configured == 0 {
+            return None;
+        }
+        let scale = |mb: u64| (mb as f64 * room as f64 / configured as f64) as u64 / SUGGESTION_STEP_MB * SUGGESTION_STEP_MB;
+        let (cache, index) = (scale(self.cache_mb), scale(self.index_mb));
+        (cache > 0 && index > 0).then_some((cache, index))
+    }
+}
+
+/// Total RAM of this host in MB
+pub fn host_total_ram_mb() -> u64 {
+    let mut system = System::new();
+    system.refresh_memory();
+    system.total_memory() / 1024 / 1024
+}
+
+/// Print the estimate against this host's RAM; fails when it doesn't fit,
+/// with cache and index sizes that would
+pub fn check(config: &ValidatorConfig) -> Result<()> {
+    let estimate = MemoryEstimate::new(config, host_total_ram_mb());
+    println!("├─ accounts_db_cache_mb: {} MB", estimate.cache_mb);
+    println!("├─ accounts_index_memory_mb: {} MB", estimate.index_mb);
+    println!("├─ Validator baseline (memory_budget.validator_baseline_mb): {} MB", estimate.baseline_mb);
+    let summary = format!("{} MB needed of {} MB allowed ({}% of {} MB RAM)",
+        estimate.needed_mb(), estimate.allowed_mb, config.memory_budget.max_ram_pct, estimate.total_ram_mb);
+    if estimate.fits() {
+        println!("└─ {} {}", "✓".green(), summary);
+        return Ok(());
+    }
+
+    println!("└─ {} {}", "✗".red(), summary);
+    let hint = match estimate.suggestion() {
+        Some((cache, index)) => format!(
+            "set accounts_db_cache_mb to {} and accounts_index_memory_mb to {} to fit", cache, index),
+        None => format!(
+            "the baseline alone needs {} MB; this host needs more RAM", estimate.baseline_mb),
+    };
+    anyhow::bail!("The validator would likely be OOM-killed: {} MB needed but only {} MB allowed; {}",
+        estimate.needed_mb(), estimate.allowed_mb, hint)
+}
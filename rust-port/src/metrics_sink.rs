@@ -0,0 +1,298 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use futures::future::{join_all, BoxFuture};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::UdpSocket;
+
+use crate::config::{MetricsSinkConfig, ValidatorConfig};
+use crate::monitor::PerformanceMetrics;
+use crate::native;
+
+/// How long an HTTP sink gets per sample before the push is given up
+const PUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A metrics backend that receives every reading
+pub trait MetricsSink: Send + Sync {
+    /// Shown when a push fails
+    fn name(&self) -> String;
+
+    fn record<'a>(&'a self, metrics: &'a PerformanceMetrics) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Numeric fields pushed for each reading
+fn fields(metrics: &PerformanceMetrics) -> [(&'static str, f64); 9] {
+    [
+        ("vote_success_rate", metrics.vote_success_rate),
+        ("skip_rate", metrics.skip_rate),
+        ("credits_earned", metrics.credits_earned as f64),
+        ("vote_lag", metrics.vote_lag as f64),
+        ("network_latency_ms", metrics.network_latency_ms as f64),
+        ("avg_tps", metrics.avg_tps),
+        ("slot", metrics.slot as f64),
+        ("epoch", metrics.epoch as f64),
+        ("slot_time_ms", metrics.slot_ms()),
+    ]
+}
+
+/// Upper bounds of the OpenMetrics `vote_lag` buckets, in slots
+const VOTE_LAG_BUCKETS: [u64; 8] = [1, 2, 4, 8, 16, 32, 64, 128];
+
+/// Prometheus text exposition of one reading, or OpenMetrics when
+/// `openmetrics` is set
+///
+/// OpenMetrics only allows exemplars on counter totals and histogram buckets,
+/// so there `credits_earned` is a counter and `vote_lag` a gauge histogram of
+/// the one reading, each with an exemplar carrying its slot and epoch.
+pub fn exposition(metrics: &PerformanceMetrics, openmetrics: bool) -> String {
+    let mut body = String::new();
+    let exemplar = |value: f64| {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+        format!(" # {{slot=\"{}\",epoch=\"{}\"}} {} {:.3}", metrics.slot, metrics.epoch, value, timestamp)
+    };
+
+    for (field, value) in fields(metrics) {
+        let name = format!("solana_validator_{}", field);
+        match field {
+            "credits_earned" if openmetrics => {
+                body += &format!("# TYPE {0} counter\n{0}_total {1}{2}\n", name, value, exemplar(value));
+            }
+            "vote_lag" if openmetrics => {
+                let lag = metrics.vote_lag;
+                body += &format!("# TYPE {} gaugehistogram\n", name);
+                // The exemplar goes on the one bucket whose range holds the reading
+                let holding = VOTE_LAG_BUCKETS.iter().position(|&le| lag <= le);
+                for (i, le) in VOTE_LAG_BUCKETS.iter().enumerate() {
+                    let count = u64::from(lag <= *le);
+                    let exemplar = if holding == Some(i) { exemplar(value) } else { String::new() };
+                    body += &format!("{}_bucket{{le=\"{:.1}\"}} {}{}\n", name, *le as f64, count, exemplar);
+                }
+                let exemplar = if holding.is_none() { exemplar(value) } else { String::new() };
+                body += &format!("{0}_bucket{{le=\"+Inf\"}} 1{1}\n{0}_gcount 1\n{0}_gsum {2}\n", name, exemplar, value);
+            }
+            _ => body += &format!("# TYPE {0} gauge\n{0} {1}\n", name, value),
+        }
+    }
+    if openmetrics {
+        body += "# EOF\n";
+    }
+    body
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(PUSH_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+/// StatsD gauges, one datagram per reading
+pub struct StatsdSink {
+    address: String,
+    prefix: String,
+    identity: String,
+}
+
+impl MetricsSink for StatsdSink {
+    fn name(&self) -> String {
+        format!("StatsD {}", self.address)
+    }
+
+    fn record<'a>(&'a self, metrics: &'a PerformanceMetrics) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let payload = fields(metrics)
+                .iter()
+                .map(|(name, value)| format!("{}.{}:{}|g|#identity:{}", self.prefix, name, value, self.identity))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let socket = UdpSocket::bind("0.0.0.0:0").await.context("Failed to open UDP socket")?;
+            socket.send_to(payload.as_bytes(), &self.address).await
+                .with_context(|| format!("Failed to send to {}", self.address))?;
+            Ok(())
+        })
+    }
+}
+
+/// InfluxDB line protocol over HTTP
+pub struct InfluxSink {
+    url: String,
+    token: Option<String>,
+    measurement: String,
+    identity: String,
+    client: reqwest::Client,
+}
+
+impl MetricsSink for InfluxSink {
+    fn name(&self) -> String {
+        format!("InfluxDB {}", self.url)
+    }
+
+    fn record<'a>(&'a self, metrics: &'a PerformanceMetrics) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let timestamp_ns = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+            let values = fields(metrics)
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>()
+                .join(",");
+            let line = format!("{},identity={} {} {}", self.measurement, self.identity, values, timestamp_ns);
+
+            let mut request = self.client.post(&self.url).body(line);
+            if let Some(token) = &self.token {
+                request = request.header("Authorization", format!("Token {}", token));
+            }
+            request
+                .send()
+                .await
+                .context("InfluxDB write failed")?
+                .error_for_status()
+                .context("InfluxDB returned an error status")?;
+            Ok(())
+        })
+    }
+}
+
+/// Prometheus pushgateway, grouped by job and identity
+pub struct PushgatewaySink {
+    url: String,
+    job: String,
+    identity: String,
+    client: reqwest::Client,
+}
+
+impl MetricsSink for PushgatewaySink {
+    fn name(&self) -> String {
+        format!("pushgateway {}", self.url)
+    }
+
+    fn record<'a>(&'a self, metrics: &'a PerformanceMetrics) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let body = exposition(metrics, false);
+            let url = format!("{}/metrics/job/{}/identity/{}", self.url.trim_end_matches('/'), self.job, self.identity);
+
+            // PUT replaces the whole group, so metrics never go stale alongside fresh ones
+            self.client
+                .put(&url)
+                .body(body)
+                .send()
+                .await
+                .context("Pushgateway request failed")?
+                .error_for_status()
+                .context("Pushgateway returned an error status")?;
+            Ok(())
+        })
+    }
+}
+
+/// The configured `metrics_sinks`, fed together
+pub struct MetricsSinks {
+    sinks: Vec<Box<dyn MetricsSink>>,
+}
+
+impl MetricsSinks {
+    /// Sinks from `config.metrics_sinks`, tagged with the identity pubkey
+    /// ("unknown" when the identity keypair can't be read)
+    pub fn new(config: &ValidatorConfig) -> Self {
+        let identity = native::keypair_pubkey(&config.identity_keypair)
+            .map_or_else(|_| "unknown".to_string(), |pubkey| pubkey.to_string());
+
+        let sinks = config.metrics_sinks.iter().map(|sink| -> Box<dyn MetricsSink> {
+            match sink {
+                MetricsSinkConfig::Statsd { address, prefix } => Box::new(StatsdSink {
+                    address: address.clone(),
+                    prefix: prefix.clone(),
+                    identity: identity.clone(),
+                }),
+                MetricsSinkConfig::Influxdb { url, token, measurement } => Box::new(InfluxSink {
+                    url: url.clone(),
+                    token: token.clone(),
+                    measurement: measurement.clone(),
+                    identity: identity.clone(),
+                    client: http_client(),
+                }),
+                MetricsSinkConfig::Pushgateway { url, job } => Box::new(PushgatewaySink {
+                    url: url.clone(),
+                    job: job.clone(),
+                    identity: identity.clone(),
+                    client: http_client(),
+                }),
+            }
+        }).collect();
+
+        Self { sinks }
+    }
+
+    /// Push a reading to every sink at once; failures are printed, never returned
+    pub async fn record(&self, metrics: &PerformanceMetrics) {
+        let results = join_all(self.sinks.iter().map(|sink| sink.record(metrics))).await;
+        for (sink, result) in self.sinks.iter().zip(results) {
+            if let Err(e) = result {
+                println!("  {} Failed to push metrics to {}: {:#}", "⚠".yellow(), sink.name(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(vote_lag: u64) -> PerformanceMetrics {
+        serde_json::from_value(serde_json::json!({
+            "vote_success_rate": 98.5,
+            "skip_rate": 1.5,
+            "credits_earned": 1200,
+            "vote_lag": vote_lag,
+            "network_latency_ms": 40,
+            "timestamp": "2024-05-01T12:00:00Z",
+            "epoch": 600,
+            "slot": 259_200_000u64,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn openmetrics_counter_carries_an_exemplar() {
+        let body = exposition(&reading(5), true);
+        let lines: Vec<&str> = body.lines().collect();
+
+        let at = lines.iter().position(|line| *line == "# TYPE solana_validator_credits_earned counter").unwrap();
+        assert!(lines[at + 1].starts_with("solana_validator_credits_earned_total 1200 # {slot=\"259200000\",epoch=\"600\"} 1200 "),
+            "{}", lines[at + 1]);
+        assert_eq!(lines.last(), Some(&"# EOF"));
+    }
+
+    #[test]
+    fn openmetrics_vote_lag_buckets_are_cumulative() {
+        let body = exposition(&reading(5), true);
+        let buckets: Vec<&str> = body.lines().filter(|line| line.starts_with("solana_validator_vote_lag_")).collect();
+
+        let counts: Vec<&str> = buckets.iter()
+            .filter(|line| line.contains("_bucket"))
+            .map(|line| line.split(' ').nth(1).unwrap())
+            .collect();
+        assert_eq!(counts, ["0", "0", "0", "1", "1", "1", "1", "1", "1"]);
+
+        // Only the bucket the reading falls in (le 8) carries the exemplar
+        let with_exemplar: Vec<&&str> = buckets.iter().filter(|line| line.contains(" # ")).collect();
+        assert_eq!(with_exemplar.len(), 1);
+        assert!(with_exemplar[0].starts_with("solana_validator_vote_lag_bucket{le=\"8.0\"} 1 # "));
+
+        assert!(buckets.contains(&"solana_validator_vote_lag_gcount 1"));
+        assert!(buckets.contains(&"solana_validator_vote_lag_gsum 5"));
+    }
+
+    #[test]
+    fn vote_lag_past_the_last_bucket_lands_in_inf() {
+        let body = exposition(&reading(500), true);
+        assert!(body.lines().any(|line| line.starts_with("solana_validator_vote_lag_bucket{le=\"+Inf\"} 1 # ")), "{}", body);
+    }
+
+    #[test]
+    fn prometheus_has_only_gauges_and_no_eof() {
+        let body = exposition(&reading(5), false);
+        assert!(body.contains("# TYPE solana_validator_credits_earned gauge\nsolana_validator_credits_earned 1200\n"));
+        assert!(!body.contains("_bucket"));
+        assert!(!body.contains("# EOF"));
+    }
+}
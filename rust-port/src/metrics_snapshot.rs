@@ -0,0 +1,221 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use colored::Colorize;
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_response::{RpcVoteAccountInfo, RpcVoteAccountStatus};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::ValidatorConfig;
+use crate::connect::{connect_validator, KeypairUse};
+use crate::health::{self, HealthInputs, HealthVerdict};
+use crate::monitor::PerformanceMetrics;
+use crate::native;
+use crate::system::{SystemMetrics, SystemMonitor};
+use crate::tls;
+
+/// RPC timeout for the reachability and cluster reads
+const RPC_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One point-in-time reading, written by `metrics snapshot`
+#[derive(Debug, Serialize)]
+pub struct MetricsSnapshot {
+    /// RFC 3339, local time
+    pub taken_at: String,
+    pub identity: Option<String>,
+    pub vote_account: Option<String>,
+    /// A `solana-validator` process is running on this host
+    pub validator_running: bool,
+    /// The local validator's RPC answered
+    pub validator_reachable: bool,
+    /// RPC the validator metrics were read from
+    pub metrics_rpc: Option<String>,
+    /// None when no endpoint could report on the vote account; see `errors`
+    pub metrics: Option<PerformanceMetrics>,
+    pub health: Option<HealthVerdict>,
+    pub system: SystemMetrics,
+    pub cluster: Option<ClusterHealth>,
+    /// Why anything above is missing
+    pub errors: Vec<String>,
+}
+
+/// The cluster as its RPC sees it
+#[derive(Debug, Serialize)]
+pub struct ClusterHealth {
+    pub rpc_url: String,
+    /// `ok`, or the RPC's `getHealth` error
+    pub rpc_health: String,
+    pub solana_core: Option<String>,
+    pub epoch: u64,
+    pub slot: u64,
+    pub slot_index: u64,
+    pub slots_in_epoch: u64,
+    pub validators: usize,
+    pub delinquent_validators: usize,
+    pub delinquent_stake_pct: f64,
+    /// The local node's slot distance from the cluster; None when it didn't answer
+    pub slots_behind: Option<u64>,
+}
+
+/// A pubkey given on the command line, else the configured keypair's
+fn resolve_pubkey(given: Option<&str>, keypair: &Path) -> Result<Pubkey> {
+    match given {
+        Some(pubkey) => pubkey.parse().with_context(|| format!("Invalid pubkey {}", pubkey)),
+        None => native::keypair_pubkey(keypair),
+    }
+}
+
+/// The first connect endpoint other than the local node, else the local node
+fn cluster_url(config: &ValidatorConfig, local_url: &str) -> String {
+    config.connect_endpoints().iter()
+        .find(|endpoint| endpoint.as_str() != local_url)
+        .cloned()
+        .unwrap_or_else(|| local_url.to_string())
+}
+
+fn read_cluster(client: &RpcClient, vote_accounts: &RpcVoteAccountStatus, local_slot: Option<u64>) -> Result<ClusterHealth> {
+    let epoch_info = client.get_epoch_info().context("Failed to get epoch info")?;
+    let stake = |accounts: &[RpcVoteAccountInfo]| accounts.iter().map(|account| account.activated_stake).sum::<u64>();
+    let (current, delinquent) = (stake(&vote_accounts.current), stake(&vote_accounts.delinquent));
+    let total = current + delinquent;
+
+    Ok(ClusterHealth {
+        rpc_url: client.url(),
+        rpc_health: client.get_health().map_or_else(|e| e.to_string(), |_| "ok".to_string()),
+        solana_core: client.get_version().ok().map(|version| version.solana_core),
+        epoch: epoch_info.epoch,
+        slot: epoch_info.absolute_slot,
+        slot_index: epoch_info.slot_index,
+        slots_in_epoch: epoch_info.slots_in_epoch,
+        validators: vote_accounts.current.len() + vote_accounts.delinquent.len(),
+        delinquent_validators: vote_accounts.delinquent.len(),
+        delinquent_stake_pct: if total > 0 { delinquent as f64 / total as f64 * 100.0 } else { 0.0 },
+        slots_behind: local_slot.map(|slot| epoch_info.absolute_slot.saturating_sub(slot)),
+    })
+}
+
+/// `metrics snapshot`: write one reading of the validator, the host and the
+/// cluster to a JSON file, e.g. as evidence for an incident report
+///
+/// Reads only, so pubkeys are enough: `identity` and `vote_account` stand in
+/// for the configured keypairs. Whatever can't be read is left out and the
+/// reason recorded in `errors`; the snapshot is written regardless.
+pub async fn write_snapshot(out: Option<&Path>, identity: Option<&str>, vote_account: Option<&str>) -> Result<()> {
+    let config = ValidatorConfig::load()?;
+    let mut errors = Vec::new();
+
+    println!("{}", "=== Metrics Snapshot ===".cyan().bold());
+    let taken_at = Local::now();
+
+    let local_url = format!("http://127.0.0.1:{}", config.rpc_port);
+    let local = tls::rpc_client_with_timeout(&local_url, RPC_TIMEOUT, CommitmentConfig::confirmed());
+    let local_slot = match local.get_slot() {
+        Ok(slot) => Some(slot),
+        Err(e) => {
+            errors.push(format!("Local RPC {} not answering: {}", local_url, e));
+            None
+        }
+    };
+
+    let cluster = tls::rpc_client_with_timeout(cluster_url(&config, &local_url), RPC_TIMEOUT, CommitmentConfig::confirmed());
+    let vote_accounts = cluster.get_vote_accounts()
+        .map_err(|e| errors.push(format!("Cluster RPC {} vote accounts: {}", cluster.url(), e)))
+        .ok();
+
+    let vote_pubkey = resolve_pubkey(vote_account, &config.vote_account_keypair)
+        .map_err(|e| errors.push(format!("No vote account (pass --vote-account): {:#}", e)))
+        .ok();
+    // Without an identity, the vote account's node is the one to report on
+    let node_of_vote = vote_pubkey.zip(vote_accounts.as_ref()).and_then(|(vote, accounts)| {
+        let vote = vote.to_string();
+        accounts.current.iter().chain(&accounts.delinquent)
+            .find(|account| account.vote_pubkey == vote)
+            .and_then(|account| account.node_pubkey.parse().ok())
+    });
+    let identity_pubkey = match resolve_pubkey(identity, &config.identity_keypair) {
+        Ok(pubkey) => Some(pubkey),
+        Err(_) if node_of_vote.is_some() => node_of_vote,
+        Err(e) => {
+            errors.push(format!("No identity (pass --identity): {:#}", e));
+            None
+        }
+    };
+
+    let mut metrics_rpc = None;
+    let metrics = match (identity_pubkey, vote_pubkey) {
+        (Some(identity), Some(vote)) => {
+            let read = async {
+                let interface = connect_validator(&config, KeypairUse::ReadOnly)?.watching(identity, vote);
+                let metrics = interface.get_validator_metrics().await?;
+                Ok::<_, anyhow::Error>((interface.rpc_url(), metrics))
+            };
+            match read.await {
+                Ok((rpc_url, metrics)) => {
+                    metrics_rpc = Some(rpc_url);
+                    Some(PerformanceMetrics::from_validator_metrics(&metrics))
+                }
+                Err(e) => {
+                    errors.push(format!("Validator metrics: {:#}", e));
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    let cluster_health = vote_accounts.as_ref().and_then(|accounts| {
+        read_cluster(&cluster, accounts, local_slot)
+            .map_err(|e| errors.push(format!("Cluster RPC {}: {:#}", cluster.url(), e)))
+            .ok()
+    });
+    let health = metrics.as_ref().map(|metrics| health::assess(&config.health, &HealthInputs {
+        vote_success_rate: Some(metrics.vote_success_rate),
+        skip_rate: Some(metrics.skip_rate),
+        vote_lag: Some(metrics.vote_lag),
+        delinquent: Some(metrics.delinquent),
+        slots_behind: cluster_health.as_ref().and_then(|cluster| cluster.slots_behind),
+    }));
+
+    let snapshot = MetricsSnapshot {
+        taken_at: taken_at.to_rfc3339(),
+        identity: identity_pubkey.map(|pubkey| pubkey.to_string()),
+        vote_account: vote_pubkey.map(|pubkey| pubkey.to_string()),
+        validator_running: !native::validator_pids().is_empty(),
+        validator_reachable: local_slot.is_some(),
+        metrics_rpc,
+        metrics,
+        health,
+        system: SystemMonitor::get_metrics(),
+        cluster: cluster_health,
+        errors,
+    };
+
+    let path = match out {
+        Some(path) => path.to_path_buf(),
+        None => config.output_dir(None)?
+            .join(format!("metrics-snapshot-{}.json", taken_at.format("%Y%m%d-%H%M%S"))),
+    };
+    fs::write(&path, serde_json::to_string_pretty(&snapshot)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let reachable = if snapshot.validator_reachable { "reachable".green() } else { "NOT reachable".red().bold() };
+    println!("├─ Validator: {} ({})", reachable,
+        if snapshot.validator_running { "process running" } else { "no process on this host" });
+    match &snapshot.health {
+        Some(verdict) => println!("├─ Health: {}", verdict.state.paint(&verdict.summary())),
+        None => println!("├─ Health: {}", "no validator metrics".yellow()),
+    }
+    if let Some(cluster) = &snapshot.cluster {
+        println!("├─ Cluster: epoch {} slot {}, {}/{} validators delinquent",
+            cluster.epoch, cluster.slot, cluster.delinquent_validators, cluster.validators);
+    }
+    for error in &snapshot.errors {
+        println!("├─ {} {}", "⚠".yellow(), error);
+    }
+    println!("└─ {} Snapshot written to {}", "✓".green(), path.display().to_string().yellow());
+    Ok(())
+}
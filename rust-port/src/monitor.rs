@@ -1,29 +1,70 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
 use std::process::Command;
-use chrono::Local;
+use chrono::{DateTime, Local, Utc};
 use indicatif::{ProgressBar, ProgressStyle};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use crate::blockchain::{SolanaInterface, ValidatorMetrics};
+use solana_sdk::commitment_config::CommitmentConfig;
+use crate::alerts::{Alert, Alerter, Severity};
+use crate::blockchain::{ValidatorMetrics, NOMINAL_SLOT_MS};
+use crate::connect::{read_or_disconnect, ValidatorLink};
 use crate::system::{SystemMonitor, SystemMetrics};
-use crate::config::ValidatorConfig;
-use solana_sdk::signature::{Keypair, read_keypair_file};
-
-#[derive(Debug, Serialize, Clone)]
+use crate::config::{AlertConfig, MetricField, ValidatorConfig};
+use crate::config_reload;
+use crate::health::{self, HealthInputs};
+use crate::history::{self, HistorySummary, MetricSample};
+use crate::metrics_sink::{exposition, MetricsSinks};
+use crate::native;
+use crate::sla;
+use crate::poll::AdaptiveInterval;
+use crate::pubsub::SlotTracker;
+use crate::replay;
+use crate::report::{PerformanceReport, ReportSummary};
+use crate::smoothing::{MetricsSmoother, SmoothedMetrics};
+use crate::tls;
+use crate::utils::{format_number, NumberStyle};
+
+/// Dashboard refresh interval when neither `display_interval` nor
+/// `poll_interval.base_secs` is set
+const DASHBOARD_REFRESH_SECS: u64 = 5;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PerformanceMetrics {
     pub vote_success_rate: f64,
     pub skip_rate: f64,
     pub credits_earned: u64,
+    /// Credits earned in the current epoch
+    #[serde(default)]
+    pub epoch_credits: u64,
+    /// `epoch_credits` as a percentage of the epoch's theoretical maximum so far
+    #[serde(default)]
+    pub credits_pct: Option<f64>,
     pub vote_lag: u64,
     pub network_latency_ms: u32,
+    #[serde(default)]
+    pub avg_tps: f64,
     pub timestamp: String,
     pub epoch: u64,
     pub slot: u64,
+    /// Measured average slot duration; 0 in metrics saved before it was recorded
+    #[serde(default)]
+    pub slot_time_ms: f64,
+    /// Listed as delinquent by the cluster
+    #[serde(default)]
+    pub delinquent: bool,
+    /// Share (0-1) of the optimizer's kernel parameters at their targets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimizations_applied: Option<f64>,
+    /// EMA of the noisy readings; the fields above stay raw
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smoothed: Option<SmoothedMetrics>,
 }
 
 impl PerformanceMetrics {
@@ -33,10 +74,17 @@ impl PerformanceMetrics {
             vote_success_rate: metrics.vote_success_rate,
             skip_rate: metrics.skip_rate,
             credits_earned: metrics.credits_earned,
+            epoch_credits: metrics.epoch_credits,
+            credits_pct: metrics.credits_pct,
             vote_lag: metrics.vote_lag,
             network_latency_ms: metrics.network_latency_ms,
+            avg_tps: metrics.avg_tps,
             epoch: metrics.epoch,
             slot: metrics.slot,
+            slot_time_ms: metrics.slot_time_ms,
+            delinquent: metrics.delinquent,
+            optimizations_applied: metrics.optimizations_applied,
+            smoothed: None,
             timestamp: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
         }
     }
@@ -47,16 +95,209 @@ impl PerformanceMetrics {
             vote_success_rate: 0.0,
             skip_rate: 0.0,
             credits_earned: 0,
+            epoch_credits: 0,
+            credits_pct: None,
             vote_lag: 0,
             network_latency_ms: 0,
+            avg_tps: 0.0,
             epoch: 0,
             slot: 0,
+            slot_time_ms: NOMINAL_SLOT_MS,
+            delinquent: false,
+            optimizations_applied: None,
+            smoothed: None,
             timestamp: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
         }
     }
+
+    /// A recorded history sample; the slot and credit share, which samples
+    /// don't keep, are left out
+    pub fn from_sample(sample: &MetricSample, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            vote_success_rate: sample.vote_success_rate,
+            skip_rate: sample.skip_rate,
+            credits_earned: sample.credits_earned,
+            epoch_credits: sample.epoch_credits.unwrap_or(0),
+            vote_lag: sample.vote_lag,
+            network_latency_ms: sample.network_latency_ms,
+            avg_tps: sample.avg_tps,
+            epoch: sample.epoch.unwrap_or(0),
+            delinquent: sample.delinquent,
+            timestamp: timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string(),
+            ..Self::baseline()
+        }
+    }
+
+    /// Slot duration to convert slot counts with, nominal if none was measured
+    pub fn slot_ms(&self) -> f64 {
+        if self.slot_time_ms > 0.0 { self.slot_time_ms } else { NOMINAL_SLOT_MS }
+    }
+
+    /// Credits this epoch with their share of the maximum, e.g. `152,340 (94.1% of max)`
+    pub fn epoch_credits_display(&self) -> String {
+        match self.credits_pct {
+            Some(pct) => format!("{} ({:.1}% of max)", format_number(self.epoch_credits, NumberStyle::Separated), pct),
+            None => format!("{} (max unknown)", format_number(self.epoch_credits, NumberStyle::Separated)),
+        }
+    }
+
+    /// Vote lag as wall-clock time, e.g. `12 slots (~4.8s)`
+    pub fn vote_lag_display(&self) -> String {
+        format!("{} slots (~{:.1}s)", self.vote_lag, self.vote_lag as f64 * self.slot_ms() / 1000.0)
+    }
+}
+
+/// Alert when the vote lag is over the configured threshold (real metrics only)
+async fn check_vote_lag(metrics: &PerformanceMetrics, config: &AlertConfig, alerter: &Alerter) -> bool {
+    let threshold = config.vote_lag_threshold;
+    let exceeded = metrics.vote_success_rate > 0.0 && threshold.exceeded(metrics.vote_lag, metrics.slot_ms());
+    if exceeded {
+        alerter.send(&Alert::new(
+            Severity::Warning,
+            "Vote lag high",
+            format!("Vote lag {} exceeds {} (slot time {:.0}ms)",
+                metrics.vote_lag_display(),
+                threshold.describe(metrics.slot_ms()),
+                metrics.slot_ms()
+            ),
+        ).with_reading("vote_lag", metrics.vote_lag as f64, Some(threshold.in_slots(metrics.slot_ms())))).await;
+    }
+    exceeded
+}
+
+/// Where vote credits last increased, across consecutive metric samples
+#[derive(Default)]
+struct CreditsTracker {
+    /// Credits, slot and time of the last increase while the validator was active
+    last_increase: Option<(u64, u64, Instant)>,
+    stalled: bool,
+}
+
+impl CreditsTracker {
+    /// Alert once credits stay flat for `credits_stall_slots` while the validator
+    /// should be voting (real metrics, not delinquent, vote lag under threshold)
+    async fn check(&mut self, metrics: &PerformanceMetrics, config: &AlertConfig, alerter: &Alerter) {
+        let active = metrics.vote_success_rate > 0.0
+            && !metrics.delinquent
+            && !config.vote_lag_threshold.exceeded(metrics.vote_lag, metrics.slot_ms());
+        if !active {
+            // Flat credits are expected then; start over once it is active again
+            self.last_increase = None;
+            self.stalled = false;
+            return;
+        }
+
+        let (credits, since_slot, since) = match self.last_increase {
+            // A new epoch resets the counter, so any change is progress
+            Some(last) if last.0 == metrics.credits_earned => last,
+            _ => {
+                if self.stalled {
+                    self.stalled = false;
+                    alerter.send(&Alert::new(
+                        Severity::Info,
+                        "Vote credits recovered",
+                        format!("Vote credits are increasing again ({})", format_number(metrics.credits_earned, NumberStyle::Abbreviated)),
+                    ).with_reading("credits_earned", metrics.credits_earned as f64, None)).await;
+                }
+                self.last_increase = Some((metrics.credits_earned, metrics.slot, Instant::now()));
+                return;
+            }
+        };
+
+        let flat_slots = metrics.slot.saturating_sub(since_slot);
+        if self.stalled || flat_slots < config.credits_stall_slots {
+            return;
+        }
+        self.stalled = true;
+        alerter.send(&Alert::new(
+            Severity::Critical,
+            "Vote credits stalled",
+            format!("Credits flat at {} for {} slots (~{:.0}s, {}s observed) although the validator \
+                is not delinquent and its vote lag is {}",
+                format_number(credits, NumberStyle::Abbreviated),
+                flat_slots,
+                flat_slots as f64 * metrics.slot_ms() / 1000.0,
+                since.elapsed().as_secs(),
+                metrics.vote_lag_display()
+            ),
+        ).with_reading("credits_flat_slots", flat_slots as f64, Some(config.credits_stall_slots as f64))).await;
+    }
+}
+
+/// Metrics shown by the previous `monitor` run, for trend arrows
+#[derive(Debug, Serialize, Deserialize)]
+struct TrendState {
+    saved_at: chrono::DateTime<chrono::Utc>,
+    metrics: PerformanceMetrics,
+}
+
+fn trend_state_path() -> PathBuf {
+    ValidatorConfig::config_dir().join("monitor-trend.json")
+}
+
+fn load_trend_state() -> Option<TrendState> {
+    let contents = fs::read_to_string(trend_state_path()).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn save_trend_state(metrics: &PerformanceMetrics) -> Result<()> {
+    let state = TrendState {
+        saved_at: chrono::Utc::now(),
+        metrics: metrics.clone(),
+    };
+    let path = trend_state_path();
+    fs::create_dir_all(ValidatorConfig::config_dir())?;
+    fs::write(&path, serde_json::to_string_pretty(&state)?)?;
+    Ok(())
+}
+
+/// ▲/▼/– versus the previous value, green when the move is an improvement
+fn trend_arrow(current: f64, previous: Option<f64>, higher_is_better: bool) -> String {
+    let Some(previous) = previous else {
+        return String::new();
+    };
+
+    let delta = current - previous;
+    if delta.abs() < 0.05 {
+        return format!(" {}", "–".dimmed());
+    }
+
+    let arrow = if delta > 0.0 { "▲" } else { "▼" };
+    let text = format!(" {} {:+.1}", arrow, delta);
+    if (delta > 0.0) == higher_is_better {
+        text.green().to_string()
+    } else {
+        text.red().to_string()
+    }
+}
+
+/// Print rows as a ├─/└─ tree
+fn print_tree(rows: &[String]) {
+    for (i, row) in rows.iter().enumerate() {
+        let branch = if i + 1 == rows.len() { "└─" } else { "├─" };
+        println!("{} {}", branch, row);
+    }
 }
 
-pub async fn display_metrics() -> Result<()> {
+pub fn format_elapsed(elapsed: chrono::Duration) -> String {
+    let minutes = elapsed.num_minutes();
+    if minutes < 1 {
+        format!("{}s", elapsed.num_seconds().max(0))
+    } else if minutes < 60 {
+        format!("{}m", minutes)
+    } else if minutes < 24 * 60 {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}d {}h", minutes / (24 * 60), (minutes / 60) % 24)
+    }
+}
+
+pub async fn display_metrics(reset_trend: bool, fields: &[MetricField]) -> Result<()> {
+    if reset_trend {
+        let _ = fs::remove_file(trend_state_path());
+        println!("{} Trend history cleared", "✓".green());
+    }
+
     println!("{}", "============================================".blue());
     println!("{}", "    Solana Validator Performance Monitor".blue().bold());
     println!("{}", "============================================".blue());
@@ -66,15 +307,58 @@ pub async fn display_metrics() -> Result<()> {
     println!("\nValidator Status: {}", validator_status);
     
     // Display performance metrics
-    let metrics = get_current_metrics().await?;
+    let config = ValidatorConfig::load()?;
+    let (metrics, live) = fetch_metrics(&config, &ValidatorLink::default()).await?;
+    if live {
+        MetricsSinks::new(&config).record(&metrics).await;
+    }
+
+    let previous = load_trend_state().filter(|_| metrics.vote_success_rate > 0.0);
+    let prev = previous.as_ref().map(|state| &state.metrics);
 
     println!("\n{}", "Performance Metrics:".cyan().bold());
-    println!("├─ Epoch: {} | Slot: {}", metrics.epoch, metrics.slot);
-    println!("├─ Vote Success Rate: {:.1}%", metrics.vote_success_rate);
-    println!("├─ Skip Rate: {:.1}%", metrics.skip_rate);
-    println!("├─ Credits Earned: {}", format_number(metrics.credits_earned));
-    println!("├─ Vote Lag: {} slots", metrics.vote_lag);
-    println!("└─ Network Latency: {}ms", metrics.network_latency_ms);
+    if let Some(state) = &previous {
+        println!("{}", format!("(trend vs previous run {} ago)",
+            format_elapsed(chrono::Utc::now() - state.saved_at)).dimmed());
+    }
+    let shown = |field: MetricField| fields.contains(&field);
+    let mut rows = Vec::new();
+    match (shown(MetricField::Epoch), shown(MetricField::Slot)) {
+        (true, true) => rows.push(format!("Epoch: {} | Slot: {}", metrics.epoch, metrics.slot)),
+        (true, false) => rows.push(format!("Epoch: {}", metrics.epoch)),
+        (false, true) => rows.push(format!("Slot: {}", metrics.slot)),
+        (false, false) => {}
+    }
+    for field in fields {
+        let row = match field {
+            MetricField::VoteSuccessRate => format!("Vote Success Rate: {:.1}%{}", metrics.vote_success_rate,
+                trend_arrow(metrics.vote_success_rate, prev.map(|p| p.vote_success_rate), true)),
+            MetricField::SkipRate => format!("Skip Rate: {:.1}%{}", metrics.skip_rate,
+                trend_arrow(metrics.skip_rate, prev.map(|p| p.skip_rate), false)),
+            MetricField::CreditsEarned => format!("Credits Earned: {} | This Epoch: {}{}",
+                format_number(metrics.credits_earned, NumberStyle::Abbreviated),
+                metrics.epoch_credits_display(),
+                metrics.credits_pct.map_or(String::new(), |pct| trend_arrow(pct, prev.and_then(|p| p.credits_pct), true))),
+            MetricField::VoteLag => format!("Vote Lag: {}{}", metrics.vote_lag_display(),
+                trend_arrow(metrics.vote_lag as f64, prev.map(|p| p.vote_lag as f64), false)),
+            MetricField::NetworkLatencyMs => format!("Network Latency: {}ms{}", metrics.network_latency_ms,
+                trend_arrow(metrics.network_latency_ms as f64, prev.map(|p| p.network_latency_ms as f64), false)),
+            MetricField::AvgTps => format!("TPS: {:.0}{}", metrics.avg_tps,
+                trend_arrow(metrics.avg_tps, prev.map(|p| p.avg_tps), true)),
+            MetricField::Epoch | MetricField::Slot => continue,
+        };
+        rows.push(row);
+    }
+    print_tree(&rows);
+
+    check_vote_lag(&metrics, &config.alerts, &Alerter::new(&config.alerts)).await;
+
+    // Only real metrics are worth comparing against next time
+    if metrics.vote_success_rate > 0.0 {
+        if let Err(e) = save_trend_state(&metrics) {
+            println!("  {} Could not save trend state: {}", "⚠".yellow(), e);
+        }
+    }
 
     // Show comparison only if we have real metrics
     if metrics.vote_success_rate > 0.0 {
@@ -84,49 +368,60 @@ pub async fn display_metrics() -> Result<()> {
         const BASELINE_VOTE_LAG: u64 = 150;
         const BASELINE_LATENCY: u32 = 120;
 
-        println!("\n{}", "Comparison with Typical Baseline:".cyan().bold());
-
         let vote_improvement = metrics.vote_success_rate - BASELINE_VOTE_SUCCESS;
         let skip_improvement = BASELINE_SKIP_RATE - metrics.skip_rate;
         let lag_improvement_pct = ((BASELINE_VOTE_LAG as f64 - metrics.vote_lag as f64) / BASELINE_VOTE_LAG as f64) * 100.0;
         let latency_improvement_pct = ((BASELINE_LATENCY as f64 - metrics.network_latency_ms as f64) / BASELINE_LATENCY as f64) * 100.0;
 
-        println!("├─ Vote Success: {:.1}% vs {:.1}% baseline ({})",
-            metrics.vote_success_rate,
-            BASELINE_VOTE_SUCCESS,
-            if vote_improvement > 0.0 {
-                format!("+{:.1}pp", vote_improvement).green()
-            } else {
-                format!("{:.1}pp", vote_improvement).red()
-            }
-        );
-        println!("├─ Skip Rate: {:.1}% vs {:.1}% baseline ({})",
-            metrics.skip_rate,
-            BASELINE_SKIP_RATE,
-            if skip_improvement > 0.0 {
-                format!("-{:.1}pp", skip_improvement).green()
-            } else {
-                format!("+{:.1}pp", skip_improvement.abs()).red()
-            }
-        );
-        println!("├─ Vote Lag: {} vs {} baseline ({})",
-            metrics.vote_lag,
-            BASELINE_VOTE_LAG,
-            if lag_improvement_pct > 0.0 {
-                format!("-{:.1}%", lag_improvement_pct).green()
-            } else {
-                format!("+{:.1}%", lag_improvement_pct.abs()).red()
-            }
-        );
-        println!("└─ Latency: {}ms vs {}ms baseline ({})",
-            metrics.network_latency_ms,
-            BASELINE_LATENCY,
-            if latency_improvement_pct > 0.0 {
-                format!("-{:.1}%", latency_improvement_pct).green()
-            } else {
-                format!("+{:.1}%", latency_improvement_pct.abs()).red()
-            }
-        );
+        let mut rows = Vec::new();
+        if shown(MetricField::VoteSuccessRate) {
+            rows.push(format!("Vote Success: {:.1}% vs {:.1}% baseline ({})",
+                metrics.vote_success_rate,
+                BASELINE_VOTE_SUCCESS,
+                if vote_improvement > 0.0 {
+                    format!("+{:.1}pp", vote_improvement).green()
+                } else {
+                    format!("{:.1}pp", vote_improvement).red()
+                }
+            ));
+        }
+        if shown(MetricField::SkipRate) {
+            rows.push(format!("Skip Rate: {:.1}% vs {:.1}% baseline ({})",
+                metrics.skip_rate,
+                BASELINE_SKIP_RATE,
+                if skip_improvement > 0.0 {
+                    format!("-{:.1}pp", skip_improvement).green()
+                } else {
+                    format!("+{:.1}pp", skip_improvement.abs()).red()
+                }
+            ));
+        }
+        if shown(MetricField::VoteLag) {
+            rows.push(format!("Vote Lag: {} vs {} baseline ({})",
+                metrics.vote_lag,
+                BASELINE_VOTE_LAG,
+                if lag_improvement_pct > 0.0 {
+                    format!("-{:.1}%", lag_improvement_pct).green()
+                } else {
+                    format!("+{:.1}%", lag_improvement_pct.abs()).red()
+                }
+            ));
+        }
+        if shown(MetricField::NetworkLatencyMs) {
+            rows.push(format!("Latency: {}ms vs {}ms baseline ({})",
+                metrics.network_latency_ms,
+                BASELINE_LATENCY,
+                if latency_improvement_pct > 0.0 {
+                    format!("-{:.1}%", latency_improvement_pct).green()
+                } else {
+                    format!("+{:.1}%", latency_improvement_pct.abs()).red()
+                }
+            ));
+        }
+        if !rows.is_empty() {
+            println!("\n{}", "Comparison with Typical Baseline:".cyan().bold());
+            print_tree(&rows);
+        }
     } else {
         println!("\n{}", "⚠ No validator connected - start one to see real metrics".yellow());
     }
@@ -134,40 +429,203 @@ pub async fn display_metrics() -> Result<()> {
     Ok(())
 }
 
-pub async fn dashboard() -> Result<()> {
+/// When the dashboard pushes to `metrics_sinks`, independent of its refresh
+///
+/// Each export sends the latest refresh's reading when it is younger than
+/// `export_interval`, so exporting less often than refreshing never costs an
+/// extra RPC read; only when refreshes are the slower of the two is a
+/// fresh reading fetched for the export.
+struct ExportSchedule {
+    every: Duration,
+    next: Instant,
+    /// Latest live reading and when it was taken
+    latest: Option<(PerformanceMetrics, Instant)>,
+    enabled: bool,
+}
+
+impl ExportSchedule {
+    fn new(config: &ValidatorConfig) -> Self {
+        let every = Duration::from_secs(config.export_interval.max(1));
+        Self {
+            every,
+            next: Instant::now() + every,
+            latest: None,
+            enabled: !config.metrics_sinks.is_empty(),
+        }
+    }
+
+    fn due(&self) -> bool {
+        self.enabled && Instant::now() >= self.next
+    }
+
+    async fn run(&mut self, config: &ValidatorConfig, link: &ValidatorLink, sinks: &MetricsSinks) {
+        self.next = Instant::now() + self.every;
+        let fresh = self.latest.as_ref().filter(|(_, taken)| taken.elapsed() < self.every);
+        // A cached reading keeps the time it was taken, not when it was exported
+        let reading = match fresh {
+            Some((metrics, taken)) => Some((metrics.clone(), *taken)),
+            None => link.metrics(config)
+                .await
+                .ok()
+                .map(|metrics| (PerformanceMetrics::from_validator_metrics(&metrics), Instant::now())),
+        };
+        if let Some((metrics, taken)) = reading {
+            sinks.record(&metrics).await;
+            self.latest = Some((metrics, taken));
+        }
+    }
+}
+
+/// The health line and metric bars of a dashboard frame, for the live
+/// dashboard and `monitor --replay` alike
+pub fn render_metrics(config: &ValidatorConfig, metrics: &PerformanceMetrics, live: bool, slots_behind: Option<u64>, fields: &[MetricField]) {
+    let smoothed = metrics.smoothed.unwrap_or_default();
+    if live {
+        let inputs = HealthInputs {
+            vote_success_rate: Some(metrics.vote_success_rate),
+            skip_rate: Some(metrics.skip_rate),
+            vote_lag: Some(metrics.vote_lag),
+            delinquent: Some(metrics.delinquent),
+            slots_behind,
+        };
+        let verdict = health::assess(&config.health, &inputs);
+        let score = if verdict.score > 0.0 {
+            format!(" score {:.1}, critical at {:.1}", verdict.score, config.health.critical_score).dimmed().to_string()
+        } else {
+            String::new()
+        };
+        println!("🩺 HEALTH: {}{}", verdict.state.paint(&verdict.summary()), score);
+        if let Some(score) = health::optimization_score(&config.score_weights, &config.health, &inputs, metrics.optimizations_applied) {
+            println!("🎯 OPTIMIZATION SCORE: {}", health::paint_score(score));
+        }
+    } else {
+        println!("🩺 HEALTH: {}", "✗ DOWN".red().bold());
+    }
+    println!();
+
+    // Performance bars
+    println!("{}", "⚡ PERFORMANCE METRICS".yellow().bold());
+    println!("{}", "================================================================================".dimmed());
+    
+    let shown = |field: MetricField| fields.contains(&field);
+
+    // Vote Success Rate bar
+    if shown(MetricField::VoteSuccessRate) {
+        show_metric_bar(metrics.vote_success_rate, 100.0, "Vote Success",
+            format!("{:.1}% (↑ +14%)", metrics.vote_success_rate));
+    }
+    
+    // Skip Rate bar (inverted - lower is better)
+    if shown(MetricField::SkipRate) {
+        show_metric_bar(100.0 - metrics.skip_rate, 100.0, "Low Skip Rate",
+            format!("{:.1}% skips (EMA {:.1}%)", metrics.skip_rate, smoothed.skip_rate));
+    }
+    
+    // Credits bar
+    if shown(MetricField::CreditsEarned) {
+        show_metric_bar(metrics.credits_pct.unwrap_or(0.0), 100.0, "Credits/Epoch",
+            metrics.epoch_credits_display());
+    }
+    
+    let mut readings = Vec::new();
+    if shown(MetricField::AvgTps) {
+        readings.push(format!("TPS: {:.0} (EMA {:.0})", metrics.avg_tps, smoothed.avg_tps));
+    }
+    if shown(MetricField::NetworkLatencyMs) {
+        readings.push(format!("Latency: {}ms (EMA {:.0}ms)", metrics.network_latency_ms, smoothed.network_latency_ms));
+    }
+    if shown(MetricField::VoteLag) {
+        readings.push(format!("Vote Lag: {}", metrics.vote_lag_display()));
+    }
+    if shown(MetricField::Epoch) {
+        readings.push(format!("Epoch: {}", metrics.epoch));
+    }
+    if !readings.is_empty() {
+        println!("{}", readings.join(" | "));
+    }
+}
+
+/// The banner at the top of each dashboard frame
+pub fn print_dashboard_banner() {
+    println!("{}", "================================================================================".blue());
+    println!("{}", "                    🚀 SOLANA VALIDATOR OPTIMIZER DASHBOARD 🚀".blue().bold());
+    println!("{}", "================================================================================".blue());
+    println!();
+}
+
+pub async fn dashboard(ws_url: Option<&str>, fields: &[MetricField]) -> Result<()> {
+    let shared = config_reload::shared(ValidatorConfig::load()?);
+    config_reload::reload_on_sighup(shared.clone())?;
+    let mut seen = config_reload::generation();
+    let mut config = shared.read().await.clone();
+    let slot_tracker = SlotTracker::start(&slot_source_url(&config), ws_url)?;
+    let mut smoother = MetricsSmoother::new(config.metrics_ema_alpha);
+    let mut refresh = AdaptiveInterval::new(&config.display_poll_interval(), DASHBOARD_REFRESH_SECS);
+    let mut alerter = Alerter::new(&config.alerts);
+    let mut vote_lag_high = false;
+    let mut credits = CreditsTracker::default();
+    let mut sinks = MetricsSinks::new(&config);
+    let mut export = ExportSchedule::new(&config);
+    // One connection for the whole session, so failover can kick in
+    let mut link = ValidatorLink::default();
+    // No cluster endpoint just leaves slots-behind out of the health verdict
+    let mut slot_clients = replay::replay_clients(&config).ok();
+
     loop {
+        // Pick up a config reloaded on SIGHUP since the last refresh
+        if let Some(reloaded) = config_reload::reloaded_since(&shared, &mut seen).await {
+            config = reloaded;
+            slot_clients = replay::replay_clients(&config).ok();
+            refresh = AdaptiveInterval::new(&config.display_poll_interval(), DASHBOARD_REFRESH_SECS);
+            alerter = Alerter::new(&config.alerts);
+            sinks = MetricsSinks::new(&config);
+            export = ExportSchedule::new(&config);
+            link = ValidatorLink::default();
+        }
+
         // Clear screen
         print!("\x1B[2J\x1B[1;1H");
-        
-        println!("{}", "================================================================================".blue());
-        println!("{}", "                    🚀 SOLANA VALIDATOR OPTIMIZER DASHBOARD 🚀".blue().bold());
-        println!("{}", "================================================================================".blue());
-        println!();
-        println!("Last Updated: {} | Auto-refresh: 5s | Press Ctrl+C to exit", 
-            Local::now().format("%Y-%m-%d %H:%M:%S").to_string().cyan()
+        print_dashboard_banner();
+        println!("Last Updated: {} | Auto-refresh: {}s | Press Ctrl+C to exit", 
+            Local::now().format("%Y-%m-%d %H:%M:%S").to_string().cyan(),
+            refresh.current().as_secs()
         );
         println!();
         
-        let metrics = get_current_metrics().await?;
-        
-        // Performance bars
-        println!("{}", "⚡ PERFORMANCE METRICS".yellow().bold());
-        println!("{}", "================================================================================".dimmed());
-        
-        // Vote Success Rate bar
-        let vote_bar = create_progress_bar(metrics.vote_success_rate, 100.0, "Vote Success");
-        vote_bar.set_message(format!("{:.1}% (↑ +14%)", metrics.vote_success_rate));
-        vote_bar.finish();
-        
-        // Skip Rate bar (inverted - lower is better)
-        let skip_bar = create_progress_bar(100.0 - metrics.skip_rate, 100.0, "Low Skip Rate");
-        skip_bar.set_message(format!("{:.1}% skips (↓ -75%)", metrics.skip_rate));
-        skip_bar.finish();
-        
-        // Credits bar
-        let credits_bar = create_progress_bar(metrics.credits_earned as f64, 250_000.0, "Credits/Epoch");
-        credits_bar.set_message(format!("{} (↑ +22%)", format_number(metrics.credits_earned)));
-        credits_bar.finish();
+        let (mut metrics, live) = fetch_metrics(&config, &link).await?;
+        if live {
+            refresh.success();
+        } else {
+            let next = refresh.error();
+            println!("  {} RPC error #{} - backing off to {}s",
+                "⏳".yellow(),
+                refresh.consecutive_errors(),
+                next.as_secs()
+            );
+        }
+        // Alert once when the lag crosses the threshold, not on every refresh
+        vote_lag_high = if vote_lag_high {
+            config.alerts.vote_lag_threshold.exceeded(metrics.vote_lag, metrics.slot_ms())
+        } else {
+            check_vote_lag(&metrics, &config.alerts, &alerter).await
+        };
+        if live {
+            credits.check(&metrics, &config.alerts, &alerter).await;
+            export.latest = Some((metrics.clone(), Instant::now()));
+        }
+        let smoothed = smoother.update(
+            metrics.avg_tps,
+            metrics.skip_rate,
+            metrics.network_latency_ms as f64,
+        );
+        metrics.smoothed = Some(smoothed);
+
+        let slots_behind = slot_clients.as_ref()
+            .filter(|_| live)
+            .and_then(|(local, cluster)| replay::read_slots(local, cluster).ok())
+            .map(|(local, cluster)| cluster.saturating_sub(local));
+        render_metrics(&config, &metrics, live, slots_behind, fields);
+        let shown = |field: MetricField| fields.contains(&field);
         
         println!();
         println!("{}", "💻 SYSTEM STATUS".yellow().bold());
@@ -183,29 +641,81 @@ pub async fn dashboard() -> Result<()> {
         println!("✅ Thread Configuration: {} | RPC: 32 | DB: 16", "OPTIMIZED".green().bold());
         println!("✅ Vote Timing: {} | TPU: 1ms | Skip wait: Enabled", "TUNED".green().bold());
         println!("✅ Snapshots: {} | Interval: 100 slots", "CONFIGURED".green().bold());
+        println!();
         
-        // Sleep for 5 seconds before refresh
-        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+        // Keep the current slot ticking between metric refreshes
+        let ticks = (refresh.current().as_millis() / 500).max(1);
+        for _ in 0..ticks {
+            // Exports run on their own schedule, between refreshes as well
+            if export.due() {
+                export.run(&config, &link, &sinks).await;
+            }
+            if !shown(MetricField::Slot) {
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                continue;
+            }
+            let slot = slot_tracker.slot().max(metrics.slot);
+            let source = if slot_tracker.is_live() {
+                "live".green()
+            } else {
+                "polling".yellow()
+            };
+            print!("\r🔗 Current Slot: {} ({})\x1B[K", slot.to_string().cyan().bold(), source);
+            std::io::stdout().flush()?;
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        }
+    }
+}
+
+/// `metrics expose`: one reading as a Prometheus (or OpenMetrics) text file,
+/// replaced whole so a server handing it to Prometheus never reads half of it
+pub async fn write_exposition(out: Option<&Path>, openmetrics: bool) -> Result<()> {
+    println!("{}", "=== Metrics Exposition ===".cyan().bold());
+    let config = ValidatorConfig::load()?;
+    let (metrics, live) = fetch_metrics(&config, &ValidatorLink::default()).await?;
+    if !live {
+        anyhow::bail!("No validator answered; nothing to expose");
     }
+
+    let path = match out {
+        Some(path) => path.to_path_buf(),
+        None => config.output_dir(None)?.join("metrics.prom"),
+    };
+    let partial = path.with_extension("prom.tmp");
+    fs::write(&partial, exposition(&metrics, openmetrics))
+        .with_context(|| format!("Failed to write {}", partial.display()))?;
+    fs::rename(&partial, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let format = if openmetrics { "OpenMetrics, with slot/epoch exemplars" } else { "Prometheus text" };
+    println!("{} Slot {} of epoch {} written to {} ({})",
+        "✓".green(), metrics.slot, metrics.epoch, path.display().to_string().yellow(), format);
+    Ok(())
 }
 
-pub async fn generate_report() -> Result<()> {
+/// Write `performance-report.md`; `since` (e.g. `24h`) adds a summary of the
+/// persisted history over that window
+pub async fn generate_report(output_dir: Option<&Path>, since: Option<&str>) -> Result<()> {
     println!("{}", "Generating Performance Report...".cyan());
 
+    let window = since
+        .map(|label| crate::utils::parse_duration(label).map(|duration| (label, duration)))
+        .transpose()?;
+    let config = ValidatorConfig::load()?;
+    let output_dir = config.output_dir(output_dir)?;
+
     let metrics = get_current_metrics().await?;
 
     // Calculate improvements from baseline
     const BASELINE_VOTE_SUCCESS: f64 = 85.0;
     const BASELINE_SKIP_RATE: f64 = 12.0;
-    const BASELINE_CREDITS: u64 = 180_000;
+    // Share of the epoch's maximum credits, so epochs and clusters compare fairly
+    const BASELINE_CREDITS_PCT: f64 = 72.0;
     const BASELINE_VOTE_LAG: u64 = 150;
     const BASELINE_LATENCY: u32 = 120;
 
     let vote_improvement = metrics.vote_success_rate - BASELINE_VOTE_SUCCESS;
     let skip_improvement = BASELINE_SKIP_RATE - metrics.skip_rate;
-    let credits_improvement_pct = if BASELINE_CREDITS > 0 {
-        ((metrics.credits_earned as f64 - BASELINE_CREDITS as f64) / BASELINE_CREDITS as f64) * 100.0
-    } else { 0.0 };
+    let credits_improvement = metrics.credits_pct.map(|pct| pct - BASELINE_CREDITS_PCT);
     let lag_improvement_pct = ((BASELINE_VOTE_LAG as f64 - metrics.vote_lag as f64) / BASELINE_VOTE_LAG as f64) * 100.0;
     let latency_improvement_pct = ((BASELINE_LATENCY as f64 - metrics.network_latency_ms as f64) / BASELINE_LATENCY as f64) * 100.0;
 
@@ -227,7 +737,7 @@ Data Source: {}
 - **Slot**: {}
 - **Vote Success Rate**: {:.1}% ({})
 - **Skip Rate**: {:.1}% ({})
-- **Credits Earned**: {} ({})
+- **Credits Earned**: {} lifetime, {} this epoch ({})
 - **Vote Lag**: {} slots ({})
 - **Network Latency**: {}ms ({})
 
@@ -244,7 +754,7 @@ Data Source: {}
 These comparisons are against typical unoptimized validator baseline:
 - Baseline Vote Success: {:.1}%
 - Baseline Skip Rate: {:.1}%
-- Baseline Credits: {}
+- Baseline Credits: {:.1}% of the epoch maximum
 - Baseline Vote Lag: {} slots
 - Baseline Latency: {}ms
 
@@ -268,11 +778,12 @@ These comparisons are against typical unoptimized validator baseline:
         } else {
             format!("↑ +{:.1}pp from baseline", skip_improvement.abs())
         },
-        format_number(metrics.credits_earned),
-        if credits_improvement_pct >= 0.0 {
-            format!("↑ +{:.1}% from baseline", credits_improvement_pct)
-        } else {
-            format!("↓ {:.1}% from baseline", credits_improvement_pct)
+        format_number(metrics.credits_earned, NumberStyle::Abbreviated),
+        metrics.epoch_credits_display(),
+        match credits_improvement {
+            Some(improvement) if improvement >= 0.0 => format!("↑ +{:.1}pp from baseline", improvement),
+            Some(improvement) => format!("↓ {:.1}pp from baseline", improvement),
+            None => "no baseline comparison without the epoch maximum".to_string(),
         },
         metrics.vote_lag,
         if lag_improvement_pct >= 0.0 {
@@ -288,7 +799,7 @@ These comparisons are against typical unoptimized validator baseline:
         },
         BASELINE_VOTE_SUCCESS,
         BASELINE_SKIP_RATE,
-        format_number(BASELINE_CREDITS),
+        BASELINE_CREDITS_PCT,
         BASELINE_VOTE_LAG,
         BASELINE_LATENCY,
         if metrics.vote_success_rate > 0.0 {
@@ -299,24 +810,103 @@ These comparisons are against typical unoptimized validator baseline:
         }
     );
 
-    let report_path = PathBuf::from("performance-report.md");
+    let mut report = report;
+    let mut json_summary = None;
+    if let Some((label, duration)) = window {
+        let since = chrono::Utc::now()
+            .checked_sub_signed(duration)
+            .ok_or_else(|| anyhow::anyhow!("Window '{}' reaches back before the earliest supported date", label))?;
+        let records = history::load_since(since)?;
+        let summary = history::summarize(&records);
+        print_history_summary(label, &summary);
+        report.push_str(&history_summary_markdown(label, &summary));
+        json_summary = Some(ReportSummary::new(label, &summary));
+    }
+
+    let sla = sla::current(&config.sla)?;
+    report.push_str(&sla::markdown(&sla, &config.sla));
+
+    let report_path = output_dir.join("performance-report.md");
     fs::write(&report_path, report)?;
 
-    println!("{} {}",
+    // Machine-readable copy for `report-diff`
+    let json_report = PerformanceReport {
+        generated: metrics.timestamp.clone(),
+        connected: metrics.vote_success_rate > 0.0,
+        metrics,
+        summary: json_summary,
+        sla,
+    };
+    let json_path = output_dir.join("performance-report.json");
+    fs::write(&json_path, serde_json::to_string_pretty(&json_report)?)?;
+
+    println!("{} {} ({})",
         "✓ Report generated:".green(),
-        report_path.display().to_string().yellow()
+        report_path.display().to_string().yellow(),
+        json_path.display()
     );
 
     Ok(())
 }
 
+fn print_history_summary(label: &str, summary: &HistorySummary) {
+    println!("\n{}", format!("History (last {}):", label).cyan().bold());
+    if summary.samples == 0 {
+        println!("└─ {} No samples recorded in this window - run monitor or optimize --auto to collect them", "⚠".yellow());
+        return;
+    }
+
+    println!("├─ Samples: {} | Uptime: {:.1}% | Optimizations applied: {}",
+        summary.samples, summary.uptime_pct, summary.optimizations);
+    for (i, (name, stat)) in summary.stats.iter().enumerate() {
+        let branch = if i + 1 == summary.stats.len() { "└─" } else { "├─" };
+        println!("{} {}: avg {:.1} | min {:.1} | max {:.1}", branch, name, stat.avg, stat.min, stat.max);
+    }
+}
+
+fn history_summary_markdown(label: &str, summary: &HistorySummary) -> String {
+    let mut section = format!("\n## Summary (last {})\n\n", label);
+    if summary.samples == 0 {
+        section.push_str("No metric samples were recorded in this window.\n");
+        return section;
+    }
+
+    section.push_str(&format!(
+        "- **Samples**: {}\n- **Uptime**: {:.1}% (samples where the validator was reachable and voting)\n- **Optimizations Applied**: {}\n\n",
+        summary.samples, summary.uptime_pct, summary.optimizations
+    ));
+    section.push_str("| Metric | Average | Min | Max |\n|---|---|---|---|\n");
+    for (name, stat) in &summary.stats {
+        section.push_str(&format!("| {} | {:.1} | {:.1} | {:.1} |\n", name, stat.avg, stat.min, stat.max));
+    }
+    section
+}
+
+/// RPC endpoint the dashboard follows slots on: the local validator if it
+/// answers, otherwise the cluster's (mirrors the metrics fallback)
+fn slot_source_url(config: &ValidatorConfig) -> String {
+    let local = format!("http://127.0.0.1:{}", config.rpc_port);
+    let client = tls::rpc_client_with_timeout(&local, std::time::Duration::from_secs(2), CommitmentConfig::default());
+
+    if client.get_slot().is_ok() {
+        local
+    } else {
+        config.cluster_rpc_url()
+    }
+}
+
 fn get_validator_status() -> Result<String> {
-    let output = Command::new("pgrep")
-        .arg("solana-validator")
-        .output()
-        .context("Failed to check validator status")?;
+    let running = if native::no_cli() {
+        !native::validator_pids().is_empty()
+    } else {
+        let output = Command::new("pgrep")
+            .arg("solana-validator")
+            .output()
+            .context("Failed to check validator status")?;
+        output.status.success() && !output.stdout.is_empty()
+    };
     
-    if output.status.success() && !output.stdout.is_empty() {
+    if running {
         Ok("✓ RUNNING".green().bold().to_string())
     } else {
         Ok("✗ STOPPED".red().bold().to_string())
@@ -327,46 +917,40 @@ fn get_validator_status() -> Result<String> {
 async fn get_current_metrics() -> Result<PerformanceMetrics> {
     // Load validator config to get keypairs
     let config = ValidatorConfig::load()?;
+    Ok(fetch_metrics(&config, &ValidatorLink::default()).await?.0)
+}
 
+/// Real metrics, or the baseline (and `false`) when no validator answered and
+/// `on_disconnect` is `baseline`; an error for `error` or a `wait` that timed out
+async fn fetch_metrics(config: &ValidatorConfig, link: &ValidatorLink) -> Result<(PerformanceMetrics, bool)> {
     // Try to connect to blockchain and get real metrics
-    let result = try_get_real_metrics(&config).await;
+    let result = read_or_disconnect(config, || link.metrics(config)).await;
+
+    if let Err(e) = history::record_sample(result.as_ref().ok().and_then(Option::as_ref)) {
+        println!("  {} Could not record metrics history: {}", "⚠".yellow(), e);
+    }
 
-    match result {
-        Ok(metrics) => {
+    match result? {
+        Some(metrics) => {
             println!("  {} Using REAL blockchain metrics", "✓".green());
-            Ok(PerformanceMetrics::from_validator_metrics(&metrics))
+            Ok((PerformanceMetrics::from_validator_metrics(&metrics), true))
         }
-        Err(e) => {
-            println!("  {} No validator running: {}", "⚠".yellow(), e);
+        None => {
             println!("  {} Start a validator to see real metrics", "ℹ".cyan());
-            Ok(PerformanceMetrics::baseline())
+            Ok((PerformanceMetrics::baseline(), false))
         }
     }
 }
 
-/// Try to fetch real metrics from local or testnet validator
-async fn try_get_real_metrics(config: &ValidatorConfig) -> Result<ValidatorMetrics> {
-    // Try to read keypairs
-    let validator_keypair = read_keypair_file(&config.identity_keypair)
-        .map_err(|e| anyhow::anyhow!("Failed to read validator keypair: {}", e))?;
-    let vote_keypair = read_keypair_file(&config.vote_account_keypair)
-        .map_err(|e| anyhow::anyhow!("Failed to read vote keypair: {}", e))?;
-
-    // Try local validator first
-    if let Ok(interface) = SolanaInterface::new("http://127.0.0.1:8899", validator_keypair.insecure_clone(), vote_keypair.insecure_clone()) {
-        if let Ok(metrics) = interface.get_validator_metrics().await {
-            println!("  {} Connected to LOCAL validator", "✓".green());
-            return Ok(metrics);
-        }
-    }
-
-    // Try testnet as fallback
-    if let Ok(interface) = SolanaInterface::new("https://api.testnet.solana.com", validator_keypair, vote_keypair) {
-        println!("  {} Connected to TESTNET validator", "✓".yellow());
-        interface.get_validator_metrics().await
-    } else {
-        Err(anyhow::anyhow!("Failed to connect to any validator"))
+/// Metric as a colored bar, or a plain `label: message` line when non-interactive
+fn show_metric_bar(current: f64, max: f64, label: &str, message: String) {
+    if !crate::progress::is_interactive() {
+        println!("{:<15} {}", label, message);
+        return;
     }
+    let bar = create_progress_bar(current, max, label);
+    bar.set_message(message);
+    bar.finish();
 }
 
 fn create_progress_bar(current: f64, max: f64, label: &str) -> ProgressBar {
@@ -428,13 +1012,3 @@ fn display_system_info() -> Result<()> {
     
     Ok(())
 }
-
-fn format_number(n: u64) -> String {
-    if n >= 1_000_000 {
-        format!("{:.1}M", n as f64 / 1_000_000.0)
-    } else if n >= 1_000 {
-        format!("{:.0}K", n as f64 / 1_000.0)
-    } else {
-        n.to_string()
-    }
-}
@@ -0,0 +1,116 @@
+use anyhow::{Context, Result};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, write_keypair_file, Keypair, Signer};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use sysinfo::System;
+
+static NO_CLI: AtomicBool = AtomicBool::new(false);
+
+/// Replace calls to external binaries (`solana`, `solana-keygen`, `pgrep`,
+/// `which`, `kill`, `ulimit`, `renice`, `sysctl`) with native code (`--no-cli`)
+pub fn set_no_cli(enabled: bool) {
+    NO_CLI.store(enabled, Ordering::Relaxed);
+}
+
+pub fn no_cli() -> bool {
+    NO_CLI.load(Ordering::Relaxed)
+}
+
+/// PIDs of running `solana-validator` processes, from the process table
+pub fn validator_pids() -> Vec<u32> {
+    let mut system = System::new();
+    system.refresh_processes();
+    system
+        .processes()
+        .iter()
+        .filter(|(_, process)| {
+            // Linux truncates process names to 15 characters, so check the executable too
+            process.name() == "solana-validator"
+                || process.exe().and_then(|exe| exe.file_name()).is_some_and(|name| name == "solana-validator")
+        })
+        .map(|(pid, _)| pid.as_u32())
+        .collect()
+}
+
+/// Public key of a keypair file (instead of `solana address` / `solana-keygen pubkey`)
+pub fn keypair_pubkey(path: &Path) -> Result<Pubkey> {
+    read_keypair_file(path)
+        .map(|keypair| keypair.pubkey())
+        .map_err(|e| anyhow::anyhow!("Failed to read keypair {}: {}", path.display(), e))
+}
+
+/// Write a new keypair to `path` (instead of `solana-keygen new`)
+pub fn generate_keypair(path: &Path) -> Result<Pubkey> {
+    let keypair = Keypair::new();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_keypair_file(&keypair, path)
+        .map_err(|e| anyhow::anyhow!("Failed to write keypair {}: {}", path.display(), e))?;
+    Ok(keypair.pubkey())
+}
+
+/// First executable named `binary` on `PATH` (instead of `which`)
+pub fn find_in_path(binary: &str) -> Option<PathBuf> {
+    use std::os::unix::fs::PermissionsExt;
+
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(binary))
+        .find(|path| {
+            fs::metadata(path).is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        })
+}
+
+/// Send SIGTERM to `pid` (instead of `kill -TERM`)
+pub fn terminate(pid: u32) -> Result<()> {
+    use nix::sys::signal::{kill, Signal};
+    use nix::unistd::Pid;
+
+    kill(Pid::from_raw(pid as i32), Signal::SIGTERM).with_context(|| format!("Failed to signal PID {}", pid))
+}
+
+/// Raise the soft open-files limit towards `limit`, capped by the hard limit
+/// (instead of `ulimit -n`); returns the limit now in effect
+pub fn raise_open_files_limit(limit: u64) -> Result<u64> {
+    use nix::sys::resource::{getrlimit, setrlimit, Resource};
+
+    let (_, hard) = getrlimit(Resource::RLIMIT_NOFILE).context("Failed to read open files limit")?;
+    let soft = limit.min(hard);
+    setrlimit(Resource::RLIMIT_NOFILE, soft, hard).context("Failed to set open files limit")?;
+    Ok(soft)
+}
+
+/// The soft open-files limit in effect (instead of `ulimit -n`)
+pub fn open_files_limit() -> Option<u64> {
+    use nix::sys::resource::{getrlimit, Resource};
+
+    getrlimit(Resource::RLIMIT_NOFILE).ok().map(|(soft, _)| soft)
+}
+
+/// Whether this process runs as root, which kernel tuning and high priority need
+pub fn is_root() -> bool {
+    // SAFETY: geteuid takes no arguments and cannot fail
+    unsafe { libc::geteuid() == 0 }
+}
+
+/// Set this process's nice value (instead of `renice`); negative values need root
+pub fn set_niceness(niceness: i32) -> Result<()> {
+    // SAFETY: setpriority only reads its integer arguments
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, niceness) };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error()).context("setpriority failed");
+    }
+    Ok(())
+}
+
+/// Write a kernel parameter through /proc/sys (instead of `sysctl -w`); needs root
+pub fn write_sysctl(key: &str, value: &str) -> Result<()> {
+    if !cfg!(target_os = "linux") {
+        anyhow::bail!("Setting {} without sysctl is only supported on Linux", key);
+    }
+    let path = Path::new("/proc/sys").join(key.replace('.', "/"));
+    fs::write(&path, value).with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -1,60 +1,410 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
 use colored::Colorize;
 use tokio::time::{sleep, Duration};
-use indicatif::{ProgressBar, ProgressStyle};
+use std::io::{IsTerminal, Write};
+use std::net::SocketAddr;
 use std::sync::Arc;
 use parking_lot::RwLock;
 
+use crate::audit::{self, AuditEvent};
 use crate::config::{ValidatorConfig, OptimizationConfig};
-use crate::system::{SystemOptimizer, SystemMonitor};
-use crate::blockchain::{SolanaInterface, ValidatorMetrics};
+use crate::config_reload;
+use crate::history;
+use crate::live_config::{self, Reload};
+use crate::real_optimizer::ConfigUpdate;
+use crate::blockchain::SolanaInterface;
+use crate::remote::SshHost;
+use crate::system::{print_sysctl_summary, SysctlOutcome, SystemOptimizer, SystemMonitor, LINUX_NETWORK_SYSCTLS};
+use crate::tls;
+use crate::connect::{connect_validator, read_or_disconnect, KeypairUse};
+use crate::progress::StepProgress;
 
-pub async fn run(auto: bool) -> Result<()> {
+/// One step of the one-time optimization, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptimizeStep {
+    Analysis,
+    Network,
+    Threads,
+    VoteTiming,
+    Snapshots,
+}
+
+impl OptimizeStep {
+    pub const ALL: [OptimizeStep; 5] = [
+        OptimizeStep::Analysis,
+        OptimizeStep::Network,
+        OptimizeStep::Threads,
+        OptimizeStep::VoteTiming,
+        OptimizeStep::Snapshots,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OptimizeStep::Analysis => "analysis",
+            OptimizeStep::Network => "network",
+            OptimizeStep::Threads => "threads",
+            OptimizeStep::VoteTiming => "vote-timing",
+            OptimizeStep::Snapshots => "snapshots",
+        }
+    }
+
+    fn message(&self) -> &'static str {
+        match self {
+            OptimizeStep::Analysis => "Analyzing current performance...",
+            OptimizeStep::Network => "Applying network optimizations...",
+            OptimizeStep::Threads => "Optimizing thread configuration...",
+            OptimizeStep::VoteTiming => "Tuning vote timing...",
+            OptimizeStep::Snapshots => "Adjusting snapshot strategy...",
+        }
+    }
+
+    /// (field, value, expected impact) the step sets in `optimization`
+    fn targets(&self) -> &'static [(&'static str, &'static str, &'static str)] {
+        match self {
+            OptimizeStep::Analysis | OptimizeStep::Network => &[],
+            OptimizeStep::Threads => &[
+                ("rpc_threads", "32", "Better parallel RPC processing"),
+                ("accounts_db_threads", "16", "Faster accounts DB reads and flushes"),
+            ],
+            OptimizeStep::VoteTiming => &[("tpu_coalesce_ms", "1", "Faster vote submission")],
+            OptimizeStep::Snapshots => &[
+                ("incremental_snapshot_interval", "100", "Smaller incremental snapshots, less I/O per snapshot"),
+                ("full_snapshot_interval", "25000", "Fewer full snapshots"),
+            ],
+        }
+    }
+
+    /// The step's targets that differ from `opt`, as changes to it
+    fn proposals(&self, opt: &OptimizationConfig) -> Vec<ConfigUpdate> {
+        let current = live_config::optimization_values(opt);
+        self.targets()
+            .iter()
+            .filter_map(|&(field, value, impact)| {
+                let (_, old) = current.iter().find(|(name, _)| *name == field)?;
+                (old != value).then(|| ConfigUpdate {
+                    parameter: field.to_string(),
+                    old_value: old.clone(),
+                    new_value: value.to_string(),
+                    expected_impact: impact.to_string(),
+                    requires_restart: !matches!(live_config::reload_for(field), Some(Reload::AdminRpc(_) | Reload::Sysctl(_))),
+                })
+            })
+            .collect()
+    }
+
+    async fn run(&self) -> Result<()> {
+        match self {
+            OptimizeStep::Analysis => analyze_performance().await,
+            OptimizeStep::Network => apply_network_optimizations(),
+            OptimizeStep::Threads => optimize_threads(),
+            OptimizeStep::VoteTiming => tune_vote_timing(),
+            OptimizeStep::Snapshots => adjust_snapshots(),
+        }
+    }
+}
+
+impl std::str::FromStr for OptimizeStep {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        OptimizeStep::ALL
+            .into_iter()
+            .find(|step| step.name() == s.trim())
+            .ok_or_else(|| format!(
+                "unknown optimization step '{}' (valid: {})",
+                s,
+                OptimizeStep::ALL.map(|step| step.name()).join(", ")
+            ))
+    }
+}
+
+/// Run the optimizer; `only` limits the one-time run to those steps (all when empty),
+/// `interactive` asks before each of its changes, `max_iterations` and
+/// `max_duration` bound the continuous one
+pub async fn run(
+    auto: bool,
+    interactive: bool,
+    only: &[OptimizeStep],
+    max_iterations: Option<u32>,
+    max_duration: Option<Duration>,
+) -> Result<()> {
     if auto {
         println!("{}", "Starting Auto-Optimizer (Continuous Mode)...".cyan().bold());
-        auto_optimize_loop().await
+        auto_optimize_loop(max_iterations, max_duration).await
+    } else if interactive {
+        println!("{}", "Running Interactive Optimization...".cyan().bold());
+        optimize_interactively(only).await
     } else {
         println!("{}", "Running One-Time Optimization...".cyan().bold());
-        optimize_once().await
+        optimize_once(only).await
     }
 }
 
-async fn optimize_once() -> Result<()> {
-    let pb = create_optimization_progress();
-    
-    // Step 1: Analyze current performance
-    pb.set_message("Analyzing current performance...");
-    analyze_performance().await?;
-    pb.inc(20);
-    
-    // Step 2: Apply network optimizations
-    pb.set_message("Applying network optimizations...");
-    apply_network_optimizations()?;
-    pb.inc(20);
-    
-    // Step 3: Optimize thread configuration
-    pb.set_message("Optimizing thread configuration...");
-    optimize_threads()?;
-    pb.inc(20);
-    
-    // Step 4: Tune vote timing
-    pb.set_message("Tuning vote timing...");
-    tune_vote_timing()?;
-    pb.inc(20);
-    
-    // Step 5: Adjust snapshot strategy
-    pb.set_message("Adjusting snapshot strategy...");
-    adjust_snapshots()?;
-    pb.inc(20);
-    
+async fn optimize_once(only: &[OptimizeStep]) -> Result<()> {
+    let steps: Vec<OptimizeStep> = OptimizeStep::ALL
+        .into_iter()
+        .filter(|step| only.is_empty() || only.contains(step))
+        .collect();
+    let mut pb = create_optimization_progress(steps.len() as u64);
+
+    for step in &steps {
+        pb.set_message(step.message());
+        step.run().await?;
+        pb.inc(1);
+    }
+
     pb.finish_with_message("✅ Optimization complete!");
-    
-    display_optimization_results();
-    
+
+    display_optimization_results(&steps);
+
     Ok(())
 }
 
-async fn auto_optimize_loop() -> Result<()> {
+/// Answer to the `optimize --interactive` prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Choice {
+    Apply,
+    Skip,
+    Quit,
+}
+
+/// Ask until the answer is apply, skip or quit; end of input counts as quit
+fn prompt_choice() -> Result<Choice> {
+    let stdin = std::io::stdin();
+    loop {
+        print!("  {} ", "[a]pply / [s]kip / [q]uit:".bold());
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        if stdin.read_line(&mut answer)? == 0 {
+            println!();
+            return Ok(Choice::Quit);
+        }
+        match answer.trim().to_lowercase().as_str() {
+            "a" | "apply" => return Ok(Choice::Apply),
+            "s" | "skip" => return Ok(Choice::Skip),
+            "q" | "quit" => return Ok(Choice::Quit),
+            other => println!("  {} '{}' is not a, s or q", "⚠".yellow(), other),
+        }
+    }
+}
+
+fn print_proposal(update: &ConfigUpdate) {
+    println!("\n{} {}: {} → {}",
+        "▶".cyan(), update.parameter.yellow(), update.old_value.red(), update.new_value.green());
+    println!("├─ Expected: {}", update.expected_impact.cyan());
+    if update.requires_restart {
+        println!("└─ Restart: {}", "required for the validator to pick it up".yellow());
+    } else {
+        println!("└─ Restart: {}", "not needed, `apply --live` pushes it".green());
+    }
+}
+
+/// `optimize --interactive`: the one-time run, asking before each change
+///
+/// Applied changes are saved as they are accepted; skipped ones are recorded
+/// in the history and left as they are. Quitting keeps what was applied.
+async fn optimize_interactively(only: &[OptimizeStep]) -> Result<()> {
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!("optimize --interactive needs a terminal to prompt on; run `optimize` without it to apply every change");
+    }
+
+    let mut config = ValidatorConfig::load()?;
+    let mut applied = Vec::new();
+    let mut skipped = Vec::new();
+    let mut quit = false;
+
+    let steps = OptimizeStep::ALL.into_iter().filter(|step| only.is_empty() || only.contains(step));
+    for step in steps {
+        if step.targets().is_empty() {
+            step.run().await?;
+            continue;
+        }
+
+        println!("\n{} {}", "▶".cyan(), step.message());
+        let proposals = step.proposals(&config.optimization);
+        if proposals.is_empty() {
+            println!("  {} Already set", "✓".green());
+        }
+        for update in proposals {
+            print_proposal(&update);
+            match prompt_choice()? {
+                Choice::Apply => {
+                    live_config::set_optimization_value(&mut config.optimization, &update.parameter, &update.new_value)?;
+                    config.save()?;
+                    println!("  {} Saved", "✓".green());
+                    audit_saved(std::slice::from_ref(&update), "optimize --interactive");
+                    applied.push(update);
+                }
+                Choice::Skip => {
+                    if let Err(e) = history::record_skipped(&update.parameter, &update.new_value, "optimize --interactive") {
+                        println!("  {} Could not record the skip in the history: {}", "⚠".yellow(), e);
+                    }
+                    println!("  {} Skipped", "ℹ".cyan());
+                    skipped.push(update);
+                }
+                Choice::Quit => {
+                    quit = true;
+                    break;
+                }
+            }
+        }
+        if quit {
+            break;
+        }
+    }
+
+    println!("\n{}", "=== Interactive Optimization Summary ===".cyan().bold());
+    if quit {
+        println!("{} Quit; the remaining changes were not offered", "ℹ".cyan());
+    }
+    println!("Applied: {} | Skipped: {}", applied.len(), skipped.len());
+    for (i, (update, verb)) in applied.iter().map(|u| (u, "applied".green()))
+        .chain(skipped.iter().map(|u| (u, "skipped".yellow())))
+        .enumerate()
+    {
+        let branch = if i + 1 == applied.len() + skipped.len() { "└─" } else { "├─" };
+        println!("{} {}: {} → {} ({})", branch, update.parameter.bold(), update.old_value, update.new_value, verb);
+    }
+    if applied.iter().any(|update| update.requires_restart) {
+        println!("\n{} Restart the validator for the applied changes to take effect:", "⚠".yellow());
+        println!("   {}", "solana-validator-optimizer stop && solana-validator-optimizer start".cyan());
+    } else if !applied.is_empty() {
+        println!("\n{} Push the applied changes with {}", "ℹ".cyan(), "apply --live".cyan());
+    }
+    Ok(())
+}
+
+/// Chain metrics for the validator behind `rpc_url`, read from this machine;
+/// its identity comes from the RPC, so no keypair is needed
+async fn analyze_over_rpc(rpc_url: &str) -> Result<()> {
+    let client = tls::rpc_client(rpc_url, CommitmentConfig::confirmed());
+    let identity = client.get_identity().with_context(|| format!("{} not answering", rpc_url))?;
+    let node = identity.to_string();
+    let accounts = client.get_vote_accounts().context("Failed to get vote accounts")?;
+    let vote_account: Pubkey = accounts.current.into_iter()
+        .chain(accounts.delinquent)
+        .find(|account| account.node_pubkey == node)
+        .with_context(|| format!("No vote account for identity {}", identity))?
+        .vote_pubkey
+        .parse()?;
+
+    let metrics = SolanaInterface::new(rpc_url, Keypair::new(), Keypair::new())?
+        .watching(identity, vote_account)
+        .get_validator_metrics()
+        .await?;
+    let icon = if metrics.vote_success_rate < 90.0 { "⚠".yellow() } else { "✓".green() };
+    println!("    {} Vote Success: {:.1}%, skip rate {:.1}%, vote lag {}",
+        icon, metrics.vote_success_rate, metrics.skip_rate, metrics.vote_lag);
+    Ok(())
+}
+
+/// Set the network kernel parameters on `host`, reading each first
+fn apply_remote_sysctls(host: &mut SshHost, triggered_by: &str) -> Result<()> {
+    let mut outcomes = Vec::new();
+    for &(key, value) in LINUX_NETWORK_SYSCTLS {
+        let old = host.read_sysctl(key);
+        if old.as_deref() == Some(value) {
+            println!("    {} {}: {} (already set)", "✓".green(), key, value);
+            outcomes.push(SysctlOutcome::applied(key, value));
+            continue;
+        }
+        let output = host.write_sysctl(key, value)?;
+        if output.success() {
+            println!("    {} {}: {}", "✓".green(), key, value);
+            let verified = host.read_sysctl(key).as_deref() == Some(value);
+            audit::record_or_warn(&AuditEvent::new(key, old, value, "network tuning", triggered_by).verified(verified));
+            outcomes.push(SysctlOutcome::applied(key, value));
+        } else {
+            outcomes.push(SysctlOutcome::failed(key, value, output.reason()));
+        }
+    }
+    print_sysctl_summary(&outcomes);
+    Ok(())
+}
+
+/// The RPC of a validator on `hostname` configured with `config`, as seen
+/// from this machine; None when it only listens on the host's loopback
+fn remote_rpc_url(hostname: &str, config: &ValidatorConfig) -> Option<String> {
+    let ip = config.rpc_bind_address;
+    if ip.is_loopback() {
+        None
+    } else if ip.is_unspecified() {
+        Some(format!("http://{}:{}", hostname, config.rpc_port))
+    } else {
+        Some(format!("http://{}", SocketAddr::new(ip, config.rpc_port)))
+    }
+}
+
+/// `optimize --ssh`: the one-time run against a validator on another host
+///
+/// Kernel parameters and the config file at `remote_config` are changed on
+/// the host over ssh; chain metrics are read from this machine over RPC, at
+/// `rpc_url` or the host's configured RPC bind address and port.
+pub async fn optimize_remote(target: &str, remote_config: &str, rpc_url: Option<&str>, only: &[OptimizeStep]) -> Result<()> {
+    let steps: Vec<OptimizeStep> = OptimizeStep::ALL
+        .into_iter()
+        .filter(|step| only.is_empty() || only.contains(step))
+        .collect();
+    let mut host = SshHost::new(target);
+    host.connect()?;
+    let mut config = host.read_config(remote_config)?;
+    // Only the analysis reads the RPC
+    let rpc_url = match rpc_url {
+        _ if !steps.contains(&OptimizeStep::Analysis) => None,
+        Some(url) => Some(url.to_string()),
+        None => Some(remote_rpc_url(host.hostname(), &config).ok_or_else(|| anyhow::anyhow!(
+            "The RPC on {} only listens on its loopback (rpc_bind_address {}); pass --rpc-url, e.g. a local end of `ssh -L 8899:127.0.0.1:{} {}`",
+            target, config.rpc_bind_address, config.rpc_port, target
+        ))?),
+    };
+
+    println!("{}", "=== Remote Optimization ===".cyan().bold());
+    println!("├─ Host: {}", target.yellow());
+    if let Some(rpc_url) = &rpc_url {
+        println!("├─ Local (this machine): chain metrics over RPC from {}", rpc_url);
+    }
+    println!("└─ Remote (over ssh): kernel parameters and the config file {}", remote_config);
+
+    let triggered_by = format!("optimize --ssh {}", target);
+    let mut updates = Vec::new();
+    for step in &steps {
+        match step {
+            OptimizeStep::Analysis => {
+                println!("\n  {} [local] {}", "▶".cyan(), step.message());
+                let rpc_url = rpc_url.as_deref().unwrap_or_default();
+                if let Err(e) = analyze_over_rpc(rpc_url).await {
+                    println!("    {} No metrics: {:#}", "⚠".yellow(), e);
+                }
+            }
+            OptimizeStep::Network => {
+                println!("\n  {} [remote] {}", "▶".cyan(), step.message());
+                apply_remote_sysctls(&mut host, &triggered_by)?;
+            }
+            OptimizeStep::Threads | OptimizeStep::VoteTiming | OptimizeStep::Snapshots => {
+                println!("\n  {} [remote] {}", "▶".cyan(), step.message());
+                updates.extend(set_targets(&mut config, *step)?);
+            }
+        }
+    }
+
+    let config_changed = !updates.is_empty();
+    if config_changed {
+        host.write_config(remote_config, &config)?;
+        println!("\n{} Wrote the changes to {}:{}", "✓".green(), target, remote_config);
+        let written = host.read_config(remote_config).ok();
+        audit_updates(&updates, written.as_ref(), &triggered_by);
+    }
+    host.print_log();
+    if config_changed {
+        println!("\n{} Restart the validator on {} for the config changes to take effect", "ℹ".cyan(), target);
+    }
+    Ok(())
+}
+
+async fn auto_optimize_loop(max_iterations: Option<u32>, max_duration: Option<Duration>) -> Result<()> {
     println!("{}", "🚀 Starting Real Auto-Optimizer (Continuous Mode)...".green().bold());
     println!("Connecting to Solana validator for real-time optimization...");
     println!("Press Ctrl+C to stop\n");
@@ -63,39 +413,16 @@ async fn auto_optimize_loop() -> Result<()> {
     let config = ValidatorConfig::load()?;
     
     // Try to connect to validator
-    let solana_interface = if let (Ok(validator_keypair), Ok(vote_keypair)) = (
-        solana_sdk::signature::read_keypair_file(&config.identity_keypair).map_err(|e| anyhow::anyhow!("Failed to read validator keypair: {}", e)),
-        solana_sdk::signature::read_keypair_file(&config.vote_account_keypair).map_err(|e| anyhow::anyhow!("Failed to read vote keypair: {}", e))
-    ) {
-        // Try local validator first
-        match SolanaInterface::new("http://127.0.0.1:8899", validator_keypair, vote_keypair) {
-            Ok(interface) => Some(interface),
-            Err(_) => {
-                // Fallback to testnet - need to read keypairs again since they were moved
-                match (
-                    solana_sdk::signature::read_keypair_file(&config.identity_keypair).map_err(|e| anyhow::anyhow!("Failed to read validator keypair: {}", e)),
-                    solana_sdk::signature::read_keypair_file(&config.vote_account_keypair).map_err(|e| anyhow::anyhow!("Failed to read vote keypair: {}", e))
-                ) {
-                    (Ok(validator_keypair), Ok(vote_keypair)) => {
-                        println!("{} Local validator not found, connecting to testnet...", "⚠".yellow());
-                        SolanaInterface::new("https://api.testnet.solana.com", validator_keypair, vote_keypair).ok()
-                    }
-                    _ => None
-                }
-            }
-        }
-    } else {
-        println!("{} Keypairs not found, generating new ones...", "⚠".yellow());
-        // Generate temporary keypairs for testing
-        let validator_keypair = solana_sdk::signature::Keypair::new();
-        let vote_keypair = solana_sdk::signature::Keypair::new();
-        SolanaInterface::new("https://api.testnet.solana.com", validator_keypair, vote_keypair).ok()
-    };
+    let solana_interface = read_or_disconnect(&config, || async {
+        connect_validator(&config, KeypairUse::ReadOnly)
+    }).await?;
     
     match solana_interface {
         Some(interface) => {
-            println!("{} Connected to validator, starting auto-optimization loop...", "✅".green());
-            interface.auto_optimize_loop().await
+            println!("{} Connected to validator at {}, starting auto-optimization loop...", "✅".green(), interface.rpc_url());
+            let shared = config_reload::shared(config);
+            config_reload::reload_on_sighup(shared.clone())?;
+            interface.auto_optimize_loop(max_iterations, max_duration, &shared).await
         }
         None => {
             println!("{} No validator connection available", "⚠".yellow());
@@ -163,7 +490,10 @@ async fn analyze_performance() -> Result<()> {
     println!("  {} Analyzing current performance metrics...", "▶".cyan());
     
     // Check current vote success rate
-    let vote_success = get_current_vote_success().await?;
+    let Some(vote_success) = get_current_vote_success().await? else {
+        println!("    {} Vote Success: unknown, no validator connected", "ℹ".cyan());
+        return Ok(());
+    };
     
     if vote_success < 90.0 {
         println!("    {} Vote Success: {:.1}% ({})", 
@@ -192,64 +522,78 @@ fn apply_network_optimizations() -> Result<()> {
     Ok(())
 }
 
-fn optimize_threads() -> Result<()> {
-    println!("  {} Optimizing thread configuration...", "▶".cyan());
-    
+/// Set the step's targets in `config`, showing what changed; the changes made
+fn set_targets(config: &mut ValidatorConfig, step: OptimizeStep) -> Result<Vec<ConfigUpdate>> {
+    let proposals = step.proposals(&config.optimization);
+    if proposals.is_empty() {
+        println!("    {} Already set", "✓".green());
+    }
+    for update in &proposals {
+        live_config::set_optimization_value(&mut config.optimization, &update.parameter, &update.new_value)?;
+        println!("    {} {}: {} → {}", "✓".green(), update.parameter, update.old_value, update.new_value);
+    }
+    Ok(proposals)
+}
+
+/// Audit-log `updates` saved to the config file, each checked against the file
+fn audit_saved(updates: &[ConfigUpdate], triggered_by: &str) {
+    audit_updates(updates, ValidatorConfig::load().ok().as_ref(), triggered_by);
+}
+
+/// Audit config updates, verified against the config as read back after writing
+fn audit_updates(updates: &[ConfigUpdate], written: Option<&ValidatorConfig>, triggered_by: &str) {
+    let saved = written
+        .map(|config| live_config::optimization_values(&config.optimization))
+        .unwrap_or_default();
+    for update in updates {
+        let verified = saved.iter().any(|(field, value)| *field == update.parameter && *value == update.new_value);
+        audit::record_or_warn(&AuditEvent::new(
+            &update.parameter, Some(update.old_value.clone()), &update.new_value, &update.expected_impact, triggered_by,
+        ).verified(verified));
+    }
+}
+
+/// Set the step's targets in the config file
+fn apply_targets(step: OptimizeStep) -> Result<()> {
     let mut config = ValidatorConfig::load()?;
-    
-    // Update thread counts
-    config.optimization.rpc_threads = 32;
-    config.optimization.accounts_db_threads = 16;
-    
-    println!("    {} RPC threads: 8 → 32", "✓".green());
-    println!("    {} DB threads: 8 → 16", "✓".green());
-    
+    let updates = set_targets(&mut config, step)?;
     config.save()?;
-    
+    audit_saved(&updates, "optimize");
     Ok(())
 }
 
+fn optimize_threads() -> Result<()> {
+    println!("  {} Optimizing thread configuration...", "▶".cyan());
+    apply_targets(OptimizeStep::Threads)
+}
+
 fn tune_vote_timing() -> Result<()> {
     println!("  {} Tuning vote timing...", "▶".cyan());
-    
-    let mut config = ValidatorConfig::load()?;
-    
-    // Optimize TPU coalesce time
-    config.optimization.tpu_coalesce_ms = 1;
-    
-    println!("    {} TPU coalesce: 5ms → 1ms", "✓".green());
+    apply_targets(OptimizeStep::VoteTiming)?;
     println!("    {} Skip wait for vote: Enabled", "✓".green());
-    
-    config.save()?;
-    
     Ok(())
 }
 
 fn adjust_snapshots() -> Result<()> {
     println!("  {} Adjusting snapshot strategy...", "▶".cyan());
-    
-    let mut config = ValidatorConfig::load()?;
-    
-    // Optimize snapshot intervals
-    config.optimization.incremental_snapshot_interval = 100;
-    config.optimization.full_snapshot_interval = 25000;
-    
-    println!("    {} Incremental interval: 500 → 100 slots", "✓".green());
+    apply_targets(OptimizeStep::Snapshots)?;
     println!("    {} Compression: none → zstd", "✓".green());
-    
-    config.save()?;
-    
     Ok(())
 }
 
-fn display_optimization_results() {
+fn display_optimization_results(steps: &[OptimizeStep]) {
     println!("\n{}", "✅ Optimizations Applied to Configuration".green().bold());
     println!();
     println!("The following configuration changes have been saved:");
-    println!("   • Network: UDP buffers increased, TCP Fast Open enabled");
-    println!("   • Threads: RPC=32, DB=16");
-    println!("   • Voting: TPU coalesce=1ms, skip-wait enabled");
-    println!("   • Snapshots: Interval=100 slots, compression=zstd");
+    for step in steps {
+        match step {
+            OptimizeStep::Analysis => {}
+            OptimizeStep::Network => println!("   • Network: UDP buffers increased, TCP Fast Open enabled"),
+            OptimizeStep::Threads => println!("   • Threads: RPC=32, DB=16"),
+            OptimizeStep::VoteTiming => println!("   • Voting: TPU coalesce=1ms, skip-wait enabled"),
+            OptimizeStep::Snapshots => println!("   • Snapshots: Interval=100 slots, compression=zstd"),
+        }
+    }
     println!();
 
     println!("{}", "⚠ To see REAL performance improvements:".yellow().bold());
@@ -271,43 +615,19 @@ fn display_optimization_results() {
     println!("• Performance improvements take time to materialize");
 }
 
-/// Get real vote success rate from running validator
-async fn get_current_vote_success() -> Result<f64> {
+/// Real vote success rate from the running validator; None for the baseline
+/// when none answers (see `on_disconnect`)
+async fn get_current_vote_success() -> Result<Option<f64>> {
     // Load validator config to get keypairs
     let config = ValidatorConfig::load()?;
 
-    // Try to read keypairs
-    let validator_keypair = solana_sdk::signature::read_keypair_file(&config.identity_keypair)
-        .map_err(|e| anyhow::anyhow!("Failed to read validator keypair: {}", e))?;
-    let vote_keypair = solana_sdk::signature::read_keypair_file(&config.vote_account_keypair)
-        .map_err(|e| anyhow::anyhow!("Failed to read vote keypair: {}", e))?;
-
-    // Try local validator first
-    if let Ok(interface) = SolanaInterface::new("http://127.0.0.1:8899", validator_keypair.insecure_clone(), vote_keypair.insecure_clone()) {
-        if let Ok(metrics) = interface.get_validator_metrics().await {
-            return Ok(metrics.vote_success_rate);
-        }
-    }
-
-    // Try testnet as fallback
-    if let Ok(interface) = SolanaInterface::new("https://api.testnet.solana.com", validator_keypair, vote_keypair) {
-        if let Ok(metrics) = interface.get_validator_metrics().await {
-            return Ok(metrics.vote_success_rate);
-        }
-    }
-
-    // Return baseline if no validator found (not fake optimized value)
-    println!("  {} No validator found - returning baseline", "⚠".yellow());
-    Ok(85.0) // Baseline unoptimized
+    let read = || async {
+        let interface = connect_validator(&config, KeypairUse::ReadOnly)?;
+        interface.get_validator_metrics().await
+    };
+    Ok(read_or_disconnect(&config, read).await?.map(|metrics| metrics.vote_success_rate))
 }
 
-fn create_optimization_progress() -> ProgressBar {
-    let pb = ProgressBar::new(100);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {percent}% {msg}")
-            .expect("Failed to create progress style")
-            .progress_chars("#>-")
-    );
-    pb
+fn create_optimization_progress(steps: u64) -> StepProgress {
+    StepProgress::new(steps, "[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
 }
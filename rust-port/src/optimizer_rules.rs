@@ -0,0 +1,350 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+use crate::real_optimizer::{ConfigUpdate, OptimizationStrategy, PerformanceSnapshot};
+
+/// A user-defined optimizer rules file
+///
+/// ```json
+/// {
+///   "rules": [
+///     {
+///       "name": "More RPC threads when skipping",
+///       "metric": "skip_rate", "op": ">", "threshold": 5.0,
+///       "parameter": "rpc_threads", "value": "32",
+///       "requires_restart": true,
+///       "expected_impact": "Faster replay"
+///     }
+///   ]
+/// }
+/// ```
+///
+/// Unknown metrics, parameters, comparisons and values that don't fit the
+/// parameter are rejected while parsing, so errors carry the line and column.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RulesFile {
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawRule")]
+pub struct Rule {
+    pub name: String,
+    pub metric: RuleMetric,
+    pub op: Comparison,
+    pub threshold: f64,
+    pub parameter: RuleParameter,
+    pub value: String,
+    pub requires_restart: bool,
+    pub expected_impact: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRule {
+    name: Option<String>,
+    metric: RuleMetric,
+    op: Comparison,
+    threshold: f64,
+    parameter: RuleParameter,
+    value: serde_json::Value,
+    #[serde(default)]
+    requires_restart: bool,
+    expected_impact: Option<String>,
+}
+
+/// Snapshot fields a rule can test
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleMetric {
+    VoteSuccessRate,
+    SkipRate,
+    CreditsEarned,
+    VoteLag,
+    NetworkLatencyMs,
+    Tps,
+    CpuUsage,
+    MemoryUsageMb,
+}
+
+impl RuleMetric {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RuleMetric::VoteSuccessRate => "vote_success_rate",
+            RuleMetric::SkipRate => "skip_rate",
+            RuleMetric::CreditsEarned => "credits_earned",
+            RuleMetric::VoteLag => "vote_lag",
+            RuleMetric::NetworkLatencyMs => "network_latency_ms",
+            RuleMetric::Tps => "tps",
+            RuleMetric::CpuUsage => "cpu_usage",
+            RuleMetric::MemoryUsageMb => "memory_usage_mb",
+        }
+    }
+
+    fn read(&self, snapshot: &PerformanceSnapshot) -> f64 {
+        match self {
+            RuleMetric::VoteSuccessRate => snapshot.vote_success_rate,
+            RuleMetric::SkipRate => snapshot.skip_rate,
+            RuleMetric::CreditsEarned => snapshot.credits_earned as f64,
+            RuleMetric::VoteLag => snapshot.vote_lag as f64,
+            RuleMetric::NetworkLatencyMs => snapshot.network_latency_ms as f64,
+            RuleMetric::Tps => snapshot.tps,
+            RuleMetric::CpuUsage => snapshot.cpu_usage as f64,
+            RuleMetric::MemoryUsageMb => snapshot.memory_usage_mb as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Comparison {
+    #[serde(rename = "<")]
+    Less,
+    #[serde(rename = "<=")]
+    LessOrEqual,
+    #[serde(rename = ">")]
+    Greater,
+    #[serde(rename = ">=")]
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    fn holds(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Less => value < threshold,
+            Comparison::LessOrEqual => value <= threshold,
+            Comparison::Greater => value > threshold,
+            Comparison::GreaterOrEqual => value >= threshold,
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+        }
+    }
+}
+
+/// Optimizer parameters a rule can set (the ones `RealOptimizer` knows how to apply)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleParameter {
+    RpcThreads,
+    TpuCoalesceMs,
+    SnapshotInterval,
+    CacheSize,
+    EnableQuic,
+}
+
+impl RuleParameter {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RuleParameter::RpcThreads => "rpc_threads",
+            RuleParameter::TpuCoalesceMs => "tpu_coalesce_ms",
+            RuleParameter::SnapshotInterval => "snapshot_interval",
+            RuleParameter::CacheSize => "cache_size",
+            RuleParameter::EnableQuic => "enable_quic",
+        }
+    }
+
+    /// Normalized value, or why it doesn't fit the parameter
+    fn validate(&self, value: &serde_json::Value) -> std::result::Result<String, String> {
+        let text = match value {
+            serde_json::Value::String(s) => s.trim().to_string(),
+            serde_json::Value::Number(n) => n.to_string(),
+            serde_json::Value::Bool(b) => b.to_string(),
+            other => return Err(format!("value for {} must be a string, number or bool, got {}", self.name(), other)),
+        };
+        let valid = match self {
+            RuleParameter::EnableQuic => text.parse::<bool>().is_ok(),
+            _ => text.parse::<u32>().is_ok_and(|n| n > 0),
+        };
+        if valid {
+            Ok(text)
+        } else if *self == RuleParameter::EnableQuic {
+            Err(format!("value for {} must be true or false, got '{}'", self.name(), text))
+        } else {
+            Err(format!("value for {} must be a positive integer, got '{}'", self.name(), text))
+        }
+    }
+}
+
+impl TryFrom<RawRule> for Rule {
+    type Error = String;
+
+    fn try_from(raw: RawRule) -> std::result::Result<Self, Self::Error> {
+        if !raw.threshold.is_finite() {
+            return Err("threshold must be a finite number".to_string());
+        }
+        let value = raw.parameter.validate(&raw.value)?;
+        let name = raw.name.unwrap_or_else(|| {
+            format!("{} {} {}", raw.metric.name(), raw.op.symbol(), raw.threshold)
+        });
+
+        Ok(Rule {
+            expected_impact: raw.expected_impact.unwrap_or_else(|| format!("Custom rule '{}'", name)),
+            name,
+            metric: raw.metric,
+            op: raw.op,
+            threshold: raw.threshold,
+            parameter: raw.parameter,
+            value,
+            requires_restart: raw.requires_restart,
+        })
+    }
+}
+
+/// Line and column to report for a parse error
+///
+/// Rule-level checks (`TryFrom<RawRule>`) fail after the whole object was
+/// read, so serde points at the token after its closing brace; point at the
+/// rule's opening brace instead.
+fn error_position(contents: &str, e: &serde_json::Error) -> (usize, usize) {
+    let offset: usize = contents
+        .split_inclusive('\n')
+        .take(e.line().saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>()
+        + e.column().saturating_sub(1);
+    let before = contents[..offset.min(contents.len())].trim_end();
+    if !before.ends_with('}') {
+        return (e.line(), e.column());
+    }
+
+    let mut depth = 0;
+    for (index, c) in before.char_indices().rev() {
+        match c {
+            '}' => depth += 1,
+            '{' => depth -= 1,
+            _ => continue,
+        }
+        if depth == 0 {
+            let line_start = before[..index].rfind('\n').map_or(0, |i| i + 1);
+            return (before[..index].matches('\n').count() + 1, index - line_start + 1);
+        }
+    }
+    (e.line(), e.column())
+}
+
+/// Parse and validate a rules file; errors name the file, line and column
+pub fn load_rules(path: &Path) -> Result<Vec<Rule>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read rules file {}", path.display()))?;
+    let file: RulesFile = serde_json::from_str(&contents).map_err(|e| {
+        let (line, column) = error_position(&contents, &e);
+        let message = e.to_string();
+        let message = message.rsplit_once(" at line ").map_or(message.as_str(), |(message, _)| message);
+        let text = contents.lines().nth(line.saturating_sub(1)).unwrap_or_default().trim();
+        anyhow::anyhow!("{}:{}:{}: {}\n    {}", path.display(), line, column, message, text)
+    })?;
+    Ok(file.rules)
+}
+
+/// A rule from the rules file acting as an optimization strategy
+pub struct RuleStrategy {
+    rule: Rule,
+}
+
+impl RuleStrategy {
+    pub fn new(rule: Rule) -> Self {
+        Self { rule }
+    }
+}
+
+impl OptimizationStrategy for RuleStrategy {
+    fn analyze(&self, snapshot: &PerformanceSnapshot) -> Option<ConfigUpdate> {
+        let rule = &self.rule;
+        rule.op.holds(rule.metric.read(snapshot), rule.threshold).then(|| ConfigUpdate {
+            parameter: rule.parameter.name().to_string(),
+            // Filled in from the running config when applied
+            old_value: String::new(),
+            new_value: rule.value.clone(),
+            expected_impact: rule.expected_impact.clone(),
+            requires_restart: rule.requires_restart,
+        })
+    }
+
+    fn name(&self) -> &str {
+        &self.rule.name
+    }
+}
+
+/// `config validate-rules`: parse the rules file and list what each rule does
+pub fn validate_rules_file(path: &Path) -> Result<()> {
+    let rules = load_rules(path)?;
+
+    println!("{} {} rule(s) in {}", "✓".green(), rules.len(), path.display());
+    for (i, rule) in rules.iter().enumerate() {
+        let branch = if i + 1 == rules.len() { "└─" } else { "├─" };
+        println!("{} {}: when {} {} {} → {}={}{}",
+            branch,
+            rule.name.bold(),
+            rule.metric.name(),
+            rule.op.symbol(),
+            rule.threshold,
+            rule.parameter.name(),
+            rule.value,
+            if rule.requires_restart { " (restart)" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Where `error_position` puts the error `contents` fails with
+    fn error_at(contents: &str) -> (usize, usize) {
+        let e = serde_json::from_str::<RulesFile>(contents).unwrap_err();
+        error_position(contents, &e)
+    }
+
+    #[test]
+    fn rules_parse_with_defaults() {
+        let file: RulesFile = serde_json::from_str(r#"{"rules": [
+            {"metric": "skip_rate", "op": ">", "threshold": 5, "parameter": "rpc_threads", "value": 32}
+        ]}"#).unwrap();
+
+        let rule = &file.rules[0];
+        assert_eq!(rule.name, "skip_rate > 5");
+        assert_eq!(rule.value, "32");
+        assert_eq!(rule.expected_impact, "Custom rule 'skip_rate > 5'");
+        assert!(!rule.requires_restart);
+    }
+
+    #[test]
+    fn invalid_rule_points_at_its_opening_brace() {
+        let contents = "{\n  \"rules\": [\n    {\"metric\": \"skip_rate\", \"op\": \">\", \"threshold\": 5,\n     \"parameter\": \"rpc_threads\", \"value\": 0}\n  ]\n}\n";
+        let e = serde_json::from_str::<RulesFile>(contents).unwrap_err();
+        assert!(e.to_string().contains("must be a positive integer"), "{}", e);
+        assert_eq!(error_position(contents, &e), (3, 5));
+
+        let path = std::env::temp_dir().join(format!("rules-{}.json", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        let message = load_rules(&path).unwrap_err().to_string();
+        fs::remove_file(&path).unwrap();
+        assert!(message.starts_with(&format!("{}:3:5: value for rpc_threads must be a positive integer, got '0'\n", path.display())), "{}", message);
+    }
+
+    #[test]
+    fn field_errors_keep_serdes_position() {
+        let contents = "{\"rules\": [\n  {\"metric\": \"slot_height\", \"op\": \">\", \"threshold\": 1,\n   \"parameter\": \"rpc_threads\", \"value\": 8}\n]}";
+        // The unknown metric's closing quote
+        assert_eq!(error_at(contents), (2, 26));
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        assert!(RuleParameter::EnableQuic.validate(&serde_json::json!("yes")).is_err());
+        assert_eq!(RuleParameter::EnableQuic.validate(&serde_json::json!(true)), Ok("true".to_string()));
+        assert!(RuleParameter::CacheSize.validate(&serde_json::json!(-1)).is_err());
+        assert!(RuleParameter::CacheSize.validate(&serde_json::json!([1])).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use colored::Colorize;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use std::fs;
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::config::{MetricField, ValidatorConfig};
+use crate::history::{HistoryEvent, HistoryRecord, MetricSample};
+use crate::monitor::{self, PerformanceMetrics};
+use crate::smoothing::MetricsSmoother;
+
+/// Longest wait between frames; anything longer is a gap in the capture and
+/// is skipped over
+const MAX_FRAME_WAIT: Duration = Duration::from_secs(5);
+
+/// Seconds `gap` takes at `speed`; kept in f64 since a tiny `speed` would
+/// overflow a `Duration`
+fn playback_secs(gap: chrono::Duration, speed: f64) -> f64 {
+    gap.to_std().unwrap_or_default().as_secs_f64() / speed
+}
+
+/// One recorded reading and the events logged since the one before
+struct Frame {
+    timestamp: DateTime<Utc>,
+    /// None when no validator answered
+    metrics: Option<MetricSample>,
+    events: Vec<String>,
+}
+
+/// A CSV cell as the JSON value it most likely is
+fn csv_value(cell: &str) -> serde_json::Value {
+    if let Ok(flag) = cell.parse::<bool>() {
+        return flag.into();
+    }
+    if let Ok(number) = cell.parse::<u64>() {
+        return number.into();
+    }
+    cell.parse::<f64>().map_or_else(|_| cell.into(), Into::into)
+}
+
+/// Rows with a `timestamp` column and `MetricSample` field columns; a row
+/// with every metric cell empty is a reading no validator answered
+fn parse_csv(contents: &str) -> Result<Vec<HistoryRecord>> {
+    let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<&str> = lines.next().context("The CSV is empty")?.split(',').map(str::trim).collect();
+    let time_column = header.iter().position(|column| *column == "timestamp")
+        .context("The CSV needs a timestamp column")?;
+
+    let mut records = Vec::new();
+    for (row, line) in lines.enumerate() {
+        let cells: Vec<&str> = line.split(',').map(str::trim).collect();
+        let timestamp = cells.get(time_column)
+            .and_then(|cell| DateTime::parse_from_rfc3339(cell).ok())
+            .with_context(|| format!("Row {} has no RFC 3339 timestamp", row + 2))?
+            .with_timezone(&Utc);
+        let fields: serde_json::Map<String, serde_json::Value> = header.iter().zip(&cells)
+            .filter(|(column, cell)| **column != "timestamp" && !cell.is_empty())
+            .map(|(column, cell)| (column.to_string(), csv_value(cell)))
+            .collect();
+        let metrics = if fields.is_empty() {
+            None
+        } else {
+            Some(serde_json::from_value(fields.into())
+                .with_context(|| format!("Row {} is not a metrics sample", row + 2))?)
+        };
+        records.push(HistoryRecord { timestamp, event: HistoryEvent::Sample { metrics } });
+    }
+    Ok(records)
+}
+
+/// `history.jsonl` records; lines that aren't one are skipped and counted
+fn parse_jsonl(contents: &str) -> (Vec<HistoryRecord>, usize) {
+    let mut skipped = 0;
+    let records = contents.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let record = serde_json::from_str(line).ok();
+            skipped += usize::from(record.is_none());
+            record
+        })
+        .collect();
+    (records, skipped)
+}
+
+/// The samples of a capture in time order, each carrying the events before it
+fn load(path: &Path) -> Result<Vec<Frame>> {
+    let contents = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut records = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv")) {
+        parse_csv(&contents)?
+    } else {
+        let (records, skipped) = parse_jsonl(&contents);
+        if skipped > 0 {
+            println!("{} Skipped {} line(s) that aren't history records", "⚠".yellow(), skipped);
+        }
+        records
+    };
+    records.sort_by_key(|record| record.timestamp);
+
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut events = Vec::new();
+    for record in records {
+        let event = match record.event {
+            HistoryEvent::Sample { metrics } => {
+                frames.push(Frame { timestamp: record.timestamp, metrics, events: std::mem::take(&mut events) });
+                continue;
+            }
+            HistoryEvent::Optimization { parameter, value, source } => format!("⚙ {} → {} ({})", parameter, value, source),
+            HistoryEvent::Skipped { parameter, value, source } => format!("✗ declined {} → {} ({})", parameter, value, source),
+            HistoryEvent::Failover { from, to, reason } => format!("⚠ RPC failover {} → {} ({})", from, to, reason),
+        };
+        let at = record.timestamp.with_timezone(&Local).format("%H:%M:%S");
+        events.push(format!("{} {}", at, event));
+    }
+    // Events after the last sample still belong to the incident
+    if let Some(last) = frames.last_mut() {
+        last.events.append(&mut events);
+    }
+    Ok(frames)
+}
+
+/// Raw terminal input while waiting for a key; restored on drop
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> Result<Self> {
+        terminal::enable_raw_mode().context("Failed to read keys from the terminal")?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+enum Control {
+    Next,
+    Quit,
+}
+
+/// Wait until `deadline` (forever while paused), handling the playback keys
+fn wait_for_next(deadline: Instant, paused: &mut bool) -> Result<Control> {
+    let _raw = RawMode::enable()?;
+    loop {
+        let status = if *paused { "⏸ paused".yellow() } else { "▶ playing".green() };
+        print!("\r{}  {}\x1B[K", status, "space pause/resume · n step · q quit".dimmed());
+        std::io::stdout().flush()?;
+
+        let timeout = if *paused { Duration::from_secs(3600) } else { deadline.saturating_duration_since(Instant::now()) };
+        if !event::poll(timeout)? {
+            if *paused {
+                continue;
+            }
+            return Ok(Control::Next);
+        }
+        let Event::Key(key) = event::read()? else { continue };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(Control::Quit),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(Control::Quit),
+            KeyCode::Char(' ') | KeyCode::Char('p') => *paused = !*paused,
+            KeyCode::Char('n') | KeyCode::Right => return Ok(Control::Next),
+            _ => {}
+        }
+    }
+}
+
+/// `monitor --replay`: a captured CSV or `history.jsonl` through the
+/// dashboard, at `speed` times the cadence it was recorded at
+pub fn replay(path: &Path, speed: f64, fields: &[MetricField]) -> Result<()> {
+    if !speed.is_finite() || speed <= 0.0 {
+        anyhow::bail!("--speed must be above 0, got {}", speed);
+    }
+    let config = ValidatorConfig::load()?;
+    let frames = load(path)?;
+    if frames.is_empty() {
+        anyhow::bail!("No metric samples in {}", path.display());
+    }
+    // Without a terminal to read keys from, it just plays through
+    let controls = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+    let mut smoother = MetricsSmoother::new(config.metrics_ema_alpha);
+    let mut paused = false;
+
+    for (i, frame) in frames.iter().enumerate() {
+        let mut metrics = frame.metrics.as_ref()
+            .map_or_else(PerformanceMetrics::baseline, |sample| PerformanceMetrics::from_sample(sample, frame.timestamp));
+        if frame.metrics.is_some() {
+            metrics.smoothed = Some(smoother.update(metrics.avg_tps, metrics.skip_rate, metrics.network_latency_ms as f64));
+        }
+
+        print!("\x1B[2J\x1B[1;1H");
+        monitor::print_dashboard_banner();
+        println!("Replay: {} | Sample {}/{} recorded {} | {}× speed",
+            path.display().to_string().cyan(),
+            i + 1,
+            frames.len(),
+            frame.timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string().cyan(),
+            speed
+        );
+        let gap = i.checked_sub(1).map(|previous| frame.timestamp - frames[previous].timestamp);
+        if let Some(gap) = gap.filter(|gap| playback_secs(*gap, speed) > MAX_FRAME_WAIT.as_secs_f64()) {
+            println!("  {} {} gap in the capture before this sample, skipped", "⏭".yellow(), monitor::format_elapsed(gap));
+        }
+        println!();
+        monitor::render_metrics(&config, &metrics, frame.metrics.is_some(), None, fields);
+
+        if !frame.events.is_empty() {
+            println!();
+            println!("{}", "📝 EVENTS SINCE THE LAST SAMPLE".yellow().bold());
+            println!("{}", "================================================================================".dimmed());
+            for event in &frame.events {
+                println!("{}", event);
+            }
+        }
+        println!();
+
+        let Some(next) = frames.get(i + 1) else { break };
+        let wait = Duration::from_secs_f64(
+            playback_secs(next.timestamp - frame.timestamp, speed).min(MAX_FRAME_WAIT.as_secs_f64()));
+        if controls {
+            if let Control::Quit = wait_for_next(Instant::now() + wait, &mut paused)? {
+                println!();
+                return Ok(());
+            }
+        } else {
+            std::thread::sleep(wait);
+        }
+    }
+    println!("{} End of capture ({} samples)", "✓".green(), frames.len());
+    Ok(())
+}
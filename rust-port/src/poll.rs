@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use crate::config::PollIntervalConfig;
+
+/// Refresh interval of commands without a cadence of their own, when
+/// `poll_interval.base_secs` is unset
+pub const DEFAULT_BASE_SECS: u64 = 10;
+
+/// Refresh interval that grows multiplicatively on consecutive RPC errors
+/// and snaps back to the base interval on the next success
+#[derive(Debug, Clone)]
+pub struct AdaptiveInterval {
+    base: Duration,
+    max: Duration,
+    multiplier: f64,
+    current: Duration,
+    consecutive_errors: u32,
+}
+
+impl AdaptiveInterval {
+    /// Backing off from `config`, or from `default_base_secs` when it has no base
+    pub fn new(config: &PollIntervalConfig, default_base_secs: u64) -> Self {
+        let base = Duration::from_secs(config.base_secs_or(default_base_secs));
+        Self {
+            base,
+            max: Duration::from_secs(config.max_secs).max(base),
+            multiplier: config.backoff_multiplier.max(1.0),
+            current: base,
+            consecutive_errors: 0,
+        }
+    }
+
+    /// Interval to wait before the next refresh
+    pub fn current(&self) -> Duration {
+        self.current
+    }
+
+    pub fn consecutive_errors(&self) -> u32 {
+        self.consecutive_errors
+    }
+
+    /// Record a successful refresh and return to the base interval
+    pub fn success(&mut self) -> Duration {
+        self.consecutive_errors = 0;
+        self.current = self.base;
+        self.current
+    }
+
+    /// Record a failed refresh and return the backed-off interval
+    pub fn error(&mut self) -> Duration {
+        // A huge multiplier or interval would overflow Duration
+        self.current = Duration::try_from_secs_f64(self.current.as_secs_f64() * self.multiplier)
+            .unwrap_or(self.max)
+            .min(self.max);
+        self.consecutive_errors += 1;
+        self.current
+    }
+
+    /// Sleep for the current interval
+    pub async fn sleep(&self) {
+        tokio::time::sleep(self.current).await;
+    }
+}
@@ -1,5 +1,8 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::process::{Command, Child, Stdio};
 use std::time::Duration;
@@ -8,10 +11,20 @@ use tokio::time::{sleep, interval};
 use parking_lot::Mutex;
 use nix::sys::signal::{self, Signal};
 use nix::unistd::Pid;
+use solana_client::rpc_client::RpcClient;
+use solana_rpc_client_api::config::RpcLeaderScheduleConfig;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Signer},
+};
 
+use crate::audit::{self, AuditEvent};
 use crate::config::{ValidatorConfig, OptimizationConfig};
 use crate::blockchain::SolanaInterface;
 use crate::system::SystemMonitor;
+use crate::log_rotation::{RotatingLog, spawn_drain};
+use crate::tls;
 
 /// Advanced process manager with hot-reload and real-time optimization
 pub struct ProcessManager {
@@ -28,7 +41,10 @@ pub struct OptimizationState {
     pub last_optimization: std::time::Instant,
     pub current_metrics: ValidatorMetrics,
     pub target_metrics: TargetMetrics,
-    pub optimization_history: Vec<OptimizationEvent>,
+    /// Changes made this run; each is also appended to the audit log
+    pub optimization_history: Vec<AuditEvent>,
+    /// Failed optimization attempts in a row; reset by the first success
+    pub consecutive_failures: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -50,21 +66,12 @@ pub struct TargetMetrics {
     pub max_memory_usage: f32,  // 80%
 }
 
-#[derive(Debug, Clone)]
-pub struct OptimizationEvent {
-    pub timestamp: chrono::DateTime<chrono::Utc>,
-    pub parameter: String,
-    pub old_value: String,
-    pub new_value: String,
-    pub reason: String,
-}
-
 #[derive(Debug)]
 pub enum ManagerCommand {
     StartValidator,
     StopValidator,
     RestartValidator,
-    ApplyConfig(ValidatorConfig),
+    ApplyConfig(Box<ValidatorConfig>),
     EnableAutoOptimize,
     DisableAutoOptimize,
     HotReload(HotReloadParams),
@@ -81,11 +88,17 @@ pub struct HotReloadParams {
 impl ProcessManager {
     pub fn new() -> Result<Self> {
         let (tx, rx) = mpsc::channel(100);
-        
+        let config = ValidatorConfig::load()?;
+        // Optimize toward the same limits the health verdict calls healthy
+        let mut state = OptimizationState::default();
+        state.target_metrics.min_vote_success = config.health.vote_success.degraded;
+        state.target_metrics.max_skip_rate = config.health.skip_rate.degraded;
+        state.target_metrics.max_vote_lag = config.health.vote_lag.degraded as u32;
+
         Ok(Self {
-            config: Arc::new(RwLock::new(ValidatorConfig::load()?)),
+            config: Arc::new(RwLock::new(config)),
             validator_process: Arc::new(Mutex::new(None)),
-            optimization_state: Arc::new(RwLock::new(OptimizationState::default())),
+            optimization_state: Arc::new(RwLock::new(state)),
             command_tx: tx,
             command_rx: Arc::new(Mutex::new(Some(rx))),
         })
@@ -198,9 +211,19 @@ impl ProcessManager {
                             Optimization::HotReload(params) => {
                                 let _ = tx.send(ManagerCommand::HotReload(params)).await;
                             }
-                            Optimization::Restart => {
-                                println!("{}", "Optimization requires restart".yellow());
-                                let _ = tx.send(ManagerCommand::RestartValidator).await;
+                            Optimization::Restart(reason) => {
+                                println!("{} Optimization requires restart: {}", "⚠".yellow(), reason);
+                                if config.read().await.graceful_restart.auto_restart {
+                                    let _ = tx.send(ManagerCommand::RestartValidator).await;
+                                } else {
+                                    match queue_restart(&reason) {
+                                        Ok(()) => println!("  {} Queued - run {} to apply",
+                                            "▶".cyan(),
+                                            "apply --restart".cyan()
+                                        ),
+                                        Err(e) => println!("  {} Failed to queue restart: {}", "✗".red(), e),
+                                    }
+                                }
                             }
                         }
                     }
@@ -236,17 +259,21 @@ impl ProcessManager {
                         Self::stop_validator_internal(&process).await;
                     }
                     ManagerCommand::RestartValidator => {
+                        let cfg = config.read().await.clone();
+                        wait_for_leader_gap(&cfg).await;
                         println!("{}", "Restarting validator...".yellow());
                         Self::stop_validator_internal(&process).await;
                         sleep(Duration::from_secs(2)).await;
                         Self::start_validator_internal(&config, &process).await;
                     }
                     ManagerCommand::ApplyConfig(new_config) => {
-                        *config.write().await = new_config;
+                        *config.write().await = *new_config;
                         println!("{}", "Configuration updated".green());
                     }
                     ManagerCommand::EnableAutoOptimize => {
-                        state.write().await.auto_optimize = true;
+                        let mut state = state.write().await;
+                        state.auto_optimize = true;
+                        state.consecutive_failures = 0;
                         println!("{}", "Auto-optimization enabled".green());
                     }
                     ManagerCommand::DisableAutoOptimize => {
@@ -254,7 +281,8 @@ impl ProcessManager {
                         println!("{}", "Auto-optimization disabled".yellow());
                     }
                     ManagerCommand::HotReload(params) => {
-                        Self::apply_hot_reload(&config, &process, params).await;
+                        let result = Self::apply_hot_reload(&config, &process, &state, params).await;
+                        Self::record_optimization_result(&config, &state, result).await;
                     }
                     ManagerCommand::GetStatus => {
                         let status = Self::get_status_internal(&process, &state).await;
@@ -269,11 +297,17 @@ impl ProcessManager {
     async fn apply_hot_reload(
         config: &Arc<RwLock<ValidatorConfig>>,
         process: &Arc<Mutex<Option<Child>>>,
+        state: &Arc<RwLock<OptimizationState>>,
         params: HotReloadParams,
-    ) {
+    ) -> Result<()> {
         println!("{}", "Applying hot-reload configuration...".cyan());
         
         let has_child = process.lock().is_some();
+        let mut result = Ok(());
+        let mut events = Vec::new();
+        let change = |parameter: &str, old: String, new: String| {
+            AuditEvent::new(parameter, Some(old), &new, "auto-optimization hot reload", "process manager")
+        };
         
         if has_child {
             // Update configuration
@@ -285,6 +319,7 @@ impl ProcessManager {
                     cfg.optimization.rpc_threads, 
                     threads
                 );
+                events.push(change("rpc_threads", cfg.optimization.rpc_threads.to_string(), threads.to_string()));
                 cfg.optimization.rpc_threads = threads;
                 
                 // Send SIGUSR1 to trigger thread pool resize
@@ -297,10 +332,13 @@ impl ProcessManager {
                     cfg.optimization.tpu_coalesce_ms, 
                     coalesce
                 );
+                events.push(change("tpu_coalesce_ms", cfg.optimization.tpu_coalesce_ms.to_string(), coalesce.to_string()));
                 cfg.optimization.tpu_coalesce_ms = coalesce;
                 
                 // Use RPC to update TPU settings
-                Self::update_via_rpc("tpu_coalesce_ms", &coalesce.to_string()).await;
+                if let Err(e) = Self::update_via_rpc("tpu_coalesce_ms", &coalesce.to_string()).await {
+                    result = Err(e);
+                }
             }
             
             if let Some(interval) = params.snapshot_interval {
@@ -309,16 +347,55 @@ impl ProcessManager {
                     cfg.optimization.incremental_snapshot_interval, 
                     interval
                 );
+                events.push(change("incremental_snapshot_interval", cfg.optimization.incremental_snapshot_interval.to_string(), interval.to_string()));
                 cfg.optimization.incremental_snapshot_interval = interval;
                 
                 // Update via admin RPC
-                Self::update_via_rpc("snapshot_interval", &interval.to_string()).await;
+                if let Err(e) = Self::update_via_rpc("snapshot_interval", &interval.to_string()).await {
+                    result = Err(e);
+                }
             }
             
             // Save updated config
             let _ = cfg.save();
             
-            println!("{}", "✓ Hot-reload complete".green());
+            if result.is_ok() {
+                println!("{}", "✓ Hot-reload complete".green());
+            }
+        }
+
+        for event in &events {
+            audit::record_or_warn(event);
+        }
+        state.write().await.optimization_history.extend(events);
+        result
+    }
+
+    /// Count a failed optimization toward the circuit breaker, or reset it on
+    /// success; after `auto_optimize_max_failures` failures in a row
+    /// auto-optimize is switched off until re-enabled by hand
+    async fn record_optimization_result(
+        config: &Arc<RwLock<ValidatorConfig>>,
+        state: &Arc<RwLock<OptimizationState>>,
+        result: Result<()>,
+    ) {
+        let max_failures = config.read().await.auto_optimize_max_failures;
+        let mut state = state.write().await;
+
+        let Err(e) = result else {
+            state.consecutive_failures = 0;
+            return;
+        };
+
+        state.consecutive_failures += 1;
+        println!("{} Optimization failed ({}/{}): {:#}",
+            "✗".red(), state.consecutive_failures, max_failures, e);
+
+        if state.auto_optimize && state.consecutive_failures >= max_failures {
+            state.auto_optimize = false;
+            println!("\n{}", "⛔ AUTO-OPTIMIZE DISABLED".red().bold());
+            println!("  {} consecutive optimizations failed. Last error: {:#}", state.consecutive_failures, e);
+            println!("  Fix the cause, then re-enable auto-optimization.");
         }
     }
     
@@ -332,20 +409,14 @@ impl ProcessManager {
     
     /// Update validator settings via RPC
     async fn update_via_rpc(param: &str, value: &str) -> Result<()> {
-        // Send admin RPC command to validator
-        let output = Command::new("solana-validator")
-            .args(&["--url", "http://127.0.0.1:8899"])
-            .args(&["admin", "set", param, value])
-            .output();
-        
-        match output {
-            Ok(out) if out.status.success() => {
+        match admin_set("http://127.0.0.1:8899", param, value) {
+            Ok(()) => {
                 println!("    {} RPC update successful", "✓".green());
                 Ok(())
             }
-            _ => {
+            Err(e) => {
                 println!("    {} RPC update failed (will apply on restart)", "⚠".yellow());
-                Ok(())
+                Err(e)
             }
         }
     }
@@ -387,7 +458,9 @@ impl ProcessManager {
         // Memory usage optimization
         if metrics.memory_usage > 80.0 {
             // This requires restart to apply memory limits
-            optimizations.push(Optimization::Restart);
+            optimizations.push(Optimization::Restart(
+                format!("memory usage at {:.1}% - restart to apply memory limits", metrics.memory_usage)
+            ));
         }
         
         optimizations
@@ -401,16 +474,57 @@ impl ProcessManager {
         println!("{}", "Starting validator with optimizations...".green());
         
         let cfg = config.read().await;
-        let args = cfg.build_validator_args();
+        let mut args = match cfg.build_validator_args() {
+            Ok(args) => args,
+            Err(e) => {
+                println!("{} Invalid validator config: {:#}", "✗".red(), e);
+                return;
+            }
+        };
+
+        // Open the rotating log before spawning: a validator logging to a
+        // pipe nobody drains blocks once the pipe fills
+        let rotating = if cfg.log_rotation.enabled {
+            match RotatingLog::open(&cfg.log_path, &cfg.log_rotation) {
+                Ok(log) => Some(Arc::new(Mutex::new(log))),
+                Err(e) => {
+                    println!("{} Failed to open validator log, letting the validator write {} unrotated: {}",
+                        "⚠".yellow(), cfg.log_path.display(), e);
+                    for arg in args.iter_mut().filter(|arg| *arg == "--log=-") {
+                        *arg = format!("--log={}", cfg.log_path.display());
+                    }
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let output = || if rotating.is_some() { Stdio::piped() } else { Stdio::null() };
         
         match Command::new("solana-validator")
             .args(&args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
+            .stdout(output())
+            .stderr(output())
             .spawn()
         {
-            Ok(child) => {
+            Ok(mut child) => {
                 let pid = child.id();
+
+                if let Some(log) = rotating {
+                    if let Some(stdout) = child.stdout.take() {
+                        spawn_drain(stdout, log.clone());
+                    }
+                    if let Some(stderr) = child.stderr.take() {
+                        spawn_drain(stderr, log);
+                    }
+                    println!("  {} Logging to {} (rotating at {}MB, keeping {})",
+                        "▶".cyan(),
+                        cfg.log_path.display(),
+                        cfg.log_rotation.max_size_mb,
+                        cfg.log_rotation.max_files
+                    );
+                }
+
                 *process.lock() = Some(child);
                 println!("{} Validator started with PID: {}", "✓".green(), pid);
             }
@@ -503,7 +617,154 @@ impl ProcessManager {
 #[derive(Debug)]
 enum Optimization {
     HotReload(HotReloadParams),
-    Restart,
+    Restart(String),
+}
+
+/// A restart the auto-optimizer wanted but left for the operator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingRestart {
+    pub queued_at: chrono::DateTime<chrono::Utc>,
+    pub reason: String,
+}
+
+fn pending_restarts_path() -> PathBuf {
+    ValidatorConfig::config_dir().join("pending-restarts.json")
+}
+
+/// Restarts queued by the auto-optimizer, oldest first
+pub fn pending_restarts() -> Vec<PendingRestart> {
+    fs::read_to_string(pending_restarts_path())
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Queue a restart for `apply --restart`; a reason already queued is kept once
+pub(crate) fn queue_restart(reason: &str) -> Result<()> {
+    let mut pending = pending_restarts();
+    if pending.iter().any(|p| p.reason == reason) {
+        return Ok(());
+    }
+    pending.push(PendingRestart {
+        queued_at: chrono::Utc::now(),
+        reason: reason.to_string(),
+    });
+
+    fs::create_dir_all(ValidatorConfig::config_dir())?;
+    fs::write(pending_restarts_path(), serde_json::to_string_pretty(&pending)?)
+        .context("Failed to save pending restarts")?;
+    Ok(())
+}
+
+/// Forget queued restarts once the validator has been restarted
+pub fn clear_pending_restarts() -> Result<()> {
+    match fs::remove_file(pending_restarts_path()) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Defer a restart until the validator is clear of its leader slots
+///
+/// Polls the leader schedule until the next leader slot is at least
+/// `min_leader_gap_slots` away, giving up after `max_wait_secs`.
+/// Returns how long the restart was deferred.
+pub async fn wait_for_leader_gap(config: &ValidatorConfig) -> Duration {
+    let rpc_url = format!("http://127.0.0.1:{}", config.rpc_port);
+    let identity = read_keypair_file(&config.identity_keypair).ok().map(|k| k.pubkey());
+    let policy = &config.graceful_restart;
+
+    let Some(identity) = identity else {
+        println!("{} Identity keypair unreadable - restarting without leader check", "⚠".yellow());
+        return Duration::ZERO;
+    };
+
+    let rpc = Arc::new(tls::rpc_client(rpc_url, CommitmentConfig::processed()));
+    let max_wait = Duration::from_secs(policy.max_wait_secs);
+    let started = std::time::Instant::now();
+    let mut deferred = false;
+
+    loop {
+        // The RPC client blocks, so the schedule is read off the runtime's threads
+        let reader = rpc.clone();
+        let gap = tokio::task::spawn_blocking(move || slots_until_next_leader(&reader, &identity))
+            .await
+            .unwrap_or_else(|e| Err(anyhow::anyhow!("Leader schedule task failed: {}", e)));
+        match gap {
+            Ok(Some(gap)) if gap < policy.min_leader_gap_slots => {
+                if !deferred {
+                    println!("{} Leader slot in {} slots - deferring restart (need {} slot gap)",
+                        "⏸".yellow(),
+                        gap,
+                        policy.min_leader_gap_slots
+                    );
+                    deferred = true;
+                }
+            }
+            Ok(_) => break,
+            Err(e) => {
+                println!("{} Could not read leader schedule ({}) - restarting without deferral",
+                    "⚠".yellow(), e);
+                break;
+            }
+        }
+
+        if started.elapsed() >= max_wait {
+            println!("{} Still near leader slots after {}s - restarting anyway",
+                "⚠".yellow().bold(),
+                policy.max_wait_secs
+            );
+            break;
+        }
+
+        sleep(Duration::from_secs(2)).await;
+    }
+
+    let waited = started.elapsed();
+    if deferred {
+        println!("  {} Restart deferred for {:.0}s", "▶".cyan(), waited.as_secs_f64());
+    }
+    waited
+}
+
+/// Slots from now until this identity's next leader slot in the current epoch
+fn slots_until_next_leader(rpc: &RpcClient, identity: &Pubkey) -> Result<Option<u64>> {
+    let epoch_info = rpc.get_epoch_info()?;
+    let current_slot = epoch_info.absolute_slot;
+    let epoch_start = current_slot - epoch_info.slot_index;
+
+    let schedule = rpc.get_leader_schedule_with_config(
+        Some(current_slot),
+        RpcLeaderScheduleConfig {
+            identity: Some(identity.to_string()),
+            commitment: None,
+        },
+    )?;
+
+    let next_leader_slot = schedule
+        .and_then(|s| s.get(&identity.to_string()).cloned())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|index| epoch_start + index as u64)
+        .filter(|slot| *slot >= current_slot)
+        .min();
+
+    Ok(next_leader_slot.map(|slot| slot - current_slot))
+}
+
+/// Push a single setting to the validator behind `rpc_url` over admin RPC
+pub fn admin_set(rpc_url: &str, param: &str, value: &str) -> Result<()> {
+    let output = Command::new("solana-validator")
+        .args(["--url", rpc_url])
+        .args(["admin", "set", param, value])
+        .output()
+        .context("Failed to run solana-validator admin")?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim())
+    }
 }
 
 impl Default for OptimizationState {
@@ -529,6 +790,7 @@ impl Default for OptimizationState {
                 max_memory_usage: 80.0,   // Threshold for resource limits
             },
             optimization_history: Vec::new(),
+            consecutive_failures: 0,
         }
     }
 }
@@ -0,0 +1,64 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static FORCE_NON_INTERACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Disable progress bars regardless of the terminal (`--quiet`)
+pub fn set_non_interactive(enabled: bool) {
+    FORCE_NON_INTERACTIVE.store(enabled, Ordering::Relaxed);
+}
+
+/// Progress bars are drawn only on a TTY and without `--quiet`; redraws
+/// otherwise end up as escape sequences in journald or CI logs
+pub fn is_interactive() -> bool {
+    !FORCE_NON_INTERACTIVE.load(Ordering::Relaxed) && std::io::stdout().is_terminal()
+}
+
+/// Step progress that falls back to one plain line per step when non-interactive
+pub struct StepProgress {
+    bar: Option<ProgressBar>,
+    total: u64,
+    done: u64,
+    message: String,
+}
+
+impl StepProgress {
+    pub fn new(total: u64, template: &str) -> Self {
+        let bar = is_interactive().then(|| {
+            let pb = ProgressBar::new(total);
+            pb.set_style(
+                ProgressStyle::default_bar()
+                    .template(template)
+                    .expect("Failed to create progress style")
+                    .progress_chars("#>-")
+            );
+            pb
+        });
+        Self { bar, total, done: 0, message: String::new() }
+    }
+
+    /// Start a step
+    pub fn set_message(&mut self, message: &str) {
+        self.message = message.trim_end_matches("...").to_string();
+        if let Some(pb) = &self.bar {
+            pb.set_message(message.to_string());
+        }
+    }
+
+    /// Finish the current step, advancing by `delta`
+    pub fn inc(&mut self, delta: u64) {
+        self.done = (self.done + delta).min(self.total);
+        match &self.bar {
+            Some(pb) => pb.inc(delta),
+            None => println!("[{}/{}] {}: done", self.done, self.total, self.message),
+        }
+    }
+
+    pub fn finish_with_message(&self, message: &str) {
+        match &self.bar {
+            Some(pb) => pb.finish_with_message(message.to_string()),
+            None => println!("{}", message),
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use futures::{SinkExt, StreamExt};
+use serde_json::json;
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client_api::config::RpcAccountInfoConfig;
+use solana_rpc_client_api::response::Response as RpcResponse;
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey};
+use solana_vote_program::vote_state::VoteState;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+use crate::alerts::{Alert, Alerter, Severity};
+use crate::config::AlertConfig;
+use crate::tls;
+
+/// How often to poll `getSlot` while the WebSocket is unavailable
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long to stay on polling before trying the WebSocket again
+const WS_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Reconnect backoff bounds for the vote account subscription
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Derive the PubSub WebSocket URL for an RPC endpoint
+///
+/// Follows the validator's convention: `http` becomes `ws`, `https` becomes
+/// `wss`, and an explicit port is bumped by one (8899 -> 8900).
+pub fn ws_url_from_rpc(rpc_url: &str) -> Result<String> {
+    let mut url = Url::parse(rpc_url).with_context(|| format!("Invalid RPC URL: {}", rpc_url))?;
+
+    let scheme = match url.scheme() {
+        "http" => "ws",
+        "https" => "wss",
+        "ws" | "wss" => return Ok(url.to_string()),
+        other => anyhow::bail!("Unsupported RPC URL scheme: {}", other),
+    };
+    url.set_scheme(scheme)
+        .map_err(|_| anyhow::anyhow!("Failed to derive WebSocket URL from {}", rpc_url))?;
+
+    if let Some(port) = url.port() {
+        url.set_port(Some(port + 1))
+            .map_err(|_| anyhow::anyhow!("Failed to derive WebSocket URL from {}", rpc_url))?;
+    }
+
+    Ok(url.to_string())
+}
+
+/// Tracks the cluster's current slot in the background
+///
+/// Prefers a `slotSubscribe` WebSocket stream and falls back to polling
+/// `getSlot` whenever the WebSocket endpoint can't be reached.
+pub struct SlotTracker {
+    slot: Arc<AtomicU64>,
+    live: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl SlotTracker {
+    /// Start tracking; `ws_url` defaults to one derived from `rpc_url`
+    pub fn start(rpc_url: &str, ws_url: Option<&str>) -> Result<Self> {
+        let ws_url = match ws_url {
+            Some(url) => url.to_string(),
+            None => ws_url_from_rpc(rpc_url)?,
+        };
+        let rpc_url = rpc_url.to_string();
+
+        let slot = Arc::new(AtomicU64::new(0));
+        let live = Arc::new(AtomicBool::new(false));
+
+        let handle = tokio::spawn({
+            let slot = slot.clone();
+            let live = live.clone();
+            async move {
+                let rpc_client = tls::nonblocking_rpc_client(rpc_url, tls::DEFAULT_RPC_TIMEOUT, CommitmentConfig::default());
+                loop {
+                    // Errors just mean the WebSocket is unavailable; is_live() reports that
+                    let _ = stream_slots(&ws_url, &slot, &live).await;
+                    live.store(false, Ordering::Relaxed);
+
+                    poll_slots(&rpc_client, &slot).await;
+                }
+            }
+        });
+
+        Ok(Self { slot, live, handle })
+    }
+
+    /// Latest known slot (0 until the first update arrives)
+    pub fn slot(&self) -> u64 {
+        self.slot.load(Ordering::Relaxed)
+    }
+
+    /// Whether updates are currently arriving over the WebSocket
+    pub fn is_live(&self) -> bool {
+        self.live.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for SlotTracker {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// One PubSub subscription on its own WebSocket, opened with the TLS options
+struct Subscription {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl Subscription {
+    /// Connect and call the `method` subscription with `params`
+    async fn open(ws_url: &str, method: &str, params: serde_json::Value) -> Result<Self> {
+        let mut socket = tls::connect_websocket(ws_url).await?;
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        socket.send(Message::Text(request.to_string())).await?;
+
+        loop {
+            let reply = next_json(&mut socket).await?
+                .with_context(|| format!("{} closed before {} was answered", ws_url, method))?;
+            if reply["id"] != 1 {
+                continue;
+            }
+            if let Some(error) = reply.get("error") {
+                anyhow::bail!("{} failed: {}", method, error);
+            }
+            return Ok(Self { socket });
+        }
+    }
+
+    /// The next notification's `result`; None once the socket closes
+    async fn next(&mut self) -> Result<Option<serde_json::Value>> {
+        while let Some(mut message) = next_json(&mut self.socket).await? {
+            if message.get("method").is_some() {
+                return Ok(Some(message["params"]["result"].take()));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// The next text frame as JSON; None once the socket closes
+async fn next_json(socket: &mut WebSocketStream<MaybeTlsStream<TcpStream>>) -> Result<Option<serde_json::Value>> {
+    while let Some(message) = socket.next().await {
+        match message? {
+            Message::Text(text) => return Ok(Some(serde_json::from_str(&text)?)),
+            Message::Close(_) => return Ok(None),
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+/// Stream slot notifications until the subscription ends
+async fn stream_slots(ws_url: &str, slot: &AtomicU64, live: &AtomicBool) -> Result<()> {
+    let mut subscription = Subscription::open(ws_url, "slotSubscribe", json!([])).await?;
+
+    while let Some(info) = subscription.next().await? {
+        if let Some(current) = info["slot"].as_u64() {
+            slot.fetch_max(current, Ordering::Relaxed);
+            live.store(true, Ordering::Relaxed);
+        }
+    }
+
+    anyhow::bail!("slot subscription closed")
+}
+
+/// Poll `getSlot` for one WebSocket retry interval
+async fn poll_slots(rpc_client: &RpcClient, slot: &AtomicU64) {
+    let deadline = tokio::time::Instant::now() + WS_RETRY_INTERVAL;
+    while tokio::time::Instant::now() < deadline {
+        if let Ok(current) = rpc_client.get_slot().await {
+            slot.fetch_max(current, Ordering::Relaxed);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Watch a vote account over `accountSubscribe` and alert on missed votes
+///
+/// Every notification is decoded into a `VoteState`; an alert fires when the
+/// latest voted slot jumps further than `max_vote_gap_slots`, or when the
+/// account goes `vote_stall_secs` without changing at all (once per stall,
+/// until a vote lands again). Dropped
+/// subscriptions are re-established with exponential backoff.
+pub async fn watch_vote_account(
+    rpc_url: &str,
+    ws_url: Option<&str>,
+    vote_account: &Pubkey,
+    alert_config: &AlertConfig,
+) -> Result<()> {
+    let ws_url = match ws_url {
+        Some(url) => url.to_string(),
+        None => ws_url_from_rpc(rpc_url)?,
+    };
+    let alerter = Alerter::new(alert_config);
+
+    println!("├─ Vote account: {}", vote_account);
+    println!("├─ WebSocket: {}", ws_url);
+    println!("└─ Gap threshold: {} slots | Stall after: {}s",
+        alert_config.max_vote_gap_slots,
+        alert_config.vote_stall_secs
+    );
+    println!("\nPress Ctrl+C to stop\n");
+
+    let mut watch = VoteWatch::default();
+    let mut backoff = MIN_BACKOFF;
+
+    loop {
+        match follow_votes(&ws_url, vote_account, alert_config, &alerter, &mut watch, &mut backoff).await {
+            Ok(()) => println!("{} Vote subscription closed", "⚠".yellow()),
+            Err(e) => println!("{} Vote subscription failed: {}", "⚠".yellow(), e),
+        }
+
+        println!("  Reconnecting in {}s...", backoff.as_secs());
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// What `watch_vote_account` knows about the vote account across reconnects
+#[derive(Default)]
+struct VoteWatch {
+    last_vote: Option<u64>,
+    /// The stall alert went out and votes haven't resumed since
+    stalled: bool,
+    /// When the account last changed, or the first subscription came up
+    last_update: Option<Instant>,
+}
+
+/// Run one subscription until it drops, resetting `backoff` once it is up
+async fn follow_votes(
+    ws_url: &str,
+    vote_account: &Pubkey,
+    alert_config: &AlertConfig,
+    alerter: &Alerter,
+    watch: &mut VoteWatch,
+    backoff: &mut Duration,
+) -> Result<()> {
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        ..RpcAccountInfoConfig::default()
+    };
+    let mut subscription = Subscription::open(ws_url, "accountSubscribe", json!([vote_account.to_string(), config])).await?;
+
+    println!("{} Subscribed to vote account updates", "✓".green());
+    *backoff = MIN_BACKOFF;
+    watch.last_update.get_or_insert_with(Instant::now);
+
+    let stall_timeout = Duration::from_secs(alert_config.vote_stall_secs.max(1));
+    loop {
+        let update = match tokio::time::timeout(stall_timeout, subscription.next()).await {
+            Ok(update) => match update? {
+                Some(update) => serde_json::from_value::<RpcResponse<UiAccount>>(update)
+                    .context("Malformed account notification")?,
+                None => break,
+            },
+            // Alert once per stall; a vote landing re-arms it
+            Err(_) if watch.stalled => continue,
+            Err(_) => {
+                watch.stalled = true;
+                let idle_secs = watch.last_update.map_or(stall_timeout, |at| at.elapsed()).as_secs();
+                alerter.send(&Alert::new(
+                    Severity::Critical,
+                    "Vote account stalled",
+                    format!("{} has not changed in {}s (last vote: {})",
+                        vote_account,
+                        idle_secs,
+                        watch.last_vote.map_or("unknown".to_string(), |s| s.to_string())
+                    ),
+                ).with_reading("vote_account_idle_secs", idle_secs as f64, Some(alert_config.vote_stall_secs as f64))).await;
+                continue;
+            }
+        };
+        watch.last_update = Some(Instant::now());
+
+        let Some(account) = update.value.decode::<Account>() else {
+            println!("{} Could not decode vote account data at slot {}", "⚠".yellow(), update.context.slot);
+            continue;
+        };
+        let vote_state = match VoteState::deserialize(&account.data) {
+            Ok(state) => state,
+            Err(e) => {
+                println!("{} Failed to deserialize vote state: {}", "⚠".yellow(), e);
+                continue;
+            }
+        };
+        let Some(voted) = vote_state.last_voted_slot() else {
+            continue;
+        };
+
+        if watch.stalled {
+            watch.stalled = false;
+            alerter.send(&Alert::new(
+                Severity::Info,
+                "Vote account recovered",
+                format!("{} is updating again (latest vote: slot {})", vote_account, voted),
+            ).with_reading("last_voted_slot", voted as f64, None)).await;
+        }
+
+        match watch.last_vote {
+            Some(previous) if voted > previous + alert_config.max_vote_gap_slots => {
+                alerter.send(&Alert::new(
+                    Severity::Warning,
+                    "Vote gap detected",
+                    format!("Latest vote jumped {} slots, from slot {} to {}",
+                        voted - previous, previous, voted
+                    ),
+                ).with_reading("vote_gap_slots", (voted - previous) as f64, Some(alert_config.max_vote_gap_slots as f64))).await;
+            }
+            Some(previous) if voted > previous => {
+                println!("{} Vote landed on slot {} (+{})", "✓".green(), voted, voted - previous);
+            }
+            Some(_) => {}
+            None => println!("{} Latest vote: slot {}", "▶".cyan(), voted),
+        }
+        watch.last_vote = Some(watch.last_vote.map_or(voted, |previous| previous.max(voted)));
+    }
+
+    Ok(())
+}
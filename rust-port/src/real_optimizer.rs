@@ -2,18 +2,37 @@ use anyhow::{Context, Result};
 use colored::Colorize;
 use std::process::Command;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use serde::{Deserialize, Serialize};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
 
+use crate::config::{PollIntervalConfig, StatusBands, ValidatorConfig};
+use crate::connect::{connect_validator, read_or_disconnect, KeypairUse};
+use crate::native;
+use crate::optimizer_rules::{self, Rule, RuleStrategy};
+use crate::poll::{self, AdaptiveInterval};
+use crate::tls;
+
+/// Bumped whenever the persisted snapshot format changes incompatibly
+const HISTORY_FORMAT_VERSION: u32 = 1;
+
+/// A past change counts as harmful when vote success fell, or skip rate rose,
+/// by more than this many percentage points after it
+const DEGRADATION_THRESHOLD_PP: f64 = 1.0;
+
 /// Real-time validator optimizer that achieves documented performance gains
 pub struct RealOptimizer {
     rpc_client: Arc<RpcClient>,
     current_config: Arc<RwLock<OptimizedConfig>>,
-    metrics_history: Arc<RwLock<Vec<PerformanceSnapshot>>>,
+    metrics_history: Arc<RwLock<OptimizerHistory>>,
     optimization_engine: OptimizationEngine,
+    status_bands: StatusBands,
+    output_dir: PathBuf,
+    history_retention: usize,
+    poll_interval: PollIntervalConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,7 +67,9 @@ pub struct OptimizedConfig {
     pub ledger_max_shreds: u64,             // 50M to prevent overflow
 }
 
-#[derive(Debug, Clone)]
+/// Fields missing from older persisted snapshots load as their defaults
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PerformanceSnapshot {
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub vote_success_rate: f64,
@@ -61,11 +82,38 @@ pub struct PerformanceSnapshot {
     pub memory_usage_mb: u64,
 }
 
+/// Snapshots and applied changes the optimizer remembers across runs
+#[derive(Debug, Clone, Default)]
+pub struct OptimizerHistory {
+    pub snapshots: Vec<PerformanceSnapshot>,
+    pub events: Vec<OptimizationEvent>,
+}
+
+/// A `ConfigUpdate` the optimizer applied
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationEvent {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub parameter: String,
+    pub new_value: String,
+}
+
+/// How the metrics moved around a past application of the same change
+#[derive(Debug, Clone)]
+pub struct Degradation {
+    pub applied_at: chrono::DateTime<chrono::Utc>,
+    /// Average vote success rate (%) before and after
+    pub vote_success: (f64, f64),
+    /// Average skip rate (%) before and after
+    pub skip_rate: (f64, f64),
+}
+
 pub struct OptimizationEngine {
     strategies: Vec<Box<dyn OptimizationStrategy>>,
+    /// Window either side of a past change used to judge its effect
+    correlation_window: chrono::Duration,
 }
 
-trait OptimizationStrategy: Send + Sync {
+pub(crate) trait OptimizationStrategy: Send + Sync {
     fn analyze(&self, snapshot: &PerformanceSnapshot) -> Option<ConfigUpdate>;
     fn name(&self) -> &str;
 }
@@ -80,47 +128,104 @@ pub struct ConfigUpdate {
 }
 
 impl RealOptimizer {
-    pub async fn new() -> Result<Self> {
-        let rpc_client = RpcClient::new_with_commitment(
-            "http://127.0.0.1:8899".to_string(),
-            CommitmentConfig::confirmed(),
-        );
+    /// `output_dir` overrides the configured one for `validator-optimized.json`,
+    /// as `--output-dir` does for the other generated files
+    pub async fn new(output_dir: Option<&Path>) -> Result<Self> {
+        let rpc_client = tls::rpc_client("http://127.0.0.1:8899", CommitmentConfig::confirmed());
         
+        let config = ValidatorConfig::load().unwrap_or_default();
+        let output_dir = config.output_dir(output_dir)?;
+        let retention = config.optimizer_history.retention.max(1);
+        // Capped at a year, which also keeps it within `chrono::Duration`
+        let correlation_window = chrono::Duration::seconds(
+            config.optimizer_history.correlation_window_secs.min(365 * 86_400) as i64,
+        );
+
+        let rules = match &config.optimizer_rules {
+            Some(path) => {
+                let rules = optimizer_rules::load_rules(path)?;
+                println!("{} Loaded {} custom rule(s) from {}", "✓".green(), rules.len(), path.display());
+                rules
+            }
+            None => Vec::new(),
+        };
+
+        let history = load_history(&history_path(), retention);
+        if !history.snapshots.is_empty() {
+            println!("{} Resuming with {} snapshot(s) and {} applied change(s) from previous runs",
+                "✓".green(),
+                history.snapshots.len(),
+                history.events.len()
+            );
+        }
+
         Ok(Self {
             rpc_client: Arc::new(rpc_client),
             current_config: Arc::new(RwLock::new(OptimizedConfig::default())),
-            metrics_history: Arc::new(RwLock::new(Vec::new())),
-            optimization_engine: OptimizationEngine::new(),
+            metrics_history: Arc::new(RwLock::new(history)),
+            optimization_engine: OptimizationEngine::new(correlation_window, rules),
+            status_bands: config.status_bands,
+            output_dir,
+            history_retention: retention,
+            poll_interval: config.poll_interval,
         })
     }
     
-    /// Start real-time optimization loop
+    /// Start real-time optimization loop, every `poll_interval.base_secs`
+    /// (10s when unset) and backing off while the RPC fails
     pub async fn start_optimization(&self) -> Result<()> {
         println!("{}", "Starting Real-Time Validator Optimizer".cyan().bold());
         println!("{}", "Target: 97% vote success, <3% skip rate".green());
         
+        let mut refresh = AdaptiveInterval::new(&self.poll_interval, poll::DEFAULT_BASE_SECS);
         loop {
-            // Collect current metrics
-            let snapshot = self.collect_performance_snapshot().await?;
+            // Collect current metrics, backing off while the RPC fails
+            let snapshot = match self.collect_performance_snapshot().await {
+                Ok(snapshot) => {
+                    refresh.success();
+                    snapshot
+                }
+                Err(e) => {
+                    let next = refresh.error();
+                    println!("{} RPC error #{}: {:#} - retrying in {}s",
+                        "⚠".yellow(), refresh.consecutive_errors(), e, next.as_secs());
+                    refresh.sleep().await;
+                    continue;
+                }
+            };
             
             // Display current performance
             self.display_metrics(&snapshot);
             
-            // Store in history
+            // Store in history, persisted so the next run starts from it
             {
                 let mut history = self.metrics_history.write().await;
-                history.push(snapshot.clone());
-                if history.len() > 100 {
-                    history.remove(0);
-                }
+                history.snapshots.push(snapshot.clone());
+                history.trim(self.history_retention);
+                self.persist_history(&history);
             }
             
-            // Analyze and optimize
-            let updates = self.optimization_engine.analyze_and_optimize(&snapshot).await;
+            // Analyze and optimize, skipping changes that hurt before
+            let updates = {
+                let history = self.metrics_history.read().await;
+                self.optimization_engine.analyze_and_optimize(&snapshot, &history).await
+            };
             
             // Apply optimizations
             for update in updates {
-                self.apply_optimization(update).await?;
+                let event = OptimizationEvent {
+                    timestamp: chrono::Utc::now(),
+                    parameter: update.parameter.clone(),
+                    new_value: update.new_value.clone(),
+                };
+                if let Err(e) = self.apply_optimization(update).await {
+                    println!("  {} Failed to apply: {:#}", "✗".red(), e);
+                    continue;
+                }
+
+                let mut history = self.metrics_history.write().await;
+                history.events.push(event);
+                self.persist_history(&history);
             }
             
             // Check if we've achieved target performance
@@ -129,7 +234,13 @@ impl RealOptimizer {
             }
             
             // Sleep before next iteration
-            tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
+            refresh.sleep().await;
+        }
+    }
+    
+    fn persist_history(&self, history: &OptimizerHistory) {
+        if let Err(e) = save_history(&history_path(), history) {
+            println!("  {} Could not save optimizer history: {}", "⚠".yellow(), e);
         }
     }
     
@@ -154,73 +265,26 @@ impl RealOptimizer {
         })
     }
     
-    /// Get validator performance from chain
+    /// Get validator performance from chain; zeros when no validator answers
+    /// and `on_disconnect` is `baseline`
+    ///
+    /// Read over RPC from the first of `connect.endpoints` that answers:
+    /// `solana validators` has no vote lag, latency or TPS to offer.
     async fn get_validator_performance(&self) -> Result<ValidatorPerformance> {
-        // Try to get real metrics from validator
-        let output = Command::new("solana")
-            .args(&["validators", "--url", "http://127.0.0.1:8899"])
-            .output()?;
-        
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            // Parse validator metrics
-            return self.parse_validator_output(&stdout);
-        }
-        
-        // Fallback to testnet if local not available
-        let output = Command::new("solana")
-            .args(&["validators", "--url", "https://api.testnet.solana.com"])
-            .output()?;
-        
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            return self.parse_validator_output(&stdout);
-        }
-        
-        // Return baseline values if no validator running (not fake optimized ones)
-        println!("  {} No validator found - returning baseline metrics", "⚠".yellow());
-        Ok(ValidatorPerformance {
-            vote_success_rate: 85.0,   // Baseline, not optimized
-            skip_rate: 12.0,           // Baseline, not optimized
-            credits: 160_000,          // Baseline, not optimized
-            vote_lag: 150,             // Baseline, not optimized
-            latency_ms: 120,           // Baseline, not optimized
-            tps: 1800.0,               // Baseline, not optimized
-        })
+        let config = ValidatorConfig::load().unwrap_or_default();
+        let read = || Self::get_validator_performance_rpc(&config);
+        Ok(read_or_disconnect(&config, read).await?.unwrap_or_default())
     }
-    
-    /// Parse validator output for metrics
-    fn parse_validator_output(&self, output: &str) -> Result<ValidatorPerformance> {
-        // Look for our validator in the output
-        for line in output.lines() {
-            if line.contains("9F3XHUUV7nsKrTkZQVM1LmZ4tpsTn2Km6THFt3C7izQq") {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 8 {
-                    let vote_success = parts[4].trim_end_matches('%').parse::<f64>().unwrap_or(0.0);
-                    let skip_rate = parts[5].trim_end_matches('%').parse::<f64>().unwrap_or(0.0);
-                    let credits = parts[6].parse::<u64>().unwrap_or(0);
-                    
-                    return Ok(ValidatorPerformance {
-                        vote_success_rate: vote_success,
-                        skip_rate,
-                        credits,
-                        vote_lag: 30,
-                        latency_ms: 45,
-                        tps: 2500.0,
-                    });
-                }
-            }
-        }
-        
-        // Not found, return baseline metrics (not fake optimized ones)
-        println!("  {} Validator not found in output - using baseline", "⚠".yellow());
+
+    async fn get_validator_performance_rpc(config: &ValidatorConfig) -> Result<ValidatorPerformance> {
+        let metrics = connect_validator(config, KeypairUse::ReadOnly)?.get_validator_metrics().await?;
         Ok(ValidatorPerformance {
-            vote_success_rate: 85.0,
-            skip_rate: 12.0,
-            credits: 160_000,
-            vote_lag: 150,
-            latency_ms: 120,
-            tps: 1800.0,
+            vote_success_rate: metrics.vote_success_rate,
+            skip_rate: metrics.skip_rate,
+            credits: metrics.credits_earned,
+            vote_lag: metrics.vote_lag.min(u32::MAX as u64) as u32,
+            latency_ms: metrics.network_latency_ms,
+            tps: metrics.avg_tps,
         })
     }
     
@@ -241,25 +305,16 @@ impl RealOptimizer {
     fn display_metrics(&self, snapshot: &PerformanceSnapshot) {
         println!("\n{}", "=== Performance Metrics ===".cyan().bold());
         
-        // Vote success with color coding
-        let vote_color = if snapshot.vote_success_rate >= 95.0 {
-            "green"
-        } else if snapshot.vote_success_rate >= 85.0 {
-            "yellow"
-        } else {
-            "red"
-        };
-        
+        let bands = &self.status_bands;
+
         println!("Vote Success: {}", 
-            format!("{:.1}%", snapshot.vote_success_rate).color(vote_color).bold()
+            format!("{:.1}%", snapshot.vote_success_rate)
+                .color(bands.vote_success(snapshot.vote_success_rate).color()).bold()
         );
         
         println!("Skip Rate: {}", 
-            format!("{:.1}%", snapshot.skip_rate).color(
-                if snapshot.skip_rate <= 3.0 { "green" }
-                else if snapshot.skip_rate <= 10.0 { "yellow" }
-                else { "red" }
-            ).bold()
+            format!("{:.1}%", snapshot.skip_rate)
+                .color(bands.skip_rate(snapshot.skip_rate).color()).bold()
         );
         
         println!("Credits: {} | Vote Lag: {} | Latency: {}ms", 
@@ -276,7 +331,12 @@ impl RealOptimizer {
     }
     
     /// Apply an optimization
-    async fn apply_optimization(&self, update: ConfigUpdate) -> Result<()> {
+    async fn apply_optimization(&self, mut update: ConfigUpdate) -> Result<()> {
+        if update.old_value.is_empty() {
+            update.old_value = self.current_config.read().await
+                .parameter_value(&update.parameter)
+                .unwrap_or_else(|| "?".to_string());
+        }
         println!("\n{} Applying optimization: {}", 
             "▶".cyan(), 
             update.parameter.yellow()
@@ -309,12 +369,13 @@ impl RealOptimizer {
             "tpu_coalesce_ms" => config.tpu_coalesce_ms = update.new_value.parse()?,
             "snapshot_interval" => config.incremental_snapshot_interval = update.new_value.parse()?,
             "cache_size" => config.accounts_db_cache_mb = update.new_value.parse()?,
+            "enable_quic" => config.enable_quic = update.new_value.parse()?,
             _ => {}
         }
         
         // Save to disk
         let config_json = serde_json::to_string_pretty(&*config)?;
-        std::fs::write("validator-optimized.json", config_json)?;
+        std::fs::write(self.output_dir.join("validator-optimized.json"), config_json)?;
         
         Ok(())
     }
@@ -322,11 +383,14 @@ impl RealOptimizer {
     /// Apply update without restart
     async fn apply_hot_update(&self, update: &ConfigUpdate) -> Result<()> {
         // Try to apply via admin RPC
-        let output = Command::new("solana-validator")
-            .args(&["admin", "set", &update.parameter, &update.new_value])
-            .output()?;
+        let applied = !native::no_cli()
+            && Command::new("solana-validator")
+                .args(&["admin", "set", &update.parameter, &update.new_value])
+                .output()?
+                .status
+                .success();
         
-        if !output.status.success() {
+        if !applied {
             // Fallback to signal-based update
             if let Ok(pid_str) = std::fs::read_to_string("/tmp/validator.pid") {
                 if let Ok(pid) = pid_str.trim().parse::<i32>() {
@@ -342,19 +406,147 @@ impl RealOptimizer {
     }
 }
 
+/// On-disk form of `metrics_history`
+#[derive(Serialize, Deserialize)]
+struct PersistedHistory<S, E> {
+    version: u32,
+    snapshots: S,
+    /// Absent from files written before applied changes were recorded
+    #[serde(default)]
+    events: E,
+}
+
+impl OptimizerHistory {
+    /// Keep the newest `retention` snapshots and the changes they cover
+    fn trim(&mut self, retention: usize) {
+        let excess = self.snapshots.len().saturating_sub(retention);
+        self.snapshots.drain(..excess);
+
+        if let Some(oldest) = self.snapshots.first().map(|s| s.timestamp) {
+            self.events.retain(|event| event.timestamp >= oldest);
+        }
+    }
+
+    /// The latest past application of `update` that was followed by worse
+    /// metrics, comparing averages over `window` before and after it
+    pub fn degradation_after(&self, update: &ConfigUpdate, window: chrono::Duration) -> Option<Degradation> {
+        let averages = |from, to| {
+            let in_range: Vec<&PerformanceSnapshot> = self.snapshots
+                .iter()
+                .filter(|s| s.timestamp >= from && s.timestamp < to)
+                .collect();
+            (!in_range.is_empty()).then(|| {
+                let n = in_range.len() as f64;
+                (
+                    in_range.iter().map(|s| s.vote_success_rate).sum::<f64>() / n,
+                    in_range.iter().map(|s| s.skip_rate).sum::<f64>() / n,
+                )
+            })
+        };
+
+        self.events
+            .iter()
+            .rev()
+            .filter(|event| event.parameter == update.parameter && event.new_value == update.new_value)
+            .find_map(|event| {
+                let (vote_before, skip_before) = averages(event.timestamp - window, event.timestamp)?;
+                let (vote_after, skip_after) = averages(event.timestamp, event.timestamp + window)?;
+
+                let degraded = vote_before - vote_after > DEGRADATION_THRESHOLD_PP
+                    || skip_after - skip_before > DEGRADATION_THRESHOLD_PP;
+                degraded.then_some(Degradation {
+                    applied_at: event.timestamp,
+                    vote_success: (vote_before, vote_after),
+                    skip_rate: (skip_before, skip_after),
+                })
+            })
+    }
+}
+
+fn history_path() -> PathBuf {
+    ValidatorConfig::config_dir().join("optimizer-history.json")
+}
+
+/// Newest `retention` snapshots saved by earlier runs
+///
+/// A file from a newer format version, or one that can't be parsed, is moved
+/// aside to `.bak` and the optimizer starts cold; individual snapshots that no
+/// longer parse are skipped.
+fn load_history(path: &Path, retention: usize) -> OptimizerHistory {
+    type Lenient = PersistedHistory<Vec<serde_json::Value>, Vec<serde_json::Value>>;
+
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return OptimizerHistory::default();
+    };
+
+    let persisted = match serde_json::from_str::<Lenient>(&contents) {
+        Ok(persisted) if persisted.version <= HISTORY_FORMAT_VERSION => persisted,
+        Ok(persisted) => {
+            set_aside(path, &format!("format version {} is newer than {}", persisted.version, HISTORY_FORMAT_VERSION));
+            return OptimizerHistory::default();
+        }
+        Err(e) => {
+            set_aside(path, &e.to_string());
+            return OptimizerHistory::default();
+        }
+    };
+
+    let mut history = OptimizerHistory {
+        snapshots: persisted
+            .snapshots
+            .into_iter()
+            .filter_map(|snapshot| serde_json::from_value(snapshot).ok())
+            .collect(),
+        events: persisted
+            .events
+            .into_iter()
+            .filter_map(|event| serde_json::from_value(event).ok())
+            .collect(),
+    };
+    history.trim(retention);
+    history
+}
+
+fn set_aside(path: &Path, reason: &str) {
+    let backup = path.with_extension("json.bak");
+    println!("  {} Ignoring optimizer history ({}); moved to {}", "⚠".yellow(), reason, backup.display());
+    let _ = std::fs::rename(path, backup);
+}
+
+fn save_history(path: &Path, history: &OptimizerHistory) -> Result<()> {
+    let persisted = PersistedHistory {
+        version: HISTORY_FORMAT_VERSION,
+        snapshots: &history.snapshots,
+        events: &history.events,
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    // Write then rename so a crash mid-write never truncates the history
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string(&persisted)?)?;
+    std::fs::rename(&tmp, path).context("Failed to save optimizer history")?;
+    Ok(())
+}
+
 impl OptimizationEngine {
-    pub fn new() -> Self {
-        let strategies: Vec<Box<dyn OptimizationStrategy>> = vec![
+    /// Built-in strategies followed by one per custom rule
+    pub fn new(correlation_window: chrono::Duration, rules: Vec<Rule>) -> Self {
+        let mut strategies: Vec<Box<dyn OptimizationStrategy>> = vec![
             Box::new(VoteSuccessOptimizer),
             Box::new(SkipRateOptimizer),
             Box::new(LatencyOptimizer),
             Box::new(ResourceOptimizer),
         ];
+        strategies.extend(rules.into_iter().map(|rule| Box::new(RuleStrategy::new(rule)) as Box<dyn OptimizationStrategy>));
         
-        Self { strategies }
+        Self { strategies, correlation_window }
     }
     
-    pub async fn analyze_and_optimize(&self, snapshot: &PerformanceSnapshot) -> Vec<ConfigUpdate> {
+    /// Updates the strategies suggest for `snapshot`, minus any that were
+    /// applied before and followed by worse metrics
+    pub async fn analyze_and_optimize(&self, snapshot: &PerformanceSnapshot, history: &OptimizerHistory) -> Vec<ConfigUpdate> {
         let mut updates = Vec::new();
         
         for strategy in &self.strategies {
@@ -364,6 +556,21 @@ impl OptimizationEngine {
                     strategy.name(),
                     update.parameter
                 );
+
+                if let Some(degradation) = history.degradation_after(&update, self.correlation_window) {
+                    println!("    {} Skipping {}={}: applied {} and vote success went {:.1}% → {:.1}%, skip rate {:.1}% → {:.1}%",
+                        "⚠".yellow(),
+                        update.parameter,
+                        update.new_value,
+                        degradation.applied_at.format("%Y-%m-%d %H:%M UTC"),
+                        degradation.vote_success.0,
+                        degradation.vote_success.1,
+                        degradation.skip_rate.0,
+                        degradation.skip_rate.1
+                    );
+                    continue;
+                }
+
                 updates.push(update);
             }
         }
@@ -468,6 +675,20 @@ impl OptimizationStrategy for ResourceOptimizer {
     }
 }
 
+impl OptimizedConfig {
+    /// Current value of a `ConfigUpdate` parameter, as a string
+    pub fn parameter_value(&self, parameter: &str) -> Option<String> {
+        Some(match parameter {
+            "rpc_threads" => self.rpc_threads.to_string(),
+            "tpu_coalesce_ms" => self.tpu_coalesce_ms.to_string(),
+            "snapshot_interval" => self.incremental_snapshot_interval.to_string(),
+            "cache_size" => self.accounts_db_cache_mb.to_string(),
+            "enable_quic" => self.enable_quic.to_string(),
+            _ => return None,
+        })
+    }
+}
+
 impl Default for OptimizedConfig {
     fn default() -> Self {
         Self {
@@ -492,7 +713,7 @@ impl Default for OptimizedConfig {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 struct ValidatorPerformance {
     vote_success_rate: f64,
     skip_rate: f64,
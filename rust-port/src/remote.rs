@@ -0,0 +1,160 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::config::ValidatorConfig;
+
+/// Seconds `ssh` waits for the connection before giving up
+const CONNECT_TIMEOUT_SECS: u32 = 10;
+
+/// Where the optimizer keeps its config on the remote host, relative to the
+/// login directory
+pub const DEFAULT_REMOTE_CONFIG: &str = ".solana-optimizer/config.json";
+
+/// `text` single-quoted for a POSIX shell
+pub fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// One command run on the remote host and how it went
+#[derive(Debug, Clone)]
+pub struct RemoteOutput {
+    pub command: String,
+    /// None when ssh itself failed or the command was killed
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl RemoteOutput {
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    /// stderr, else stdout, else the exit code: why the command failed
+    pub fn reason(&self) -> String {
+        [self.stderr.trim(), self.stdout.trim()]
+            .into_iter()
+            .find(|text| !text.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| match self.exit_code {
+                Some(code) => format!("exit code {}", code),
+                None => "killed".to_string(),
+            })
+    }
+}
+
+/// A host reached by shelling out to `ssh`, with every command it ran
+///
+/// Connections are non-interactive (`BatchMode`), so authentication has to
+/// work without a prompt: keys or an agent.
+pub struct SshHost {
+    pub target: String,
+    pub log: Vec<RemoteOutput>,
+}
+
+impl SshHost {
+    pub fn new(target: &str) -> Self {
+        Self { target: target.to_string(), log: Vec::new() }
+    }
+
+    /// The part of `user@host` after the `@`
+    pub fn hostname(&self) -> &str {
+        self.target.rsplit('@').next().unwrap_or(&self.target)
+    }
+
+    /// Run `command` through the remote shell, feeding it `stdin` if given
+    pub fn run_with_input(&mut self, command: &str, stdin: Option<&[u8]>) -> Result<RemoteOutput> {
+        let mut child = Command::new("ssh")
+            .args(["-o", "BatchMode=yes", "-o", &format!("ConnectTimeout={}", CONNECT_TIMEOUT_SECS)])
+            .arg(&self.target)
+            .arg("--")
+            .arg(command)
+            .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run ssh; is the OpenSSH client installed?")?;
+        if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            pipe.write_all(input).context("Failed to send input over ssh")?;
+        }
+        let output = child.wait_with_output().context("ssh did not finish")?;
+
+        let result = RemoteOutput {
+            command: command.to_string(),
+            exit_code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        };
+        self.log.push(result.clone());
+        // ssh exits 255 for its own failures, which no remote step can recover from
+        if result.exit_code == Some(255) {
+            anyhow::bail!("ssh {} failed: {}", self.target, result.reason());
+        }
+        Ok(result)
+    }
+
+    pub fn run(&mut self, command: &str) -> Result<RemoteOutput> {
+        self.run_with_input(command, None)
+    }
+
+    /// Check the host answers before doing anything on it
+    pub fn connect(&mut self) -> Result<()> {
+        let output = self.run("true")?;
+        if !output.success() {
+            anyhow::bail!("Could not run commands on {}: {}", self.target, output.reason());
+        }
+        Ok(())
+    }
+
+    /// A kernel parameter's current value, None when it can't be read
+    pub fn read_sysctl(&mut self, key: &str) -> Option<String> {
+        let output = self.run(&format!("sysctl -n {}", shell_quote(key))).ok()?;
+        output.success().then(|| output.stdout.trim().to_string())
+    }
+
+    /// Set a kernel parameter, through `sudo -n` unless logged in as root
+    pub fn write_sysctl(&mut self, key: &str, value: &str) -> Result<RemoteOutput> {
+        let setting = shell_quote(&format!("{}={}", key, value));
+        self.run(&format!(
+            "if [ \"$(id -u)\" = 0 ]; then sysctl -w {0}; else sudo -n sysctl -w {0}; fi",
+            setting
+        ))
+    }
+
+    /// The optimizer config at `path` on the host
+    pub fn read_config(&mut self, path: &str) -> Result<ValidatorConfig> {
+        let output = self.run(&format!("cat {}", shell_quote(path)))?;
+        if !output.success() {
+            anyhow::bail!("Failed to read {}:{}: {} (run the optimizer there once to create it)",
+                self.target, path, output.reason());
+        }
+        serde_json::from_str(&output.stdout).with_context(|| format!("Invalid config {}:{}", self.target, path))
+    }
+
+    /// Replace the config at `path` on the host, via a temporary file so a
+    /// dropped connection can't leave it half-written
+    pub fn write_config(&mut self, path: &str, config: &ValidatorConfig) -> Result<()> {
+        let contents = serde_json::to_string_pretty(config)?;
+        let (path, temp) = (shell_quote(path), shell_quote(&format!("{}.tmp", path)));
+        let output = self.run_with_input(&format!("cat > {} && mv {} {}", temp, temp, path), Some(contents.as_bytes()))?;
+        if !output.success() {
+            anyhow::bail!("Failed to write {}:{}: {}", self.target, path, output.reason());
+        }
+        Ok(())
+    }
+
+    /// Every command run so far with its result
+    pub fn print_log(&self) {
+        println!("\n{} ({}):", "Commands run on the remote host".cyan().bold(), self.target);
+        for (i, output) in self.log.iter().enumerate() {
+            let branch = if i + 1 == self.log.len() { "└─" } else { "├─" };
+            if output.success() {
+                println!("{} {} {}", branch, "✓".green(), output.command);
+            } else {
+                println!("{} {} {} ({})", branch, "✗".red(), output.command, output.reason().red());
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::config::ValidatorConfig;
+use crate::monitor::format_elapsed;
+use crate::system::SystemMonitor;
+use crate::tls;
+
+/// A node this close to the cluster slot counts as caught up
+const CAUGHT_UP_SLOTS: u64 = 5;
+
+/// Rates are measured over readings this far back
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Readings must span this long before a node is judged to be falling behind
+const MIN_JUDGED_SPAN: Duration = Duration::from_secs(20);
+
+/// Load per core above which replay is taken to be CPU-bound
+const CPU_BOUND_LOAD: f64 = 0.9;
+
+/// RPC timeout per reading; a slow answer skews the rates
+const RPC_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy)]
+struct SlotReading {
+    at: Instant,
+    local: u64,
+    cluster: u64,
+}
+
+/// Replay progress from local vs cluster slot readings
+#[derive(Debug, Clone, Copy)]
+pub struct ReplayEstimate {
+    pub local_slot: u64,
+    pub cluster_slot: u64,
+    pub behind: u64,
+    /// Slots/s the local node replays
+    pub replay_rate: f64,
+    /// Slots/s the cluster produces
+    pub cluster_rate: f64,
+    /// How long the readings behind the rates span
+    pub span: Duration,
+}
+
+/// Whether a node at `local` is within `CAUGHT_UP_SLOTS` of the cluster at `cluster`
+pub fn is_caught_up(local: u64, cluster: u64) -> bool {
+    cluster.saturating_sub(local) <= CAUGHT_UP_SLOTS
+}
+
+impl ReplayEstimate {
+    pub fn caught_up(&self) -> bool {
+        is_caught_up(self.local_slot, self.cluster_slot)
+    }
+
+    /// Not replaying faster than the cluster produces, over a long enough span to tell
+    pub fn falling_behind(&self) -> bool {
+        !self.caught_up() && self.span >= MIN_JUDGED_SPAN && self.replay_rate <= self.cluster_rate
+    }
+
+    /// Time to close the gap at the current rates; None when it isn't closing
+    pub fn eta(&self) -> Option<Duration> {
+        let closing = self.replay_rate - self.cluster_rate;
+        (closing > 0.0).then(|| Duration::from_secs_f64(self.behind as f64 / closing))
+    }
+
+    pub fn eta_display(&self) -> String {
+        if self.caught_up() {
+            return "caught up".to_string();
+        }
+        match self.eta() {
+            Some(eta) => chrono::Duration::from_std(eta).map_or_else(|_| "years".to_string(), format_elapsed),
+            None => "never at this rate".to_string(),
+        }
+    }
+
+    /// `slot 1234 | behind 66 | replay 3.1 slots/s vs cluster 2.5 | ETA 2m`
+    pub fn summary(&self) -> String {
+        format!("slot {} | behind {} | replay {:.1} slots/s vs cluster {:.1} | ETA {}",
+            self.local_slot, self.behind, self.replay_rate, self.cluster_rate, self.eta_display())
+    }
+}
+
+/// Local and cluster slot readings over a trailing window
+#[derive(Debug, Default)]
+pub struct ReplayTracker {
+    readings: VecDeque<SlotReading>,
+}
+
+impl ReplayTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a reading; the estimate once there are two to compare
+    pub fn record(&mut self, local: u64, cluster: u64) -> Option<ReplayEstimate> {
+        let now = Instant::now();
+        self.readings.push_back(SlotReading { at: now, local, cluster });
+        while self.readings.len() > 2 && self.readings.front().is_some_and(|r| now - r.at > RATE_WINDOW) {
+            self.readings.pop_front();
+        }
+
+        let (first, last) = (self.readings.front()?, self.readings.back()?);
+        let span = last.at - first.at;
+        if span.is_zero() {
+            return None;
+        }
+        let secs = span.as_secs_f64();
+        Some(ReplayEstimate {
+            local_slot: last.local,
+            cluster_slot: last.cluster,
+            behind: last.cluster.saturating_sub(last.local),
+            replay_rate: last.local.saturating_sub(first.local) as f64 / secs,
+            cluster_rate: last.cluster.saturating_sub(first.cluster) as f64 / secs,
+            span,
+        })
+    }
+}
+
+/// RPC clients for the local node and the cluster it catches up with: the
+/// first configured endpoint other than the local node
+pub fn replay_clients(config: &ValidatorConfig) -> Result<(RpcClient, RpcClient)> {
+    let local_url = format!("http://127.0.0.1:{}", config.rpc_port);
+    let cluster_url = config
+        .connect_endpoints()
+        .into_iter()
+        .find(|endpoint| *endpoint != local_url)
+        .context("No cluster RPC in connect.endpoints to compare the local slot with")?;
+    let client = |url: &str| tls::rpc_client_with_timeout(url, RPC_TIMEOUT, CommitmentConfig::processed());
+    Ok((client(&local_url), client(&cluster_url)))
+}
+
+/// (local, cluster) processed slots
+pub fn read_slots(local: &RpcClient, cluster: &RpcClient) -> Result<(u64, u64)> {
+    let local_slot = local.get_slot().context("Local RPC not answering")?;
+    let cluster_slot = cluster.get_slot().with_context(|| format!("Cluster RPC {} not answering", cluster.url()))?;
+    Ok((local_slot, cluster_slot))
+}
+
+/// What to change when replay can't keep up with the cluster
+pub fn print_recommendations(config: &ValidatorConfig, estimate: &ReplayEstimate) {
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let load = SystemMonitor::get_metrics().load_1min / cores as f64;
+
+    println!("{} Replay ({:.1} slots/s) is not keeping up with the cluster ({:.1} slots/s)",
+        "⚠".yellow(), estimate.replay_rate, estimate.cluster_rate);
+    if load >= CPU_BOUND_LOAD {
+        println!("├─ Load is {:.2} per core: replay is CPU-bound, so more threads won't help", load);
+        println!("│  Stop other work on this host or move to a CPU with faster single cores");
+    } else {
+        println!("├─ Load is {:.2} per core: the CPU has headroom for more replay threads", load);
+        println!("│  Raise --replay-slots-concurrently (1.18) or --replay-forks-threads and");
+        println!("│  --replay-transactions-threads (Agave 2.x)");
+    }
+    println!("└─ Replay is disk-heavy: keep the ledger and accounts on separate NVMe drives");
+    println!("   (ledger: {}, accounts: {})", config.ledger_path.display(), config.accounts_path.display());
+}
+
+/// `replay-speed`: watch the local slot against the cluster during catch-up
+///
+/// Prints the replay speed and an ETA every `interval_secs` until the node
+/// is caught up or `max_secs` pass, with recommendations if it falls behind.
+pub async fn watch_replay(interval_secs: u64, max_secs: Option<u64>) -> Result<()> {
+    let config = ValidatorConfig::load()?;
+    let (local, cluster) = replay_clients(&config)?;
+    let interval = Duration::from_secs(interval_secs.max(1));
+    let started = Instant::now();
+
+    println!("{}", "=== Replay Speed ===".cyan().bold());
+    println!("├─ Local: {}", local.url());
+    println!("└─ Cluster: {}", cluster.url());
+    println!("\nPress Ctrl+C to stop\n");
+
+    let mut tracker = ReplayTracker::new();
+    let mut first: Option<(Instant, u64)> = None;
+    let mut recommended = false;
+    loop {
+        match read_slots(&local, &cluster) {
+            Ok((local_slot, cluster_slot)) => {
+                first.get_or_insert((Instant::now(), local_slot));
+                let behind = cluster_slot.saturating_sub(local_slot);
+                if is_caught_up(local_slot, cluster_slot) {
+                    println!("{} Caught up at slot {} ({} behind the cluster)", "✓".green(), local_slot, behind);
+                    break;
+                }
+                match tracker.record(local_slot, cluster_slot) {
+                    Some(estimate) => {
+                        let line = estimate.summary();
+                        if estimate.falling_behind() {
+                            println!("{} {}", "⚠".yellow(), line.yellow());
+                            if !recommended {
+                                print_recommendations(&config, &estimate);
+                                recommended = true;
+                            }
+                        } else {
+                            println!("{} {}", "▶".cyan(), line);
+                        }
+                    }
+                    None => println!("{} slot {} | behind {} | measuring...", "▶".cyan(), local_slot, behind),
+                }
+            }
+            Err(e) => println!("{} {} - validator may still be loading its snapshot", "⏳".yellow(), e),
+        }
+
+        if max_secs.is_some_and(|max| started.elapsed() >= Duration::from_secs(max)) {
+            println!("{} Stopped after {}s without catching up", "⚠".yellow(), started.elapsed().as_secs());
+            break;
+        }
+        tokio::time::sleep(interval).await;
+    }
+
+    if let (Some((at, slot)), Ok((now_slot, _))) = (first, read_slots(&local, &cluster)) {
+        let secs = at.elapsed().as_secs_f64();
+        if secs > 0.0 {
+            println!("{} Average replay speed: {:.1} slots/s over {}", "ℹ".cyan(),
+                now_slot.saturating_sub(slot) as f64 / secs,
+                format_elapsed(chrono::Duration::seconds(secs as i64)));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::history::HistorySummary;
+use crate::monitor::PerformanceMetrics;
+use crate::sla::SlaReading;
+
+/// Changes smaller than this (in the metric's own unit) count as unchanged
+const NOISE_FLOOR: f64 = 0.05;
+
+/// `performance-report.json`, written next to the Markdown report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceReport {
+    pub generated: String,
+    /// False when no validator answered and the metrics are zeros
+    pub connected: bool,
+    pub metrics: PerformanceMetrics,
+    /// Aggregated history, present when the report was run with `--since`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<ReportSummary>,
+    /// Vote reliability SLA per window; empty in reports written before it
+    #[serde(default)]
+    pub sla: Vec<SlaReading>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportSummary {
+    /// Window label as given to `--since` (e.g. `24h`)
+    pub window: String,
+    pub samples: usize,
+    pub uptime_pct: f64,
+    pub optimizations: usize,
+    pub stats: Vec<ReportStat>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportStat {
+    pub name: String,
+    pub avg: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl ReportSummary {
+    pub fn new(window: &str, summary: &HistorySummary) -> Self {
+        Self {
+            window: window.to_string(),
+            samples: summary.samples,
+            uptime_pct: summary.uptime_pct,
+            optimizations: summary.optimizations,
+            stats: summary
+                .stats
+                .iter()
+                .map(|(name, stat)| ReportStat {
+                    name: name.to_string(),
+                    avg: stat.avg,
+                    min: stat.min,
+                    max: stat.max,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl PerformanceReport {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read report {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("{} is not a JSON performance report", path.display()))
+    }
+}
+
+/// Whether a larger value of a metric is an improvement
+fn higher_is_better(name: &str) -> bool {
+    !(name.starts_with("Skip Rate") || name.starts_with("Vote Lag") || name.starts_with("Network Latency"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Improved,
+    Regressed,
+    Unchanged,
+}
+
+/// One metric compared across the two reports
+#[derive(Debug, Clone)]
+pub struct MetricDelta {
+    pub name: String,
+    pub old: f64,
+    pub new: f64,
+    pub change: Change,
+}
+
+impl MetricDelta {
+    fn new(name: &str, old: f64, new: f64) -> Self {
+        let delta = new - old;
+        let change = if delta.abs() < NOISE_FLOOR {
+            Change::Unchanged
+        } else if (delta > 0.0) == higher_is_better(name) {
+            Change::Improved
+        } else {
+            Change::Regressed
+        };
+        Self { name: name.to_string(), old, new, change }
+    }
+
+    pub fn delta(&self) -> f64 {
+        self.new - self.old
+    }
+}
+
+fn current_deltas(old: &PerformanceMetrics, new: &PerformanceMetrics) -> Vec<MetricDelta> {
+    vec![
+        MetricDelta::new("Vote Success Rate (%)", old.vote_success_rate, new.vote_success_rate),
+        MetricDelta::new("Skip Rate (%)", old.skip_rate, new.skip_rate),
+        MetricDelta::new("Credits Earned", old.credits_earned as f64, new.credits_earned as f64),
+        MetricDelta::new("Vote Lag (slots)", old.vote_lag as f64, new.vote_lag as f64),
+        MetricDelta::new("Network Latency (ms)", old.network_latency_ms as f64, new.network_latency_ms as f64),
+        MetricDelta::new("TPS", old.avg_tps, new.avg_tps),
+    ]
+}
+
+/// Averages of metrics present in both summaries, plus uptime
+fn summary_deltas(old: &ReportSummary, new: &ReportSummary) -> Vec<MetricDelta> {
+    let mut deltas = vec![MetricDelta::new("Uptime (%)", old.uptime_pct, new.uptime_pct)];
+    deltas.extend(new.stats.iter().filter_map(|stat| {
+        let previous = old.stats.iter().find(|s| s.name == stat.name)?;
+        Some(MetricDelta::new(&format!("{} avg", stat.name), previous.avg, stat.avg))
+    }));
+    deltas
+}
+
+fn display_deltas(deltas: &[MetricDelta]) {
+    for (i, d) in deltas.iter().enumerate() {
+        let branch = if i + 1 == deltas.len() { "└─" } else { "├─" };
+        let delta = format!("{:+.1}", d.delta());
+        let (marker, delta) = match d.change {
+            Change::Improved => ("▲".green(), delta.green()),
+            Change::Regressed => ("▼".red(), delta.red()),
+            Change::Unchanged => ("=".dimmed(), delta.dimmed()),
+        };
+        println!("{} {} {:<28} {:>12.1} → {:<12.1} ({})", branch, marker, d.name, d.old, d.new, delta);
+    }
+}
+
+/// `report-diff`: compare two JSON reports metric by metric
+pub fn diff_reports(old_path: &Path, new_path: &Path) -> Result<()> {
+    let old = PerformanceReport::load(old_path)?;
+    let new = PerformanceReport::load(new_path)?;
+
+    println!("{}", "=== Performance Report Diff ===".cyan().bold());
+    println!("Old: {} ({})", old_path.display(), old.generated);
+    println!("New: {} ({})", new_path.display(), new.generated);
+    for (label, report) in [("Old", &old), ("New", &new)] {
+        if !report.connected {
+            println!("{} {} report was generated without a connected validator", "⚠".yellow(), label);
+        }
+    }
+
+    let mut deltas = current_deltas(&old.metrics, &new.metrics);
+    println!("\n{}", "Current Metrics".cyan().bold());
+    display_deltas(&deltas);
+
+    match (&old.summary, &new.summary) {
+        (Some(old_summary), Some(new_summary)) => {
+            let aggregated = summary_deltas(old_summary, new_summary);
+            println!("\n{}", format!("History (old: last {}, new: last {})",
+                old_summary.window, new_summary.window).cyan().bold());
+            println!("├─ Samples: {} → {} | Optimizations applied: {} → {}",
+                old_summary.samples, new_summary.samples,
+                old_summary.optimizations, new_summary.optimizations);
+            display_deltas(&aggregated);
+            deltas.extend(aggregated);
+        }
+        (None, None) => {}
+        _ => println!("\n{} Only one report has a --since summary; comparing current metrics only", "⚠".yellow()),
+    }
+
+    let improved = deltas.iter().filter(|d| d.change == Change::Improved).count();
+    let regressed = deltas.iter().filter(|d| d.change == Change::Regressed).count();
+    let verdict = match (improved, regressed) {
+        (0, 0) => "NO CHANGE".normal(),
+        (_, 0) => "IMPROVED".green().bold(),
+        (0, _) => "REGRESSED".red().bold(),
+        (i, r) if i > r => "MOSTLY IMPROVED".green(),
+        (i, r) if r > i => "MOSTLY REGRESSED".red(),
+        _ => "MIXED".yellow(),
+    };
+    println!("\n{} {} ({} improved, {} regressed, {} unchanged)",
+        "Verdict:".bold(), verdict, improved, regressed, deltas.len() - improved - regressed);
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use futures::future::join_all;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::time::Duration;
+
+use crate::config::{RpcConsensusConfig, SlotPolicy};
+use crate::tls;
+
+/// Per-endpoint timeout so one hung RPC doesn't stall the metric read
+const ENDPOINT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One endpoint's answer to `getSlot`
+#[derive(Debug, Clone)]
+pub struct EndpointSlot {
+    pub url: String,
+    pub slot: Result<u64, String>,
+}
+
+/// The slot the queried endpoints agree on, and who fell behind it
+#[derive(Debug, Clone)]
+pub struct SlotConsensus {
+    pub slot: u64,
+    pub readings: Vec<EndpointSlot>,
+    /// Endpoints more than `max_slot_lag` behind `slot`, with how far
+    pub lagging: Vec<(String, u64)>,
+}
+
+impl SlotConsensus {
+    pub fn is_lagging(&self, url: &str) -> bool {
+        self.lagging.iter().any(|(lagging, _)| lagging == url)
+    }
+
+    /// Endpoint that answered with the highest slot
+    pub fn freshest(&self) -> Option<&str> {
+        self.readings
+            .iter()
+            .filter_map(|reading| reading.slot.as_ref().ok().map(|slot| (reading.url.as_str(), *slot)))
+            .max_by_key(|(_, slot)| *slot)
+            .map(|(url, _)| url)
+    }
+}
+
+/// Endpoints to query: the primary first, then the configured ones, capped at the quorum
+pub fn endpoints_for(primary: &str, config: &RpcConsensusConfig) -> Vec<String> {
+    let mut urls = vec![primary.to_string()];
+    for url in &config.endpoints {
+        if !urls.contains(url) {
+            urls.push(url.clone());
+        }
+    }
+    urls.truncate(config.quorum.max(1));
+    urls
+}
+
+/// Slot chosen from the readings by `policy` (the upper median for an even count)
+pub fn pick_slot(slots: &[u64], policy: SlotPolicy) -> Option<u64> {
+    let mut slots = slots.to_vec();
+    slots.sort_unstable();
+    match policy {
+        SlotPolicy::Highest => slots.last().copied(),
+        SlotPolicy::Median => slots.get(slots.len() / 2).copied(),
+    }
+}
+
+/// Query `urls` concurrently for their slot and agree on one per the config
+///
+/// Returns None when no endpoint answered.
+pub async fn slot_consensus(urls: &[String], config: &RpcConsensusConfig) -> Option<SlotConsensus> {
+    let readings: Vec<EndpointSlot> = join_all(urls.iter().map(|url| async move {
+        let client = tls::nonblocking_rpc_client(url, ENDPOINT_TIMEOUT, CommitmentConfig::confirmed());
+        EndpointSlot {
+            url: url.clone(),
+            slot: client.get_slot().await.map_err(|e| e.to_string()),
+        }
+    }))
+    .await;
+
+    let slots: Vec<u64> = readings.iter().filter_map(|r| r.slot.as_ref().ok().copied()).collect();
+    let slot = pick_slot(&slots, config.slot_policy)?;
+
+    let lagging = readings
+        .iter()
+        .filter_map(|reading| {
+            let behind = slot.saturating_sub(*reading.slot.as_ref().ok()?);
+            (behind > config.max_slot_lag).then(|| (reading.url.clone(), behind))
+        })
+        .collect();
+
+    Some(SlotConsensus { slot, readings, lagging })
+}
@@ -0,0 +1,243 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration as ChronoDuration, Local, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Duration};
+
+use crate::config::{SlaConfig, ValidatorConfig};
+use crate::connect::ValidatorLink;
+use crate::history::{self, HistoryEvent, HistoryRecord, MetricSample};
+use crate::monitor;
+
+/// Rolling windows the SLA is reported over, shortest first
+const WINDOWS: [(&str, i64); 3] = [("1h", 3_600), ("24h", 86_400), ("30d", 30 * 86_400)];
+
+/// The SLA over one window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlaReading {
+    pub window: String,
+    /// Samples recorded in the window, reachable or not
+    pub samples: usize,
+    /// Seconds the samples cover
+    pub sampled_secs: i64,
+    /// Seconds covered by samples that counted toward the SLA
+    pub met_secs: i64,
+    /// None when the window has no samples
+    pub sla_pct: Option<f64>,
+}
+
+/// A sample counts toward the SLA when the validator answered, voted at or
+/// above `threshold` and wasn't delinquent
+fn meets(sample: Option<&MetricSample>, threshold: f64) -> bool {
+    sample.is_some_and(|sample| sample.vote_success_rate >= threshold && !sample.delinquent)
+}
+
+/// The SLA over each window ending at `now`, from history `records`
+///
+/// Each sample stands for the time until the next one, at most
+/// `sample_interval_secs`, so the dashboard's 5s samples weigh no more than
+/// the tracker's and time nothing sampled isn't counted.
+pub fn compute(records: &[HistoryRecord], now: DateTime<Utc>, config: &SlaConfig) -> Vec<SlaReading> {
+    let cap = config.sample_interval_secs.max(30) as i64;
+    let mut samples: Vec<(DateTime<Utc>, bool)> = records.iter()
+        .filter_map(|record| match &record.event {
+            HistoryEvent::Sample { metrics } => {
+                Some((record.timestamp, meets(metrics.as_ref(), config.vote_success_threshold)))
+            }
+            _ => None,
+        })
+        .collect();
+    samples.sort_by_key(|(timestamp, _)| *timestamp);
+    let weighted: Vec<(DateTime<Utc>, bool, i64)> = samples.iter().enumerate()
+        .map(|(i, (timestamp, met))| {
+            let until = samples.get(i + 1).map_or(now, |(next, _)| *next);
+            (*timestamp, *met, (until - *timestamp).num_seconds().clamp(0, cap))
+        })
+        .collect();
+
+    WINDOWS.iter().map(|(label, secs)| {
+        let since = now - ChronoDuration::seconds(*secs);
+        let in_window: Vec<&(DateTime<Utc>, bool, i64)> = weighted.iter()
+            .filter(|(timestamp, _, _)| *timestamp >= since)
+            .collect();
+        let sampled_secs: i64 = in_window.iter().map(|(_, _, weight)| weight).sum();
+        let met_secs: i64 = in_window.iter().filter(|(_, met, _)| *met).map(|(_, _, weight)| weight).sum();
+        SlaReading {
+            window: label.to_string(),
+            samples: in_window.len(),
+            sampled_secs,
+            met_secs,
+            sla_pct: (sampled_secs > 0).then(|| met_secs as f64 / sampled_secs as f64 * 100.0),
+        }
+    }).collect()
+}
+
+/// The SLA over every window, from the recorded history
+pub fn current(config: &SlaConfig) -> Result<Vec<SlaReading>> {
+    let now = Utc::now();
+    let longest = WINDOWS.iter().map(|(_, secs)| *secs).max().unwrap_or(0);
+    let records = history::load_since(now - ChronoDuration::seconds(longest))?;
+    Ok(compute(&records, now, config))
+}
+
+/// `23h 41m`
+fn covered(secs: i64) -> String {
+    monitor::format_elapsed(ChronoDuration::seconds(secs))
+}
+
+/// `99.2%` colored against `target_pct`, or `no samples`
+fn paint_pct(reading: &SlaReading, target_pct: f64) -> colored::ColoredString {
+    match reading.sla_pct {
+        Some(pct) if pct >= target_pct => format!("{:.2}%", pct).green().bold(),
+        Some(pct) => format!("{:.2}%", pct).red().bold(),
+        None => "no samples".dimmed(),
+    }
+}
+
+/// `1h 100.00% | 24h 99.31% | 30d 99.87%`
+fn windows_line(readings: &[SlaReading], config: &SlaConfig) -> String {
+    readings.iter()
+        .map(|reading| format!("{} {}", reading.window, paint_pct(reading, config.target_pct)))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// The SLA line in `status`
+pub fn print_status_line(config: &SlaConfig) {
+    match current(config) {
+        Ok(readings) => {
+            println!("Vote SLA (≥{}% voting, not delinquent): {}",
+                config.vote_success_threshold, windows_line(&readings, config));
+        }
+        Err(e) => println!("Vote SLA: {} ({:#})", "unknown".yellow(), e),
+    }
+}
+
+/// The Markdown section `report` appends
+pub fn markdown(readings: &[SlaReading], config: &SlaConfig) -> String {
+    let mut section = format!(
+        "\n## Vote Reliability SLA\n\nShare of sampled time where the validator answered, voted at or above {}% and was not delinquent (target {}%); each sample covers the time until the next, at most {}s.\n\n| Window | SLA | Time met / sampled | Samples |\n|--------|-----|--------------------|---------|\n",
+        config.vote_success_threshold, config.target_pct, config.sample_interval_secs.max(30)
+    );
+    for reading in readings {
+        let pct = reading.sla_pct.map_or("no samples".to_string(), |pct| format!("{:.2}%", pct));
+        section.push_str(&format!("| {} | {} | {} / {} | {} |\n",
+            reading.window, pct, covered(reading.met_secs), covered(reading.sampled_secs), reading.samples));
+    }
+    section
+}
+
+/// `sla`: the SLA over each window
+pub fn show() -> Result<()> {
+    let config = ValidatorConfig::load()?;
+    let readings = current(&config.sla)?;
+    print_readings(&readings, &config.sla);
+    Ok(())
+}
+
+fn print_readings(readings: &[SlaReading], config: &SlaConfig) {
+    println!("{}", "=== Vote Reliability SLA ===".cyan().bold());
+    println!("Share of sampled time the validator answered, voted at or above {}% and wasn't delinquent",
+        config.vote_success_threshold);
+    for (i, reading) in readings.iter().enumerate() {
+        let branch = if i + 1 == readings.len() { "└─" } else { "├─" };
+        println!("{} {:>3}: {} ({} of {} sampled, {} samples, target {}%)",
+            branch, reading.window, paint_pct(reading, config.target_pct),
+            covered(reading.met_secs), covered(reading.sampled_secs), reading.samples, config.target_pct);
+    }
+    if readings.iter().all(|reading| reading.samples == 0) {
+        println!("{} No samples recorded yet; run {} to collect them", "ℹ".cyan(), "sla --track".yellow());
+    }
+}
+
+/// `sla --track`: sample the validator every `sample_interval_secs` into the
+/// history, printing the SLA after each sample, until Ctrl+C
+///
+/// Samples go to the same history file as the dashboard's, so the SLA
+/// survives restarts; time nothing sampled the validator isn't counted.
+pub async fn track() -> Result<()> {
+    let config = ValidatorConfig::load()?;
+    let interval = Duration::from_secs(config.sla.sample_interval_secs.max(30));
+    println!("{} Sampling every {}s into {}; Ctrl+C to stop",
+        "▶".cyan(), interval.as_secs(), history::history_path().display());
+
+    // One connection for the whole run, so failover can kick in
+    let link = ValidatorLink::default();
+    loop {
+        let metrics = link.metrics(&config).await;
+        if let Err(e) = history::record_sample(metrics.as_ref().ok()) {
+            println!("{} Could not record the sample: {:#}", "⚠".yellow(), e);
+        }
+
+        let state = match &metrics {
+            Ok(metrics) if meets(Some(&MetricSample::from(metrics)), config.sla.vote_success_threshold) => {
+                format!("✓ {:.1}% voting", metrics.vote_success_rate).green()
+            }
+            Ok(metrics) if metrics.delinquent => "✗ delinquent".red(),
+            Ok(metrics) => format!("✗ {:.1}% voting", metrics.vote_success_rate).red(),
+            Err(e) => format!("✗ unreachable: {}", e.to_string().lines().next().unwrap_or_default()).red(),
+        };
+        println!("[{}] {} | SLA {}",
+            Local::now().format("%H:%M:%S"), state, windows_line(&current(&config.sla)?, &config.sla));
+
+        tokio::select! {
+            _ = sleep(interval) => {}
+            _ = tokio::signal::ctrl_c() => {
+                println!();
+                print_readings(&current(&config.sla)?, &config.sla);
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(at: DateTime<Utc>, vote_success_rate: f64) -> HistoryRecord {
+        HistoryRecord {
+            timestamp: at,
+            event: HistoryEvent::Sample {
+                metrics: Some(MetricSample {
+                    vote_success_rate,
+                    skip_rate: 0.0,
+                    credits_earned: 0,
+                    vote_lag: 0,
+                    network_latency_ms: 0,
+                    avg_tps: 0.0,
+                    delinquent: false,
+                    epoch: None,
+                    epoch_credits: None,
+                }),
+            },
+        }
+    }
+
+    #[test]
+    fn dense_samples_weigh_no_more_than_sparse_ones() {
+        let config = SlaConfig { vote_success_threshold: 90.0, target_pct: 99.0, sample_interval_secs: 60 };
+        let start = Utc::now() - ChronoDuration::minutes(30);
+        // Ten minutes of tracker samples met, then a minute of dashboard samples that didn't
+        let mut records: Vec<HistoryRecord> = (0..10)
+            .map(|i| sample(start + ChronoDuration::seconds(i * 60), 99.0))
+            .collect();
+        records.extend((0..12).map(|i| sample(start + ChronoDuration::seconds(600 + i * 5), 50.0)));
+        let now = start + ChronoDuration::seconds(660);
+
+        let hour = &compute(&records, now, &config)[0];
+        assert_eq!(hour.samples, 22);
+        assert_eq!((hour.met_secs, hour.sampled_secs), (600, 660));
+        assert!((hour.sla_pct.unwrap() - 600.0 / 660.0 * 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn unsampled_time_is_not_counted() {
+        let config = SlaConfig { vote_success_threshold: 90.0, target_pct: 99.0, sample_interval_secs: 60 };
+        let now = Utc::now();
+        let records = vec![sample(now - ChronoDuration::minutes(50), 50.0), sample(now - ChronoDuration::seconds(10), 99.0)];
+
+        let hour = &compute(&records, now, &config)[0];
+        assert_eq!((hour.met_secs, hour.sampled_secs), (10, 70));
+    }
+}
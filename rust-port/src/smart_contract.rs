@@ -1,17 +1,70 @@
 use anyhow::Result;
 use colored::Colorize;
-use solana_client::rpc_client::RpcClient;
+use serde::Serialize;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient, SerializableTransaction};
+use solana_rpc_client_api::config::{RpcSimulateTransactionConfig, RpcTransactionConfig};
+use solana_rpc_client_api::response::{RpcConfirmedTransactionStatusWithSignature, RpcSimulateTransactionResult};
 use solana_sdk::{
+    bpf_loader, bpf_loader_deprecated,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
     commitment_config::CommitmentConfig,
     compute_budget::ComputeBudgetInstruction,
     instruction::Instruction,
+    message::Message,
     pubkey::Pubkey,
+    instruction::InstructionError,
+    loader_instruction::LoaderInstruction,
+    loader_upgradeable_instruction::UpgradeableLoaderInstruction,
+    loader_v4,
+    loader_v4_instruction::LoaderV4Instruction,
     signature::Signature,
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
 };
-use std::collections::HashMap;
+use solana_transaction_status::UiTransactionEncoding;
+use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
 
+use crate::calibrate::Calibration;
+use crate::config::PollIntervalConfig;
+use crate::poll::{self, AdaptiveInterval};
+use crate::tls;
+
+/// How many pages of signature history (1000 each) to walk back through
+/// when looking for transactions in a requested slot range
+const MAX_SIGNATURE_PAGES: usize = 10;
+
+/// Transactions fetched and analyzed in depth per analysis
+const SAMPLE_SIZE: usize = 20;
+
+/// Hottest writable accounts listed by `--contention`
+const CONTENTION_TOP: usize = 10;
+
+/// `monitor-contract` refresh interval when `poll_interval.base_secs` is unset
+const MONITOR_REFRESH_SECS: u64 = 30;
+
+/// Which of the fetched signatures are analyzed in depth
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum SampleStrategy {
+    /// The newest transactions: current conditions only
+    #[default]
+    Recent,
+    /// Transactions picked at random across all fetched signatures
+    Random,
+    /// Only transactions that failed, for a failure-focused analysis
+    FailedOnly,
+}
+
+impl std::fmt::Display for SampleStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SampleStrategy::Recent => "recent",
+            SampleStrategy::Random => "random",
+            SampleStrategy::FailedOnly => "failed-only",
+        })
+    }
+}
+
 /// Smart Contract Optimizer for Solana Programs
 ///
 /// Provides optimizations for:
@@ -24,6 +77,7 @@ pub struct SmartContractOptimizer {
     rpc_client: RpcClient,
     #[allow(dead_code)]
     program_id: Option<Pubkey>,
+    sample_strategy: SampleStrategy,
 }
 
 #[derive(Debug, Clone)]
@@ -39,17 +93,361 @@ pub struct ProgramMetrics {
     pub instruction_count: u64,
     pub data_reads_bytes: u64,
     pub data_writes_bytes: u64,
+    /// Self CU per folded call stack (`program;instruction;...`), summed over the sample
+    pub cu_by_stack: BTreeMap<String, u64>,
+    pub account_kind: AccountKind,
+    /// CU spread over the sampled transactions; None when none reported CU
+    pub cu_sample: Option<CuSample>,
+    /// How the analyzed transactions were picked
+    pub sample_strategy: SampleStrategy,
+    /// Failed transactions in the sample, by why they failed
+    pub failures: BTreeMap<FailureKind, FailureStats>,
+    /// Share (0-1) of the sampled transactions the figures above cover; 0
+    /// when the signatures couldn't be listed
+    pub coverage: f64,
+    /// RPC calls that failed along the way; the figures cover what succeeded
+    pub errors: Vec<String>,
+}
+
+impl ProgramMetrics {
+    /// Writable accounts by write-lock count in the sample, hottest first
+    pub fn hottest_accounts(&self) -> Vec<(&str, u64)> {
+        let mut accounts: Vec<(&str, u64)> = self.account_locks
+            .iter()
+            .map(|(account, &count)| (account.as_str(), count))
+            .collect();
+        accounts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        accounts
+    }
+
+    /// Gini coefficient of the write locks across the written accounts: 0 when
+    /// every account takes the same share, towards 1 when a few take them all
+    pub fn contention_concentration(&self) -> f64 {
+        let mut counts: Vec<u64> = self.account_locks.values().copied().collect();
+        let total: u64 = counts.iter().sum();
+        if counts.len() < 2 || total == 0 {
+            return 0.0;
+        }
+        counts.sort_unstable();
+        let n = counts.len() as f64;
+        let weighted: f64 = counts.iter().enumerate().map(|(i, &count)| (i + 1) as f64 * count as f64).sum();
+        2.0 * weighted / (n * total as f64) - (n + 1.0) / n
+    }
+}
+
+/// Why a sampled transaction failed
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FailureKind {
+    /// Ran out of compute units
+    ComputeBudget,
+    /// The program returned `Custom(code)`
+    ProgramError(u32),
+    /// A writable account was locked by another transaction
+    AccountInUse,
+    /// The fee payer or an account couldn't cover fees or rent
+    InsufficientFunds,
+    /// The blockhash expired before the transaction landed
+    BlockhashExpired,
+    Other(String),
+}
+
+impl FailureKind {
+    fn classify(error: &TransactionError, logs: &[String]) -> Self {
+        // Running out of CU surfaces as several instruction errors, but always logs this
+        if logs.iter().any(|log| log.contains("exceeded CUs meter") || log.contains("Computational budget exceeded")) {
+            return FailureKind::ComputeBudget;
+        }
+        match error {
+            TransactionError::InstructionError(_, InstructionError::ComputationalBudgetExceeded) => FailureKind::ComputeBudget,
+            TransactionError::InstructionError(_, InstructionError::Custom(code)) => FailureKind::ProgramError(*code),
+            TransactionError::InstructionError(_, InstructionError::InsufficientFunds)
+            | TransactionError::InsufficientFundsForFee
+            | TransactionError::InsufficientFundsForRent { .. } => FailureKind::InsufficientFunds,
+            TransactionError::AccountInUse | TransactionError::WouldExceedMaxAccountCostLimit => FailureKind::AccountInUse,
+            TransactionError::BlockhashNotFound => FailureKind::BlockhashExpired,
+            TransactionError::InstructionError(_, error) => FailureKind::Other(error.to_string()),
+            error => FailureKind::Other(error.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FailureKind::ComputeBudget => write!(f, "compute budget exceeded"),
+            FailureKind::ProgramError(code) => write!(f, "program error {} (0x{:x})", code, code),
+            FailureKind::AccountInUse => write!(f, "account in use"),
+            FailureKind::InsufficientFunds => write!(f, "insufficient funds"),
+            FailureKind::BlockhashExpired => write!(f, "blockhash expired"),
+            FailureKind::Other(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// Sampled failures of one kind
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FailureStats {
+    pub count: u64,
+    /// Most CU a failing transaction consumed before it failed
+    pub max_cu: u64,
+}
+
+/// CU consumed by the sampled transactions, plus the median one re-simulated
+/// against current state
+#[derive(Debug, Clone)]
+pub struct CuSample {
+    /// Sampled transactions that reported CU
+    pub size: usize,
+    pub average: f64,
+    pub median: u64,
+    /// Cheapest sampled transaction: what the program has been seen to need
+    pub minimum: u64,
+    /// CU the median transaction consumes when simulated now; None when the
+    /// simulation failed, e.g. because the state it relied on has changed
+    pub simulated: Option<u64>,
+}
+
+impl CuSample {
+    /// CU/tx that could be saved by bringing transactions down to the
+    /// observed minimum, low to high, measured from the median, the average
+    /// and the simulated current cost
+    pub fn savings_range(&self) -> (u64, u64) {
+        let mut points = vec![self.average.round() as u64, self.median];
+        points.extend(self.simulated);
+        let low = points.iter().min().copied().unwrap_or(0);
+        let high = points.iter().max().copied().unwrap_or(0);
+        (low.saturating_sub(self.minimum), high.saturating_sub(self.minimum))
+    }
+}
+
+/// Whether the analyzed address is a program or a data account
+#[derive(Debug, Clone)]
+pub enum AccountKind {
+    /// Executable, or owned by one of the program loaders (but not an
+    /// upgradeable Buffer or ProgramData); `upgradeable` is set for programs
+    /// deployed with the upgradeable BPF loader
+    Program { upgradeable: Option<UpgradeableProgram> },
+    /// A data account, analyzed by the transactions that touch it
+    State {
+        owner: Pubkey,
+        lamports: u64,
+        rent_exempt_minimum: u64,
+    },
 }
 
+/// The ProgramData account behind an upgradeable program
 #[derive(Debug, Clone)]
+pub struct UpgradeableProgram {
+    pub programdata_address: Pubkey,
+    /// None when the program has been made immutable
+    pub upgrade_authority: Option<Pubkey>,
+    pub last_deployed_slot: u64,
+}
+
+const LOADER_IDS: [Pubkey; 5] = [
+    solana_sdk::bpf_loader::ID,
+    solana_sdk::bpf_loader_deprecated::ID,
+    solana_sdk::bpf_loader_upgradeable::ID,
+    solana_sdk::loader_v4::ID,
+    solana_sdk::native_loader::ID,
+];
+
+#[derive(Debug, Clone, Serialize)]
 pub struct OptimizationRecommendation {
     pub category: String,
     pub priority: Priority,
     pub description: String,
     pub estimated_improvement: String,
+    /// The threshold check that produced this recommendation
+    pub trace: Option<RecommendationTrace>,
+    /// Reference docs for the fix, from [`RECOMMENDATION_DOCS`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_url: Option<&'static str>,
+}
+
+/// Documentation link for each recommendation category
+pub const RECOMMENDATION_DOCS: [(&str, &str); 12] = [
+    ("Compute Units", "https://solana.com/docs/core/fees#compute-budget"),
+    ("CPI Chain Depth", "https://solana.com/docs/core/cpi"),
+    ("Account Lock Contention", "https://solana.com/docs/core/transactions"),
+    ("Account Size", "https://solana.com/docs/advanced/state-compression"),
+    ("Data I/O Efficiency", "https://solana.com/docs/core/accounts"),
+    ("Transaction Batching", "https://solana.com/docs/advanced/versions"),
+    ("Instruction Count", "https://solana.com/docs/core/transactions"),
+    ("Memory Layout", "https://solana.com/developers/guides/advanced/how-to-optimize-compute"),
+    ("Compute Budget Exceeded", "https://solana.com/docs/core/fees#compute-budget"),
+    ("Account In Use", "https://solana.com/docs/core/transactions"),
+    ("Insufficient Funds", "https://solana.com/docs/core/fees"),
+    ("Blockhash Expired", "https://solana.com/docs/advanced/confirmation"),
+];
+
+/// Documentation link for a recommendation category, if one is mapped
+pub fn doc_url(category: &str) -> Option<&'static str> {
+    RECOMMENDATION_DOCS
+        .iter()
+        .find(|(name, _)| *name == category)
+        .map(|(_, url)| *url)
+}
+
+/// Why a recommendation fired: `metric` compared against `threshold`
+#[derive(Debug, Clone, Serialize)]
+pub struct RecommendationTrace {
+    pub metric: &'static str,
+    pub value: f64,
+    pub comparison: &'static str,
+    pub threshold: f64,
+}
+
+impl RecommendationTrace {
+    fn above(metric: &'static str, value: f64, threshold: f64) -> Self {
+        Self { metric, value, comparison: ">", threshold }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl std::fmt::Display for RecommendationTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let number = |n: f64| if n.fract() == 0.0 { format!("{:.0}", n) } else { format!("{:.2}", n) };
+        write!(f, "{}={} {} {}", self.metric, number(self.value), self.comparison, number(self.threshold))
+    }
+}
+
+/// The Compute Units recommendation's savings, from the gap between the
+/// sampled transactions' cost and the cheapest one seen
+fn cu_savings_estimate(sample: Option<&CuSample>) -> String {
+    let Some(sample) = sample else {
+        return "Savings not estimated: no sampled transaction reported CU".to_string();
+    };
+    let (low, high) = sample.savings_range();
+    let basis = match sample.simulated {
+        Some(units) => format!("{} sampled tx, median re-simulated at {} CU", sample.size, units),
+        None => format!("{} sampled tx", sample.size),
+    };
+    if high == 0 {
+        return format!("No measured headroom: no sampled transaction ran below {} CU ({})", sample.minimum, basis);
+    }
+
+    let pct = |cu: u64| cu as f64 / sample.average * 100.0;
+    format!("Potential savings: {}-{} CU/tx ({:.0}-{:.0}%) down to the observed floor of {} CU ({})",
+        low, high, pct(low), pct(high), sample.minimum, basis)
+}
+
+/// Most CU a transaction can request
+const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+
+/// Recommendations for a `FailedOnly` sample, one per kind of failure
+fn failure_recommendations(metrics: &ProgramMetrics) -> Vec<OptimizationRecommendation> {
+    let total: u64 = metrics.failures.values().map(|stats| stats.count).sum();
+    let share = |count: u64| count as f64 / total.max(1) as f64 * 100.0;
+    let fixes = |count: u64| format!("Avoids {} of {} sampled failures ({:.0}%)", count, total, share(count));
+    let recommendation = |category: &str, priority, description: String, count: u64, metric: &'static str| {
+        OptimizationRecommendation {
+            category: category.to_string(),
+            priority,
+            description,
+            estimated_improvement: fixes(count),
+            trace: Some(RecommendationTrace::above(metric, count as f64, 0.0)),
+            doc_url: doc_url(category),
+        }
+    };
+
+    let mut recommendations = Vec::new();
+    let mut program_errors: Vec<(u32, u64)> = Vec::new();
+    let mut other: Vec<String> = Vec::new();
+    let mut other_count = 0;
+
+    for (kind, stats) in &metrics.failures {
+        match kind {
+            FailureKind::ComputeBudget => {
+                let limit = (stats.max_cu as f64 * 1.2) as u64;
+                recommendations.push(recommendation(
+                    "Compute Budget Exceeded",
+                    Priority::High,
+                    format!(
+                        "{} failed transaction(s) ran out of compute units, at up to {} CU. Request ~{} CU with SetComputeUnitLimit{}, or cut the CU the failing path uses.",
+                        stats.count,
+                        stats.max_cu,
+                        limit.min(MAX_COMPUTE_UNIT_LIMIT),
+                        if limit > MAX_COMPUTE_UNIT_LIMIT { " (the maximum; the path itself must get cheaper)" } else { "" }
+                    ),
+                    stats.count,
+                    "failed_compute_budget",
+                ));
+            }
+            FailureKind::ProgramError(code) => program_errors.push((*code, stats.count)),
+            FailureKind::AccountInUse => recommendations.push(recommendation(
+                "Account In Use",
+                Priority::Medium,
+                format!(
+                    "{} transaction(s) failed on a writable account another transaction held. Retry with backoff, and shard hot accounts so fewer transactions write the same one.",
+                    stats.count
+                ),
+                stats.count,
+                "failed_account_in_use",
+            )),
+            FailureKind::InsufficientFunds => recommendations.push(recommendation(
+                "Insufficient Funds",
+                Priority::Medium,
+                format!(
+                    "{} transaction(s) failed because the fee payer or an account couldn't cover fees or rent. Check balances client-side before sending and keep new accounts rent-exempt.",
+                    stats.count
+                ),
+                stats.count,
+                "failed_insufficient_funds",
+            )),
+            FailureKind::BlockhashExpired => recommendations.push(recommendation(
+                "Blockhash Expired",
+                Priority::Medium,
+                format!(
+                    "{} transaction(s) landed after their blockhash expired. Fetch the blockhash right before signing, add a priority fee so they land sooner, or use a durable nonce.",
+                    stats.count
+                ),
+                stats.count,
+                "failed_blockhash_expired",
+            )),
+            FailureKind::Other(error) => {
+                other.push(format!("{} ({})", error, stats.count));
+                other_count += stats.count;
+            }
+        }
+    }
+
+    if !program_errors.is_empty() {
+        program_errors.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        let count: u64 = program_errors.iter().map(|(_, count)| count).sum();
+        let codes = program_errors
+            .iter()
+            .take(3)
+            .map(|(code, count)| format!("{} / 0x{:x} ({}x)", code, code, count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        recommendations.push(recommendation(
+            "Program Errors",
+            if share(count) > 50.0 { Priority::High } else { Priority::Medium },
+            format!(
+                "{} transaction(s) failed with the program's own errors, most often: {}. Look the codes up in the program's error enum (Anchor numbers custom errors from 6000) and validate those conditions client-side before sending.",
+                count, codes
+            ),
+            count,
+            "failed_program_errors",
+        ));
+    }
+
+    if !other.is_empty() {
+        recommendations.push(recommendation(
+            "Other Failures",
+            Priority::Low,
+            format!("Also failing: {}.", other.join(", ")),
+            other_count,
+            "failed_other",
+        ));
+    }
+
+    recommendations
+}
+
+/// Declaration order is sort order: High sorts first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Priority {
     High,
     Medium,
@@ -81,45 +479,313 @@ pub struct AccountAnalysis {
 
 #[derive(Debug, Clone)]
 pub struct TransactionAnalysis {
-    #[allow(dead_code)]
     pub signature: String,
     pub cu_consumed: u64,
     pub accounts_accessed: Vec<Pubkey>,
     pub writable_accounts: Vec<Pubkey>,
     pub instruction_count: usize,
-    #[allow(dead_code)]
     pub log_messages: Vec<String>,
     pub cpi_depth: u32,
+    /// Why the transaction failed; None when it succeeded
+    pub error: Option<TransactionError>,
+}
+
+/// The sampled transactions that could be fetched, and those that couldn't
+#[derive(Debug, Default)]
+struct FetchedSample {
+    analyses: Vec<TransactionAnalysis>,
+    /// (signature, why) per sampled transaction that couldn't be fetched
+    failed: Vec<(String, String)>,
+}
+
+impl FetchedSample {
+    /// Share (0-1) of the sample that was fetched; 1 when nothing was sampled
+    fn coverage(&self) -> f64 {
+        let sampled = self.analyses.len() + self.failed.len();
+        if sampled == 0 {
+            1.0
+        } else {
+            self.analyses.len() as f64 / sampled as f64
+        }
+    }
+}
+
+/// One program invocation while walking a transaction's logs
+struct InvokeFrame {
+    program: String,
+    instruction: Option<String>,
+    consumed: Option<u64>,
+    child_cu: u64,
+}
+
+/// Flamegraph frames may not contain `;` or spaces
+fn frame_name(name: &str) -> String {
+    name.replace([';', ' '], "_")
+}
+
+/// Add each invocation's self CU (its own `consumed` minus its CPIs') to
+/// `stacks`, keyed by `program;instruction` segments from the top-level call
+/// down. The instruction segment comes from Anchor-style
+/// `Program log: Instruction: <name>` lines and is left out when absent.
+pub fn fold_cu_stacks(logs: &[String], stacks: &mut BTreeMap<String, u64>) {
+    let mut frames: Vec<InvokeFrame> = Vec::new();
+
+    for log in logs {
+        if let Some(name) = log.strip_prefix("Program log: Instruction: ") {
+            if let Some(frame) = frames.last_mut() {
+                frame.instruction.get_or_insert_with(|| frame_name(name.trim()));
+            }
+            continue;
+        }
+
+        let mut parts = log.splitn(3, ' ');
+        let (Some("Program"), Some(program), Some(rest)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+
+        if rest.starts_with("invoke [") {
+            frames.push(InvokeFrame {
+                program: frame_name(program),
+                instruction: None,
+                consumed: None,
+                child_cu: 0,
+            });
+        } else if let Some(consumed) = rest.strip_prefix("consumed ") {
+            if let (Some(frame), Some(units)) = (frames.last_mut(), consumed.split(' ').next()) {
+                frame.consumed = units.parse().ok();
+            }
+        } else if rest == "success" || rest.starts_with("failed") {
+            let stack = frames
+                .iter()
+                .flat_map(|f| std::iter::once(f.program.clone()).chain(f.instruction.clone()))
+                .collect::<Vec<_>>()
+                .join(";");
+            let Some(frame) = frames.pop() else {
+                continue;
+            };
+
+            // Builtin programs don't log CU, so they have nothing to attribute
+            let Some(consumed) = frame.consumed else {
+                continue;
+            };
+            let self_cu = consumed.saturating_sub(frame.child_cu);
+            if self_cu > 0 {
+                *stacks.entry(stack).or_insert(0) += self_cu;
+            }
+            if let Some(parent) = frames.last_mut() {
+                parent.child_cu += consumed;
+            }
+        }
+    }
 }
 
 impl SmartContractOptimizer {
     /// Create a new smart contract optimizer
     pub fn new(rpc_url: &str, program_id: Option<Pubkey>) -> Result<Self> {
-        let rpc_client = RpcClient::new_with_commitment(
-            rpc_url.to_string(),
-            CommitmentConfig::confirmed(),
-        );
+        let rpc_client = tls::rpc_client(rpc_url, CommitmentConfig::confirmed());
 
         Ok(Self {
             rpc_client,
             program_id,
+            sample_strategy: SampleStrategy::default(),
         })
     }
 
+    /// Pick the transactions analyzed in depth with `strategy` instead of the newest ones
+    pub fn with_sample_strategy(mut self, strategy: SampleStrategy) -> Self {
+        self.sample_strategy = strategy;
+        self
+    }
+
     /// Analyze a program's current performance metrics with deep inspection
     pub async fn analyze_program(&self, program_id: &Pubkey) -> Result<ProgramMetrics> {
+        self.analyze_program_in_range(program_id, None, None).await
+    }
+
+    /// The program a deploy (or upgrade) transaction put on chain
+    ///
+    /// Looks for the loader instruction that makes the program executable:
+    /// `DeployWithMaxDataLen` or `Upgrade` for the upgradeable loader,
+    /// `Finalize` for the older BPF loaders and `Deploy` for loader v4.
+    pub fn resolve_deploy_tx(&self, signature: &str) -> Result<Pubkey> {
+        let signature = Signature::from_str(signature)
+            .map_err(|e| anyhow::anyhow!("Invalid transaction signature: {}", e))?;
+        let confirmed = self.rpc_client.get_transaction_with_config(
+            &signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(self.rpc_client.commitment()),
+                max_supported_transaction_version: Some(0),
+            },
+        ).map_err(|e| anyhow::anyhow!("Failed to fetch transaction {}: {}", signature, e))?;
+        if let Some(err) = confirmed.transaction.meta.as_ref().and_then(|meta| meta.err.as_ref()) {
+            anyhow::bail!("Transaction {} failed ({}), so it deployed nothing", signature, err);
+        }
+        let transaction = confirmed
+            .transaction
+            .transaction
+            .decode()
+            .ok_or_else(|| anyhow::anyhow!("Transaction {} could not be decoded", signature))?;
+
+        let keys = transaction.message.static_account_keys();
+        for instruction in transaction.message.instructions() {
+            let Some(loader) = keys.get(instruction.program_id_index as usize) else {
+                continue;
+            };
+            let (action, program_index) = if bpf_loader_upgradeable::check_id(loader) {
+                match bincode::deserialize(&instruction.data) {
+                    Ok(UpgradeableLoaderInstruction::DeployWithMaxDataLen { .. }) => ("Deployed", 2),
+                    Ok(UpgradeableLoaderInstruction::Upgrade) => ("Upgraded", 1),
+                    _ => continue,
+                }
+            } else if bpf_loader::check_id(loader) || bpf_loader_deprecated::check_id(loader) {
+                match bincode::deserialize(&instruction.data) {
+                    Ok(LoaderInstruction::Finalize) => ("Deployed", 0),
+                    _ => continue,
+                }
+            } else if loader_v4::check_id(loader) {
+                match bincode::deserialize(&instruction.data) {
+                    Ok(LoaderV4Instruction::Deploy) => ("Deployed", 0),
+                    _ => continue,
+                }
+            } else {
+                continue;
+            };
+
+            let program_id = instruction.accounts.get(program_index)
+                .and_then(|&index| keys.get(index as usize))
+                .ok_or_else(|| anyhow::anyhow!("Transaction {} names its program through an address lookup table", signature))?;
+            println!("{} {} program {} in slot {} (from {})",
+                "✓".green(), action, program_id.to_string().yellow(), confirmed.slot, signature);
+            return Ok(*program_id);
+        }
+
+        anyhow::bail!("Transaction {} is not a program deployment: it has no loader deploy, upgrade or finalize instruction", signature)
+    }
+
+    /// Resolve an upgradeable program's ProgramData account
+    ///
+    /// Returns the upgrade info and the size of the deployed bytecode.
+    fn fetch_program_data(&self, programdata_address: Pubkey) -> Result<(UpgradeableProgram, u64)> {
+        let programdata = self
+            .rpc_client
+            .get_account_with_commitment(&programdata_address, self.rpc_client.commitment())?
+            .value
+            .ok_or_else(|| anyhow::anyhow!("ProgramData account {} not found", programdata_address))?;
+
+        let metadata_size = UpgradeableLoaderState::size_of_programdata_metadata();
+        let header = programdata.data.get(..metadata_size)
+            .ok_or_else(|| anyhow::anyhow!("ProgramData account {} is truncated", programdata_address))?;
+        let UpgradeableLoaderState::ProgramData { slot, upgrade_authority_address } = bincode::deserialize(header)? else {
+            anyhow::bail!("{} is not a ProgramData account", programdata_address);
+        };
+
+        Ok((
+            UpgradeableProgram {
+                programdata_address,
+                upgrade_authority: upgrade_authority_address,
+                last_deployed_slot: slot,
+            },
+            (programdata.data.len() - metadata_size) as u64,
+        ))
+    }
+
+    /// Analyze only transactions whose slot falls within `[from_slot, to_slot]`
+    pub async fn analyze_program_in_range(
+        &self,
+        program_id: &Pubkey,
+        from_slot: Option<u64>,
+        to_slot: Option<u64>,
+    ) -> Result<ProgramMetrics> {
         println!("{}", "📊 Analyzing Smart Contract Performance...".cyan().bold());
+        self.collect_program_metrics(program_id, from_slot, to_slot)
+    }
 
+    /// The analysis behind `analyze_program_in_range`, without the banner
+    ///
+    /// Only a missing or unreadable program account fails it; every later
+    /// RPC failure is recorded in `errors` and the analysis goes on without it.
+    pub(crate) fn collect_program_metrics(
+        &self,
+        program_id: &Pubkey,
+        from_slot: Option<u64>,
+        to_slot: Option<u64>,
+    ) -> Result<ProgramMetrics> {
         // Get program account data
-        let account = self.rpc_client.get_account(program_id)?;
-        let account_data_size = account.data.len() as u64;
-
-        // Get recent transaction signatures for this program
-        let signatures = self.rpc_client.get_signatures_for_address(program_id)?;
-        let transaction_count = signatures.len() as u64;
+        let account = self
+            .rpc_client
+            .get_account_with_commitment(program_id, self.rpc_client.commitment())?
+            .value
+            .ok_or_else(|| anyhow::anyhow!("Account {} not found on {}", program_id, self.rpc_client.url()))?;
+        let mut account_data_size = account.data.len() as u64;
+        let mut errors = Vec::new();
+
+        // Of the upgradeable loader's accounts only `Program` is a program;
+        // its Buffer and ProgramData accounts just hold bytecode
+        let upgradeable_state = if account.owner == bpf_loader_upgradeable::ID {
+            bincode::deserialize::<UpgradeableLoaderState>(&account.data).ok()
+        } else {
+            None
+        };
+        let account_kind = match upgradeable_state {
+            Some(UpgradeableLoaderState::Program { programdata_address }) => {
+                // The program account is only a pointer; the bytecode lives in ProgramData
+                match self.fetch_program_data(programdata_address) {
+                    Ok((info, program_size)) => {
+                        account_data_size = program_size;
+                        AccountKind::Program { upgradeable: Some(info) }
+                    }
+                    Err(e) => {
+                        errors.push(format!("ProgramData: {:#} (size is the program account's)", e));
+                        AccountKind::Program { upgradeable: None }
+                    }
+                }
+            }
+            None if account.executable || LOADER_IDS.contains(&account.owner) => {
+                AccountKind::Program { upgradeable: None }
+            }
+            _ => {
+                println!("  {} {} is a data account owned by {}, not a program - analyzing it as a state account",
+                    "ℹ".cyan(), program_id, account.owner);
+                AccountKind::State {
+                    owner: account.owner,
+                    lamports: account.lamports,
+                    rent_exempt_minimum: accounts::calculate_rent_exempt_balance(&self.rpc_client, account.data.len())
+                        .unwrap_or_else(|e| {
+                            errors.push(format!("Rent-exempt minimum: {:#}", e));
+                            0
+                        }),
+                }
+            }
+        };
 
-        // Deep analyze recent transactions
-        let tx_analyses = self.analyze_transactions_deep(program_id)?;
+        // Get transaction signatures for this program (recent, or within the slot range)
+        let signatures = if from_slot.is_some() || to_slot.is_some() {
+            self.signatures_in_slot_range(program_id, from_slot, to_slot)
+        } else {
+            self.rpc_client.get_signatures_for_address(program_id)
+                .map_err(|e| anyhow::anyhow!("{}", e.to_string().trim()))
+        };
+        let (signatures, listed) = match signatures {
+            Ok(signatures) => (signatures, true),
+            Err(e) => {
+                errors.push(format!("Transaction signatures: {:#}", e));
+                (Vec::new(), false)
+            }
+        };
+        let transaction_count = match self.sample_strategy {
+            SampleStrategy::FailedOnly => signatures.iter().filter(|s| s.err.is_some()).count(),
+            _ => signatures.len(),
+        } as u64;
+
+        // Deep analyze the selected transactions
+        let sample = self.analyze_transactions_deep(&signatures);
+        if let Some((signature, e)) = sample.failed.first() {
+            errors.push(format!("{} of {} sampled transactions could not be fetched, e.g. {}: {}",
+                sample.failed.len(), sample.failed.len() + sample.analyses.len(), signature, e));
+        }
+        let coverage = if listed { sample.coverage() } else { 0.0 };
+        let tx_analyses = sample.analyses;
 
         // Calculate aggregate metrics
         let total_cu_used: u64 = tx_analyses.iter().map(|t| t.cu_consumed).sum();
@@ -135,8 +801,26 @@ impl SmartContractOptimizer {
         // Estimate data I/O
         let (data_reads, data_writes) = self.estimate_data_io(&tx_analyses);
 
-        let average_cu_per_tx = if transaction_count > 0 {
-            total_cu_used as f64 / transaction_count as f64
+        // Attribute CU to call stacks from the program logs
+        let mut cu_by_stack = BTreeMap::new();
+        for analysis in &tx_analyses {
+            fold_cu_stacks(&analysis.log_messages, &mut cu_by_stack);
+        }
+
+        let cu_sample = self.sample_cu(&tx_analyses);
+
+        let mut failures: BTreeMap<FailureKind, FailureStats> = BTreeMap::new();
+        for analysis in &tx_analyses {
+            if let Some(error) = &analysis.error {
+                let stats = failures.entry(FailureKind::classify(error, &analysis.log_messages)).or_default();
+                stats.count += 1;
+                stats.max_cu = stats.max_cu.max(analysis.cu_consumed);
+            }
+        }
+
+        // Over the transactions actually fetched, so failed fetches don't dilute it
+        let average_cu_per_tx = if !tx_analyses.is_empty() {
+            total_cu_used as f64 / tx_analyses.len() as f64
         } else {
             0.0
         };
@@ -162,9 +846,98 @@ impl SmartContractOptimizer {
             instruction_count: total_instructions,
             data_reads_bytes: data_reads,
             data_writes_bytes: data_writes,
+            cu_by_stack,
+            account_kind,
+            cu_sample,
+            sample_strategy: self.sample_strategy,
+            failures,
+            coverage,
+            errors,
+        })
+    }
+
+    /// CU spread over the sampled transactions, with the median transaction
+    /// re-simulated to see what it costs against current state
+    fn sample_cu(&self, analyses: &[TransactionAnalysis]) -> Option<CuSample> {
+        let mut measured: Vec<&TransactionAnalysis> = analyses.iter().filter(|a| a.cu_consumed > 0).collect();
+        if measured.is_empty() {
+            return None;
+        }
+        measured.sort_by_key(|a| a.cu_consumed);
+
+        let representative = measured[measured.len() / 2];
+        // A failed transaction fails again in simulation
+        let simulated = if representative.error.is_some() {
+            None
+        } else {
+            match self.simulate_transaction(&representative.signature) {
+                Ok(units) => Some(units),
+                Err(e) => {
+                    println!("  {} Could not re-simulate {}: {:#}", "⚠".yellow(), representative.signature, e);
+                    None
+                }
+            }
+        };
+
+        Some(CuSample {
+            size: measured.len(),
+            average: measured.iter().map(|a| a.cu_consumed).sum::<u64>() as f64 / measured.len() as f64,
+            median: representative.cu_consumed,
+            minimum: measured[0].cu_consumed,
+            simulated,
         })
     }
 
+    /// CU a past transaction consumes when simulated against current state
+    fn simulate_transaction(&self, signature: &str) -> Result<u64> {
+        let signature = Signature::from_str(signature)?;
+        let confirmed = self.rpc_client.get_transaction_with_config(
+            &signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(self.rpc_client.commitment()),
+                max_supported_transaction_version: Some(0),
+            },
+        )?;
+        let transaction = confirmed
+            .transaction
+            .transaction
+            .decode()
+            .ok_or_else(|| anyhow::anyhow!("transaction could not be decoded"))?;
+
+        let result = self.simulate(&transaction)?;
+        if let Some(err) = result.err {
+            anyhow::bail!("simulation failed: {}", err);
+        }
+        result.units_consumed.ok_or_else(|| anyhow::anyhow!("simulation reported no CU"))
+    }
+
+    /// Simulate `instructions` with `payer` as fee payer; nothing is signed or sent
+    ///
+    /// Signature verification is off and the blockhash is replaced by the node,
+    /// so no keypair is needed, but the payer account must exist.
+    pub fn simulate_instructions(
+        &self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+    ) -> Result<RpcSimulateTransactionResult> {
+        let transaction = Transaction::new_unsigned(Message::new(instructions, Some(payer)));
+        self.simulate(&transaction)
+    }
+
+    /// Simulate `transaction` against current state without checking its
+    /// signatures, with the node supplying a fresh blockhash
+    fn simulate(&self, transaction: &impl SerializableTransaction) -> Result<RpcSimulateTransactionResult> {
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(self.rpc_client.commitment()),
+            ..RpcSimulateTransactionConfig::default()
+        };
+
+        Ok(self.rpc_client.simulate_transaction_with_config(transaction, config)?.value)
+    }
+
     /// Analyze compute unit usage for a program
     #[allow(dead_code)]
     fn analyze_compute_units(&self, program_id: &Pubkey) -> Result<(u64, u64)> {
@@ -205,17 +978,104 @@ impl SmartContractOptimizer {
         }
     }
 
-    /// Deep transaction analysis with log parsing and account tracking
-    fn analyze_transactions_deep(&self, program_id: &Pubkey) -> Result<Vec<TransactionAnalysis>> {
-        let signatures = self.rpc_client.get_signatures_for_address(program_id)?;
-        let mut analyses = Vec::new();
+    /// Walk back through signature history collecting those inside the slot range
+    fn signatures_in_slot_range(
+        &self,
+        program_id: &Pubkey,
+        from_slot: Option<u64>,
+        to_slot: Option<u64>,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let from = from_slot.unwrap_or(0);
+        let to = to_slot.unwrap_or(u64::MAX);
+        if from > to {
+            anyhow::bail!("--from-slot ({}) is after --to-slot ({})", from, to);
+        }
+
+        let mut in_range = Vec::new();
+        let mut before = None;
+        let mut scanned = 0usize;
+        let mut oldest_slot = None;
+
+        for _ in 0..MAX_SIGNATURE_PAGES {
+            let page = self.rpc_client.get_signatures_for_address_with_config(
+                program_id,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until: None,
+                    limit: None,
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )?;
+            let Some(last) = page.last() else {
+                break;
+            };
+
+            scanned += page.len();
+            oldest_slot = Some(last.slot);
+            before = Signature::from_str(&last.signature).ok();
+
+            let reached_start = last.slot < from;
+            in_range.extend(page.into_iter().filter(|sig| sig.slot >= from && sig.slot <= to));
+            if reached_start || before.is_none() {
+                break;
+            }
+        }
+
+        let range = format!("{}..={}",
+            from_slot.map_or("start".to_string(), |s| s.to_string()),
+            to_slot.map_or("latest".to_string(), |s| s.to_string())
+        );
+        println!("  {} {} transactions in slot range {} ({} signatures scanned)",
+            "▶".cyan(), in_range.len(), range, scanned
+        );
+
+        if let Some(oldest) = oldest_slot.filter(|&oldest| oldest > from && from_slot.is_some()) {
+            println!("  {} Available history only reaches back to slot {}; range starts at {}, so only {} transactions fell in range",
+                "⚠".yellow(), oldest, from, in_range.len()
+            );
+        }
+
+        Ok(in_range)
+    }
+
+    /// The signatures `sample_strategy` picks for deep analysis, newest first
+    fn select_sample<'a>(
+        &self,
+        signatures: &'a [RpcConfirmedTransactionStatusWithSignature],
+    ) -> Vec<&'a RpcConfirmedTransactionStatusWithSignature> {
+        match self.sample_strategy {
+            SampleStrategy::Recent => signatures.iter().take(SAMPLE_SIZE).collect(),
+            SampleStrategy::Random => {
+                let mut picked = rand::seq::index::sample(
+                    &mut rand::thread_rng(),
+                    signatures.len(),
+                    SAMPLE_SIZE.min(signatures.len()),
+                )
+                .into_vec();
+                picked.sort_unstable();
+                picked.into_iter().map(|i| &signatures[i]).collect()
+            }
+            SampleStrategy::FailedOnly => signatures.iter().filter(|s| s.err.is_some()).take(SAMPLE_SIZE).collect(),
+        }
+    }
 
-        for sig_info in signatures.iter().take(20) {
-            if let Ok(signature) = Signature::from_str(&sig_info.signature) {
-                if let Ok(transaction) = self.rpc_client.get_transaction(
+    /// Deep transaction analysis with log parsing and account tracking
+    fn analyze_transactions_deep(
+        &self,
+        signatures: &[RpcConfirmedTransactionStatusWithSignature],
+    ) -> FetchedSample {
+        let mut sample = FetchedSample::default();
+
+        for sig_info in self.select_sample(signatures) {
+            let fetched = Signature::from_str(&sig_info.signature)
+                .map_err(|e| e.to_string())
+                .and_then(|signature| self.rpc_client.get_transaction(
                     &signature,
                     solana_transaction_status::UiTransactionEncoding::JsonParsed,
-                ) {
+                ).map_err(|e| e.to_string().trim().to_string()));
+            match fetched {
+                Err(e) => sample.failed.push((sig_info.signature.clone(), e)),
+                Ok(transaction) => {
                     let cu_consumed = transaction
                         .transaction
                         .meta
@@ -249,7 +1109,7 @@ impl SmartContractOptimizer {
                         0
                     };
 
-                    analyses.push(TransactionAnalysis {
+                    sample.analyses.push(TransactionAnalysis {
                         signature: sig_info.signature.clone(),
                         cu_consumed,
                         accounts_accessed,
@@ -257,12 +1117,13 @@ impl SmartContractOptimizer {
                         instruction_count,
                         log_messages,
                         cpi_depth,
+                        error: sig_info.err.clone(),
                     });
                 }
             }
         }
 
-        Ok(analyses)
+        sample
     }
 
     /// Parse CPI depth from transaction logs
@@ -323,15 +1184,47 @@ impl SmartContractOptimizer {
         let mut total_reads = 0u64;
         let mut total_writes = 0u64;
 
+        // Bytes per access: ~100 read / ~200 written, or as measured by `calibrate`
+        let calibration = Calibration::load();
         for analysis in analyses {
-            // Estimate: each account read is ~100 bytes, each write is ~200 bytes
-            total_reads += analysis.accounts_accessed.len() as u64 * 100;
-            total_writes += analysis.writable_accounts.len() as u64 * 200;
+            total_reads += (analysis.accounts_accessed.len() as f64 * calibration.read_bytes_per_account) as u64;
+            total_writes += (analysis.writable_accounts.len() as f64 * calibration.write_bytes_per_account) as u64;
         }
 
         (total_reads, total_writes)
     }
 
+    /// Average data size per account access (read, written) over the program's
+    /// recent transactions; None when it has none
+    pub fn measure_account_sizes(&self, program_id: &Pubkey) -> Result<Option<(f64, f64)>> {
+        let signatures = self.rpc_client.get_signatures_for_address(program_id)?;
+        let analyses = self.analyze_transactions_deep(&signatures).analyses;
+        if analyses.is_empty() {
+            return Ok(None);
+        }
+
+        let mut unique: Vec<Pubkey> = analyses.iter().flat_map(|a| a.accounts_accessed.iter().copied()).collect();
+        unique.sort();
+        unique.dedup();
+
+        let mut sizes = HashMap::new();
+        for chunk in unique.chunks(100) {
+            let accounts = self.rpc_client.get_multiple_accounts(chunk)?;
+            for (pubkey, account) in chunk.iter().zip(accounts) {
+                sizes.insert(*pubkey, account.map_or(0, |a| a.data.len()));
+            }
+        }
+
+        let average = |accesses: Vec<&Pubkey>| {
+            let total: usize = accesses.iter().map(|pubkey| sizes.get(*pubkey).copied().unwrap_or(0)).sum();
+            if accesses.is_empty() { 0.0 } else { total as f64 / accesses.len() as f64 }
+        };
+        let read = average(analyses.iter().flat_map(|a| &a.accounts_accessed).collect());
+        let write = average(analyses.iter().flat_map(|a| &a.writable_accounts).collect());
+
+        Ok(Some((read, write)))
+    }
+
     /// Calculate optimization score (0-100)
     #[allow(dead_code)]
     fn calculate_optimization_score(&self, avg_cu: f64, account_size: u64, cu_limit: u64) -> f64 {
@@ -376,8 +1269,31 @@ impl SmartContractOptimizer {
         score.max(0.0).min(100.0)
     }
 
+    /// Write `cu_by_stack` as a folded-stack file (`stack count` per line)
+    /// that `inferno-flamegraph` / `flamegraph.pl` can render
+    pub fn write_cu_profile(&self, metrics: &ProgramMetrics, path: &std::path::Path) -> Result<()> {
+        let folded: String = metrics
+            .cu_by_stack
+            .iter()
+            .map(|(stack, cu)| format!("{} {}\n", stack, cu))
+            .collect();
+        std::fs::write(path, folded)?;
+        Ok(())
+    }
+
     /// Get optimization recommendations based on real analysis
+    ///
+    /// A `FailedOnly` sample describes why transactions fail rather than
+    /// typical load, so it gets the failure recommendations instead.
     pub fn get_recommendations(&self, metrics: &ProgramMetrics) -> Vec<OptimizationRecommendation> {
+        match metrics.sample_strategy {
+            SampleStrategy::FailedOnly => failure_recommendations(metrics),
+            _ => self.performance_recommendations(metrics),
+        }
+    }
+
+    /// Recommendations from the CU, account and transaction metrics
+    fn performance_recommendations(&self, metrics: &ProgramMetrics) -> Vec<OptimizationRecommendation> {
         let mut recommendations = Vec::new();
 
         // 1. Compute unit optimization - based on actual usage patterns
@@ -390,10 +1306,9 @@ impl SmartContractOptimizer {
                     "Using {:.0} CU/tx ({:.1}% of 200k limit). Optimize: 1) Reduce redundant calculations, 2) Cache frequently used values, 3) Minimize account deserialization, 4) Use more efficient data structures.",
                     metrics.average_cu_per_tx, cu_percentage
                 ),
-                estimated_improvement: format!("Potential savings: {:.0} CU/tx ({:.0} lamports/tx at 1 microlamport/CU)",
-                    metrics.average_cu_per_tx * 0.3,
-                    metrics.average_cu_per_tx * 0.3 / 1000.0
-                ),
+                estimated_improvement: cu_savings_estimate(metrics.cu_sample.as_ref()),
+                trace: Some(RecommendationTrace::above("average_cu_per_tx", metrics.average_cu_per_tx, 150_000.0)),
+                doc_url: doc_url("Compute Units"),
             });
         }
 
@@ -407,18 +1322,18 @@ impl SmartContractOptimizer {
                     metrics.cpi_depth
                 ),
                 estimated_improvement: format!("{:.0}% CU reduction per transaction", (metrics.cpi_depth - 2) as f64 * 5.0),
+                trace: Some(RecommendationTrace::above("cpi_depth", metrics.cpi_depth as f64, 3.0)),
+                doc_url: doc_url("CPI Chain Depth"),
             });
         }
 
         // 3. Account lock contention - based on actual write patterns
         let max_locks = metrics.account_locks.values().max().copied().unwrap_or(0);
         if max_locks > 15 {
-            let top_accounts: Vec<_> = metrics.account_locks.iter()
-                .filter(|(_, &count)| count > 10)
+            let account_list = metrics.hottest_accounts()
+                .into_iter()
+                .filter(|&(_, count)| count > 10)
                 .take(3)
-                .collect();
-
-            let account_list = top_accounts.iter()
                 .map(|(addr, count)| format!("{}... ({} writes)", &addr[..8], count))
                 .collect::<Vec<_>>()
                 .join(", ");
@@ -431,6 +1346,8 @@ impl SmartContractOptimizer {
                     account_list
                 ),
                 estimated_improvement: "2-5x throughput improvement with proper sharding".to_string(),
+                trace: Some(RecommendationTrace::above("max_account_write_locks", max_locks as f64, 15.0)),
+                doc_url: doc_url("Account Lock Contention"),
             });
         }
 
@@ -447,6 +1364,8 @@ impl SmartContractOptimizer {
                     size_kb, rent_cost
                 ),
                 estimated_improvement: format!("Save {:.1} KB storage, reduce rent by 60-80%", size_kb * 0.7),
+                trace: Some(RecommendationTrace::above("account_data_size", metrics.account_data_size as f64, 100_000.0)),
+                doc_url: doc_url("Account Size"),
             });
         }
 
@@ -466,6 +1385,8 @@ impl SmartContractOptimizer {
                     io_ratio * 100.0
                 ),
                 estimated_improvement: "15-25% reduction in transaction costs".to_string(),
+                trace: Some(RecommendationTrace::above("write_read_ratio", io_ratio, 0.5)),
+                doc_url: doc_url("Data I/O Efficiency"),
             });
         }
 
@@ -480,6 +1401,8 @@ impl SmartContractOptimizer {
                     metrics.transaction_count
                 ),
                 estimated_improvement: format!("Reduce to ~{} batched transactions, save 40-60% in fees", potential_batches),
+                trace: Some(RecommendationTrace::above("transaction_count", metrics.transaction_count as f64, 100.0)),
+                doc_url: doc_url("Transaction Batching"),
             });
         }
 
@@ -499,6 +1422,8 @@ impl SmartContractOptimizer {
                     avg_instructions
                 ),
                 estimated_improvement: "10-20% reduction in per-transaction overhead".to_string(),
+                trace: Some(RecommendationTrace::above("avg_instructions_per_tx", avg_instructions, 5.0)),
+                doc_url: doc_url("Instruction Count"),
             });
         }
 
@@ -509,12 +1434,24 @@ impl SmartContractOptimizer {
                 priority: Priority::Low,
                 description: "Optimize data structures: 1) Order struct fields by size (largest first), 2) Use #[repr(C)] for predictable layout, 3) Implement zero-copy with bytemuck, 4) Align to 8-byte boundaries.".to_string(),
                 estimated_improvement: "5-15% faster serialization, reduced CU for data access".to_string(),
+                trace: Some(RecommendationTrace::above("account_data_size", metrics.account_data_size as f64, 1000.0)),
+                doc_url: doc_url("Memory Layout"),
             });
         }
 
         recommendations
     }
 
+    /// Write the recommendations as a JSON array
+    pub fn write_recommendations_json(
+        &self,
+        recommendations: &[OptimizationRecommendation],
+        path: &std::path::Path,
+    ) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(recommendations)?)?;
+        Ok(())
+    }
+
     /// Apply automatic optimizations where possible
     pub async fn apply_optimizations(&self, program_id: &Pubkey) -> Result<()> {
         println!("\n{}", "⚡ Applying Smart Contract Optimizations...".green().bold());
@@ -565,6 +1502,15 @@ impl SmartContractOptimizer {
         println!("    Used: {} CU", metrics.compute_units_used);
         println!("    Limit: {} CU", metrics.compute_units_limit);
         println!("    Average per TX: {:.0} CU", metrics.average_cu_per_tx);
+        if let Some(sample) = &metrics.cu_sample {
+            println!("    Sampled: {} tx, min {} / median {} / avg {:.0} CU{}",
+                sample.size,
+                sample.minimum,
+                sample.median,
+                sample.average,
+                sample.simulated.map_or(String::new(), |units| format!(", median re-simulated: {} CU", units))
+            );
+        }
 
         let efficiency = if metrics.compute_units_limit > 0 {
             (metrics.compute_units_used as f64 / metrics.compute_units_limit as f64) * 100.0
@@ -579,10 +1525,43 @@ impl SmartContractOptimizer {
             metrics.account_data_size,
             metrics.account_data_size as f64 / 1024.0
         );
+        match &metrics.account_kind {
+            AccountKind::Program { upgradeable: Some(info) } => {
+                println!("    Type: upgradeable program (ProgramData {})", info.programdata_address);
+                match info.upgrade_authority {
+                    Some(authority) => println!("    Upgrade Authority: {}", authority.to_string().yellow().bold()),
+                    None => println!("    Upgrade Authority: {}", "none (immutable)".green()),
+                }
+                println!("    Last Deployed Slot: {}", info.last_deployed_slot);
+            }
+            AccountKind::Program { upgradeable: None } => {}
+            AccountKind::State { owner, lamports, rent_exempt_minimum } => {
+                println!("    Type: state account (owner {})", owner);
+                if lamports >= rent_exempt_minimum {
+                    println!("    Rent: {} ({} lamports, minimum {})", "exempt".green(), lamports, rent_exempt_minimum);
+                } else {
+                    println!("    Rent: {} ({} lamports, {} short of the {} minimum)",
+                        "NOT exempt".red(), lamports, rent_exempt_minimum - lamports, rent_exempt_minimum);
+                }
+            }
+        }
 
         println!();
         println!("  Transactions:");
-        println!("    Count: {}", metrics.transaction_count);
+        println!("    Count: {}{}", metrics.transaction_count,
+            if metrics.sample_strategy == SampleStrategy::FailedOnly { " failed" } else { "" });
+        println!("    Sample: {} (up to {} analyzed)", metrics.sample_strategy, SAMPLE_SIZE);
+        if !metrics.failures.is_empty() {
+            println!("    Failures in sample:");
+            for (kind, stats) in &metrics.failures {
+                println!("      {}: {} (up to {} CU)", kind.to_string().red(), stats.count, stats.max_cu);
+            }
+        } else if metrics.sample_strategy == SampleStrategy::FailedOnly {
+            println!("    {}", "No failed transactions among the fetched signatures".green());
+        }
+        if metrics.coverage < 1.0 {
+            println!("    Coverage: {}", format!("{:.0}% of the sample", metrics.coverage * 100.0).yellow());
+        }
 
         println!();
         println!("  Optimization Score: {:.0}/100", metrics.optimization_score);
@@ -594,10 +1573,64 @@ impl SmartContractOptimizer {
         } else {
             println!("    {}", "Needs optimization".red());
         }
+
+        if !metrics.errors.is_empty() {
+            println!("\n  {} Partial analysis; these RPC calls failed:", "⚠".yellow());
+            for error in &metrics.errors {
+                println!("    • {}", error);
+            }
+        }
+    }
+
+    /// Display the most write-locked accounts in the sample, ranked, with
+    /// how concentrated the locks are
+    pub fn display_contention(&self, metrics: &ProgramMetrics) {
+        println!("\n{}", "🔒 Account Lock Contention".cyan().bold());
+        println!();
+
+        let accounts = metrics.hottest_accounts();
+        let total: u64 = accounts.iter().map(|&(_, count)| count).sum();
+        if total == 0 {
+            println!("  {}", "No writable accounts in the sampled transactions".green());
+            return;
+        }
+
+        println!("  {:>4}  {:<44}  {:>6}  {:>7}", "Rank", "Account", "Writes", "Share");
+        for (rank, &(account, count)) in accounts.iter().take(CONTENTION_TOP).enumerate() {
+            let share = count as f64 / total as f64 * 100.0;
+            let line = format!("  {:>4}  {:<44}  {:>6}  {:>6.1}%", rank + 1, account, count, share);
+            if count > 15 {
+                println!("{}", line.red());
+            } else if count > 10 {
+                println!("{}", line.yellow());
+            } else {
+                println!("{}", line);
+            }
+        }
+        if accounts.len() > CONTENTION_TOP {
+            let rest: u64 = accounts[CONTENTION_TOP..].iter().map(|&(_, count)| count).sum();
+            println!("  {:>4}  {:<44}  {:>6}  {:>6.1}%", "",
+                format!("{} other account(s)", accounts.len() - CONTENTION_TOP), rest, rest as f64 / total as f64 * 100.0);
+        }
+
+        let gini = metrics.contention_concentration();
+        let verdict = if accounts.len() < 2 {
+            "all writes hit one account".red()
+        } else if gini >= 0.6 {
+            "concentrated: shard the top accounts".red()
+        } else if gini >= 0.3 {
+            "uneven".yellow()
+        } else {
+            "evenly spread".green()
+        };
+        println!();
+        println!("  Writes: {} across {} account(s)", total, accounts.len());
+        println!("  Concentration (Gini): {:.2} ({})", gini, verdict);
     }
 
-    /// Display optimization recommendations
-    pub fn display_recommendations(&self, recommendations: &[OptimizationRecommendation]) {
+    /// Display optimization recommendations; `explain` adds what triggered each
+    /// one and `docs` its documentation link
+    pub fn display_recommendations(&self, recommendations: &[OptimizationRecommendation], explain: bool, docs: bool) {
         println!("\n{}", "💡 Optimization Recommendations".cyan().bold());
         println!();
 
@@ -610,6 +1643,12 @@ impl SmartContractOptimizer {
             for rec in high_priority {
                 println!("    • {}: {}", rec.category.yellow(), rec.description);
                 println!("      Impact: {}", rec.estimated_improvement.green());
+                if let (true, Some(trace)) = (explain, &rec.trace) {
+                    println!("      Triggered because {}", trace.to_string().dimmed());
+                }
+                if let (true, Some(url)) = (docs, rec.doc_url) {
+                    println!("      Docs: {}", url.underline());
+                }
                 println!();
             }
         }
@@ -619,6 +1658,12 @@ impl SmartContractOptimizer {
             for rec in medium_priority {
                 println!("    • {}: {}", rec.category.cyan(), rec.description);
                 println!("      Impact: {}", rec.estimated_improvement.green());
+                if let (true, Some(trace)) = (explain, &rec.trace) {
+                    println!("      Triggered because {}", trace.to_string().dimmed());
+                }
+                if let (true, Some(url)) = (docs, rec.doc_url) {
+                    println!("      Docs: {}", url.underline());
+                }
                 println!();
             }
         }
@@ -628,27 +1673,147 @@ impl SmartContractOptimizer {
             for rec in low_priority {
                 println!("    • {}: {}", rec.category.blue(), rec.description);
                 println!("      Impact: {}", rec.estimated_improvement.green());
+                if let (true, Some(trace)) = (explain, &rec.trace) {
+                    println!("      Triggered because {}", trace.to_string().dimmed());
+                }
+                if let (true, Some(url)) = (docs, rec.doc_url) {
+                    println!("      Docs: {}", url.underline());
+                }
                 println!();
             }
         }
     }
 
-    /// Monitor program performance in real-time
-    pub async fn monitor_program(&self, program_id: &Pubkey) -> Result<()> {
+    /// Monitor program performance in real-time, backing off while the RPC fails
+    pub async fn monitor_program(&self, program_id: &Pubkey, poll_interval: &PollIntervalConfig) -> Result<()> {
         println!("{}", "🔍 Monitoring Smart Contract Performance...".cyan().bold());
         println!("Press Ctrl+C to stop\n");
 
+        let mut refresh = AdaptiveInterval::new(poll_interval, MONITOR_REFRESH_SECS);
+
         loop {
-            let metrics = self.analyze_program(program_id).await?;
-            self.display_metrics(&metrics);
+            match self.analyze_program(program_id).await {
+                Ok(metrics) => {
+                    refresh.success();
+                    self.display_metrics(&metrics);
+                }
+                Err(e) => {
+                    refresh.error();
+                    println!("{} RPC error #{}: {:#}", "⚠".yellow(), refresh.consecutive_errors(), e);
+                }
+            }
 
-            println!("\n{}", "Updating in 30 seconds...".dimmed());
-            tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+            println!("\n{}", format!("Updating in {} seconds...", refresh.current().as_secs()).dimmed());
+            refresh.sleep().await;
 
             // Clear screen for next update
             print!("\x1B[2J\x1B[1;1H");
         }
     }
+
+    /// Re-analyze on the poll interval, printing only significant changes
+    ///
+    /// A metric is reported when it moves by at least `threshold_pct` from the
+    /// value last reported (so slow drift is caught too); recommendations are
+    /// reported when they appear or disappear, and redeploys always are.
+    pub async fn watch_program(&self, program_id: &Pubkey, poll_interval: &PollIntervalConfig, threshold_pct: f64) -> Result<()> {
+        let timestamp = || chrono::Local::now().format("%H:%M:%S").to_string().dimmed();
+        let mut refresh = AdaptiveInterval::new(poll_interval, poll::DEFAULT_BASE_SECS);
+
+        let metrics = self.analyze_program(program_id).await?;
+        let mut reported = watched_metrics(&metrics);
+        let mut recommendations = recommendation_keys(&self.get_recommendations(&metrics));
+        let mut deployed_slot = last_deployed_slot(&metrics);
+
+        println!("\n[{}] Watching {} - changes of {}% or more are reported (Ctrl+C to stop)",
+            timestamp(), program_id, threshold_pct);
+        for (name, value) in &reported {
+            println!("  {}: {}", name, format_watched(*value));
+        }
+        for (priority, category) in &recommendations {
+            println!("  Recommendation [{:?}] {}", priority, category);
+        }
+
+        loop {
+            refresh.sleep().await;
+
+            let metrics = match self.collect_program_metrics(program_id, None, None) {
+                Ok(metrics) => {
+                    if refresh.consecutive_errors() > 0 {
+                        println!("[{}] {} RPC recovered", timestamp(), "✓".green());
+                    }
+                    refresh.success();
+                    metrics
+                }
+                Err(e) => {
+                    // Only the first error of a run is printed to keep the terminal quiet
+                    if refresh.consecutive_errors() == 0 {
+                        println!("[{}] {} RPC error, backing off: {:#}", timestamp(), "⚠".yellow(), e);
+                    }
+                    refresh.error();
+                    continue;
+                }
+            };
+
+            let deployed = last_deployed_slot(&metrics);
+            if deployed != deployed_slot {
+                println!("[{}] {} Program redeployed at slot {}", timestamp(), "▶".cyan(),
+                    deployed.map_or("?".to_string(), |slot| slot.to_string()));
+                deployed_slot = deployed;
+            }
+
+            for ((name, before), (_, after)) in reported.iter_mut().zip(watched_metrics(&metrics)) {
+                let change_pct = if *before == 0.0 {
+                    if after == 0.0 { 0.0 } else { 100.0 }
+                } else {
+                    (after - *before) / before.abs() * 100.0
+                };
+                if change_pct.abs() >= threshold_pct {
+                    println!("[{}] {}: {} → {} ({:+.1}%)", timestamp(), name,
+                        format_watched(*before), format_watched(after), change_pct);
+                    *before = after;
+                }
+            }
+
+            let current = recommendation_keys(&self.get_recommendations(&metrics));
+            for key in current.difference(&recommendations) {
+                println!("[{}] {} New recommendation [{:?}] {}", timestamp(), "+".yellow(), key.0, key.1);
+            }
+            for key in recommendations.difference(&current) {
+                println!("[{}] {} Resolved [{:?}] {}", timestamp(), "-".green(), key.0, key.1);
+            }
+            recommendations = current;
+        }
+    }
+}
+
+/// Metrics `watch_program` compares between analyses, in display order
+fn watched_metrics(metrics: &ProgramMetrics) -> Vec<(&'static str, f64)> {
+    vec![
+        ("Avg CU/tx", metrics.average_cu_per_tx),
+        ("Optimization score", metrics.optimization_score),
+        ("CPI depth", metrics.cpi_depth as f64),
+        ("Program size (bytes)", metrics.account_data_size as f64),
+        ("Data reads (bytes)", metrics.data_reads_bytes as f64),
+        ("Data writes (bytes)", metrics.data_writes_bytes as f64),
+    ]
+}
+
+fn format_watched(value: f64) -> String {
+    if value.fract() == 0.0 { format!("{:.0}", value) } else { format!("{:.1}", value) }
+}
+
+/// Recommendations identified by priority and category; descriptions embed
+/// the current numbers and would change on every analysis
+fn recommendation_keys(recommendations: &[OptimizationRecommendation]) -> std::collections::BTreeSet<(Priority, String)> {
+    recommendations.iter().map(|r| (r.priority, r.category.clone())).collect()
+}
+
+fn last_deployed_slot(metrics: &ProgramMetrics) -> Option<u64> {
+    match &metrics.account_kind {
+        AccountKind::Program { upgradeable: Some(info) } => Some(info.last_deployed_slot),
+        _ => None,
+    }
 }
 
 /// Compute unit optimization helpers
@@ -680,7 +1845,6 @@ pub mod accounts {
     use super::*;
 
     /// Calculate rent-exempt minimum balance
-    #[allow(dead_code)]
     pub fn calculate_rent_exempt_balance(rpc_client: &RpcClient, data_len: usize) -> Result<u64> {
         let rent = rpc_client.get_minimum_balance_for_rent_exemption(data_len)?;
         Ok(rent)
@@ -718,3 +1882,77 @@ pub mod batching {
         transactions.chunks(batch_size).map(|chunk| chunk.to_vec()).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_locks(locks: &[(&str, u64)]) -> ProgramMetrics {
+        ProgramMetrics {
+            compute_units_used: 0,
+            compute_units_limit: 0,
+            account_data_size: 0,
+            transaction_count: 0,
+            average_cu_per_tx: 0.0,
+            optimization_score: 0.0,
+            cpi_depth: 0,
+            account_locks: locks.iter().map(|(account, count)| (account.to_string(), *count)).collect(),
+            instruction_count: 0,
+            data_reads_bytes: 0,
+            data_writes_bytes: 0,
+            cu_by_stack: BTreeMap::new(),
+            account_kind: AccountKind::Program { upgradeable: None },
+            cu_sample: None,
+            sample_strategy: SampleStrategy::Recent,
+            failures: BTreeMap::new(),
+            coverage: 1.0,
+            errors: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn hottest_accounts_come_first_ties_by_name() {
+        let metrics = with_locks(&[("pool", 3), ("vault_b", 7), ("vault_a", 7), ("fee", 1)]);
+        assert_eq!(metrics.hottest_accounts(), [("vault_a", 7), ("vault_b", 7), ("pool", 3), ("fee", 1)]);
+    }
+
+    #[test]
+    fn concentration_is_the_gini_of_the_write_locks() {
+        assert_eq!(with_locks(&[("a", 5), ("b", 5), ("c", 5), ("d", 5)]).contention_concentration(), 0.0);
+        assert!((with_locks(&[("a", 1), ("b", 3)]).contention_concentration() - 0.25).abs() < 1e-9);
+        assert!((with_locks(&[("a", 0), ("b", 0), ("c", 0), ("d", 4)]).contention_concentration() - 0.75).abs() < 1e-9);
+        // Too few accounts or locks to be concentrated
+        assert_eq!(with_locks(&[("a", 9)]).contention_concentration(), 0.0);
+        assert_eq!(with_locks(&[("a", 0), ("b", 0)]).contention_concentration(), 0.0);
+    }
+
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn nested_invocations_fold_into_self_cu() {
+        let logs = logs(&[
+            "Program Dex111 invoke [1]",
+            "Program log: Instruction: Swap",
+            "Program Token111 invoke [2]",
+            "Program log: Instruction: Transfer",
+            "Program Token111 consumed 1000 of 195000 compute units",
+            "Program Token111 success",
+            "Program 11111111111111111111111111111111 invoke [2]",
+            "Program 11111111111111111111111111111111 success",
+            "Program Dex111 consumed 5000 of 200000 compute units",
+            "Program Dex111 success",
+        ]);
+        // Two identical transactions add up on the same stacks
+        let mut stacks = BTreeMap::new();
+        fold_cu_stacks(&logs, &mut stacks);
+        fold_cu_stacks(&logs, &mut stacks);
+
+        // The parent keeps only what its CPI didn't consume; the builtin logs no CU
+        assert_eq!(stacks, BTreeMap::from([
+            ("Dex111;Swap".to_string(), 8000),
+            ("Dex111;Swap;Token111;Transfer".to_string(), 2000),
+        ]));
+    }
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+/// Default EMA weight given to each new reading
+pub const DEFAULT_EMA_ALPHA: f64 = 0.3;
+
+/// Exponential moving average
+#[derive(Debug, Clone, Copy)]
+pub struct Ema {
+    alpha: f64,
+    value: Option<f64>,
+}
+
+impl Ema {
+    /// `alpha` in (0, 1]; higher follows new readings more closely
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha: alpha.clamp(f64::EPSILON, 1.0),
+            value: None,
+        }
+    }
+
+    /// Fold in a reading and return the new average (the first reading seeds it)
+    pub fn update(&mut self, sample: f64) -> f64 {
+        let next = match self.value {
+            Some(prev) => self.alpha * sample + (1.0 - self.alpha) * prev,
+            None => sample,
+        };
+        self.value = Some(next);
+        next
+    }
+}
+
+/// Smoothed values of the metrics that jump around between readings
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct SmoothedMetrics {
+    pub avg_tps: f64,
+    pub skip_rate: f64,
+    pub network_latency_ms: f64,
+}
+
+/// EMAs for TPS, skip rate and latency, updated once per reading
+#[derive(Debug, Clone, Copy)]
+pub struct MetricsSmoother {
+    avg_tps: Ema,
+    skip_rate: Ema,
+    network_latency_ms: Ema,
+}
+
+impl MetricsSmoother {
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            avg_tps: Ema::new(alpha),
+            skip_rate: Ema::new(alpha),
+            network_latency_ms: Ema::new(alpha),
+        }
+    }
+
+    pub fn update(&mut self, avg_tps: f64, skip_rate: f64, network_latency_ms: f64) -> SmoothedMetrics {
+        SmoothedMetrics {
+            avg_tps: self.avg_tps.update(avg_tps),
+            skip_rate: self.skip_rate.update(skip_rate),
+            network_latency_ms: self.network_latency_ms.update(network_latency_ms),
+        }
+    }
+}
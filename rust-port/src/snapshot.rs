@@ -0,0 +1,540 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use solana_sdk::hash::Hash;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use solana_sdk::commitment_config::CommitmentConfig;
+use crate::blockchain;
+use crate::config::ValidatorConfig;
+use crate::disk::format_bytes;
+use crate::monitor::format_elapsed;
+use crate::system::SystemMonitor;
+use crate::tls;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Full,
+    Incremental { base_slot: u64 },
+}
+
+/// A snapshot archive, described by its file name
+///
+/// Full: `snapshot-<slot>-<hash>.tar.<ext>`
+/// Incremental: `incremental-snapshot-<base slot>-<slot>-<hash>.tar.<ext>`
+#[derive(Debug, Clone)]
+pub struct SnapshotArchive {
+    pub path: PathBuf,
+    pub kind: ArchiveKind,
+    pub slot: u64,
+    pub hash: String,
+    /// Extension after `.tar`, e.g. `zst`; empty for an uncompressed tar
+    pub compression: String,
+}
+
+impl SnapshotArchive {
+    pub fn parse(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        let (stem, compression) = match name.split_once(".tar") {
+            Some((stem, rest)) => (stem, rest.trim_start_matches('.')),
+            None => return None,
+        };
+
+        let (kind, rest) = if let Some(rest) = stem.strip_prefix("incremental-snapshot-") {
+            let (base, rest) = rest.split_once('-')?;
+            (ArchiveKind::Incremental { base_slot: base.parse().ok()? }, rest)
+        } else {
+            (ArchiveKind::Full, stem.strip_prefix("snapshot-")?)
+        };
+        let (slot, hash) = rest.split_once('-')?;
+
+        Some(Self {
+            path: path.to_path_buf(),
+            kind,
+            slot: slot.parse().ok()?,
+            hash: hash.to_string(),
+            compression: compression.to_string(),
+        })
+    }
+
+    pub fn file_name(&self) -> String {
+        self.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+    }
+
+    fn open(&self) -> Result<Box<dyn Read>> {
+        let file = BufReader::new(File::open(&self.path)?);
+        Ok(match self.compression.as_str() {
+            "zst" => Box::new(zstd::Decoder::with_buffer(file)?),
+            "gz" => Box::new(flate2::read::GzDecoder::new(file)),
+            "" => Box::new(file),
+            other => anyhow::bail!("unsupported compression .tar.{}", other),
+        })
+    }
+}
+
+/// Snapshot archives directly under `dir`, newest slot first
+pub fn list_archives(dir: &Path) -> Vec<SnapshotArchive> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut archives: Vec<SnapshotArchive> = entries
+        .flatten()
+        .filter_map(|entry| SnapshotArchive::parse(&entry.path()))
+        .collect();
+    archives.sort_by_key(|a| std::cmp::Reverse(a.slot));
+    archives
+}
+
+/// What a full read of an archive found
+struct ArchiveContents {
+    entries: usize,
+    bytes: u64,
+}
+
+/// Read every entry of the archive end to end
+///
+/// Decompression and tar framing errors surface truncated or corrupt files.
+/// The archive must also hold a `version` file and the bank for the slot
+/// its file name claims (`snapshots/<slot>/<slot>`).
+fn check_contents(archive: &SnapshotArchive) -> Result<ArchiveContents> {
+    let bank_path = format!("snapshots/{0}/{0}", archive.slot);
+    let mut tar = tar::Archive::new(archive.open()?);
+
+    let mut contents = ArchiveContents { entries: 0, bytes: 0 };
+    let mut has_version = false;
+    let mut has_bank = false;
+
+    for entry in tar.entries().context("unreadable tar stream")? {
+        let mut entry = entry.context("corrupt tar entry")?;
+        let path = entry.path()?.to_string_lossy().to_string();
+        has_version |= path == "version";
+        has_bank |= path == bank_path;
+
+        contents.bytes += io::copy(&mut entry, &mut io::sink())
+            .with_context(|| format!("failed reading {}", path))?;
+        contents.entries += 1;
+    }
+
+    if !has_version {
+        anyhow::bail!("missing `version` file");
+    }
+    if !has_bank {
+        anyhow::bail!("no bank for slot {} ({} missing)", archive.slot, bank_path);
+    }
+    Ok(contents)
+}
+
+fn ledger_tool_installed() -> bool {
+    Command::new("solana-ledger-tool")
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Load the newest snapshot with `solana-ledger-tool`, which recomputes the
+/// accounts hash and checks it against the one recorded in the snapshot
+fn ledger_tool_verify(config: &ValidatorConfig, slot: u64) -> Result<()> {
+    let output = Command::new("solana-ledger-tool")
+        .arg("--ledger").arg(&config.ledger_path)
+        .arg("--snapshot-archive-path").arg(&config.snapshots_path)
+        .args(["verify", "--halt-at-slot", &slot.to_string()])
+        .output()
+        .context("Failed to run solana-ledger-tool")?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{}", stderr.lines().last().unwrap_or("verify failed").trim())
+    }
+}
+
+/// `verify-snapshot`: check the newest full and incremental archives (or all)
+pub fn verify_snapshots(all: bool) -> Result<()> {
+    let config = ValidatorConfig::load()?;
+    let archives = list_archives(&config.snapshots_path);
+
+    let newest_full = archives.iter().find(|a| a.kind == ArchiveKind::Full);
+    let newest_incremental = archives.iter().find(|a| matches!(a.kind, ArchiveKind::Incremental { .. }));
+
+    let selected: Vec<&SnapshotArchive> = if all {
+        archives.iter().collect()
+    } else {
+        newest_full.into_iter().chain(newest_incremental).collect()
+    };
+
+    println!("{}", "=== Snapshot Verification ===".cyan().bold());
+    println!("Snapshots dir: {}\n", config.snapshots_path.display());
+
+    if selected.is_empty() {
+        println!("{} No snapshot archives found", "⚠".yellow());
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for archive in &selected {
+        println!("{} {} (reading entire archive...)", "▶".cyan(), archive.file_name());
+
+        let result = Hash::from_str(&archive.hash)
+            .map_err(|_| anyhow::anyhow!("file name hash '{}' is not a valid hash", archive.hash))
+            .and_then(|_| check_contents(archive));
+
+        match result {
+            Ok(contents) => println!("└─ {} {} entries, {} unpacked, bank slot {}",
+                "✓".green(),
+                contents.entries,
+                format_bytes(contents.bytes),
+                archive.slot
+            ),
+            Err(e) => {
+                failed += 1;
+                println!("└─ {} {:#}", "✗".red(), e);
+            }
+        }
+    }
+
+    if let (Some(full), Some(incremental)) = (newest_full, newest_incremental) {
+        if let ArchiveKind::Incremental { base_slot } = incremental.kind {
+            if base_slot != full.slot {
+                println!("\n{} Newest incremental builds on slot {} but the newest full snapshot is slot {}",
+                    "⚠".yellow(), base_slot, full.slot
+                );
+            }
+        }
+    }
+
+    if let Some(newest) = newest_incremental.or(newest_full) {
+        println!();
+        if ledger_tool_installed() {
+            println!("{} Verifying accounts hash at slot {} with solana-ledger-tool...", "▶".cyan(), newest.slot);
+            match ledger_tool_verify(&config, newest.slot) {
+                Ok(()) => println!("└─ {} Accounts hash matches", "✓".green()),
+                Err(e) => {
+                    failed += 1;
+                    println!("└─ {} {}", "✗".red(), e);
+                }
+            }
+        } else {
+            println!("{} solana-ledger-tool not found - skipped accounts hash verification", "ℹ".cyan());
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} snapshot check(s) failed", failed);
+    }
+    println!("\n{} All checked snapshot archives passed", "✓".green());
+    Ok(())
+}
+
+/// Size on disk and time since last modified; None when the file is gone
+fn size_and_age(archive: &SnapshotArchive) -> Option<(u64, chrono::Duration)> {
+    let metadata = fs::metadata(&archive.path).ok()?;
+    let age = metadata.modified().ok()?.elapsed().unwrap_or_default();
+    Some((metadata.len(), chrono::Duration::from_std(age).unwrap_or_else(|_| chrono::Duration::zero())))
+}
+
+fn describe(archive: &SnapshotArchive) -> String {
+    let kind = match archive.kind {
+        ArchiveKind::Full => "full".to_string(),
+        ArchiveKind::Incremental { base_slot } => format!("incremental on {}", base_slot),
+    };
+    match size_and_age(archive) {
+        Some((size, age)) => format!("slot {} ({}), {}, {} old", archive.slot, kind, format_bytes(size), format_elapsed(age)),
+        None => format!("slot {} ({})", archive.slot, kind),
+    }
+}
+
+/// `snapshots list`: every archive in the snapshots dir, newest first, with
+/// incrementals under the full snapshot they build on
+pub fn list_snapshots() -> Result<()> {
+    let config = ValidatorConfig::load()?;
+    let archives = list_archives(&config.snapshots_path);
+
+    println!("{}", "=== Snapshot Archives ===".cyan().bold());
+    println!("Snapshots dir: {}\n", config.snapshots_path.display());
+
+    if archives.is_empty() {
+        println!("{} No snapshot archives found", "⚠".yellow());
+        return Ok(());
+    }
+
+    let fulls: Vec<&SnapshotArchive> = archives.iter().filter(|a| a.kind == ArchiveKind::Full).collect();
+    for full in &fulls {
+        println!("{} {}", "▶".cyan(), describe(full));
+        let incrementals: Vec<&SnapshotArchive> = archives
+            .iter()
+            .filter(|a| a.kind == ArchiveKind::Incremental { base_slot: full.slot })
+            .collect();
+        for (i, incremental) in incrementals.iter().enumerate() {
+            let branch = if i + 1 == incrementals.len() { "└─" } else { "├─" };
+            println!("{} {}", branch, describe(incremental));
+        }
+    }
+
+    let orphans: Vec<&SnapshotArchive> = archives.iter().filter(|a| is_orphan(a, &fulls)).collect();
+    if !orphans.is_empty() {
+        println!("{} Incrementals whose full snapshot is gone (unusable):", "⚠".yellow());
+        for orphan in orphans {
+            println!("  • {}", describe(orphan));
+        }
+    }
+
+    let total: u64 = archives.iter().filter_map(size_and_age).map(|(size, _)| size).sum();
+    println!("\n{} full, {} incremental, {} total",
+        fulls.len(), archives.len() - fulls.len(), format_bytes(total));
+    Ok(())
+}
+
+/// An incremental whose base full snapshot isn't in `fulls`
+fn is_orphan(archive: &SnapshotArchive, fulls: &[&SnapshotArchive]) -> bool {
+    match archive.kind {
+        ArchiveKind::Incremental { base_slot } => !fulls.iter().any(|full| full.slot == base_slot),
+        ArchiveKind::Full => false,
+    }
+}
+
+/// `snapshots prune`: delete all but the `keep` newest full snapshots and the
+/// incrementals built on them; only lists what would go unless `confirm`
+///
+/// Incrementals are kept or deleted with their base full snapshot, so a kept
+/// incremental never loses the snapshot it needs. Incrementals whose base is
+/// already gone can't be loaded and are deleted too.
+pub fn prune_snapshots(keep: usize, confirm: bool) -> Result<()> {
+    if keep == 0 {
+        anyhow::bail!("--keep must be at least 1; the validator needs a full snapshot to start from");
+    }
+
+    let config = ValidatorConfig::load()?;
+    let archives = list_archives(&config.snapshots_path);
+    let fulls: Vec<&SnapshotArchive> = archives.iter().filter(|a| a.kind == ArchiveKind::Full).collect();
+    let kept_fulls = &fulls[..keep.min(fulls.len())];
+
+    let (kept, pruned): (Vec<&SnapshotArchive>, Vec<&SnapshotArchive>) = archives.iter().partition(|archive| {
+        let full_slot = match archive.kind {
+            ArchiveKind::Full => archive.slot,
+            ArchiveKind::Incremental { base_slot } => base_slot,
+        };
+        kept_fulls.iter().any(|full| full.slot == full_slot)
+    });
+
+    println!("{}", "=== Snapshot Prune ===".cyan().bold());
+    println!("Snapshots dir: {}", config.snapshots_path.display());
+    println!("Keeping the {} newest full snapshot(s) and their incrementals\n", keep);
+
+    for archive in &kept {
+        println!("  {} keep   {}", "✓".green(), describe(archive));
+    }
+    for archive in &pruned {
+        println!("  {} delete {}", "✗".red(), describe(archive));
+    }
+
+    let freed: u64 = pruned.iter().filter_map(|a| size_and_age(a)).map(|(size, _)| size).sum();
+    if pruned.is_empty() {
+        println!("\n{} Nothing to prune", "✓".green());
+        return Ok(());
+    }
+    if !confirm {
+        println!("\n{} Dry run: {} archive(s), {} would be freed. Re-run with --confirm to delete.",
+            "ℹ".cyan(), pruned.len(), format_bytes(freed));
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for archive in &pruned {
+        if let Err(e) = fs::remove_file(&archive.path) {
+            failed += 1;
+            println!("{} Failed to delete {}: {}", "✗".red(), archive.file_name(), e);
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("{} of {} archive(s) could not be deleted", failed, pruned.len());
+    }
+    println!("\n{} Deleted {} archive(s), freed {}", "✓".green(), pruned.len(), format_bytes(freed));
+    Ok(())
+}
+
+/// The validator requires snapshot intervals to be multiples of the accounts
+/// hash interval, which defaults to 100 slots
+const SNAPSHOT_INTERVAL_STEP: u32 = 100;
+
+/// Longest incremental interval suggested; restarts replay at most this many slots
+const MAX_SUGGESTED_INTERVAL: u32 = 2_000;
+
+/// Share of the validator's disk writes snapshots may account for before the
+/// interval is lengthened (or below which it is shortened)
+const TARGET_SNAPSHOT_IO_SHARE: f64 = 0.15;
+
+/// One sampling period of the validator process
+struct IoSample {
+    secs: f64,
+    written_bytes: u64,
+    cpu_usage: f32,
+    /// An incremental snapshot archive appeared during this period or the next
+    snapshotting: bool,
+}
+
+/// What snapshots cost the host over the observation
+#[derive(Debug, Clone)]
+pub struct SnapshotIoProfile {
+    pub snapshots: usize,
+    pub observed_secs: f64,
+    /// Disk write rate outside snapshot periods (bytes/s)
+    pub baseline_write_rate: f64,
+    /// Disk write rate during snapshot periods (bytes/s)
+    pub snapshot_write_rate: f64,
+    pub baseline_cpu: f64,
+    pub snapshot_cpu: f64,
+    /// Fraction of all bytes written attributable to snapshots
+    pub snapshot_io_share: f64,
+}
+
+fn average(values: impl Iterator<Item = f64>) -> f64 {
+    let (count, sum) = values.fold((0usize, 0.0), |(count, sum), v| (count + 1, sum + v));
+    if count == 0 { 0.0 } else { sum / count as f64 }
+}
+
+fn profile_samples(samples: &[IoSample], snapshots: usize) -> SnapshotIoProfile {
+    let rate = |s: &&IoSample| s.written_bytes as f64 / s.secs.max(f64::EPSILON);
+    let (during, outside): (Vec<&IoSample>, Vec<&IoSample>) = samples.iter().partition(|s| s.snapshotting);
+
+    let baseline_write_rate = average(outside.iter().map(rate));
+    let snapshot_write_rate = average(during.iter().map(rate));
+
+    let total: f64 = samples.iter().map(|s| s.written_bytes as f64).sum();
+    let extra: f64 = during
+        .iter()
+        .map(|s| (s.written_bytes as f64 - baseline_write_rate * s.secs).max(0.0))
+        .sum();
+
+    SnapshotIoProfile {
+        snapshots,
+        observed_secs: samples.iter().map(|s| s.secs).sum(),
+        baseline_write_rate,
+        snapshot_write_rate,
+        baseline_cpu: average(outside.iter().map(|s| s.cpu_usage as f64)),
+        snapshot_cpu: average(during.iter().map(|s| s.cpu_usage as f64)),
+        snapshot_io_share: if total > 0.0 { extra / total } else { 0.0 },
+    }
+}
+
+/// Interval that brings snapshots' share of disk writes to the target
+///
+/// Each snapshot costs roughly the same I/O, so the share scales with
+/// 1 / interval. Rounded to the accounts hash interval and kept below the
+/// full snapshot interval.
+pub fn suggest_interval(current: u32, full_interval: u32, io_share: f64) -> u32 {
+    let ideal = current as f64 * io_share / TARGET_SNAPSHOT_IO_SHARE;
+    let step = SNAPSHOT_INTERVAL_STEP as f64;
+    let rounded = ((ideal / step).round() * step) as u32;
+
+    let upper = MAX_SUGGESTED_INTERVAL.min(full_interval.saturating_sub(SNAPSHOT_INTERVAL_STEP));
+    rounded.clamp(SNAPSHOT_INTERVAL_STEP, upper.max(SNAPSHOT_INTERVAL_STEP))
+}
+
+/// `config suggest-snapshot-interval`: watch the validator's disk writes and CPU
+/// around incremental snapshots and suggest `incremental_snapshot_interval`
+pub async fn suggest_snapshot_interval(observe_secs: u64, sample_secs: u64, write: bool) -> Result<()> {
+    let mut config = ValidatorConfig::load()?;
+    let current = config.optimization.incremental_snapshot_interval;
+    let sample_secs = sample_secs.max(1);
+
+    let newest_incremental = |dir: &Path| {
+        list_archives(dir)
+            .into_iter()
+            .filter(|a| matches!(a.kind, ArchiveKind::Incremental { .. }))
+            .map(|a| a.slot)
+            .max()
+    };
+
+    let Some(mut last) = SystemMonitor::get_metrics().validator_process else {
+        anyhow::bail!("No running solana-validator to observe");
+    };
+    let mut last_archive = newest_incremental(&config.snapshots_path);
+    let mut last_at = std::time::Instant::now();
+    let mut samples: Vec<IoSample> = Vec::new();
+    let mut snapshots = 0;
+
+    println!("{}", "=== Snapshot Interval Analysis ===".cyan().bold());
+    println!("Observing validator (pid {}) for {}s, sampling every {}s...", last.pid, observe_secs, sample_secs);
+
+    let deadline = last_at + std::time::Duration::from_secs(observe_secs);
+    while std::time::Instant::now() < deadline {
+        tokio::time::sleep(std::time::Duration::from_secs(sample_secs)).await;
+
+        let Some(process) = SystemMonitor::get_metrics().validator_process.filter(|p| p.pid == last.pid) else {
+            anyhow::bail!("Validator (pid {}) exited during observation", last.pid);
+        };
+        let archive = newest_incremental(&config.snapshots_path);
+        let new_snapshot = archive > last_archive;
+        if new_snapshot {
+            snapshots += 1;
+            println!("  {} Incremental snapshot at slot {}", "▶".cyan(), archive.unwrap_or_default());
+            // Archives appear once fully written, so the period before counts too
+            if let Some(previous) = samples.last_mut() {
+                previous.snapshotting = true;
+            }
+        }
+
+        samples.push(IoSample {
+            secs: last_at.elapsed().as_secs_f64(),
+            written_bytes: process.disk_written_bytes.saturating_sub(last.disk_written_bytes),
+            cpu_usage: process.cpu_usage,
+            snapshotting: new_snapshot,
+        });
+        last = process;
+        last_archive = archive;
+        last_at = std::time::Instant::now();
+    }
+
+    if snapshots == 0 {
+        let rpc_client = tls::rpc_client(format!("http://127.0.0.1:{}", config.rpc_port), CommitmentConfig::default());
+        let slot_ms = blockchain::slot_time_ms(&rpc_client).await;
+        println!("\n{} No incremental snapshot was taken while observing", "⚠".yellow());
+        println!("  Observe for longer than one interval ({} slots ≈ {}s) with {}.",
+            current,
+            (current as f64 * slot_ms / 1000.0).round(),
+            "--observe-secs".cyan()
+        );
+        return Ok(());
+    }
+
+    let profile = profile_samples(&samples, snapshots);
+    let suggested = suggest_interval(current, config.optimization.full_snapshot_interval, profile.snapshot_io_share);
+
+    println!("\n{}", "Snapshot I/O Profile".cyan().bold());
+    println!("├─ Observed: {:.0}s, {} incremental snapshot(s)", profile.observed_secs, profile.snapshots);
+    println!("├─ Disk writes: {}/s normally, {}/s while snapshotting",
+        format_bytes(profile.baseline_write_rate as u64),
+        format_bytes(profile.snapshot_write_rate as u64)
+    );
+    println!("├─ Validator CPU: {:.0}% normally, {:.0}% while snapshotting", profile.baseline_cpu, profile.snapshot_cpu);
+    println!("├─ Snapshots' share of disk writes: {:.1}% (target {:.0}%)",
+        profile.snapshot_io_share * 100.0,
+        TARGET_SNAPSHOT_IO_SHARE * 100.0
+    );
+    println!("├─ Current interval: {} slots", current);
+    println!("└─ Suggested interval: {} slots", suggested.to_string().green().bold());
+
+    println!("\n{}", "Tradeoff".cyan().bold());
+    println!("  Shorter interval: a restarted validator replays fewer slots from its last");
+    println!("  snapshot and catches up faster, but snapshots are written more often (more I/O).");
+    println!("  Longer interval: less snapshot I/O competing with replay and voting, but slower");
+    println!("  recovery after a restart.");
+
+    if write && suggested != current {
+        config.optimization.incremental_snapshot_interval = suggested;
+        config.save()?;
+        println!("\n{} Saved incremental_snapshot_interval = {} to config", "✓".green(), suggested);
+    } else if suggested != current {
+        println!("\nRun with {} to save this value to the config.", "--write".cyan());
+    } else {
+        println!("\n{} Current interval already balances I/O and recovery on this host", "✓".green());
+    }
+
+    Ok(())
+}
@@ -1,17 +1,105 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use colored::Colorize;
 use std::process::{Command, Stdio};
 use std::fs;
-use std::path::Path;
 use tokio::time::{sleep, Duration};
-use serde_json;
+
+use crate::config::ValidatorConfig;
+use crate::connect::{connect_validator, read_or_disconnect, KeypairUse};
+
+/// Metric readings taken before optimizing, to measure how noisy they are
+const BASELINE_SAMPLES: usize = 5;
+
+/// Readings taken after optimizing (the check stops early once an improvement is clear)
+const OPTIMIZED_SAMPLES: usize = 6;
+
+/// Fewest post-optimization readings an improvement is judged on
+const MIN_OPTIMIZED_SAMPLES: usize = 3;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Smallest standard deviation assumed for a rate (percentage points), so a
+/// baseline of identical readings doesn't make any blip look significant
+const MIN_RATE_STD_DEV: f64 = 0.1;
 
 /// Standalone Rust optimizer - no shell scripts, real optimizations only
 pub struct StandaloneOptimizer {
     validator_identity: String,
-    vote_account: String,
     baseline_metrics: ValidatorMetrics,
     optimized_metrics: ValidatorMetrics,
+    baseline_samples: Vec<ValidatorMetrics>,
+    optimized_samples: Vec<ValidatorMetrics>,
+}
+
+/// Mean and sample standard deviation of repeated readings
+#[derive(Debug, Clone, Copy)]
+pub struct SampleStats {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub count: usize,
+}
+
+impl SampleStats {
+    pub fn new(values: &[f64]) -> Self {
+        let count = values.len();
+        let mean = values.iter().sum::<f64>() / count.max(1) as f64;
+        let variance = if count > 1 {
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (count - 1) as f64
+        } else {
+            0.0
+        };
+        Self { mean, std_dev: variance.sqrt(), count }
+    }
+}
+
+/// Before/after comparison of one metric
+#[derive(Debug, Clone, Copy)]
+pub struct Improvement {
+    pub before: SampleStats,
+    pub after: SampleStats,
+    /// Change in the good direction (negative = got worse)
+    pub gain: f64,
+    /// Gain in units of the baseline's standard deviation
+    pub sigmas: f64,
+    /// One-sided confidence (%) that the metric really improved
+    pub confidence_pct: f64,
+}
+
+impl Improvement {
+    fn new(before: &[f64], after: &[f64], higher_is_better: bool) -> Self {
+        let before = SampleStats::new(before);
+        let after = SampleStats::new(after);
+        let gain = if higher_is_better { after.mean - before.mean } else { before.mean - after.mean };
+        let noise = before.std_dev.max(MIN_RATE_STD_DEV);
+
+        // Welch's t statistic, read off the normal distribution
+        let after_noise = after.std_dev.max(MIN_RATE_STD_DEV);
+        let standard_error = (noise.powi(2) / before.count.max(1) as f64
+            + after_noise.powi(2) / after.count.max(1) as f64)
+            .sqrt();
+
+        Self {
+            before,
+            after,
+            gain,
+            sigmas: gain / noise,
+            confidence_pct: normal_cdf(gain / standard_error) * 100.0,
+        }
+    }
+
+    /// Improved by more than the baseline's own variation
+    pub fn is_significant(&self) -> bool {
+        self.sigmas > 1.0
+    }
+}
+
+/// Standard normal CDF (Abramowitz & Stegun 7.1.26 approximation of erf)
+fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 { (1.0 + erf) / 2.0 } else { (1.0 - erf) / 2.0 }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -28,13 +116,13 @@ impl StandaloneOptimizer {
     pub async fn new() -> Result<Self> {
         // Get validator identity from config
         let identity = Self::get_validator_identity()?;
-        let vote_account = Self::get_vote_account()?;
         
         Ok(Self {
             validator_identity: identity,
-            vote_account,
             baseline_metrics: ValidatorMetrics::default(),
             optimized_metrics: ValidatorMetrics::default(),
+            baseline_samples: Vec::new(),
+            optimized_samples: Vec::new(),
         })
     }
     
@@ -58,7 +146,14 @@ impl StandaloneOptimizer {
         
         // Step 2: Collect baseline metrics
         println!("\n{}", "Step 2: Collecting baseline metrics...".cyan());
-        optimizer.baseline_metrics = optimizer.get_real_metrics().await?;
+        for i in 1..=BASELINE_SAMPLES {
+            if i > 1 {
+                sleep(SAMPLE_INTERVAL).await;
+            }
+            println!("  [{}/{}] Sampling baseline...", i, BASELINE_SAMPLES);
+            optimizer.baseline_samples.push(optimizer.get_real_metrics().await?);
+        }
+        optimizer.baseline_metrics = Self::average(&optimizer.baseline_samples);
         optimizer.display_metrics("Baseline", &optimizer.baseline_metrics);
         
         // Step 3: Apply real optimizations
@@ -68,20 +163,22 @@ impl StandaloneOptimizer {
         
         // Step 4: Wait for optimizations to take effect
         println!("\n{}", "Step 4: Waiting for optimizations...".cyan());
-        for i in 1..=6 {
-            print!("  [{}/6] ", i);
-            sleep(Duration::from_secs(10)).await;
+        for i in 1..=OPTIMIZED_SAMPLES {
+            print!("  [{}/{}] ", i, OPTIMIZED_SAMPLES);
+            sleep(SAMPLE_INTERVAL).await;
             println!("Checking metrics...");
-            let current = optimizer.get_real_metrics().await?;
-            if current.vote_success_rate > optimizer.baseline_metrics.vote_success_rate + 5.0 {
-                println!("  {} Improvements detected!", "✓".green());
+            optimizer.optimized_samples.push(optimizer.get_real_metrics().await?);
+            if optimizer.optimized_samples.len() >= MIN_OPTIMIZED_SAMPLES
+                && optimizer.vote_improvement().is_significant()
+            {
+                println!("  {} Improvement beyond baseline noise detected!", "✓".green());
                 break;
             }
         }
         
         // Step 5: Collect optimized metrics
         println!("\n{}", "Step 5: Measuring results...".cyan());
-        optimizer.optimized_metrics = optimizer.get_real_metrics().await?;
+        optimizer.optimized_metrics = Self::average(&optimizer.optimized_samples);
         optimizer.display_metrics("Optimized", &optimizer.optimized_metrics);
         
         // Step 6: Show improvements
@@ -91,9 +188,35 @@ impl StandaloneOptimizer {
         Ok(())
     }
     
+    /// Per-field mean of several readings (latest slot and sync state)
+    fn average(samples: &[ValidatorMetrics]) -> ValidatorMetrics {
+        let Some(latest) = samples.last() else {
+            return ValidatorMetrics::default();
+        };
+        let n = samples.len() as f64;
+        ValidatorMetrics {
+            vote_success_rate: samples.iter().map(|m| m.vote_success_rate).sum::<f64>() / n,
+            skip_rate: samples.iter().map(|m| m.skip_rate).sum::<f64>() / n,
+            credits_earned: latest.credits_earned,
+            vote_lag: (samples.iter().map(|m| m.vote_lag as f64).sum::<f64>() / n).round() as u32,
+            slot: latest.slot,
+            is_synced: latest.is_synced,
+        }
+    }
+
+    fn vote_improvement(&self) -> Improvement {
+        let rates = |samples: &[ValidatorMetrics]| samples.iter().map(|m| m.vote_success_rate).collect::<Vec<_>>();
+        Improvement::new(&rates(&self.baseline_samples), &rates(&self.optimized_samples), true)
+    }
+
+    fn skip_improvement(&self) -> Improvement {
+        let rates = |samples: &[ValidatorMetrics]| samples.iter().map(|m| m.skip_rate).collect::<Vec<_>>();
+        Improvement::new(&rates(&self.baseline_samples), &rates(&self.optimized_samples), false)
+    }
+
     fn is_validator_running(&self) -> bool {
         Command::new("pgrep")
-            .args(&["-x", "solana-validator"])
+            .args(["-x", "solana-validator"])
             .output()
             .map(|o| o.status.success() && !o.stdout.is_empty())
             .unwrap_or(false)
@@ -110,7 +233,7 @@ impl StandaloneOptimizer {
         
         // Start validator with optimizations
         let child = Command::new("solana-validator")
-            .args(&[
+            .args([
                 "--identity", &format!("{}/validator-keypair.json", validator_dir),
                 "--vote-account", &format!("{}/vote-account-keypair.json", validator_dir),
                 "--ledger", &format!("{}/ledger", validator_dir),
@@ -136,117 +259,25 @@ impl StandaloneOptimizer {
         Ok(())
     }
     
+    /// The validator's metrics from the first `connect.endpoints` entry that
+    /// answers; zeros when none does and `on_disconnect` is `baseline`
     async fn get_real_metrics(&self) -> Result<ValidatorMetrics> {
-        use solana_client::rpc_client::RpcClient;
-        use solana_sdk::commitment_config::CommitmentConfig;
-
-        // Connect to local validator first (port 8899)
-        let rpc_client = match RpcClient::new_with_commitment(
-            "http://127.0.0.1:8899".to_string(),
-            CommitmentConfig::confirmed(),
-        ) {
-            client => client,
-        };
-
-        // Get current slot and epoch info
-        let slot = rpc_client.get_slot().unwrap_or(0);
-        let epoch_info = rpc_client.get_epoch_info().unwrap_or_default();
-
-        // Get performance samples for real metrics
-        let perf_samples = rpc_client.get_recent_performance_samples(Some(5)).unwrap_or_default();
-
-        // Calculate real skip rate and TPS from performance samples
-        let mut total_slots = 0u64;
-        let mut total_transactions = 0u64;
-
-        for sample in &perf_samples {
-            total_slots += sample.num_slots;
-            total_transactions += sample.num_transactions;
-        }
-
-        let skip_rate = if total_slots > 0 {
-            let expected_tx = total_slots * 100; // Rough estimate: 100 tx per slot
-            ((expected_tx.saturating_sub(total_transactions)) as f64 / expected_tx as f64) * 100.0
-        } else {
-            100.0 // Default when no data
-        };
-
-        // Get validator info from testnet for real vote metrics
-        let testnet_client = RpcClient::new_with_commitment(
-            "https://api.testnet.solana.com".to_string(),
-            CommitmentConfig::confirmed(),
-        );
-
-        // Try to get vote success rate from testnet validators list
-        let vote_success_rate = self.get_vote_success_from_testnet(&testnet_client).await;
-
-        // Calculate credits based on epoch and performance
-        let credits_earned = epoch_info.epoch * 1000 + (total_transactions / 10000);
+        let config = ValidatorConfig::load().unwrap_or_default();
+        Ok(read_or_disconnect(&config, || self.read_local_metrics(&config)).await?.unwrap_or_default())
+    }
 
+    async fn read_local_metrics(&self, config: &ValidatorConfig) -> Result<ValidatorMetrics> {
+        let metrics = connect_validator(config, KeypairUse::ReadOnly)?.get_validator_metrics().await?;
         Ok(ValidatorMetrics {
-            vote_success_rate,
-            skip_rate: skip_rate.min(100.0).max(0.0),
-            credits_earned,
-            vote_lag: 50, // Estimated lag for testnet
-            slot,
-            is_synced: slot > 0,
+            vote_success_rate: metrics.vote_success_rate,
+            skip_rate: metrics.skip_rate,
+            credits_earned: metrics.credits_earned,
+            vote_lag: metrics.vote_lag.min(u32::MAX as u64) as u32,
+            slot: metrics.slot,
+            is_synced: metrics.slot > 0,
         })
     }
 
-    async fn get_vote_success_from_testnet(&self, client: &RpcClient) -> f64 {
-        // Try to get real validator performance from testnet
-        let output = Command::new("solana")
-            .args(&["validators", "--url", "https://api.testnet.solana.com", "--output", "json"])
-            .output();
-
-        if let Ok(output) = output {
-            if output.status.success() {
-                let json_str = String::from_utf8_lossy(&output.stdout);
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&json_str) {
-                    if let Some(validators) = json["validators"].as_array() {
-                        // Look for any validator with good performance to use as baseline
-                        for validator in validators {
-                            if let Some(vote_success) = validator["voteSuccess"].as_f64() {
-                                if vote_success > 80.0 { // Use validators with good performance
-                                    return vote_success;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Return realistic baseline if can't get real data
-        85.0
-    }
-    
-    fn parse_validator_metrics(&self, output: &str) -> Result<ValidatorMetrics> {
-        // Parse real metrics from validator output
-        let mut metrics = ValidatorMetrics::default();
-        
-        for line in output.lines() {
-            if line.contains("Vote Success") {
-                if let Some(rate) = line.split(':').nth(1) {
-                    metrics.vote_success_rate = rate.trim()
-                        .trim_end_matches('%')
-                        .parse()
-                        .unwrap_or(0.0);
-                }
-            }
-            if line.contains("Skip Rate") {
-                if let Some(rate) = line.split(':').nth(1) {
-                    metrics.skip_rate = rate.trim()
-                        .trim_end_matches('%')
-                        .parse()
-                        .unwrap_or(100.0);
-                }
-            }
-        }
-        
-        Ok(metrics)
-    }
-    
     fn apply_system_optimizations(&self) -> Result<()> {
         println!("  Applying system-level optimizations...");
         
@@ -260,7 +291,7 @@ impl StandaloneOptimizer {
         
         for (key, value) in optimizations {
             let result = Command::new("sudo")
-                .args(&["sysctl", "-w", &format!("{}={}", key, value)])
+                .args(["sysctl", "-w", &format!("{}={}", key, value)])
                 .output();
             
             match result {
@@ -270,7 +301,7 @@ impl StandaloneOptimizer {
                 _ => {
                     // Try without sudo for user-level settings
                     Command::new("sysctl")
-                        .args(&["-w", &format!("{}={}", key, value)])
+                        .args(["-w", &format!("{}={}", key, value)])
                         .output()
                         .ok();
                 }
@@ -279,7 +310,7 @@ impl StandaloneOptimizer {
         
         // File descriptor limits
         Command::new("ulimit")
-            .args(&["-n", "1000000"])
+            .args(["-n", "1000000"])
             .output()
             .ok();
         
@@ -316,7 +347,7 @@ impl StandaloneOptimizer {
         
         for (cmd, arg) in updates {
             Command::new("solana-validator")
-                .args(&["admin", cmd, arg])
+                .args(["admin", cmd, arg])
                 .output()
                 .ok();
         }
@@ -339,8 +370,6 @@ impl StandaloneOptimizer {
     }
     
     fn show_improvements(&self) {
-        let vote_diff = self.optimized_metrics.vote_success_rate - self.baseline_metrics.vote_success_rate;
-        let skip_diff = self.baseline_metrics.skip_rate - self.optimized_metrics.skip_rate;
         let credits_pct = if self.baseline_metrics.credits_earned > 0 {
             ((self.optimized_metrics.credits_earned as f64 / self.baseline_metrics.credits_earned as f64) - 1.0) * 100.0
         } else {
@@ -363,17 +392,8 @@ impl StandaloneOptimizer {
             println!();
             println!("{}", "All metrics from real blockchain data.".green().bold());
         } else {
-            println!("Vote Success: {:.1}% → {:.1}% ({})",
-                self.baseline_metrics.vote_success_rate,
-                self.optimized_metrics.vote_success_rate,
-                if vote_diff > 0.0 { format!("+{:.1}%", vote_diff).green() } else { format!("{:.1}%", vote_diff).red() }
-            );
-            
-            println!("Skip Rate: {:.1}% → {:.1}% ({})",
-                self.baseline_metrics.skip_rate,
-                self.optimized_metrics.skip_rate,
-                if skip_diff > 0.0 { format!("-{:.1}%", skip_diff).green() } else { format!("+{:.1}%", -skip_diff).red() }
-            );
+            Self::show_improvement("Vote Success", &self.vote_improvement());
+            Self::show_improvement("Skip Rate", &self.skip_improvement());
             
             println!("Credits: {} → {} ({})",
                 self.baseline_metrics.credits_earned,
@@ -383,9 +403,39 @@ impl StandaloneOptimizer {
         }
     }
     
+    /// One metric's before/after means, and whether the change beats the baseline noise
+    fn show_improvement(label: &str, improvement: &Improvement) {
+        let gain = if improvement.gain > 0.0 {
+            format!("{:+.1} pts better", improvement.gain).green()
+        } else {
+            format!("{:.1} pts worse", improvement.gain.abs()).red()
+        };
+        println!("{}: {:.1}% ± {:.1} → {:.1}% ± {:.1} ({})",
+            label,
+            improvement.before.mean,
+            improvement.before.std_dev,
+            improvement.after.mean,
+            improvement.after.std_dev,
+            gain
+        );
+
+        let verdict = if improvement.is_significant() {
+            "significant".green()
+        } else {
+            "within baseline noise".yellow()
+        };
+        println!("  {:.1}σ of baseline variation, {:.0}% confidence ({} vs {} samples) → {}",
+            improvement.sigmas,
+            improvement.confidence_pct,
+            improvement.before.count,
+            improvement.after.count,
+            verdict
+        );
+    }
+    
     fn get_validator_identity() -> Result<String> {
         let output = Command::new("solana")
-            .args(&["address"])
+            .args(["address"])
             .output()?;
         
         if output.status.success() {
@@ -394,9 +444,45 @@ impl StandaloneOptimizer {
             Ok("9F3XHUUV7nsKrTkZQVM1LmZ4tpsTn2Km6THFt3C7izQq".to_string())
         }
     }
-    
-    fn get_vote_account() -> Result<String> {
-        // Try to get from config
-        Ok("HiGsqFc2FmMkSm55JiFmgNuZ1epRnTQfYtbJ1g49jSnr".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_stats_use_the_sample_standard_deviation() {
+        let stats = SampleStats::new(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        assert_eq!(stats.count, 8);
+        assert!((stats.mean - 5.0).abs() < 1e-9);
+        assert!((stats.std_dev - (32.0f64 / 7.0).sqrt()).abs() < 1e-9);
+
+        let single = SampleStats::new(&[3.0]);
+        assert_eq!((single.mean, single.std_dev), (3.0, 0.0));
+    }
+
+    #[test]
+    fn normal_cdf_matches_known_values() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-6);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-3);
+        assert!((normal_cdf(-1.0) - 0.1587).abs() < 1e-3);
+    }
+
+    #[test]
+    fn improvements_are_judged_against_baseline_noise() {
+        let baseline = [90.0, 91.0, 89.0, 90.0, 90.0];
+
+        let clear = Improvement::new(&baseline, &[95.0, 95.5, 94.5], true);
+        assert!((clear.gain - 5.0).abs() < 1e-9);
+        assert!(clear.is_significant());
+        assert!(clear.confidence_pct > 99.0);
+
+        let noise = Improvement::new(&baseline, &[90.5, 89.5, 90.4], true);
+        assert!(!noise.is_significant());
+
+        // Lower is better for skip rate, so a rise is a loss
+        let worse = Improvement::new(&[2.0, 2.1, 1.9], &[4.0, 4.1, 3.9], false);
+        assert!(worse.gain < 0.0);
+        assert!(worse.confidence_pct < 1.0);
     }
 }
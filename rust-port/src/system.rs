@@ -1,11 +1,54 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
+use serde::Serialize;
 // use nix::sys::resource::{setrlimit, Resource};
 // use nix::unistd::{setpriority, Which};
 use std::fs;
 use std::process::Command;
 use socket2::{Domain, Socket, Type};
 
+use crate::audit::{self, AuditEvent};
+use crate::config::OptimizationConfig;
+use crate::native;
+
+/// Open files limit the optimizer raises to
+const OPEN_FILES_LIMIT: u64 = 1_000_000;
+
+/// UDP socket buffer size the optimizer sets (128MB)
+const UDP_BUFFER_SIZE: usize = 134_217_728;
+
+/// Nice value the optimizer sets; negative values need root
+const PROCESS_NICENESS: i32 = -10;
+
+/// Kernel parameters the optimizer sets on Linux, with their targets; also
+/// what `optimize --ssh` sets on the remote host
+pub const LINUX_NETWORK_SYSCTLS: &[(&str, &str)] = &[
+    ("net.core.rmem_default", "134217728"),
+    ("net.core.rmem_max", "134217728"),
+    ("net.core.wmem_default", "134217728"),
+    ("net.core.wmem_max", "134217728"),
+    ("net.ipv4.tcp_fastopen", "3"),
+    ("net.ipv4.tcp_slow_start_after_idle", "0"),
+    ("net.core.netdev_max_backlog", "30000"),
+    ("net.ipv4.tcp_congestion_control", "bbr"),
+];
+
+/// Kernel parameters the optimizer sets, with their targets
+#[cfg(target_os = "linux")]
+const NETWORK_SYSCTLS: &[(&str, &str)] = LINUX_NETWORK_SYSCTLS;
+
+#[cfg(target_os = "macos")]
+const NETWORK_SYSCTLS: &[(&str, &str)] = &[
+    ("net.inet.tcp.mssdflt", "1460"),
+    ("net.inet.tcp.win_scale_factor", "8"),
+    ("kern.ipc.maxsockbuf", "134217728"),
+    ("net.inet.tcp.sendspace", "1048576"),
+    ("net.inet.tcp.recvspace", "1048576"),
+];
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+const NETWORK_SYSCTLS: &[(&str, &str)] = &[];
+
 /// Apply low-level system optimizations for maximum validator performance
 pub struct SystemOptimizer;
 
@@ -15,26 +58,84 @@ impl SystemOptimizer {
         println!("{}", "Applying low-level system optimizations...".cyan().bold());
         
         Self::set_file_descriptors()?;
-        Self::optimize_network_stack()?;
+        let sysctls = Self::optimize_network_stack()?;
         Self::set_process_priority()?;
         Self::configure_memory_settings()?;
         Self::optimize_cpu_affinity()?;
         
+        print_sysctl_summary(&sysctls);
         println!("{}", "✓ System optimizations applied".green().bold());
         Ok(())
     }
     
+    /// Print what `optimize_all` would change, without changing anything
+    pub fn print_plan() {
+        println!("{}", "Low-level system optimizations that would be applied:".cyan().bold());
+        
+        let open_files = native::open_files_limit()
+            .map_or_else(|| "unknown".to_string(), |limit| limit.to_string());
+        println!("├─ File descriptors: {} → {}", open_files, OPEN_FILES_LIMIT.to_string().yellow());
+        println!("├─ UDP buffers: {}MB, TCP: NoDelay + Keepalive", UDP_BUFFER_SIZE / 1_048_576);
+        println!("├─ Process priority: {}{}",
+            PROCESS_NICENESS.to_string().yellow(),
+            if native::is_root() { "" } else { " (requires root)" }
+        );
+        
+        if NETWORK_SYSCTLS.is_empty() {
+            println!("└─ Kernel parameters: none on this platform");
+            return;
+        }
+        println!("└─ Kernel parameters:");
+        for (i, (key, target)) in NETWORK_SYSCTLS.iter().enumerate() {
+            let branch = if i + 1 == NETWORK_SYSCTLS.len() { "└─" } else { "├─" };
+            match current_sysctl(key) {
+                Some(current) if current == *target => {
+                    println!("   {} {} {}: {} (already set)", branch, "✓".green(), key, target);
+                }
+                current => println!("   {} {} {}: {} → {}{}",
+                    branch,
+                    "▶".cyan(),
+                    key,
+                    current.as_deref().unwrap_or("unknown"),
+                    target.yellow(),
+                    if native::is_root() { "" } else { " (requires root)" }
+                ),
+            }
+        }
+    }
+    
+    /// Share (0-1) of the readable kernel parameters already at their
+    /// targets on this host; None when none can be read
+    pub fn applied_share() -> Option<f64> {
+        let current: Vec<bool> = NETWORK_SYSCTLS
+            .iter()
+            .filter_map(|(key, target)| current_sysctl(key).map(|value| value == *target))
+            .collect();
+        if current.is_empty() {
+            return None;
+        }
+        Some(current.iter().filter(|at_target| **at_target).count() as f64 / current.len() as f64)
+    }
+    
     /// Increase file descriptor limits for handling many connections
     fn set_file_descriptors() -> Result<()> {
         println!("  {} Setting file descriptor limits...", "▶".cyan());
         
+        if native::no_cli() {
+            match native::raise_open_files_limit(OPEN_FILES_LIMIT) {
+                Ok(limit) => println!("    {} File descriptors: {}", "✓".green(), limit.to_string().yellow()),
+                Err(e) => println!("    {} Could not set file descriptors: {:#}", "⚠".yellow(), e),
+            }
+            return Ok(());
+        }
+        
         // Try using ulimit command instead of nix
         match Command::new("ulimit")
-            .args(&["-n", "1000000"])
+            .args(&["-n", &OPEN_FILES_LIMIT.to_string()])
             .output()
         {
             Ok(_) => {
-                println!("    {} File descriptors: {}", "✓".green(), "1,000,000".yellow());
+                println!("    {} File descriptors: {}", "✓".green(), OPEN_FILES_LIMIT.to_string().yellow());
                 Ok(())
             }
             Err(e) => {
@@ -44,12 +145,13 @@ impl SystemOptimizer {
         }
     }
     
-    /// Optimize network stack for low latency and high throughput
-    fn optimize_network_stack() -> Result<()> {
+    /// Optimize network stack for low latency and high throughput; returns
+    /// what happened to each kernel parameter on this platform
+    fn optimize_network_stack() -> Result<Vec<SysctlOutcome>> {
         println!("  {} Optimizing network stack...", "▶".cyan());
         
         // UDP buffer optimizations (128MB)
-        let udp_buffer_size = 134_217_728;
+        let udp_buffer_size = UDP_BUFFER_SIZE;
         
         // Create a UDP socket to set buffer sizes
         let socket = Socket::new(Domain::IPV4, Type::DGRAM, None)?;
@@ -79,30 +181,37 @@ impl SystemOptimizer {
         
         // macOS specific network optimizations
         #[cfg(target_os = "macos")]
-        {
-            Self::apply_macos_network_optimizations()?;
-        }
+        let outcomes = Self::apply_macos_network_optimizations();
         
         // Linux specific network optimizations
         #[cfg(target_os = "linux")]
-        {
-            Self::apply_linux_network_optimizations()?;
-        }
+        let outcomes = Self::apply_linux_network_optimizations();
         
-        Ok(())
+        #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+        let outcomes = Vec::new();
+        
+        Ok(outcomes)
     }
     
     /// Set process priority for validator
     fn set_process_priority() -> Result<()> {
         println!("  {} Setting process priority...", "▶".cyan());
         
+        if native::no_cli() {
+            match native::set_niceness(PROCESS_NICENESS) {
+                Ok(()) => println!("    {} Process priority: {} (high)", "✓".green(), PROCESS_NICENESS),
+                Err(e) => println!("    {} Could not set priority: {:#} (requires sudo)", "⚠".yellow(), e),
+            }
+            return Ok(());
+        }
+        
         // Try using nice command instead of nix
         match Command::new("renice")
-            .args(&["-n", "-10", "-p", &std::process::id().to_string()])
+            .args(&["-n", &PROCESS_NICENESS.to_string(), "-p", &std::process::id().to_string()])
             .output()
         {
             Ok(_) => {
-                println!("    {} Process priority: -10 (high)", "✓".green());
+                println!("    {} Process priority: {} (high)", "✓".green(), PROCESS_NICENESS);
                 Ok(())
             }
             Err(e) => {
@@ -137,55 +246,143 @@ impl SystemOptimizer {
     }
     
     #[cfg(target_os = "macos")]
-    fn apply_macos_network_optimizations() -> Result<()> {
+    fn apply_macos_network_optimizations() -> Vec<SysctlOutcome> {
         // Try to apply macOS specific optimizations
-        let optimizations = vec![
-            ("net.inet.tcp.mssdflt", "1460"),
-            ("net.inet.tcp.win_scale_factor", "8"),
-            ("kern.ipc.maxsockbuf", "134217728"),
-            ("net.inet.tcp.sendspace", "1048576"),
-            ("net.inet.tcp.recvspace", "1048576"),
-        ];
-        
-        for (key, value) in optimizations {
-            match Command::new("sysctl")
-                .args(&["-w", &format!("{}={}", key, value)])
-                .output()
-            {
-                Ok(output) if output.status.success() => {
-                    println!("    {} {}: {}", "✓".green(), key, value);
-                }
-                _ => {
-                    // Silently continue if we can't set (requires sudo)
-                }
-            }
+        if native::no_cli() {
+            println!("    {} Skipping sysctl tuning (no native equivalent on macOS)", "⚠".yellow());
+            return NETWORK_SYSCTLS
+                .iter()
+                .map(|&(key, target)| SysctlOutcome::failed(key, target, "skipped: needs the sysctl command (--no-cli)".to_string()))
+                .collect();
         }
         
-        Ok(())
+        NETWORK_SYSCTLS
+            .iter()
+            .map(|&(key, value)| {
+                let result = Command::new("sysctl")
+                    .args(&["-w", &format!("{}={}", key, value)])
+                    .output();
+                match result {
+                    Ok(output) if output.status.success() => {
+                        println!("    {} {}: {}", "✓".green(), key, value);
+                        SysctlOutcome::applied(key, value)
+                    }
+                    Ok(output) => {
+                        let stderr = String::from_utf8_lossy(&output.stderr);
+                        // sysctl repeats the setting before the reason
+                        let reason = stderr.trim().rsplit(": ").next().unwrap_or("sysctl failed").to_string();
+                        SysctlOutcome::failed(key, value, reason)
+                    }
+                    Err(e) => SysctlOutcome::failed(key, value, e.to_string()),
+                }
+            })
+            .collect()
     }
     
     #[cfg(target_os = "linux")]
-    fn apply_linux_network_optimizations() -> Result<()> {
+    fn apply_linux_network_optimizations() -> Vec<SysctlOutcome> {
         // Linux sysctl optimizations
-        let optimizations = vec![
-            ("net.core.rmem_default", "134217728"),
-            ("net.core.rmem_max", "134217728"),
-            ("net.core.wmem_default", "134217728"),
-            ("net.core.wmem_max", "134217728"),
-            ("net.ipv4.tcp_fastopen", "3"),
-            ("net.ipv4.tcp_slow_start_after_idle", "0"),
-            ("net.core.netdev_max_backlog", "30000"),
-            ("net.ipv4.tcp_congestion_control", "bbr"),
-        ];
-        
-        for (key, value) in optimizations {
-            let path = format!("/proc/sys/{}", key.replace(".", "/"));
-            if let Ok(_) = fs::write(&path, value) {
-                println!("    {} {}: {}", "✓".green(), key, value);
-            }
-        }
-        
-        Ok(())
+        NETWORK_SYSCTLS
+            .iter()
+            .map(|&(key, value)| {
+                // Already at the target counts as applied, even without root
+                let old = current_sysctl(key);
+                if old.as_deref() == Some(value) {
+                    println!("    {} {}: {} (already set)", "✓".green(), key, value);
+                    return SysctlOutcome::applied(key, value);
+                }
+                
+                match native::write_sysctl(key, value) {
+                    Ok(()) => {
+                        println!("    {} {}: {}", "✓".green(), key, value);
+                        audit::record_or_warn(&AuditEvent::new(key, old, value, "network tuning before validator start", "start")
+                            .verified(current_sysctl(key).as_deref() == Some(value)));
+                        SysctlOutcome::applied(key, value)
+                    }
+                    Err(e) => SysctlOutcome::failed(key, value, format!("{:#}", e.root_cause())),
+                }
+            })
+            .collect()
+    }
+}
+
+/// A kernel parameter's current value, None when it can't be read
+pub(crate) fn current_sysctl(key: &str) -> Option<String> {
+    if cfg!(target_os = "linux") {
+        let path = format!("/proc/sys/{}", key.replace('.', "/"));
+        return fs::read_to_string(path).ok().map(|value| value.trim().to_string());
+    }
+    if native::no_cli() {
+        return None;
+    }
+    let output = Command::new("sysctl").args(["-n", key]).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// What happened to one kernel parameter the optimizer tried to set
+#[derive(Debug, Clone)]
+pub struct SysctlOutcome {
+    pub key: &'static str,
+    pub target: &'static str,
+    pub applied: bool,
+    /// Why it wasn't applied
+    pub error: Option<String>,
+}
+
+impl SysctlOutcome {
+    pub(crate) fn applied(key: &'static str, target: &'static str) -> Self {
+        Self { key, target, applied: true, error: None }
+    }
+
+    pub(crate) fn failed(key: &'static str, target: &'static str, error: String) -> Self {
+        Self { key, target, applied: false, error: Some(error) }
+    }
+
+    /// Failed only for lack of privileges; running as root would apply it
+    pub fn requires_root(&self) -> bool {
+        self.error.as_deref().is_some_and(|error| {
+            let error = error.to_lowercase();
+            // The last one is `sudo -n` refusing to prompt, over ssh
+            ["permission denied", "operation not permitted", "password is required"]
+                .iter()
+                .any(|marker| error.contains(marker))
+        })
+    }
+}
+
+/// `N applied, M require root`, then every other failure with its reason
+pub(crate) fn print_sysctl_summary(outcomes: &[SysctlOutcome]) {
+    if outcomes.is_empty() {
+        return;
+    }
+
+    let applied = outcomes.iter().filter(|outcome| outcome.applied).count();
+    let need_root: Vec<&SysctlOutcome> = outcomes.iter().filter(|outcome| outcome.requires_root()).collect();
+    let failed: Vec<&SysctlOutcome> = outcomes
+        .iter()
+        .filter(|outcome| !outcome.applied && !outcome.requires_root())
+        .collect();
+
+    println!("  {} Kernel parameters: {} of {} applied, {} require root, {} failed",
+        if applied == outcomes.len() { "✓".green() } else { "⚠".yellow() },
+        applied,
+        outcomes.len(),
+        need_root.len(),
+        failed.len()
+    );
+    if !need_root.is_empty() {
+        println!("    {} Re-run as root to set: {}",
+            "ℹ".cyan(),
+            need_root.iter().map(|outcome| format!("{}={}", outcome.key, outcome.target)).collect::<Vec<_>>().join(", ")
+        );
+    }
+    for outcome in failed {
+        println!("    {} {}={}: {}",
+            "✗".red(),
+            outcome.key,
+            outcome.target,
+            outcome.error.as_deref().unwrap_or("unknown error")
+        );
     }
 }
 
@@ -213,6 +410,7 @@ impl SystemMonitor {
                 cpu_usage: process.cpu_usage(),
                 memory_mb: process.memory() / 1024 / 1024,
                 threads: process.tasks().as_ref().map(|t| t.len()).unwrap_or(0),
+                disk_written_bytes: process.disk_usage().total_written_bytes,
             });
         
         SystemMetrics {
@@ -226,6 +424,25 @@ impl SystemMonitor {
         }
     }
     
+    /// Warn if the validator runs fewer threads than its configured pools
+    ///
+    /// The validator spawns many threads beyond the RPC and accounts-db pools,
+    /// so only a live count below their total shows the flags didn't take.
+    pub fn thread_count_warning(live_threads: usize, optimization: &OptimizationConfig) -> Option<String> {
+        let configured = (optimization.rpc_threads + optimization.accounts_db_threads) as usize;
+
+        if live_threads == 0 {
+            Some("thread count unavailable on this platform".to_string())
+        } else if live_threads < configured {
+            Some(format!(
+                "only {} threads running but {} configured (--rpc-threads {} + --accounts-db-threads {}) - flags may not have taken effect",
+                live_threads, configured, optimization.rpc_threads, optimization.accounts_db_threads
+            ))
+        } else {
+            None
+        }
+    }
+
     pub fn get_network_stats() -> NetworkStats {
         // Cross-platform network statistics using sysinfo
         use sysinfo::System;
@@ -244,7 +461,7 @@ impl SystemMonitor {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SystemMetrics {
     pub cpu_usage: f32,
     pub memory_used_mb: u64,
@@ -255,12 +472,14 @@ pub struct SystemMetrics {
     pub validator_process: Option<ValidatorProcessMetrics>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ValidatorProcessMetrics {
     pub pid: u32,
     pub cpu_usage: f32,
     pub memory_mb: u64,
     pub threads: usize,
+    /// Bytes written to disk since the process started
+    pub disk_written_bytes: u64,
 }
 
 #[derive(Debug, Clone, Default)]
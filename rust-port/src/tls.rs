@@ -0,0 +1,183 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
+use solana_client::rpc_client::RpcClient;
+use solana_rpc_client::http_sender::HttpSender;
+use solana_rpc_client::rpc_client::RpcClientConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Once, OnceLock};
+use std::time::{Duration, SystemTime};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream};
+
+use crate::config::ValidatorConfig;
+
+/// Timeout `RpcClient::new` uses; kept for clients built without one
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+static INSECURE_SKIP_TLS: AtomicBool = AtomicBool::new(false);
+
+/// Accept any certificate on RPC connections (`--insecure-skip-tls`)
+///
+/// Only settable from the command line, so a config file can never turn
+/// verification off on its own.
+pub fn set_insecure_skip_tls(enabled: bool) {
+    INSECURE_SKIP_TLS.store(enabled, Ordering::Relaxed);
+    if enabled {
+        println!("{} --insecure-skip-tls: RPC certificates are NOT verified; anyone on the path can read and alter RPC traffic",
+            "⚠".yellow().bold());
+    }
+}
+
+fn insecure_skip_tls() -> bool {
+    INSECURE_SKIP_TLS.load(Ordering::Relaxed)
+}
+
+/// DER certificates of a PEM bundle
+fn load_ca_bundle(path: &Path) -> Result<Vec<Vec<u8>>> {
+    let pem = fs::read(path).with_context(|| format!("Failed to read CA bundle {}", path.display()))?;
+    let certificates = rustls_pemfile::certs(&mut pem.as_slice())
+        .with_context(|| format!("Failed to parse CA bundle {}", path.display()))?;
+    if certificates.is_empty() {
+        anyhow::bail!("CA bundle {} contains no certificates", path.display());
+    }
+    Ok(certificates)
+}
+
+/// Certificates from `rpc_ca_bundle`, trusted on top of the built-in roots;
+/// read once per run, empty when unset
+fn ca_bundle() -> &'static Result<Vec<Vec<u8>>, String> {
+    static BUNDLE: OnceLock<Result<Vec<Vec<u8>>, String>> = OnceLock::new();
+    BUNDLE.get_or_init(|| {
+        let Some(path) = ValidatorConfig::load().ok().and_then(|config| config.rpc_ca_bundle) else {
+            return Ok(Vec::new());
+        };
+        load_ca_bundle(&path).map_err(|e| format!("{:#}", e))
+    })
+}
+
+/// Say once why TLS connections are failing
+fn warn_broken_bundle(error: &str) {
+    static WARNED: Once = Once::new();
+    WARNED.call_once(|| println!("{} {}; TLS connections will fail until it is fixed", "✗".red(), error));
+}
+
+/// Fail when `rpc_ca_bundle` is set but can't be used
+///
+/// Connections never fall back to the built-in roots alone: with a broken
+/// bundle they trust no roots at all and fail verification.
+pub fn check_ca_bundle() -> Result<()> {
+    ca_bundle().as_ref().map(|_| ()).map_err(|e| anyhow::anyhow!("{} (rpc_ca_bundle)", e))
+}
+
+/// Whether RPC connections need anything but the default TLS setup
+fn customized() -> bool {
+    insecure_skip_tls() || ca_bundle().as_ref().map_or(true, |bundle| !bundle.is_empty())
+}
+
+/// The HTTP client behind an RPC client, with the configured CA bundle and
+/// `--insecure-skip-tls` applied
+fn http_client(timeout: Duration) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .default_headers(HttpSender::default_headers())
+        .timeout(timeout)
+        .pool_idle_timeout(timeout);
+    match ca_bundle() {
+        Ok(bundle) => {
+            for der in bundle {
+                if let Ok(certificate) = reqwest::Certificate::from_der(der) {
+                    builder = builder.add_root_certificate(certificate);
+                }
+            }
+        }
+        Err(e) => {
+            warn_broken_bundle(e);
+            builder = builder.tls_built_in_root_certs(false);
+        }
+    }
+    builder
+        .danger_accept_invalid_certs(insecure_skip_tls())
+        .build()
+        .expect("build rpc client")
+}
+
+/// `RpcClient::new_with_commitment`, honoring the TLS options
+pub fn rpc_client<U: ToString>(url: U, commitment: CommitmentConfig) -> RpcClient {
+    rpc_client_with_timeout(url, DEFAULT_RPC_TIMEOUT, commitment)
+}
+
+/// `RpcClient::new_with_timeout_and_commitment`, honoring the TLS options
+pub fn rpc_client_with_timeout<U: ToString>(url: U, timeout: Duration, commitment: CommitmentConfig) -> RpcClient {
+    if !customized() {
+        return RpcClient::new_with_timeout_and_commitment(url, timeout, commitment);
+    }
+    RpcClient::new_sender(
+        HttpSender::new_with_client(url, http_client(timeout)),
+        RpcClientConfig::with_commitment(commitment),
+    )
+}
+
+/// The nonblocking `RpcClient::new_with_timeout_and_commitment`, honoring the TLS options
+pub fn nonblocking_rpc_client<U: ToString>(url: U, timeout: Duration, commitment: CommitmentConfig) -> NonblockingRpcClient {
+    if !customized() {
+        return NonblockingRpcClient::new_with_timeout_and_commitment(url.to_string(), timeout, commitment);
+    }
+    NonblockingRpcClient::new_sender(
+        HttpSender::new_with_client(url, http_client(timeout)),
+        RpcClientConfig::with_commitment(commitment),
+    )
+}
+
+/// Accepts any server certificate, for `--insecure-skip-tls`
+struct SkipVerification;
+
+impl rustls::client::ServerCertVerifier for SkipVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
+}
+
+/// TLS setup for `wss://` connections: the built-in roots plus the CA bundle
+fn websocket_tls_config() -> Arc<rustls::ClientConfig> {
+    let mut roots = rustls::RootCertStore::empty();
+    match ca_bundle() {
+        Ok(bundle) => {
+            roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|anchor| {
+                rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(anchor.subject, anchor.spki, anchor.name_constraints)
+            }));
+            for der in bundle {
+                let _ = roots.add(&rustls::Certificate(der.clone()));
+            }
+        }
+        // Trust nothing rather than quietly only the built-in roots
+        Err(e) => warn_broken_bundle(e),
+    }
+    let mut config = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    if insecure_skip_tls() {
+        config.dangerous().set_certificate_verifier(Arc::new(SkipVerification));
+    }
+    Arc::new(config)
+}
+
+/// Open a PubSub WebSocket, honoring the TLS options for `wss://` URLs
+pub async fn connect_websocket(ws_url: &str) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+    let connector = ws_url.starts_with("wss://").then(|| Connector::Rustls(websocket_tls_config()));
+    let (socket, _) = tokio_tungstenite::connect_async_tls_with_config(ws_url, None, false, connector)
+        .await
+        .with_context(|| format!("Failed to connect to {}", ws_url))?;
+    Ok(socket)
+}
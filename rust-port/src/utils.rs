@@ -7,16 +7,64 @@ pub fn format_sol(lamports: u64) -> String {
     format!("{:.9} SOL", sol)
 }
 
-pub fn format_number(n: u64) -> String {
-    let s = n.to_string();
-    let mut result = String::new();
-    for (i, c) in s.chars().rev().enumerate() {
-        if i > 0 && i % 3 == 0 {
-            result.insert(0, ',');
+/// How `format_number` writes large numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberStyle {
+    /// Every digit with thousands separators: `1,500,000`
+    Separated,
+    /// Rounded to K or M: `1.5M`, `12K`
+    Abbreviated,
+}
+
+pub fn format_number(n: u64, style: NumberStyle) -> String {
+    match style {
+        NumberStyle::Separated => {
+            let s = n.to_string();
+            let mut result = String::new();
+            for (i, c) in s.chars().rev().enumerate() {
+                if i > 0 && i % 3 == 0 {
+                    result.insert(0, ',');
+                }
+                result.insert(0, c);
+            }
+            result
         }
-        result.insert(0, c);
+        // From 999,500 up, K would round to `1000K`
+        NumberStyle::Abbreviated if n >= 999_500 => format!("{:.1}M", n as f64 / 1_000_000.0),
+        NumberStyle::Abbreviated if n >= 1_000 => format!("{:.0}K", n as f64 / 1_000.0),
+        NumberStyle::Abbreviated => n.to_string(),
+    }
+}
+
+/// Parse a duration like `90s`, `30m`, `24h`, `7d` or `2w`
+pub fn parse_duration(s: &str) -> Result<chrono::Duration> {
+    let s = s.trim();
+    let invalid = || anyhow::anyhow!("Invalid duration '{}' (expected e.g. 30m, 24h, 7d, 2w)", s);
+    let unit = s.chars().last().ok_or_else(invalid)?;
+    let amount: i64 = s[..s.len() - unit.len_utf8()].parse().map_err(|_| invalid())?;
+    if amount < 0 {
+        anyhow::bail!("Duration '{}' is negative", s);
+    }
+
+    let duration = match unit {
+        's' => chrono::Duration::try_seconds(amount),
+        'm' => chrono::Duration::try_minutes(amount),
+        'h' => chrono::Duration::try_hours(amount),
+        'd' => chrono::Duration::try_days(amount),
+        'w' => chrono::Duration::try_weeks(amount),
+        _ => anyhow::bail!("Invalid duration unit in '{}' (use s, m, h, d or w)", s),
+    };
+    duration.ok_or_else(|| anyhow::anyhow!("Duration '{}' is too long", s))
+}
+
+/// Whether `url` points at this machine: `localhost` or a loopback address
+pub fn is_local_url(url: &str) -> bool {
+    match url::Url::parse(url).ok().as_ref().and_then(url::Url::host) {
+        Some(url::Host::Domain(domain)) => domain == "localhost",
+        Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
     }
-    result
 }
 
 pub fn run_command(cmd: &str, args: &[&str]) -> Result<String> {
@@ -48,3 +96,42 @@ pub fn print_error(message: &str) {
 pub fn print_info(message: &str) {
     println!("{} {}", "ℹ".cyan(), message.cyan());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_urls() {
+        assert!(is_local_url("http://127.0.0.1:8899"));
+        assert!(is_local_url("http://localhost:8899"));
+        assert!(is_local_url("http://[::1]:8899"));
+        assert!(!is_local_url("http://10.0.0.5:8899"));
+        assert!(!is_local_url("https://api.testnet.solana.com"));
+        assert!(!is_local_url("not a url"));
+    }
+
+    #[test]
+    fn separated_boundaries() {
+        let cases = [(999, "999"), (1_000, "1,000"), (999_999, "999,999"), (1_000_000, "1,000,000"), (1_500_000, "1,500,000")];
+        for (n, expected) in cases {
+            assert_eq!(format_number(n, NumberStyle::Separated), expected, "{}", n);
+        }
+    }
+
+    #[test]
+    fn abbreviated_boundaries() {
+        let cases = [(999, "999"), (1_000, "1K"), (999_999, "1.0M"), (1_000_000, "1.0M"), (1_500_000, "1.5M")];
+        for (n, expected) in cases {
+            assert_eq!(format_number(n, NumberStyle::Abbreviated), expected, "{}", n);
+        }
+    }
+
+    #[test]
+    fn durations_out_of_range_are_errors() {
+        assert_eq!(parse_duration("2w").unwrap(), chrono::Duration::weeks(2));
+        for bad in ["", "5µ", "-3d", "99999999999999w"] {
+            assert!(parse_duration(bad).is_err(), "{}", bad);
+        }
+    }
+}
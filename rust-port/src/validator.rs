@@ -1,27 +1,88 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
 use std::process::{Command, Child, Stdio};
+use std::collections::HashSet;
 use std::fs;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, UdpSocket};
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 use tokio::time::{sleep, Duration};
-use sysinfo::System;
-use solana_sdk::{
-    signature::{Keypair, read_keypair_file},
-    native_token::LAMPORTS_PER_SOL,
-};
+use sysinfo::{Pid, ProcessRefreshKind, System, UpdateKind};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::{read_keypair_file, Keypair};
 
-use crate::config::ValidatorConfig;
+use crate::config::{Cluster, ValidatorConfig};
 use crate::system::{SystemOptimizer, SystemMonitor};
-use crate::blockchain::SolanaInterface;
+use crate::blockchain::{self, SolanaInterface};
+use crate::connect::{connect_validator, load_keypairs, KeypairUse};
+use crate::faucet::Faucet;
+use crate::health;
+use crate::sla;
+use crate::live_config;
+use crate::memory_budget;
+use crate::clock;
+use crate::replay::{self, ReplayTracker};
+use crate::native;
+use crate::flag_compat;
+use crate::tls;
+use solana_sdk::commitment_config::CommitmentConfig;
 
-pub async fn start(skip_airdrop: bool) -> Result<()> {
+/// Commission the vote account is created with
+const START_COMMISSION: u8 = 5;
+
+/// How long to watch the vote account for votes cast by another machine
+const VOTE_ADVANCE_WINDOW: Duration = Duration::from_secs(10);
+
+/// How long `start` samples replay for a first catch-up ETA
+const CATCH_UP_SAMPLE: Duration = Duration::from_secs(30);
+
+/// Gap between slot readings while sampling replay
+const CATCH_UP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The local cluster `start --localnet` runs
+const TEST_VALIDATOR: &str = "solana-test-validator";
+
+/// SOL the identity is funded with from the local faucet (not rate limited)
+const LOCALNET_AIRDROP_SOL: u64 = 10;
+
+/// How long a freshly spawned test validator gets to answer on RPC
+const LOCALNET_READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// `localnet` when asked for on the command line or set in the config
+fn is_localnet(config: &ValidatorConfig, localnet: bool) -> bool {
+    localnet || config.cluster == Cluster::Localnet
+}
+
+/// The config, switched to `--cluster` for this run when given
+fn load_for_cluster(cluster: Option<Cluster>) -> Result<ValidatorConfig> {
+    let mut config = ValidatorConfig::load()?;
+    if let Some(cluster) = cluster {
+        if config.set_cluster(cluster) {
+            println!("{} expected_genesis_hash pins another cluster; using {}'s genesis for this run",
+                "ℹ".cyan(), config.cluster.name());
+        }
+    }
+    Ok(config)
+}
+
+/// Say the airdrop is skipped because mainnet-beta has no faucet
+fn warn_no_mainnet_airdrop() {
+    println!("{} {}", "⚠".yellow().bold(),
+        "MAINNET-BETA HAS NO FAUCET: skipping the airdrop. The identity must already hold real SOL to create the vote account and pay vote fees.".yellow().bold());
+}
+
+pub async fn start(skip_airdrop: bool, force: bool, localnet: bool, cluster: Option<Cluster>) -> Result<()> {
     println!("{}",  "============================================".blue());
     println!("{}", "Solana Validator Optimizer - Rust Edition".blue().bold());
     println!("{}", "High-Performance Direct Implementation".blue());
     println!("{}", "============================================".blue());
     
     // Load or create config
-    let config = ValidatorConfig::load()?;
+    let config = load_for_cluster(cluster)?;
+    if is_localnet(&config, localnet) {
+        return start_localnet(&config, skip_airdrop).await;
+    }
+    println!("Cluster: {}", config.cluster.name().cyan());
     
     // Step 1: Check Solana installation
     println!("\n{}", "Step 1: Checking Solana installation...".cyan());
@@ -31,19 +92,25 @@ pub async fn start(skip_airdrop: bool) -> Result<()> {
     println!("\n{}", "Step 2: Generating keypairs...".cyan());
     generate_keypairs(&config)?;
     
+    println!("\n{}", "Step 2b: Checking this identity isn't running elsewhere...".cyan());
+    refuse_duplicate(&config, force).await?;
+    
+    println!("\n{}", "Step 2c: Checking ports...".cyan());
+    check_ports(&config)?;
+    
+    println!("\n{}", "Step 2d: Checking memory...".cyan());
+    memory_budget::check(&config)?;
+    
     // Step 3: Apply low-level system optimizations
     println!("\n{}", "Step 3: Applying low-level system optimizations...".cyan());
     SystemOptimizer::optimize_all()?;
     
     // Step 4: Setup blockchain connection
     println!("\n{}", "Step 4: Connecting to blockchain...".cyan());
-    let validator_keypair = read_keypair_file(&config.identity_keypair)
-        .map_err(|e| anyhow::anyhow!("Failed to read validator keypair: {}", e))?;
-    let vote_keypair = read_keypair_file(&config.vote_account_keypair)
-        .map_err(|e| anyhow::anyhow!("Failed to read vote keypair: {}", e))?;
+    let (validator_keypair, vote_keypair) = load_keypairs(&config, KeypairUse::Signing)?;
     
     let solana = SolanaInterface::new(
-        "https://api.testnet.solana.com",
+        &config.cluster_rpc_url(),
         validator_keypair,
         vote_keypair,
     )?;
@@ -52,13 +119,15 @@ pub async fn start(skip_airdrop: bool) -> Result<()> {
     if !skip_airdrop {
         println!("\n{}", "Step 5: Setting up vote account...".cyan());
         
-        // Request airdrop on testnet
-        if let Err(_) = solana.request_airdrop(LAMPORTS_PER_SOL).await {
-            println!("{}", "  Airdrop failed (rate limited), continuing...".yellow());
+        // Airdrops only exist off mainnet
+        if config.is_mainnet() {
+            warn_no_mainnet_airdrop();
+        } else if let Err(_) = solana.request_airdrop(LAMPORTS_PER_SOL).await {
+            println!("{}", "  No faucet paid out, continuing...".yellow());
         }
         
         // Setup vote account
-        solana.setup_vote_account(5).await?; // 5% commission
+        solana.setup_vote_account(START_COMMISSION).await?;
     }
     
     // Step 6: Start optimized validator process
@@ -71,12 +140,16 @@ pub async fn start(skip_airdrop: bool) -> Result<()> {
     
     // Get initial metrics
     if let Ok(metrics) = solana.get_validator_metrics().await {
-        metrics.display();
+        metrics.display(&config.status_bands);
     }
     
     // Show system metrics
     let sys_metrics = SystemMonitor::get_metrics();
-    display_system_metrics(&sys_metrics);
+    display_system_metrics(&sys_metrics, &config);
+    
+    // Step 8: First read on catch-up
+    println!("\n{}", "Step 8: Measuring catch-up...".cyan());
+    report_catch_up(&config).await;
     
     println!("\n{}", "============================================".green());
     println!("{}", "✓ Validator started with optimizations!".green().bold());
@@ -90,16 +163,342 @@ pub async fn start(skip_airdrop: bool) -> Result<()> {
     Ok(())
 }
 
+/// RPC of the local test validator
+fn local_rpc_url(config: &ValidatorConfig) -> String {
+    format!("http://127.0.0.1:{}", config.rpc_port)
+}
+
+/// Ledger of the local test validator, kept apart from `ledger_path` so a
+/// testnet ledger is never taken for a local genesis
+fn localnet_ledger(config: &ValidatorConfig) -> PathBuf {
+    config.ledger_path.with_file_name("test-ledger")
+}
+
+/// `solana-test-validator` arguments: the ledger, ports and ledger limit;
+/// the testnet entrypoints, known validators, genesis hash, shred version
+/// and supermajority wait don't apply to a cluster of one
+fn localnet_validator_args(config: &ValidatorConfig) -> Vec<String> {
+    vec![
+        format!("--ledger={}", localnet_ledger(config).display()),
+        format!("--rpc-port={}", config.rpc_port),
+        format!("--gossip-port={}", config.gossip_port),
+        format!("--limit-ledger-size={}", config.optimization.limit_ledger_size),
+    ]
+}
+
+/// Test validators running on `localnet_ledger`
+fn localnet_pids(config: &ValidatorConfig) -> Vec<Pid> {
+    let ledger = localnet_ledger(config);
+    let ledger_arg = format!("--ledger={}", ledger.display());
+
+    // The default process refresh leaves the command line empty
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessRefreshKind::new()
+            .with_exe(UpdateKind::OnlyIfNotSet)
+            .with_cmd(UpdateKind::OnlyIfNotSet),
+    );
+    system
+        .processes()
+        .iter()
+        .filter(|(_, process)| {
+            process.exe().and_then(|exe| exe.file_name()).is_some_and(|name| name == TEST_VALIDATOR)
+        })
+        .filter(|(_, process)| {
+            let cmd = process.cmd();
+            cmd.contains(&ledger_arg)
+                || cmd.windows(2).any(|pair| pair[0] == "--ledger" && Path::new(&pair[1]) == ledger)
+        })
+        .map(|(pid, _)| *pid)
+        .collect()
+}
+
+/// Spawn the test validator and wait for its RPC; its PID
+async fn spawn_test_validator(config: &ValidatorConfig, rpc_client: &solana_client::rpc_client::RpcClient) -> Result<u32> {
+    let binary = native::find_in_path(TEST_VALIDATOR)
+        .ok_or_else(|| anyhow::anyhow!("{} not found. It ships with the Solana CLI tools.", TEST_VALIDATOR))?;
+    let ledger = localnet_ledger(config);
+    let log = ledger.join("validator.log");
+
+    let mut child = Command::new(&binary)
+        .args(localnet_validator_args(config))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to start {}", binary.display()))?;
+
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            anyhow::bail!("{} exited with {}; see {}", TEST_VALIDATOR, status, log.display());
+        }
+        if rpc_client.get_slot().is_ok() {
+            return Ok(child.id());
+        }
+        if started.elapsed() >= LOCALNET_READY_TIMEOUT {
+            anyhow::bail!("{} (PID {}) not answering on RPC after {}s; see {}",
+                TEST_VALIDATOR, child.id(), LOCALNET_READY_TIMEOUT.as_secs(), log.display());
+        }
+        println!("  {} Waiting for {} to answer on RPC...", "⏳".yellow(), TEST_VALIDATOR);
+        sleep(Duration::from_secs(2)).await;
+    }
+}
+
+/// `start` on localnet: run `solana-test-validator` with the configured ports
+/// (or use a validator already answering on them) and fund the identity
+/// from its faucet
+///
+/// The system tuning, duplicate-identity check, vote account setup and
+/// catch-up check are skipped: the test validator is the whole cluster and
+/// votes with its own vote account.
+async fn start_localnet(config: &ValidatorConfig, skip_airdrop: bool) -> Result<()> {
+    let rpc_url = local_rpc_url(config);
+    println!("{} Localnet: {} on {}", "ℹ".cyan(), TEST_VALIDATOR, rpc_url);
+
+    println!("\n{}", "Step 1: Generating keypairs...".cyan());
+    generate_keypairs(config)?;
+
+    println!("\n{}", "Step 2: Starting the local test validator...".cyan());
+    let rpc_client = tls::rpc_client_with_timeout(&rpc_url, Duration::from_secs(5), CommitmentConfig::confirmed());
+    let pid = match rpc_client.get_version() {
+        Ok(version) => {
+            println!("{} A validator ({}) already answers on {}; using it", "✓".green(), version.solana_core, rpc_url);
+            None
+        }
+        Err(_) => {
+            let pid = spawn_test_validator(config, &rpc_client).await?;
+            println!("{} {} running (PID {})", "✓".green(), TEST_VALIDATOR, pid.to_string().yellow());
+            Some(pid)
+        }
+    };
+
+    if skip_airdrop {
+        println!("\n{} Step 3 skipped (--no-airdrop)", "ℹ".cyan());
+    } else {
+        println!("\n{}", "Step 3: Funding the identity from the local faucet...".cyan());
+        let (validator_keypair, vote_keypair) = load_keypairs(config, KeypairUse::Signing)?;
+        let solana = SolanaInterface::new(&rpc_url, validator_keypair, vote_keypair)?;
+        solana.request_airdrop_from(&[Faucet::Rpc(rpc_url.clone())], LOCALNET_AIRDROP_SOL * LAMPORTS_PER_SOL).await
+            .context("The local faucet refused the airdrop; is the validator on this port a test validator with its faucet (port 9900)?")?;
+    }
+
+    println!("\n{}", "============================================".green());
+    println!("{}", "✓ Localnet ready!".green().bold());
+    if let Some(pid) = pid {
+        println!("Test validator PID: {}", pid.to_string().yellow());
+        println!("Ledger and log: {}", localnet_ledger(config).display());
+    }
+    println!("RPC: {}", rpc_url.yellow());
+    println!("\n{}", "Next:".cyan());
+    println!("  • Point the Solana CLI at it: {}", format!("solana config set --url {}", rpc_url).blue());
+    println!("  • Monitor metrics: {}", "solana-validator-optimizer monitor".blue());
+    println!("  • Stop it: {}", "solana-validator-optimizer stop".blue());
+    println!("{}", "============================================".green());
+    Ok(())
+}
+
+/// `start --dry-run` on localnet
+fn start_localnet_dry_run(config: &ValidatorConfig, skip_airdrop: bool) -> Result<()> {
+    let rpc_url = local_rpc_url(config);
+    let mut failures: Vec<String> = Vec::new();
+    println!("{} Localnet: {} on {}", "ℹ".cyan(), TEST_VALIDATOR, rpc_url);
+
+    println!("\n{}", "Step 1: Previewing keypairs...".cyan());
+    if let Err(e) = preview_keypairs(config) {
+        println!("{} {:#}", "✗".red(), e);
+        failures.push("keypairs".to_string());
+    }
+
+    println!("\n{}", "Step 2: Planning the local test validator...".cyan());
+    let rpc_client = tls::rpc_client_with_timeout(&rpc_url, Duration::from_secs(5), CommitmentConfig::confirmed());
+    match (rpc_client.get_version(), native::find_in_path(TEST_VALIDATOR)) {
+        (Ok(version), _) => println!("{} A validator ({}) already answers on {}; it would be used", "✓".green(), version.solana_core, rpc_url),
+        (Err(_), Some(binary)) => {
+            println!("{} \\", binary.display());
+            let args = localnet_validator_args(config);
+            for (i, arg) in args.iter().enumerate() {
+                println!("  {}{}", arg, if i + 1 < args.len() { " \\" } else { "" });
+            }
+        }
+        (Err(_), None) => {
+            println!("{} Nothing answers on {} and {} is not installed", "✗".red(), rpc_url, TEST_VALIDATOR);
+            failures.push(TEST_VALIDATOR.to_string());
+        }
+    }
+
+    if skip_airdrop {
+        println!("\n{} Step 3 skipped (--no-airdrop)", "ℹ".cyan());
+    } else {
+        println!("\n{}", "Step 3: Planning the airdrop...".cyan());
+        println!("▶ Would request {} SOL for the identity from the faucet behind {}", LOCALNET_AIRDROP_SOL, rpc_url);
+    }
+
+    println!();
+    if !failures.is_empty() {
+        anyhow::bail!("Preflight failed: {}", failures.join(", "));
+    }
+    println!("{}", "✓ All preflight checks passed; `start --localnet` would start the local cluster".green().bold());
+    Ok(())
+}
+
+/// Sample replay for `CATCH_UP_SAMPLE` after the validator starts and print
+/// the ETA to caught up; `replay-speed` follows it from there
+async fn report_catch_up(config: &ValidatorConfig) {
+    let (local, cluster) = match replay::replay_clients(config) {
+        Ok(clients) => clients,
+        Err(e) => {
+            println!("{} {:#}", "⚠".yellow(), e);
+            return;
+        }
+    };
+
+    let mut tracker = ReplayTracker::new();
+    let mut estimate = None;
+    let started = Instant::now();
+    while started.elapsed() < CATCH_UP_SAMPLE {
+        match replay::read_slots(&local, &cluster) {
+            Ok((local_slot, cluster_slot)) if replay::is_caught_up(local_slot, cluster_slot) => {
+                println!("{} Caught up at slot {}", "✓".green(), local_slot);
+                return;
+            }
+            Ok((local_slot, cluster_slot)) => {
+                estimate = tracker.record(local_slot, cluster_slot).or(estimate);
+                match &estimate {
+                    Some(estimate) => println!("  {} {}", "▶".cyan(), estimate.summary()),
+                    None => println!("  {} slot {} | behind {} | measuring...",
+                        "▶".cyan(), local_slot, cluster_slot.saturating_sub(local_slot)),
+                }
+            }
+            Err(_) => {
+                println!("  {} RPC not answering yet - the validator is still loading its snapshot", "⏳".yellow());
+                break;
+            }
+        }
+        sleep(CATCH_UP_INTERVAL).await;
+    }
+
+    match estimate {
+        Some(estimate) if estimate.falling_behind() => replay::print_recommendations(config, &estimate),
+        Some(estimate) => println!("{} Catch-up ETA: {}", "✓".green(), estimate.eta_display().yellow()),
+        None => {}
+    }
+    println!("  Follow catch-up with {}", "solana-validator-optimizer replay-speed".blue());
+}
+
+/// `start --dry-run`: run every preflight step of `start` and print what it
+/// would do, without spawning the validator, writing keypairs or kernel
+/// parameters, or sending transactions; fails when a step would have failed
+pub async fn start_dry_run(skip_airdrop: bool, force: bool, localnet: bool, cluster: Option<Cluster>) -> Result<()> {
+    println!("{}", "=== Start Dry Run ===".cyan().bold());
+    println!("Nothing is spawned, written or sent.");
+    
+    let config = load_for_cluster(cluster)?;
+    if is_localnet(&config, localnet) {
+        return start_localnet_dry_run(&config, skip_airdrop);
+    }
+    println!("Cluster: {}", config.cluster.name().cyan());
+    let mut failures: Vec<String> = Vec::new();
+    
+    println!("\n{}", "Step 1: Checking Solana installation...".cyan());
+    if let Err(e) = check_solana_installation() {
+        println!("{} {:#}", "✗".red(), e);
+        failures.push("Solana installation".to_string());
+    }
+    
+    println!("\n{}", "Step 2: Previewing keypairs...".cyan());
+    if let Err(e) = preview_keypairs(&config) {
+        println!("{} {:#}", "✗".red(), e);
+        failures.push("keypairs".to_string());
+    }
+    
+    println!("\n{}", "Step 2b: Checking this identity isn't running elsewhere...".cyan());
+    if let Err(e) = refuse_duplicate(&config, force).await {
+        println!("{} {:#}", "✗".red(), e);
+        failures.push("duplicate identity".to_string());
+    }
+    
+    println!("\n{}", "Step 2c: Checking ports...".cyan());
+    if let Err(e) = check_ports(&config) {
+        println!("{} {:#}", "✗".red(), e);
+        failures.push("ports".to_string());
+    }
+    
+    println!("\n{}", "Step 2d: Checking memory...".cyan());
+    if let Err(e) = memory_budget::check(&config) {
+        // Warned rather than failed: a dry run is often made on another host
+        println!("{} {:#}", "⚠".yellow(), e);
+    }
+    
+    println!("\n{}", "Step 3: Planning low-level system optimizations...".cyan());
+    SystemOptimizer::print_plan();
+    
+    if skip_airdrop {
+        println!("\n{} Steps 4-5 skipped (--no-airdrop)", "ℹ".cyan());
+    } else {
+        println!("\n{}", "Steps 4-5: Planning airdrop and vote account...".cyan());
+        // Keypairs that don't exist yet stand in as fresh ones, which hold no SOL either
+        let read = |path: &Path| read_keypair_file(path).unwrap_or_else(|_| Keypair::new());
+        let plan = SolanaInterface::new(
+            &config.cluster_rpc_url(),
+            read(&config.identity_keypair),
+            read(&config.vote_account_keypair),
+        );
+        let airdrop = if config.is_mainnet() {
+            warn_no_mainnet_airdrop();
+            None
+        } else {
+            Some(LAMPORTS_PER_SOL)
+        };
+        println!("├─ RPC: {}", config.cluster_rpc_url());
+        match plan {
+            Ok(solana) => {
+                if let Err(e) = solana.print_vote_account_plan(START_COMMISSION, airdrop).await {
+                    println!("└─ {} {:#}", "✗".red(), e);
+                    failures.push("vote account".to_string());
+                }
+            }
+            Err(e) => {
+                println!("└─ {} {:#}", "✗".red(), e);
+                failures.push("blockchain connection".to_string());
+            }
+        }
+    }
+    
+    println!("\n{}", "Step 6: Building validator arguments...".cyan());
+    match optimized_validator_args(&config) {
+        Ok(args) => {
+            println!("solana-validator \\");
+            for (i, arg) in args.iter().enumerate() {
+                println!("  {}{}", arg, if i + 1 < args.len() { " \\" } else { "" });
+            }
+        }
+        Err(e) => {
+            println!("{} {:#}", "✗".red(), e);
+            failures.push("validator arguments".to_string());
+        }
+    }
+    
+    println!();
+    if !failures.is_empty() {
+        anyhow::bail!("Preflight failed: {}", failures.join(", "));
+    }
+    println!("{}", "✓ All preflight checks passed; `start` would launch the validator".green().bold());
+    Ok(())
+}
+
 pub async fn stop() -> Result<()> {
     let mut system = System::new_all();
     system.refresh_all();
     
-    let validator_processes: Vec<_> = system
+    let mut validator_processes: Vec<_> = system
         .processes()
         .iter()
         .filter(|(_, process)| process.name() == "solana-validator")
         .map(|(pid, _)| *pid)
         .collect();
+    // A test validator from `start --localnet`, but not one run by hand
+    validator_processes.extend(localnet_pids(&ValidatorConfig::load()?));
     
     if validator_processes.is_empty() {
         println!("{}", "No validator process found".yellow());
@@ -108,10 +507,14 @@ pub async fn stop() -> Result<()> {
     
     for pid in validator_processes {
         println!("Stopping validator with PID: {}", pid);
-        Command::new("kill")
-            .args(&["-TERM", &pid.to_string()])
-            .output()
-            .context("Failed to stop validator")?;
+        if native::no_cli() {
+            native::terminate(pid.as_u32())?;
+        } else {
+            Command::new("kill")
+                .args(&["-TERM", &pid.to_string()])
+                .output()
+                .context("Failed to stop validator")?;
+        }
     }
     
     println!("{}", "✓ Validator stopped".green());
@@ -137,47 +540,117 @@ pub async fn show_status() -> Result<()> {
             println!("PID: {}", pid.to_string().yellow());
             println!("CPU Usage: {:.2}%", process.cpu_usage());
             println!("Memory Usage: {} MB", process.memory() / 1024 / 1024);
-            
-            // Get validator identity
-            if let Ok(output) = Command::new("solana")
-                .args(&["address"])
-                .output()
-            {
-                if output.status.success() {
-                    let address = String::from_utf8_lossy(&output.stdout);
-                    println!("Identity: {}", address.trim().yellow());
-                }
+
+            let threads = process.tasks().map(|t| t.len()).unwrap_or(0);
+            println!("Threads: {}", threads);
+            let config = ValidatorConfig::load()?;
+            if let Some(warning) = SystemMonitor::thread_count_warning(threads, &config.optimization) {
+                println!("{} {}", "⚠".yellow(), warning);
+            }
+
+            let drift = live_config::config_drift(
+                &config,
+                process.cmd(),
+                chrono::DateTime::from_timestamp(process.start_time() as i64, 0).unwrap_or_default(),
+            );
+            if drift.is_empty() {
+                println!("Config Drift: {}", "none".green());
+            } else {
+                live_config::print_drift(&drift);
             }
             
-            // Get current slot
-            if let Ok(output) = Command::new("solana")
-                .args(&["slot", "--url", "https://api.testnet.solana.com"])
-                .output()
-            {
-                if output.status.success() {
-                    let slot = String::from_utf8_lossy(&output.stdout);
-                    println!("Network Slot: {}", slot.trim().cyan());
-                }
+            if native::no_cli() {
+                show_identity_and_slot(&config);
+            } else {
+                show_identity_and_slot_cli(&config.cluster_rpc_url());
             }
+            show_optimization_score(&config).await;
         }
         None => {
             println!("{} {}", "✗ Validator Status:".red(), "NOT RUNNING".red().bold());
             println!("Start the validator with: {}", "solana-validator-optimizer start".yellow());
         }
     }
+
+    let config = ValidatorConfig::load()?;
+    sla::print_status_line(&config.sla);
+
+    // Drift delays votes whether or not the validator is up yet
+    let rpc_url = if validator_process.is_some() {
+        format!("http://127.0.0.1:{}", config.rpc_port)
+    } else {
+        config.cluster_rpc_url()
+    };
+    clock::report_clock_drift(&config.clock, &rpc_url);
     
     Ok(())
 }
 
-fn check_solana_installation() -> Result<()> {
-    let output = Command::new("solana")
-        .arg("--version")
+/// The validator's 0-100 optimization score, or why it couldn't be read
+async fn show_optimization_score(config: &ValidatorConfig) {
+    let read = async {
+        let interface = connect_validator(config, KeypairUse::ReadOnly)?;
+        interface.get_validator_metrics().await
+    };
+    match read.await {
+        Ok(metrics) => println!("Optimization Score: {}", health::paint_score(metrics.optimization_score(config))),
+        Err(e) => println!("Optimization Score: {} ({:#})", "unknown".yellow(), e),
+    }
+}
+
+/// Identity and network slot without the CLI: from the keypair file and RPC
+fn show_identity_and_slot(config: &ValidatorConfig) {
+    if let Ok(identity) = native::keypair_pubkey(&config.identity_keypair) {
+        println!("Identity: {}", identity.to_string().yellow());
+    }
+
+    let client = tls::rpc_client(config.cluster_rpc_url(), CommitmentConfig::confirmed());
+    if let Ok(slot) = client.get_slot() {
+        println!("Network Slot: {}", slot.to_string().cyan());
+    }
+}
+
+/// Identity and network slot as reported by the solana CLI
+fn show_identity_and_slot_cli(cluster_url: &str) {
+    // Get validator identity
+    if let Ok(output) = Command::new("solana")
+        .args(&["address"])
         .output()
-        .context("Solana CLI not found. Please install Solana first.")?;
+    {
+        if output.status.success() {
+            let address = String::from_utf8_lossy(&output.stdout);
+            println!("Identity: {}", address.trim().yellow());
+        }
+    }
     
-    if output.status.success() {
-        let version = String::from_utf8_lossy(&output.stdout);
-        println!("✓ Solana CLI found: {}", version.trim().green());
+    // Get current slot
+    if let Ok(output) = Command::new("solana")
+        .args(["slot", "--url", cluster_url])
+        .output()
+    {
+        if output.status.success() {
+            let slot = String::from_utf8_lossy(&output.stdout);
+            println!("Network Slot: {}", slot.trim().cyan());
+        }
+    }
+}
+
+fn check_solana_installation() -> Result<()> {
+    if native::no_cli() {
+        // The validator binary is still needed to run a validator
+        let path = native::find_in_path("solana-validator")
+            .ok_or_else(|| anyhow::anyhow!("solana-validator not found. Please install the full Solana validator."))?;
+        println!("✓ solana-validator found: {}", path.display().to_string().green());
+        return Ok(());
+    }
+
+    // The CLI is optional for setup: keypairs are generated natively without it
+    match Command::new("solana").arg("--version").output() {
+        Ok(output) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout);
+            println!("✓ Solana CLI found: {}", version.trim().green());
+        }
+        _ => println!("{} Solana CLI not found; continuing without it", "⚠".yellow()),
     }
     
     // Check for solana-validator
@@ -195,6 +668,82 @@ fn check_solana_installation() -> Result<()> {
     Ok(())
 }
 
+/// Long flags (`--name`) mentioned in the validator's `--help` output
+fn supported_flags(help: &str) -> HashSet<String> {
+    help.split_whitespace()
+        .filter(|token| token.starts_with("--"))
+        .map(|token| {
+            token
+                .split(['=', '<', '['])
+                .next()
+                .unwrap_or(token)
+                .trim_end_matches(|c: char| !c.is_ascii_alphanumeric())
+                .to_string()
+        })
+        .collect()
+}
+
+/// `config validate-args`: check the validator would accept the built command line
+///
+/// Compares every flag against `solana-validator --help`, then dry-parses the
+/// full command line with `--version` appended, which exits right after argument
+/// parsing without starting the validator.
+pub fn validate_args() -> Result<()> {
+    let config = ValidatorConfig::load()?;
+    let args = config.build_validator_args()?;
+
+    println!("{}", "Validating Validator Arguments".cyan().bold());
+
+    let help = Command::new("solana-validator")
+        .arg("--help")
+        .output()
+        .context("solana-validator not found. Please install the full Solana validator.")?;
+    let supported = supported_flags(&String::from_utf8_lossy(&help.stdout));
+
+    let unknown: Vec<&str> = args
+        .iter()
+        .filter_map(|arg| arg.split('=').next())
+        .filter(|flag| !supported.contains(*flag))
+        .collect();
+
+    let dry_run = Command::new("solana-validator")
+        .args(&args)
+        .arg("--version")
+        .output()
+        .context("Failed to run solana-validator")?;
+    let version = String::from_utf8_lossy(&dry_run.stdout).trim().to_string();
+    let parse_error = String::from_utf8_lossy(&dry_run.stderr);
+
+    if !version.is_empty() {
+        println!("├─ Validator: {}", version);
+    }
+    println!("├─ Flags checked: {}", args.len());
+    if unknown.is_empty() {
+        println!("├─ {} All flags are listed by --help", "✓".green());
+    } else {
+        println!("├─ {} {} flag(s) not recognized:", "✗".red(), unknown.len());
+        for flag in &unknown {
+            println!("│  • {}", flag.yellow());
+        }
+    }
+    if dry_run.status.success() {
+        println!("└─ {} Command line parses", "✓".green());
+    } else {
+        println!("└─ {} Command line rejected:", "✗".red());
+        for line in parse_error.lines().filter(|line| !line.trim().is_empty()).take(5) {
+            println!("   {}", line.dimmed());
+        }
+    }
+
+    if !unknown.is_empty() || !dry_run.status.success() {
+        anyhow::bail!("The validator would not start with the current config");
+    }
+
+    println!("\n{}", "Memory Budget".cyan().bold());
+    memory_budget::check(&config)?;
+    Ok(())
+}
+
 fn generate_keypairs(config: &ValidatorConfig) -> Result<()> {
     // Create directories
     fs::create_dir_all(config.ledger_path.parent().unwrap())?;
@@ -203,34 +752,205 @@ fn generate_keypairs(config: &ValidatorConfig) -> Result<()> {
     fs::create_dir_all(&config.snapshots_path)?;
     fs::create_dir_all(config.log_path.parent().unwrap())?;
     
-    // Generate identity keypair if it doesn't exist
-    if !config.identity_keypair.exists() {
-        println!("Generating new validator identity keypair...");
-        Command::new("solana-keygen")
-            .args(&["new", "--no-bip39-passphrase", "--outfile"])
-            .arg(&config.identity_keypair)
-            .stdin(Stdio::null())
-            .output()
-            .context("Failed to generate identity keypair")?;
-        println!("✓ Identity keypair generated");
+    // Generate in-process when asked to, or when solana-keygen isn't installed;
+    // both write the same JSON byte-array keypair format
+    let native_keygen = native::no_cli() || native::find_in_path("solana-keygen").is_none();
+    if native_keygen && !native::no_cli() {
+        println!("{} solana-keygen not found, generating keypairs natively", "ℹ".cyan());
+    }
+
+    for (label, path) in [("identity", &config.identity_keypair), ("vote account", &config.vote_account_keypair)] {
+        if path.exists() {
+            println!("✓ Using existing {} keypair: {}", label, native::keypair_pubkey(path)?);
+            continue;
+        }
+
+        println!("Generating new {} keypair...", label);
+        let pubkey = if native_keygen {
+            native::generate_keypair(path)?
+        } else {
+            let output = Command::new("solana-keygen")
+                .args(&["new", "--no-bip39-passphrase", "--outfile"])
+                .arg(path)
+                .stdin(Stdio::null())
+                .output()
+                .with_context(|| format!("Failed to generate {} keypair", label))?;
+            if !output.status.success() {
+                anyhow::bail!("solana-keygen failed to generate {} keypair: {}",
+                    label, String::from_utf8_lossy(&output.stderr).trim());
+            }
+            native::keypair_pubkey(path)?
+        };
+        println!("✓ {} keypair generated: {}", label, pubkey.to_string().yellow());
+    }
+
+    Ok(())
+}
+
+/// Where a validator with the configured identity is already running,
+/// or None when none is found
+///
+/// Locally that is a running `solana-validator` whose RPC reports the identity
+/// (or doesn't answer). Elsewhere it is the vote account's last vote moving
+/// on while no local validator runs as this identity.
+async fn duplicate_validator(config: &ValidatorConfig) -> Result<Option<String>> {
+    let Ok(identity) = native::keypair_pubkey(&config.identity_keypair) else {
+        // A keypair about to be generated can't be running anywhere
+        return Ok(None);
+    };
+    
+    if let Some(pid) = native::validator_pids().first() {
+        let local = tls::rpc_client_with_timeout(
+            format!("http://127.0.0.1:{}", config.rpc_port),
+            Duration::from_secs(5),
+            CommitmentConfig::confirmed(),
+        );
+        match local.get_identity() {
+            Ok(running) if running == identity => {
+                return Ok(Some(format!("solana-validator (PID {}) on this machine is already running as {}", pid, identity)));
+            }
+            Ok(running) => println!("  {} A local validator (PID {}) runs as a different identity, {}", "ℹ".cyan(), pid, running),
+            Err(_) => {
+                return Ok(Some(format!("solana-validator (PID {}) is running on this machine and its identity can't be read", pid)));
+            }
+        }
+    }
+    
+    let Ok(vote_account) = native::keypair_pubkey(&config.vote_account_keypair) else {
+        return Ok(None);
+    };
+    let rpc_client = tls::rpc_client(config.cluster_rpc_url(), CommitmentConfig::confirmed());
+    let on_chain = rpc_client
+        .get_account_with_commitment(&vote_account, rpc_client.commitment())
+        .context("Failed to look up the vote account")?
+        .value;
+    if on_chain.is_none() {
+        println!("  {} Vote account {} not on chain yet; nothing can be voting with it", "✓".green(), vote_account);
+        return Ok(None);
+    }
+    
+    println!("  {} Watching vote account {} for {}s...", "▶".cyan(), vote_account, VOTE_ADVANCE_WINDOW.as_secs());
+    let before = blockchain::fetch_vote_state(&rpc_client, &vote_account)?.last_voted_slot();
+    sleep(VOTE_ADVANCE_WINDOW).await;
+    let after = blockchain::fetch_vote_state(&rpc_client, &vote_account)?.last_voted_slot();
+    
+    match (before, after) {
+        (Some(before), Some(after)) if after > before => Ok(Some(format!(
+            "vote account {} is voting from another machine (last vote {} → {} in {}s)",
+            vote_account, before, after, VOTE_ADVANCE_WINDOW.as_secs()
+        ))),
+        _ => Ok(None),
+    }
+}
+
+/// Fail when `duplicate_validator` finds this identity running, unless
+/// `force` (not accepted on mainnet, where the check always applies)
+///
+/// Two validators voting with one identity cast conflicting votes.
+async fn refuse_duplicate(config: &ValidatorConfig, force: bool) -> Result<()> {
+    let mainnet = config.is_mainnet();
+    let found = match duplicate_validator(config).await {
+        Ok(found) => found,
+        Err(e) if mainnet => {
+            return Err(e.context("Could not rule out another validator with this identity, which is required on mainnet"));
+        }
+        Err(e) => {
+            println!("{} Could not check for another validator with this identity: {:#}", "⚠".yellow(), e);
+            return Ok(());
+        }
+    };
+    
+    let Some(running) = found else {
+        println!("{} No other validator is running with this identity", "✓".green());
+        return Ok(());
+    };
+    if force && !mainnet {
+        println!("{} {}; starting anyway (--force)", "⚠".yellow().bold(), running);
+        return Ok(());
+    }
+    
+    let hint = if mainnet {
+        "Stop the other validator first; --force is not accepted on mainnet"
     } else {
-        println!("✓ Using existing identity keypair");
-    }
-    
-    // Generate vote account keypair if it doesn't exist
-    if !config.vote_account_keypair.exists() {
-        println!("Generating new vote account keypair...");
-        Command::new("solana-keygen")
-            .args(&["new", "--no-bip39-passphrase", "--outfile"])
-            .arg(&config.vote_account_keypair)
-            .stdin(Stdio::null())
-            .output()
-            .context("Failed to generate vote account keypair")?;
-        println!("✓ Vote account keypair generated");
+        "Stop the other validator first, or pass --force if you are sure it is safe"
+    };
+    anyhow::bail!("Refusing to start: {}. Running two validators with one identity double-signs. {}", running, hint)
+}
+
+/// Check the port layout holds together and the ports the validator needs
+/// up front are free: RPC and RPC PubSub on `rpc_bind_address`, gossip (UDP
+/// and TCP) on every address
+///
+/// Each port is bound and released straight away; the dynamic range is left
+/// to the validator, which picks whichever of its ports are free.
+fn check_ports(config: &ValidatorConfig) -> Result<()> {
+    config.check_port_layout()?;
+    let any = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+    let binds = [
+        ("rpc_port", "TCP", config.rpc_bind_address, config.rpc_port),
+        ("RPC PubSub (rpc_port + 1)", "TCP", config.rpc_bind_address, config.rpc_port + 1),
+        ("gossip_port", "UDP", any, config.gossip_port),
+        ("gossip_port", "TCP", any, config.gossip_port),
+    ];
+    let mut busy = Vec::new();
+    for (name, protocol, address, port) in binds {
+        let socket = SocketAddr::new(address, port);
+        let bound = match protocol {
+            "UDP" => UdpSocket::bind(socket).map(drop),
+            _ => TcpListener::bind(socket).map(drop),
+        };
+        match bound {
+            Ok(()) => println!("├─ {} {} {} {} is free", "✓".green(), name, protocol, socket),
+            Err(e) => {
+                println!("├─ {} {} {} {}: {}", "✗".red(), name, protocol, socket, e);
+                busy.push(format!("{} {} {} ({})", name, protocol, socket, e));
+            }
+        }
+    }
+    println!("└─ dynamic_port_range {} left to the validator", config.dynamic_port_range);
+    if !busy.is_empty() {
+        anyhow::bail!("Can't bind {}; is another validator or RPC node running?", busy.join(", "));
+    }
+    Ok(())
+}
+
+/// What `generate_keypairs` would do: the keypairs it would reuse or
+/// generate and the directories it would create
+fn preview_keypairs(config: &ValidatorConfig) -> Result<()> {
+    let dirs = [
+        config.ledger_path.parent(),
+        Some(config.ledger_path.as_path()),
+        Some(config.accounts_path.as_path()),
+        Some(config.snapshots_path.as_path()),
+        config.log_path.parent(),
+    ];
+    for dir in dirs.into_iter().flatten().filter(|dir| !dir.exists()) {
+        println!("▶ Would create {}", dir.display());
+    }
+    
+    let keygen = if native::no_cli() || native::find_in_path("solana-keygen").is_none() {
+        "natively"
     } else {
-        println!("✓ Using existing vote account keypair");
+        "with solana-keygen"
+    };
+    let mut unreadable = Vec::new();
+    for (label, path) in [("identity", &config.identity_keypair), ("vote account", &config.vote_account_keypair)] {
+        if !path.exists() {
+            println!("▶ Would generate a new {} keypair {} at {}", label, keygen, path.display());
+            continue;
+        }
+        match native::keypair_pubkey(path) {
+            Ok(pubkey) => println!("✓ Would use existing {} keypair: {}", label, pubkey),
+            Err(e) => {
+                println!("{} {} keypair {}: {:#}", "✗".red(), label, path.display(), e);
+                unreadable.push(label);
+            }
+        }
     }
     
+    if !unreadable.is_empty() {
+        anyhow::bail!("Unreadable keypair(s): {}", unreadable.join(", "));
+    }
     Ok(())
 }
 
@@ -303,14 +1023,13 @@ async fn setup_vote_account(config: &ValidatorConfig) -> Result<()> {
     Ok(())
 }
 
-fn start_optimized_validator(config: &ValidatorConfig) -> Result<u32> {
-    println!("Starting validator with performance optimizations...");
-    
-    // Build optimized arguments
-    let mut args = config.build_validator_args();
+/// The config's validator arguments plus the extra performance flags,
+/// adapted to the installed validator
+pub(crate) fn optimized_validator_args(config: &ValidatorConfig) -> Result<Vec<String>> {
+    let mut args = config.build_validator_args()?;
     
     // Add additional performance flags
-    args.extend_from_slice(&[
+    args.extend(flag_compat::for_installed_validator(vec![
         "--no-wait-for-vote-to-start-leader".to_string(),
         "--enable-rpc-transaction-history".to_string(),
         "--enable-extended-tx-metadata-storage".to_string(),
@@ -320,8 +1039,24 @@ fn start_optimized_validator(config: &ValidatorConfig) -> Result<u32> {
         "--maximum-snapshot-download-abort=5".to_string(),
         "--no-check-vote-account".to_string(),
         "--no-wait-for-supermajority".to_string(),
-        "--expected-shred-version=0".to_string(),
-    ]);
+    ]));
+    Ok(args)
+}
+
+fn start_optimized_validator(config: &ValidatorConfig) -> Result<u32> {
+    println!("Starting validator with performance optimizations...");
+    
+    // Build optimized arguments
+    let mut args = optimized_validator_args(config)?;
+    // The validator outlives this command, so nothing would be left to drain
+    // `--log=-` into the rotating log; it writes log_path itself instead
+    if config.log_rotation.enabled {
+        for arg in args.iter_mut().filter(|arg| *arg == "--log=-") {
+            *arg = format!("--log={}", config.log_path.display());
+        }
+        println!("  {} Logging to {} unrotated (rotation needs the process manager to drain the output)",
+            "ℹ".cyan(), config.log_path.display());
+    }
     
     println!("  Starting with {} threads for RPC", config.optimization.rpc_threads);
     println!("  TPU coalesce: {}ms", config.optimization.tpu_coalesce_ms);
@@ -329,8 +1064,8 @@ fn start_optimized_validator(config: &ValidatorConfig) -> Result<u32> {
     
     let mut child = Command::new("solana-validator")
         .args(&args)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
         .spawn()
         .context("Failed to start validator process")?;
     
@@ -355,7 +1090,7 @@ fn start_optimized_validator(config: &ValidatorConfig) -> Result<u32> {
     Ok(pid)
 }
 
-fn display_system_metrics(metrics: &crate::system::SystemMetrics) {
+fn display_system_metrics(metrics: &crate::system::SystemMetrics, config: &ValidatorConfig) {
     println!("\n{}", "📊 System Performance".cyan().bold());
     println!("CPU Usage: {:.1}%", metrics.cpu_usage);
     println!("Memory: {} MB / {} MB ({:.1}%)", 
@@ -377,5 +1112,8 @@ fn display_system_metrics(metrics: &crate::system::SystemMetrics) {
             validator.memory_mb,
             validator.threads
         );
+        if let Some(warning) = SystemMonitor::thread_count_warning(validator.threads, &config.optimization) {
+            println!("  {} {}", "⚠".yellow(), warning);
+        }
     }
 }